@@ -19,6 +19,9 @@ pub struct SupabaseClaims {
     pub exp: usize,
 }
 
+/// Supabase role carried by tokens that may call admin endpoints.
+pub const SERVICE_ROLE: &str = "service_role";
+
 #[derive(Debug, Clone)]
 pub struct AuthUser {
     pub user_id: Uuid,
@@ -26,6 +29,12 @@ pub struct AuthUser {
     pub role: String,
 }
 
+impl AuthUser {
+    pub fn is_service_role(&self) -> bool {
+        self.role == SERVICE_ROLE
+    }
+}
+
 #[derive(Debug)]
 pub struct AuthError(anyhow::Error);
 
@@ -1,6 +1,7 @@
 use crate::{
     axum_http::{default_routers, routers},
     config::config_model::DotEnvyConfig,
+    usecases::plan_cache::PlanCache,
 };
 use anyhow::Result;
 use axum::{
@@ -23,28 +24,88 @@ use tower_http::{
 };
 use tracing::info;
 
-pub async fn start(config: Arc<DotEnvyConfig>, db_pool: Arc<PgPoolSquad>) -> Result<()> {
+pub async fn start(
+    config: Arc<DotEnvyConfig>,
+    db_pool: Arc<PgPoolSquad>,
+    plan_cache: Arc<PlanCache>,
+) -> Result<()> {
     let app = Router::new()
         .fallback(default_routers::not_found)
         .nest(
             "/api/v1/live-following",
-            routers::live_following::routes(Arc::clone(&db_pool), Arc::clone(&config)),
+            routers::live_following::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
         )
         .nest(
             "/api/v1/watch-url",
-            routers::watch_url::routes(Arc::clone(&db_pool), Arc::clone(&config)),
+            routers::watch_url::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
         )
         .nest(
             "/api/v1/subscriptions",
-            routers::subscriptions::routes(Arc::clone(&db_pool), Arc::clone(&config)),
+            routers::subscriptions::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
+        )
+        .nest(
+            "/api/v1/gifts",
+            routers::gift_codes::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
+        )
+        .nest(
+            "/api/v1/plan-features",
+            routers::plan_features::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
         )
         .nest(
             "/api/v1/recordings",
-            routers::recordings::routes(Arc::clone(&db_pool), Arc::clone(&config)),
+            routers::recordings::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
+        )
+        .nest(
+            "/api/v1/admin/subscription-grants",
+            routers::subscription_grants::routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
         )
         .nest(
             "/api",
-            routers::subscriptions::webhook_routes(Arc::clone(&db_pool), Arc::clone(&config)),
+            routers::subscriptions::webhook_routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
+        )
+        .nest(
+            "/internal/v1/stripe",
+            routers::subscriptions::internal_routes(
+                Arc::clone(&db_pool),
+                Arc::clone(&config),
+                Arc::clone(&plan_cache),
+            ),
+        )
+        .nest(
+            "/internal/v1/plan-cache",
+            routers::plan_cache::internal_routes(Arc::clone(&config), Arc::clone(&plan_cache)),
         )
         .route("/api/v1/health-check", get(default_routers::health_check))
         .layer(TimeoutLayer::new(Duration::from_secs(
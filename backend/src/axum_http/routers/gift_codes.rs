@@ -0,0 +1,111 @@
+use crate::{
+    axum_http::{auth::AuthUser, error_responses::ErrorResponse},
+    config::config_model::DotEnvyConfig,
+    usecases::{
+        gift_codes::{GiftCodeError, GiftCodeUseCase},
+        plan_cache::PlanCache,
+        plan_resolver::PlanResolver,
+    },
+};
+use axum::{
+    Json, Router,
+    extract::State,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use chrono::Utc;
+use crates::{
+    domain::{
+        repositories::{
+            gift_codes::GiftCodeRepository, live_following::LiveFollowingRepository,
+            plans::PlanRepository, subscriptions::SubscriptionRepository,
+        },
+        value_objects::gift_codes::RedeemGiftCodeRequest,
+    },
+    infra::db::{
+        postgres::postgres_connection::PgPoolSquad,
+        repositories::{
+            gift_codes::GiftCodePostgres, live_following::LiveFollowingPostgres,
+            plans::PlanPostgres, subscriptions::SubscriptionPostgres,
+        },
+    },
+};
+use std::sync::Arc;
+use tracing::info;
+
+// Run example
+//   curl -X POST "http://localhost:$SERVER_PORT_BACKEND/api/v1/gifts/redeem" \
+//     -H "Authorization: Bearer $USER_JWT" -H "Content-Type: application/json" \
+//     -d '{"code":"ABCD-EFGH-JKMN-PQRS"}'
+
+pub fn routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
+    let plan_repo = Arc::new(PlanPostgres::new(Arc::clone(&db_pool)));
+    let subscription_repo = Arc::new(SubscriptionPostgres::new(Arc::clone(&db_pool)));
+    let plan_resolver = Arc::new(
+        PlanResolver::new(
+            plan_repo,
+            Arc::clone(&subscription_repo),
+            config.free_plan_id,
+        )
+        .with_cache(plan_cache),
+    );
+
+    let usecase = GiftCodeUseCase::new(
+        Arc::new(GiftCodePostgres::new(Arc::clone(&db_pool))),
+        Arc::new(LiveFollowingPostgres::new(Arc::clone(&db_pool))),
+        subscription_repo,
+        plan_resolver,
+    );
+
+    Router::new()
+        .route("/", get(list_purchased_gifts))
+        .route("/redeem", post(redeem_gift_code))
+        .with_state(Arc::new(usecase))
+}
+
+pub async fn redeem_gift_code<G, L, P, S>(
+    State(usecase): State<Arc<GiftCodeUseCase<G, L, P, S>>>,
+    auth: AuthUser,
+    Json(body): Json<RedeemGiftCodeRequest>,
+) -> impl IntoResponse
+where
+    G: GiftCodeRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    info!(%auth.user_id, "gift_codes: redeem request received");
+    match usecase.redeem(auth.user_id, &body.code, Utc::now()).await {
+        Ok(redemption) => Json(redemption).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn list_purchased_gifts<G, L, P, S>(
+    State(usecase): State<Arc<GiftCodeUseCase<G, L, P, S>>>,
+    auth: AuthUser,
+) -> impl IntoResponse
+where
+    G: GiftCodeRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    match usecase.list_purchased(auth.user_id).await {
+        Ok(gifts) => Json(gifts).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+fn map_error(err: GiftCodeError) -> axum::response::Response {
+    let status = err.status_code();
+    let body = Json(ErrorResponse {
+        code: status.as_u16(),
+        message: err.to_string(),
+    });
+    (status, body).into_response()
+}
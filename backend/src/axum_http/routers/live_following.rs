@@ -1,5 +1,6 @@
 use crate::usecases::{
     live_following::{FollowCooldownError, LiveFollowingUseCase},
+    plan_cache::PlanCache,
     plan_resolver::PlanResolver,
 };
 use crate::{axum_http::auth::AuthUser, config::config_model::DotEnvyConfig};
@@ -37,7 +38,11 @@ struct FollowCooldownPayload {
     remaining_seconds: i64,
 }
 
-pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
+pub fn routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
     let live_following_repository = LiveFollowingPostgres::new(Arc::clone(&db_pool));
     let plan_repository = PlanPostgres::new(Arc::clone(&db_pool));
     let subscription_repository = SubscriptionPostgres::new(Arc::clone(&db_pool));
@@ -46,7 +51,8 @@ pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
         Arc::new(plan_repository),
         Arc::new(subscription_repository),
         config.free_plan_id,
-    );
+    )
+    .with_cache(plan_cache);
 
     let live_following_usecase =
         LiveFollowingUseCase::new(Arc::new(live_following_repository), Arc::new(plan_resolver));
@@ -1,4 +1,8 @@
+pub mod gift_codes;
 pub mod live_following;
+pub mod plan_cache;
+pub mod plan_features;
 pub mod recordings;
+pub mod subscription_grants;
 pub mod subscriptions;
 pub mod watch_url;
@@ -0,0 +1,48 @@
+use crate::{
+    axum_http::routers::subscriptions::authorize_bearer, config::config_model::DotEnvyConfig,
+    usecases::plan_cache::PlanCache,
+};
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing::get,
+};
+use std::sync::Arc;
+
+// Run example
+//   curl "http://localhost:$SERVER_PORT_BACKEND/internal/v1/plan-cache/stats" \
+//     -H "Authorization: Bearer $INTERNAL_API_TOKEN"
+
+#[derive(Clone)]
+pub struct PlanCacheRouteState {
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+}
+
+pub fn internal_routes(config: Arc<DotEnvyConfig>, plan_cache: Arc<PlanCache>) -> Router {
+    Router::new()
+        .route("/stats", get(plan_cache_stats))
+        .with_state(PlanCacheRouteState { config, plan_cache })
+}
+
+/// Hit/miss counters of the effective plan cache since the backend started.
+pub async fn plan_cache_stats(
+    State(state): State<PlanCacheRouteState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(expected_token) = state.config.internal.token.as_deref() else {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "internal token is not configured",
+        )
+            .into_response();
+    };
+
+    if let Err(status) = authorize_bearer(&headers, expected_token) {
+        return (status, "unauthorized").into_response();
+    }
+
+    Json(state.plan_cache.stats()).into_response()
+}
@@ -0,0 +1,107 @@
+use crate::{
+    axum_http::{auth::AuthUser, error_responses::ErrorResponse},
+    config::config_model::DotEnvyConfig,
+    usecases::{
+        plan_cache::PlanCache,
+        plan_features::{PlanFeaturesError, PlanFeaturesUseCase},
+        plan_resolver::PlanResolver,
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::get,
+};
+use crates::{
+    domain::repositories::{
+        live_following::LiveFollowingRepository, plans::PlanRepository,
+        recording_view::RecordingViewRepository, subscriptions::SubscriptionRepository,
+    },
+    infra::db::{
+        postgres::postgres_connection::PgPoolSquad,
+        repositories::{
+            live_following::LiveFollowingPostgres, plans::PlanPostgres,
+            recording_view::RecordingViewPostgres, subscriptions::SubscriptionPostgres,
+        },
+    },
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+// Run example
+//   curl "http://localhost:$SERVER_PORT_BACKEND/api/v1/plan-features" \
+//     -H "Authorization: Bearer $USER_JWT"
+//   curl "http://localhost:$SERVER_PORT_BACKEND/api/v1/plan-features/compare/$PLAN_ID" \
+//     -H "Authorization: Bearer $USER_JWT"
+
+pub fn routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
+    let plan_repo = Arc::new(PlanPostgres::new(Arc::clone(&db_pool)));
+    let plan_resolver = Arc::new(
+        PlanResolver::new(
+            Arc::clone(&plan_repo),
+            Arc::new(SubscriptionPostgres::new(Arc::clone(&db_pool))),
+            config.free_plan_id,
+        )
+        .with_cache(plan_cache),
+    );
+
+    let usecase = PlanFeaturesUseCase::new(
+        plan_repo,
+        Arc::new(LiveFollowingPostgres::new(Arc::clone(&db_pool))),
+        Arc::new(RecordingViewPostgres::new(Arc::clone(&db_pool))),
+        plan_resolver,
+        config.quota.enforcement,
+    );
+
+    Router::new()
+        .route("/", get(effective_features))
+        .route("/compare/:plan_id", get(compare_with_plan))
+        .with_state(Arc::new(usecase))
+}
+
+pub async fn effective_features<P, S, L, R>(
+    State(usecase): State<Arc<PlanFeaturesUseCase<P, S, L, R>>>,
+    auth: AuthUser,
+) -> impl IntoResponse
+where
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    R: RecordingViewRepository + Send + Sync + 'static,
+{
+    match usecase.effective_features(auth.user_id).await {
+        Ok(features) => Json(features).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn compare_with_plan<P, S, L, R>(
+    State(usecase): State<Arc<PlanFeaturesUseCase<P, S, L, R>>>,
+    auth: AuthUser,
+    Path(plan_id): Path<Uuid>,
+) -> impl IntoResponse
+where
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    R: RecordingViewRepository + Send + Sync + 'static,
+{
+    match usecase.compare_with(auth.user_id, plan_id).await {
+        Ok(comparison) => Json(comparison).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+fn map_error(err: PlanFeaturesError) -> axum::response::Response {
+    let status = err.status_code();
+    let body = Json(ErrorResponse {
+        code: status.as_u16(),
+        message: err.to_string(),
+    });
+    (status, body).into_response()
+}
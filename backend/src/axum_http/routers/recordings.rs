@@ -2,6 +2,7 @@ use crate::{
     axum_http::auth::AuthUser,
     config::config_model::DotEnvyConfig,
     usecases::{
+        plan_cache::PlanCache,
         plan_resolver::PlanResolver,
         recordings::{HomeRecordingsCursor, RecordingsUseCase},
     },
@@ -48,7 +49,11 @@ pub struct FollowsRecordingsQuery {
     live_account_id: Option<String>,
 }
 
-pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
+pub fn routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
     let recording_view_repository = RecordingViewPostgres::new(Arc::clone(&db_pool));
     let plan_repository = PlanPostgres::new(Arc::clone(&db_pool));
     let subscription_repository = SubscriptionPostgres::new(Arc::clone(&db_pool));
@@ -57,10 +62,14 @@ pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
         Arc::new(plan_repository),
         Arc::new(subscription_repository),
         config.free_plan_id,
-    );
+    )
+    .with_cache(plan_cache);
 
-    let usecase =
-        RecordingsUseCase::new(Arc::new(recording_view_repository), Arc::new(plan_resolver));
+    let usecase = RecordingsUseCase::new(
+        Arc::new(recording_view_repository),
+        Arc::new(plan_resolver),
+        config.quota.enforcement,
+    );
 
     Router::new()
         .route("/home", get(list_home_recordings))
@@ -0,0 +1,188 @@
+use crate::{
+    axum_http::{auth::AuthUser, error_responses::ErrorResponse},
+    config::config_model::DotEnvyConfig,
+    usecases::{
+        plan_cache::PlanCache,
+        plan_resolver::PlanResolver,
+        subscription_grants::{SubscriptionGrantError, SubscriptionGrantUseCase},
+    },
+};
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{get, post},
+};
+use chrono::Utc;
+use crates::{
+    domain::{
+        repositories::{
+            live_following::LiveFollowingRepository, plans::PlanRepository,
+            subscription_grants::SubscriptionGrantRepository,
+            subscriptions::SubscriptionRepository,
+        },
+        value_objects::subscription_grants::{
+            CreateSubscriptionGrantRequest, RevokeSubscriptionGrantRequest,
+        },
+    },
+    infra::db::{
+        postgres::postgres_connection::PgPoolSquad,
+        repositories::{
+            live_following::LiveFollowingPostgres, plans::PlanPostgres,
+            subscription_grants::SubscriptionGrantPostgres, subscriptions::SubscriptionPostgres,
+        },
+    },
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct ListSubscriptionGrantsQuery {
+    user_id: Option<Uuid>,
+    #[serde(default)]
+    include_revoked: bool,
+}
+
+// Run example
+//   curl -X POST "http://localhost:$SERVER_PORT_BACKEND/api/v1/admin/subscription-grants" \
+//     -H "Authorization: Bearer $SERVICE_ROLE_JWT" -H "Content-Type: application/json" \
+//     -d '{"user_id":"...","plan_id":"...","duration_days":30,"reason":"streamer partnership"}'
+
+pub fn routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
+    let plan_repo = Arc::new(PlanPostgres::new(Arc::clone(&db_pool)));
+    let subscription_repo = Arc::new(SubscriptionPostgres::new(Arc::clone(&db_pool)));
+    let plan_resolver = Arc::new(
+        PlanResolver::new(
+            Arc::clone(&plan_repo),
+            Arc::clone(&subscription_repo),
+            config.free_plan_id,
+        )
+        .with_cache(plan_cache),
+    );
+
+    let usecase = SubscriptionGrantUseCase::new(
+        Arc::new(SubscriptionGrantPostgres::new(Arc::clone(&db_pool))),
+        Arc::new(LiveFollowingPostgres::new(Arc::clone(&db_pool))),
+        plan_repo,
+        subscription_repo,
+        plan_resolver,
+        config.free_plan_id,
+    );
+
+    Router::new()
+        .route("/", get(list_grants).post(create_grant))
+        .route("/:subscription_id/revoke", post(revoke_grant))
+        .with_state(Arc::new(usecase))
+}
+
+pub async fn create_grant<G, L, P, S>(
+    State(usecase): State<Arc<SubscriptionGrantUseCase<G, L, P, S>>>,
+    auth: AuthUser,
+    Json(body): Json<CreateSubscriptionGrantRequest>,
+) -> impl IntoResponse
+where
+    G: SubscriptionGrantRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    if !auth.is_service_role() {
+        return forbidden(&auth);
+    }
+
+    info!(
+        admin_id = %auth.user_id,
+        user_id = %body.user_id,
+        plan_id = %body.plan_id,
+        "subscription_grants: create grant request received"
+    );
+    match usecase.grant(auth.user_id, body, Utc::now()).await {
+        Ok(grant) => (StatusCode::CREATED, Json(grant)).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn revoke_grant<G, L, P, S>(
+    State(usecase): State<Arc<SubscriptionGrantUseCase<G, L, P, S>>>,
+    auth: AuthUser,
+    Path(subscription_id): Path<Uuid>,
+    body: Option<Json<RevokeSubscriptionGrantRequest>>,
+) -> impl IntoResponse
+where
+    G: SubscriptionGrantRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    if !auth.is_service_role() {
+        return forbidden(&auth);
+    }
+
+    info!(
+        admin_id = %auth.user_id,
+        %subscription_id,
+        "subscription_grants: revoke grant request received"
+    );
+    let request = body.map(|Json(body)| body).unwrap_or_default();
+    match usecase
+        .revoke(subscription_id, auth.user_id, request, Utc::now())
+        .await
+    {
+        Ok(grant) => Json(grant).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn list_grants<G, L, P, S>(
+    State(usecase): State<Arc<SubscriptionGrantUseCase<G, L, P, S>>>,
+    auth: AuthUser,
+    Query(query): Query<ListSubscriptionGrantsQuery>,
+) -> impl IntoResponse
+where
+    G: SubscriptionGrantRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    if !auth.is_service_role() {
+        return forbidden(&auth);
+    }
+
+    match usecase.list(query.user_id, query.include_revoked).await {
+        Ok(grants) => Json(grants).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+fn forbidden(auth: &AuthUser) -> axum::response::Response {
+    warn!(
+        user_id = %auth.user_id,
+        role = %auth.role,
+        status = StatusCode::FORBIDDEN.as_u16(),
+        "subscription_grants: admin endpoint called without service role"
+    );
+    (
+        StatusCode::FORBIDDEN,
+        Json(ErrorResponse {
+            code: StatusCode::FORBIDDEN.as_u16(),
+            message: "service role required".to_string(),
+        }),
+    )
+        .into_response()
+}
+
+fn map_error(err: SubscriptionGrantError) -> axum::response::Response {
+    let status = err.status_code();
+    let body = Json(ErrorResponse {
+        code: status.as_u16(),
+        message: err.to_string(),
+    });
+    (status, body).into_response()
+}
@@ -1,112 +1,171 @@
 use crate::{
     axum_http::{auth::AuthUser, error_responses::ErrorResponse},
     config::config_model::DotEnvyConfig,
-    usecases::subscriptions::{StripeGateway, SubscriptionError, SubscriptionUseCase},
+    usecases::{
+        plan_cache::PlanCache,
+        subscriptions::{
+            PaymentReversalPolicy, SubscriptionError, SubscriptionRepositories, SubscriptionUseCase,
+        },
+    },
 };
 use axum::{
     Json, Router,
     body::Bytes,
-    extract::State,
-    http::{HeaderMap, StatusCode},
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
     response::IntoResponse,
     routing::{get, post},
 };
+use chrono::{DateTime, Utc};
 use crates::{
-    domain::{
-        repositories::{
-            invoices::InvoiceRepository,
-            payment_provider_customers::PaymentProviderCustomerRepository,
-            payments::PaymentRepository, plans::PlanRepository,
-            subscriptions::SubscriptionRepository,
-        },
-        value_objects::{
-            enums::{billing_modes::BillingMode, payment_methods::PaymentMethod},
-            subscriptions::{CreateCheckoutRequest, CreateCheckoutResponse},
+    domain::value_objects::{
+        enums::{billing_modes::BillingMode, payment_methods::PaymentMethod},
+        gift_codes::CreateGiftCheckoutRequest,
+        subscriptions::{
+            BillingHistoryCursor, CheckoutPriceSelection, CreateCheckoutRequest,
+            CreateCheckoutResponse,
         },
     },
     infra::db::{
         postgres::postgres_connection::PgPoolSquad,
         repositories::{
-            invoices::InvoicePostgres, payment_provider_customers::PaymentProviderCustomerPostgres,
-            payments::PaymentPostgres, plans::PlanPostgres, subscriptions::SubscriptionPostgres,
+            gift_codes::GiftCodePostgres, invoices::InvoicePostgres, job::JobPostgres,
+            live_following::LiveFollowingPostgres,
+            payment_provider_customers::PaymentProviderCustomerPostgres, payments::PaymentPostgres,
+            plans::PlanPostgres, stripe_events::StripeEventPostgres,
+            subscriptions::SubscriptionPostgres,
         },
     },
     payments::stripe_client::StripeClient,
 };
+use serde::Deserialize;
 use std::sync::Arc;
+use subtle::ConstantTimeEq;
 use tracing::info;
+use uuid::Uuid;
 
-type SubscriptionUseCaseState = SubscriptionUseCase<
+const DEFAULT_BILLING_HISTORY_LIMIT: i64 = 20;
+const MAX_BILLING_HISTORY_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct BillingHistoryQuery {
+    limit: Option<i64>,
+    cursor_created_at: Option<String>,
+    cursor_id: Option<String>,
+}
+
+pub type SubscriptionUseCaseState = SubscriptionUseCase<
     PlanPostgres,
     SubscriptionPostgres,
     PaymentPostgres,
     PaymentProviderCustomerPostgres,
     InvoicePostgres,
+    StripeEventPostgres,
+    JobPostgres,
+    GiftCodePostgres,
+    LiveFollowingPostgres,
     StripeClient,
 >;
 
+type SubscriptionState = Arc<SubscriptionUseCaseState>;
+
 pub fn build_usecase(
     db_pool: Arc<PgPoolSquad>,
     config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
 ) -> Arc<SubscriptionUseCaseState> {
-    let stripe_client = Arc::new(StripeClient::new(
-        config.stripe.secret_key.clone(),
-        config.stripe.webhook_secret.clone(),
-        config.stripe.success_url.clone(),
-        config.stripe.cancel_url.clone(),
-    ));
-
-    let plan_repo = Arc::new(PlanPostgres::new(Arc::clone(&db_pool)));
-    let subscription_repo = Arc::new(SubscriptionPostgres::new(Arc::clone(&db_pool)));
-    let payment_repo = Arc::new(PaymentPostgres::new(Arc::clone(&db_pool)));
-    let customer_repo = Arc::new(PaymentProviderCustomerPostgres::new(
-        Arc::clone(&db_pool),
-        stripe_client.clone(),
-    ));
-    let invoice_repo = Arc::new(InvoicePostgres::new(Arc::clone(&db_pool)));
-
-    Arc::new(SubscriptionUseCase::new(
-        plan_repo,
-        subscription_repo,
-        payment_repo,
-        customer_repo,
-        invoice_repo,
-        stripe_client,
-        config.free_plan_id,
-    ))
+    let stripe_client = Arc::new(
+        StripeClient::new(
+            config.stripe.secret_key.clone(),
+            config.stripe.webhook_secret.clone(),
+            config.stripe.success_url.clone(),
+            config.stripe.cancel_url.clone(),
+        )
+        .with_api_base(config.stripe.api_base.clone()),
+    );
+
+    let repositories = SubscriptionRepositories {
+        plan_repo: Arc::new(PlanPostgres::new(Arc::clone(&db_pool))),
+        subscription_repo: Arc::new(SubscriptionPostgres::new(Arc::clone(&db_pool))),
+        payment_repo: Arc::new(PaymentPostgres::new(Arc::clone(&db_pool))),
+        customer_repo: Arc::new(PaymentProviderCustomerPostgres::new(
+            Arc::clone(&db_pool),
+            stripe_client.clone(),
+        )),
+        invoice_repo: Arc::new(InvoicePostgres::new(Arc::clone(&db_pool))),
+        stripe_event_repo: Arc::new(StripeEventPostgres::new(Arc::clone(&db_pool))),
+        job_repo: Arc::new(JobPostgres::new(Arc::clone(&db_pool))),
+        gift_repo: Arc::new(GiftCodePostgres::new(Arc::clone(&db_pool))),
+        live_following_repo: Arc::new(LiveFollowingPostgres::new(Arc::clone(&db_pool))),
+    };
+
+    Arc::new(
+        SubscriptionUseCase::new(repositories, stripe_client, config.free_plan_id)
+            .with_reversal_policy(PaymentReversalPolicy {
+                end_on_full_refund: config.stripe.end_subscription_on_refund,
+                end_on_lost_dispute: config.stripe.end_subscription_on_lost_dispute,
+            })
+            .with_plan_cache(plan_cache),
+    )
 }
 
-pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
-    let subscription_usecase = build_usecase(Arc::clone(&db_pool), Arc::clone(&config));
+pub fn routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
+    let subscription_usecase = build_usecase(Arc::clone(&db_pool), Arc::clone(&config), plan_cache);
 
     Router::new()
         .route("/plans", get(list_plans))
         .route("/current", get(check_current_user_subscription))
         .route("/checkout", post(create_checkout))
+        .route("/gift-checkout", post(create_gift_checkout))
         .route("/cancel", post(cancel_subscription))
+        .route("/invoices", get(list_invoices))
+        .route("/invoices/:invoice_id/receipt", get(get_invoice_receipt))
+        .route("/payments", get(list_payments))
         .with_state(subscription_usecase)
 }
 
-pub fn webhook_routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
-    let subscription_usecase = build_usecase(Arc::clone(&db_pool), Arc::clone(&config));
+pub fn webhook_routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
+    let subscription_usecase = build_usecase(Arc::clone(&db_pool), Arc::clone(&config), plan_cache);
 
     Router::new()
         .route("/stripe/webhook", post(stripe_webhook))
         .with_state(subscription_usecase)
 }
 
-pub async fn list_plans<P, S, Pay, Cust, Inv, Stripe>(
-    State(usecase): State<Arc<SubscriptionUseCase<P, S, Pay, Cust, Inv, Stripe>>>,
+// Run example
+//   curl -X POST "http://localhost:$SERVER_PORT_BACKEND/internal/v1/stripe/events/evt_123/replay" \
+//     -H "Authorization: Bearer $INTERNAL_API_TOKEN"
+
+#[derive(Clone)]
+pub struct InternalRouteState {
+    config: Arc<DotEnvyConfig>,
+    usecase: Arc<SubscriptionUseCaseState>,
+}
+
+pub fn internal_routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
+    let usecase = build_usecase(Arc::clone(&db_pool), Arc::clone(&config), plan_cache);
+
+    Router::new()
+        .route("/events/:event_id/replay", post(replay_stripe_event))
+        .with_state(InternalRouteState { config, usecase })
+}
+
+pub async fn list_plans(
+    State(usecase): State<SubscriptionState>,
     _auth: AuthUser,
-) -> impl IntoResponse
-where
-    P: PlanRepository + Send + Sync + 'static,
-    S: SubscriptionRepository + Send + Sync + 'static,
-    Pay: PaymentRepository + Send + Sync + 'static,
-    Cust: PaymentProviderCustomerRepository + Send + Sync + 'static,
-    Inv: InvoiceRepository + Send + Sync + 'static,
-    Stripe: StripeGateway + Send + Sync + 'static,
-{
+) -> impl IntoResponse {
     info!("subscriptions: list_plans request received");
     match usecase.list_plans().await {
         Ok(plans) => {
@@ -120,18 +179,10 @@ where
     }
 }
 
-pub async fn check_current_user_subscription<P, S, Pay, Cust, Inv, Stripe>(
-    State(usecase): State<Arc<SubscriptionUseCase<P, S, Pay, Cust, Inv, Stripe>>>,
+pub async fn check_current_user_subscription(
+    State(usecase): State<SubscriptionState>,
     auth: AuthUser,
-) -> impl IntoResponse
-where
-    P: PlanRepository + Send + Sync + 'static,
-    S: SubscriptionRepository + Send + Sync + 'static,
-    Pay: PaymentRepository + Send + Sync + 'static,
-    Cust: PaymentProviderCustomerRepository + Send + Sync + 'static,
-    Inv: InvoiceRepository + Send + Sync + 'static,
-    Stripe: StripeGateway + Send + Sync + 'static,
-{
+) -> impl IntoResponse {
     info!(%auth.user_id, "subscriptions: current subscription request received");
     match usecase.get_current_subscription(auth.user_id).await {
         Ok(Some(subscription)) => Json(subscription).into_response(),
@@ -140,19 +191,11 @@ where
     }
 }
 
-pub async fn create_checkout<P, S, Pay, Cust, Inv, Stripe>(
-    State(usecase): State<Arc<SubscriptionUseCase<P, S, Pay, Cust, Inv, Stripe>>>,
+pub async fn create_checkout(
+    State(usecase): State<SubscriptionState>,
     auth: AuthUser,
     Json(body): Json<CreateCheckoutRequest>,
-) -> impl IntoResponse
-where
-    P: PlanRepository + Send + Sync + 'static,
-    S: SubscriptionRepository + Send + Sync + 'static,
-    Pay: PaymentRepository + Send + Sync + 'static,
-    Cust: PaymentProviderCustomerRepository + Send + Sync + 'static,
-    Inv: InvoiceRepository + Send + Sync + 'static,
-    Stripe: StripeGateway + Send + Sync + 'static,
-{
+) -> impl IntoResponse {
     info!(
         %auth.user_id,
         plan_id = %body.plan_id,
@@ -201,13 +244,60 @@ where
         }
     };
 
+    let promotion = match body.promotion() {
+        Ok(promotion) => promotion,
+        Err(message) => {
+            info!(
+                %auth.user_id,
+                status = StatusCode::BAD_REQUEST.as_u16(),
+                "subscriptions: invalid promotion options"
+            );
+            return bad_request(message.to_string());
+        }
+    };
+
+    let currency = match body.currency() {
+        Ok(currency) => currency,
+        Err(message) => {
+            info!(
+                %auth.user_id,
+                status = StatusCode::BAD_REQUEST.as_u16(),
+                currency = ?body.currency,
+                "subscriptions: invalid currency"
+            );
+            return bad_request(message.to_string());
+        }
+    };
+
+    let (term_months, quantity) = match body.term() {
+        Ok(term) => term,
+        Err(message) => {
+            info!(
+                %auth.user_id,
+                status = StatusCode::BAD_REQUEST.as_u16(),
+                term_months = ?body.term_months,
+                quantity = ?body.quantity,
+                "subscriptions: invalid term options"
+            );
+            return bad_request(message.to_string());
+        }
+    };
+
+    let selection = CheckoutPriceSelection {
+        billing_mode,
+        payment_method,
+        currency,
+        term_months,
+        quantity,
+    };
+
     match usecase
         .create_checkout_session(
             auth.user_id,
             auth.email.clone(),
             body.plan_id,
-            billing_mode,
-            payment_method,
+            selection,
+            promotion,
         )
         .await
     {
@@ -223,18 +313,59 @@ where
     }
 }
 
-pub async fn cancel_subscription<P, S, Pay, Cust, Inv, Stripe>(
-    State(usecase): State<Arc<SubscriptionUseCase<P, S, Pay, Cust, Inv, Stripe>>>,
+pub async fn create_gift_checkout(
+    State(usecase): State<SubscriptionState>,
     auth: AuthUser,
-) -> impl IntoResponse
-where
-    P: PlanRepository + Send + Sync + 'static,
-    S: SubscriptionRepository + Send + Sync + 'static,
-    Pay: PaymentRepository + Send + Sync + 'static,
-    Cust: PaymentProviderCustomerRepository + Send + Sync + 'static,
-    Inv: InvoiceRepository + Send + Sync + 'static,
-    Stripe: StripeGateway + Send + Sync + 'static,
-{
+    Json(body): Json<CreateGiftCheckoutRequest>,
+) -> impl IntoResponse {
+    info!(
+        %auth.user_id,
+        plan_id = %body.plan_id,
+        payment_method = %body.payment_method,
+        "subscriptions: create gift checkout request received"
+    );
+
+    let Some(payment_method) = PaymentMethod::from_str(&body.payment_method) else {
+        info!(
+            %auth.user_id,
+            status = StatusCode::BAD_REQUEST.as_u16(),
+            payment_method = %body.payment_method,
+            "subscriptions: invalid payment_method"
+        );
+        return bad_request("invalid payment_method".to_string());
+    };
+
+    let currency = match body.currency() {
+        Ok(currency) => currency,
+        Err(message) => return bad_request(message.to_string()),
+    };
+
+    let (term_months, quantity) = match body.term() {
+        Ok(term) => term,
+        Err(message) => return bad_request(message.to_string()),
+    };
+
+    let selection = CheckoutPriceSelection {
+        billing_mode: BillingMode::OneTime,
+        payment_method,
+        currency,
+        term_months,
+        quantity,
+    };
+
+    match usecase
+        .create_gift_checkout_session(auth.user_id, auth.email.clone(), body.plan_id, selection)
+        .await
+    {
+        Ok(url) => Json(CreateCheckoutResponse { checkout_url: url }).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn cancel_subscription(
+    State(usecase): State<SubscriptionState>,
+    auth: AuthUser,
+) -> impl IntoResponse {
     info!(
         %auth.user_id,
         "subscriptions: cancel recurring subscription request received"
@@ -252,19 +383,57 @@ where
     }
 }
 
-pub async fn stripe_webhook<P, S, Pay, Cust, Inv, Stripe>(
-    State(usecase): State<Arc<SubscriptionUseCase<P, S, Pay, Cust, Inv, Stripe>>>,
+pub async fn list_invoices(
+    State(usecase): State<SubscriptionState>,
+    auth: AuthUser,
+    Query(query): Query<BillingHistoryQuery>,
+) -> impl IntoResponse {
+    info!(%auth.user_id, "subscriptions: list invoices request received");
+    let (limit, cursor) = match parse_billing_history_query(query) {
+        Ok(parsed) => parsed,
+        Err(message) => return bad_request(message),
+    };
+
+    match usecase.list_invoices(auth.user_id, limit, cursor).await {
+        Ok(page) => Json(page).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn list_payments(
+    State(usecase): State<SubscriptionState>,
+    auth: AuthUser,
+    Query(query): Query<BillingHistoryQuery>,
+) -> impl IntoResponse {
+    info!(%auth.user_id, "subscriptions: list payments request received");
+    let (limit, cursor) = match parse_billing_history_query(query) {
+        Ok(parsed) => parsed,
+        Err(message) => return bad_request(message),
+    };
+
+    match usecase.list_payments(auth.user_id, limit, cursor).await {
+        Ok(page) => Json(page).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn get_invoice_receipt(
+    State(usecase): State<SubscriptionState>,
+    auth: AuthUser,
+    Path(invoice_id): Path<Uuid>,
+) -> impl IntoResponse {
+    info!(%auth.user_id, %invoice_id, "subscriptions: invoice receipt request received");
+    match usecase.get_invoice_receipt(auth.user_id, invoice_id).await {
+        Ok(receipt) => Json(receipt).into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub async fn stripe_webhook(
+    State(usecase): State<SubscriptionState>,
     headers: HeaderMap,
     payload: Bytes,
-) -> impl IntoResponse
-where
-    P: PlanRepository + Send + Sync + 'static,
-    S: SubscriptionRepository + Send + Sync + 'static,
-    Pay: PaymentRepository + Send + Sync + 'static,
-    Cust: PaymentProviderCustomerRepository + Send + Sync + 'static,
-    Inv: InvoiceRepository + Send + Sync + 'static,
-    Stripe: StripeGateway + Send + Sync + 'static,
-{
+) -> impl IntoResponse {
     info!(
         payload_len = payload.len(),
         "subscriptions: stripe webhook request received"
@@ -313,6 +482,56 @@ where
     }
 }
 
+pub async fn replay_stripe_event(
+    State(state): State<InternalRouteState>,
+    headers: HeaderMap,
+    Path(event_id): Path<String>,
+) -> impl IntoResponse {
+    let expected_token = match state.config.internal.token.as_deref() {
+        Some(token) => token,
+        None => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "internal token is not configured",
+            )
+                .into_response();
+        }
+    };
+
+    if let Err(status) = authorize_bearer(&headers, expected_token) {
+        return (status, "unauthorized").into_response();
+    }
+
+    info!(
+        stripe_event_id = %event_id,
+        "subscriptions: stripe event replay requested"
+    );
+    match state.usecase.replay_stripe_event(&event_id).await {
+        Ok(()) => StatusCode::OK.into_response(),
+        Err(err) => map_error(err),
+    }
+}
+
+pub(crate) fn authorize_bearer(
+    headers: &HeaderMap,
+    expected_token: &str,
+) -> Result<(), StatusCode> {
+    let auth = headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let token = auth
+        .strip_prefix("Bearer ")
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    if bool::from(token.as_bytes().ct_eq(expected_token.as_bytes())) {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
 fn map_error(err: SubscriptionError) -> axum::response::Response {
     let status = err.status_code();
     let body = Json(ErrorResponse {
@@ -321,3 +540,43 @@ fn map_error(err: SubscriptionError) -> axum::response::Response {
     });
     (status, body).into_response()
 }
+
+fn bad_request(message: String) -> axum::response::Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse {
+            code: StatusCode::BAD_REQUEST.as_u16(),
+            message,
+        }),
+    )
+        .into_response()
+}
+
+fn parse_billing_history_query(
+    query: BillingHistoryQuery,
+) -> Result<(i64, Option<BillingHistoryCursor>), String> {
+    let limit = query.limit.unwrap_or(DEFAULT_BILLING_HISTORY_LIMIT);
+    if limit <= 0 {
+        return Err("limit must be a positive number".to_string());
+    }
+    if limit > MAX_BILLING_HISTORY_LIMIT {
+        return Err(format!("limit must be <= {}", MAX_BILLING_HISTORY_LIMIT));
+    }
+
+    let cursor = match (query.cursor_created_at, query.cursor_id) {
+        (None, None) => None,
+        (Some(_), None) | (None, Some(_)) => {
+            return Err("cursor_created_at and cursor_id must be provided together".to_string());
+        }
+        (Some(raw_created_at), Some(raw_id)) => {
+            let created_at = DateTime::parse_from_rfc3339(&raw_created_at)
+                .map_err(|_| "cursor_created_at must be RFC3339 timestamp".to_string())?
+                .with_timezone(&Utc);
+            let id = Uuid::parse_str(&raw_id)
+                .map_err(|_| "cursor_id must be a valid UUID".to_string())?;
+            Some(BillingHistoryCursor { created_at, id })
+        }
+    };
+
+    Ok((limit, cursor))
+}
@@ -1,7 +1,7 @@
 use crate::{
     axum_http::auth::AuthUser,
     config::config_model::DotEnvyConfig,
-    usecases::{plan_resolver::PlanResolver, watch_url::WatchUrlUseCase},
+    usecases::{plan_cache::PlanCache, plan_resolver::PlanResolver, watch_url::WatchUrlUseCase},
 };
 use axum::{
     Json, Router,
@@ -10,17 +10,12 @@ use axum::{
     response::IntoResponse,
     routing::get,
 };
-use crates::{
-    domain::repositories::{
-        live_following::LiveFollowingRepository, plans::PlanRepository,
-        recording_upload::RecordingUploadRepository, subscriptions::SubscriptionRepository,
-    },
-    infra::db::{
-        postgres::postgres_connection::PgPoolSquad,
-        repositories::{
-            live_following::LiveFollowingPostgres, plans::PlanPostgres,
-            recording_upload::RecordingUploadPostgres, subscriptions::SubscriptionPostgres,
-        },
+use crates::infra::db::{
+    postgres::postgres_connection::PgPoolSquad,
+    repositories::{
+        live_following::LiveFollowingPostgres, plans::PlanPostgres,
+        recording_upload::RecordingUploadPostgres, recording_view::RecordingViewPostgres,
+        subscriptions::SubscriptionPostgres,
     },
 };
 use serde::{Deserialize, Serialize};
@@ -38,9 +33,22 @@ pub struct WatchUrlResponse {
     pub url: String,
 }
 
-pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
+pub type WatchUrlUseCaseState = WatchUrlUseCase<
+    RecordingUploadPostgres,
+    LiveFollowingPostgres,
+    RecordingViewPostgres,
+    PlanPostgres,
+    SubscriptionPostgres,
+>;
+
+pub fn routes(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Router {
     let recording_repository = RecordingUploadPostgres::new(Arc::clone(&db_pool));
     let live_following_repository = LiveFollowingPostgres::new(Arc::clone(&db_pool));
+    let recording_view_repository = RecordingViewPostgres::new(Arc::clone(&db_pool));
     let plan_repository = PlanPostgres::new(Arc::clone(&db_pool));
     let subscription_repository = SubscriptionPostgres::new(Arc::clone(&db_pool));
 
@@ -48,12 +56,15 @@ pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
         Arc::new(plan_repository),
         Arc::new(subscription_repository),
         config.free_plan_id,
-    );
+    )
+    .with_cache(plan_cache);
 
     let usecase = WatchUrlUseCase::new(
         Arc::new(recording_repository),
         Arc::new(live_following_repository),
+        Arc::new(recording_view_repository),
         Arc::new(plan_resolver),
+        config.quota.enforcement,
         config.watch_url.clone(),
     );
 
@@ -62,17 +73,11 @@ pub fn routes(db_pool: Arc<PgPoolSquad>, config: Arc<DotEnvyConfig>) -> Router {
         .with_state(Arc::new(usecase))
 }
 
-pub async fn generate_watch_url<R, F, P, S>(
-    State(usecase): State<Arc<WatchUrlUseCase<R, F, P, S>>>,
+pub async fn generate_watch_url(
+    State(usecase): State<Arc<WatchUrlUseCaseState>>,
     AuthUser { user_id, .. }: AuthUser,
     Query(query): Query<WatchUrlQuery>,
-) -> impl IntoResponse
-where
-    R: RecordingUploadRepository + Send + Sync + 'static,
-    F: LiveFollowingRepository + Send + Sync + 'static,
-    P: PlanRepository + Send + Sync + 'static,
-    S: SubscriptionRepository + Send + Sync + 'static,
-{
+) -> impl IntoResponse {
     info!(
         %user_id,
         recording_id = %query.recording_id,
@@ -112,6 +117,7 @@ where
                 StatusCode::NOT_FOUND
             } else if message.contains("Follow is not active")
                 || message.contains("Recording exceeds retention window")
+                || message.contains("Recording exceeds storage quota")
             {
                 StatusCode::FORBIDDEN
             } else {
@@ -1,7 +1,14 @@
 use crate::config::stage::Stage;
 
-use super::config_model::{BackendServer, DotEnvyConfig, StripeConfig};
+use super::config_model::{
+    BackendServer, DotEnvyConfig, Internal, PlanCacheConfig, QuotaConfig, StripeConfig,
+    SubscriptionLifecycle,
+};
 use anyhow::Result;
+use crates::{
+    domain::value_objects::enums::quota_enforcement_policies::QuotaEnforcementPolicy,
+    payments::stripe_client::DEFAULT_STRIPE_API_BASE,
+};
 use uuid::Uuid;
 
 pub fn load() -> Result<DotEnvyConfig> {
@@ -38,6 +45,8 @@ pub fn load() -> Result<DotEnvyConfig> {
     };
 
     let stripe = StripeConfig {
+        api_base: std::env::var("STRIPE_API_BASE")
+            .unwrap_or_else(|_| DEFAULT_STRIPE_API_BASE.to_string()),
         secret_key: std::env::var("STRIPE_SECRET_KEY").expect("STRIPE_SECRET_KEY is invalid"),
         webhook_secret: std::env::var("STRIPE_WEBHOOK_SECRET")
             .expect("STRIPE_WEBHOOK_SECRET is invalid"),
@@ -46,6 +55,68 @@ pub fn load() -> Result<DotEnvyConfig> {
         }),
         cancel_url: std::env::var("STRIPE_CANCEL_URL")
             .unwrap_or_else(|_| "https://example.com/checkout/cancel".to_string()),
+        event_retry_interval_secs: std::env::var("STRIPE_EVENT_RETRY_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(300),
+        event_retry_max_attempts: std::env::var("STRIPE_EVENT_RETRY_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(5),
+        reconcile_interval_secs: std::env::var("STRIPE_RECONCILE_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(3600),
+        end_subscription_on_refund: std::env::var("STRIPE_END_SUBSCRIPTION_ON_REFUND")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(true),
+        end_subscription_on_lost_dispute: std::env::var("STRIPE_END_SUBSCRIPTION_ON_LOST_DISPUTE")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(true),
+    };
+
+    let subscription_lifecycle = SubscriptionLifecycle {
+        interval_secs: std::env::var("SUBSCRIPTION_LIFECYCLE_INTERVAL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(300),
+        past_due_grace_days: std::env::var("SUBSCRIPTION_PAST_DUE_GRACE_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v >= 0)
+            .unwrap_or(7),
+        renewal_reminder_days: std::env::var("SUBSCRIPTION_RENEWAL_REMINDER_DAYS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v >= 0)
+            .unwrap_or(3),
+    };
+
+    let plan_cache = PlanCacheConfig {
+        ttl_secs: std::env::var("PLAN_CACHE_TTL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(60),
+    };
+
+    let quota = QuotaConfig {
+        enforcement: std::env::var("QUOTA_ENFORCEMENT_POLICY")
+            .ok()
+            .and_then(|v| v.parse::<QuotaEnforcementPolicy>().ok())
+            .unwrap_or_default(),
+    };
+
+    let internal = Internal {
+        token: std::env::var("INTERNAL_API_TOKEN").ok().and_then(|v| {
+            let trimmed = v.trim().to_string();
+            (!trimmed.is_empty()).then_some(trimmed)
+        }),
     };
 
     let free_plan_id =
@@ -58,6 +129,10 @@ pub fn load() -> Result<DotEnvyConfig> {
         supabase,
         watch_url,
         stripe,
+        internal,
+        subscription_lifecycle,
+        plan_cache,
+        quota,
         free_plan_id,
     })
 }
@@ -1,3 +1,4 @@
+use crates::domain::value_objects::enums::quota_enforcement_policies::QuotaEnforcementPolicy;
 use uuid::Uuid;
 
 #[derive(Debug, Clone)]
@@ -7,6 +8,10 @@ pub struct DotEnvyConfig {
     pub supabase: Supabase,
     pub watch_url: WatchUrl,
     pub stripe: StripeConfig,
+    pub internal: Internal,
+    pub subscription_lifecycle: SubscriptionLifecycle,
+    pub plan_cache: PlanCacheConfig,
+    pub quota: QuotaConfig,
     pub free_plan_id: Uuid,
 }
 
@@ -36,8 +41,38 @@ pub struct WatchUrl {
 
 #[derive(Debug, Clone)]
 pub struct StripeConfig {
+    pub api_base: String,
     pub secret_key: String,
     pub webhook_secret: String,
     pub success_url: String,
     pub cancel_url: String,
+    pub event_retry_interval_secs: u64,
+    pub event_retry_max_attempts: i32,
+    pub reconcile_interval_secs: u64,
+    pub end_subscription_on_refund: bool,
+    pub end_subscription_on_lost_dispute: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubscriptionLifecycle {
+    pub interval_secs: u64,
+    pub past_due_grace_days: i64,
+    pub renewal_reminder_days: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanCacheConfig {
+    /// How long resolved plans are cached; 0 disables the cache.
+    pub ttl_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuotaConfig {
+    /// What happens once a user passes their storage or monthly recording-hour quota.
+    pub enforcement: QuotaEnforcementPolicy,
+}
+
+#[derive(Debug, Clone)]
+pub struct Internal {
+    pub token: Option<String>,
 }
@@ -1,3 +1,6 @@
 pub mod axum_http;
 pub mod config;
+pub mod stripe_event_retry;
+pub mod subscription_lifecycle;
+pub mod subscription_reconciliation;
 pub mod usecases;
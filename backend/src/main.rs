@@ -1,8 +1,10 @@
 use anyhow::Result;
-use backend::axum_http::http_serve;
+use backend::axum_http::{http_serve, routers};
 use backend::config::config_loader;
+use backend::usecases::plan_cache::PlanCache;
+use backend::{stripe_event_retry, subscription_lifecycle, subscription_reconciliation};
 use crates::infra::db::postgres::postgres_connection;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tracing::{error, info};
 
 #[tokio::main]
@@ -23,7 +25,38 @@ async fn run() -> Result<()> {
     let postgres_pool = postgres_connection::establish_connection(&dotenvy_env.database.url)?;
     info!("Postgres connection has been established");
 
-    http_serve::start(Arc::new(dotenvy_env), Arc::new(postgres_pool)).await?;
+    let config = Arc::new(dotenvy_env);
+    let db_pool = Arc::new(postgres_pool);
+    // Shared by every plan resolver so subscription changes invalidate all of them.
+    let plan_cache = Arc::new(PlanCache::new(Duration::from_secs(
+        config.plan_cache.ttl_secs,
+    )));
+
+    let subscription_usecase = routers::subscriptions::build_usecase(
+        Arc::clone(&db_pool),
+        Arc::clone(&config),
+        Arc::clone(&plan_cache),
+    );
+    tokio::spawn(stripe_event_retry::worker::run(
+        Arc::clone(&subscription_usecase),
+        config.stripe.clone(),
+    ));
+    tokio::spawn(subscription_reconciliation::worker::run(
+        subscription_usecase,
+        config.stripe.clone(),
+    ));
+
+    let lifecycle_usecase = subscription_lifecycle::worker::build_usecase(
+        Arc::clone(&db_pool),
+        Arc::clone(&config),
+        Arc::clone(&plan_cache),
+    );
+    tokio::spawn(subscription_lifecycle::worker::run(
+        lifecycle_usecase,
+        config.subscription_lifecycle.clone(),
+    ));
+
+    http_serve::start(config, db_pool, plan_cache).await?;
 
     Ok(())
 }
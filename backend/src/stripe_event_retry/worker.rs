@@ -0,0 +1,40 @@
+use anyhow::Result;
+use chrono::Duration as ChronoDuration;
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info};
+
+use crate::{
+    axum_http::routers::subscriptions::SubscriptionUseCaseState, config::config_model::StripeConfig,
+};
+
+const RETRY_BATCH_SIZE: i64 = 50;
+// Leave fresh events alone so Stripe's own redelivery and the live request get the first shot.
+const MIN_EVENT_AGE_SECS: i64 = 60;
+
+pub async fn run(usecase: Arc<SubscriptionUseCaseState>, config: StripeConfig) -> Result<()> {
+    info!(
+        interval_secs = config.event_retry_interval_secs,
+        max_attempts = config.event_retry_max_attempts,
+        "stripe_event_retry: starting worker loop"
+    );
+    loop {
+        tokio::time::sleep(Duration::from_secs(config.event_retry_interval_secs)).await;
+
+        match usecase
+            .retry_failed_stripe_events(
+                config.event_retry_max_attempts,
+                ChronoDuration::seconds(MIN_EVENT_AGE_SECS),
+                RETRY_BATCH_SIZE,
+            )
+            .await
+        {
+            Ok(0) => {}
+            Ok(succeeded) => {
+                info!(succeeded, "stripe_event_retry: retried stripe events");
+            }
+            Err(err) => {
+                error!(error = %err, "stripe_event_retry: failed to retry stripe events");
+            }
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, Utc};
+use crates::infra::db::{
+    postgres::postgres_connection::PgPoolSquad,
+    repositories::{
+        job::JobPostgres, live_following::LiveFollowingPostgres, plans::PlanPostgres,
+        subscriptions::SubscriptionPostgres,
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::config_model::{DotEnvyConfig, SubscriptionLifecycle},
+    usecases::{
+        plan_cache::PlanCache, plan_resolver::PlanResolver,
+        subscription_lifecycle::SubscriptionLifecycleUseCase,
+    },
+};
+
+pub type SubscriptionLifecycleUseCaseState = SubscriptionLifecycleUseCase<
+    LiveFollowingPostgres,
+    PlanPostgres,
+    SubscriptionPostgres,
+    JobPostgres,
+>;
+
+pub fn build_usecase(
+    db_pool: Arc<PgPoolSquad>,
+    config: Arc<DotEnvyConfig>,
+    plan_cache: Arc<PlanCache>,
+) -> Arc<SubscriptionLifecycleUseCaseState> {
+    let subscription_repo = Arc::new(SubscriptionPostgres::new(Arc::clone(&db_pool)));
+    let plan_resolver = Arc::new(
+        PlanResolver::new(
+            Arc::new(PlanPostgres::new(Arc::clone(&db_pool))),
+            Arc::clone(&subscription_repo),
+            config.free_plan_id,
+        )
+        .with_cache(plan_cache),
+    );
+
+    Arc::new(SubscriptionLifecycleUseCase::new(
+        Arc::new(LiveFollowingPostgres::new(Arc::clone(&db_pool))),
+        subscription_repo,
+        plan_resolver,
+        Arc::new(JobPostgres::new(Arc::clone(&db_pool))),
+        ChronoDuration::days(config.subscription_lifecycle.past_due_grace_days),
+        ChronoDuration::days(config.subscription_lifecycle.renewal_reminder_days),
+    ))
+}
+
+pub async fn run(
+    usecase: Arc<SubscriptionLifecycleUseCaseState>,
+    config: SubscriptionLifecycle,
+) -> Result<()> {
+    info!(
+        interval_secs = config.interval_secs,
+        past_due_grace_days = config.past_due_grace_days,
+        renewal_reminder_days = config.renewal_reminder_days,
+        "subscription_lifecycle: starting worker loop"
+    );
+    loop {
+        match usecase.run(Utc::now()).await {
+            Ok(report) if !report.failed_user_ids.is_empty() => {
+                warn!(
+                    expired = report.expired,
+                    canceled = report.canceled,
+                    follows_deactivated = report.follows_deactivated,
+                    follows_restored = report.follows_restored,
+                    notifications_enqueued = report.notifications_enqueued,
+                    failed_user_ids = ?report.failed_user_ids,
+                    "subscription_lifecycle: run finished with failures"
+                );
+            }
+            Ok(report) => {
+                info!(
+                    expired = report.expired,
+                    canceled = report.canceled,
+                    follows_deactivated = report.follows_deactivated,
+                    follows_restored = report.follows_restored,
+                    notifications_enqueued = report.notifications_enqueued,
+                    "subscription_lifecycle: run finished"
+                );
+            }
+            Err(err) => {
+                error!(error = %err, "subscription_lifecycle: run failed");
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(config.interval_secs)).await;
+    }
+}
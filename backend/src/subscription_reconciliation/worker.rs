@@ -0,0 +1,53 @@
+use anyhow::Result;
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info, warn};
+
+use crate::{
+    axum_http::routers::subscriptions::SubscriptionUseCaseState, config::config_model::StripeConfig,
+};
+
+pub async fn run(usecase: Arc<SubscriptionUseCaseState>, config: StripeConfig) -> Result<()> {
+    info!(
+        interval_secs = config.reconcile_interval_secs,
+        "subscription_reconciliation: starting worker loop"
+    );
+    loop {
+        tokio::time::sleep(Duration::from_secs(config.reconcile_interval_secs)).await;
+
+        match usecase.reconcile_recurring_subscriptions().await {
+            Ok(report) if report.failed > 0 => {
+                error!(
+                    scanned = report.scanned,
+                    corrected = report.corrected,
+                    unchanged = report.unchanged,
+                    skipped = report.skipped,
+                    failed = report.failed,
+                    corrected_ids = ?report.corrected_ids,
+                    failed_ids = ?report.failed_ids,
+                    "subscription_reconciliation: run finished with failures"
+                );
+            }
+            Ok(report) if report.corrected > 0 => {
+                warn!(
+                    scanned = report.scanned,
+                    corrected = report.corrected,
+                    unchanged = report.unchanged,
+                    skipped = report.skipped,
+                    corrected_ids = ?report.corrected_ids,
+                    "subscription_reconciliation: run finished with corrections"
+                );
+            }
+            Ok(report) => {
+                info!(
+                    scanned = report.scanned,
+                    unchanged = report.unchanged,
+                    skipped = report.skipped,
+                    "subscription_reconciliation: run finished; no drift found"
+                );
+            }
+            Err(err) => {
+                error!(error = %err, "subscription_reconciliation: run failed");
+            }
+        }
+    }
+}
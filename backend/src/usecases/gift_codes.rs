@@ -0,0 +1,513 @@
+use chrono::{DateTime, Utc};
+use crates::domain::{
+    entities::{gift_codes::GiftCodeEntity, subscriptions::SubscriptionEntity},
+    repositories::{
+        gift_codes::GiftCodeRepository, live_following::LiveFollowingRepository,
+        plans::PlanRepository, subscriptions::SubscriptionRepository,
+    },
+    value_objects::{
+        enums::{
+            billing_modes::BillingMode, gift_code_statuses::GiftCodeStatus,
+            subscription_statuses::SubscriptionStatus,
+        },
+        gift_codes::{
+            GIFT_CODE_ALPHABET, GIFT_CODE_GROUP_LEN, GIFT_CODE_GROUPS, GiftCodeDto,
+            GiftRedemptionDto, normalize_gift_code,
+        },
+    },
+};
+use rand::Rng;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::usecases::{one_time_terms::plan_one_time_term, plan_resolver::PlanResolver};
+
+#[derive(Debug, Error)]
+pub enum GiftCodeError {
+    #[error("invalid gift code")]
+    InvalidCode,
+    #[error("gift code not found")]
+    NotFound,
+    #[error("gift code payment has not cleared yet")]
+    NotPaid,
+    #[error("gift code was already redeemed")]
+    AlreadyRedeemed,
+    #[error("gift code expired at {0}")]
+    Expired(DateTime<Utc>),
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl GiftCodeError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+        match self {
+            GiftCodeError::InvalidCode => StatusCode::BAD_REQUEST,
+            GiftCodeError::NotFound => StatusCode::NOT_FOUND,
+            GiftCodeError::NotPaid | GiftCodeError::AlreadyRedeemed => StatusCode::CONFLICT,
+            GiftCodeError::Expired(_) => StatusCode::GONE,
+            GiftCodeError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+pub type UseCaseResult<T> = std::result::Result<T, GiftCodeError>;
+
+/// Random code in the canonical `XXXX-XXXX-XXXX-XXXX` form accepted by `normalize_gift_code`.
+pub fn generate_gift_code() -> String {
+    let mut rng = rand::rng();
+    let groups: Vec<String> = (0..GIFT_CODE_GROUPS)
+        .map(|_| {
+            (0..GIFT_CODE_GROUP_LEN)
+                .map(|_| GIFT_CODE_ALPHABET[rng.random_range(0..GIFT_CODE_ALPHABET.len())] as char)
+                .collect()
+        })
+        .collect();
+    groups.join("-")
+}
+
+/// Redemption of gift codes issued by `SubscriptionUseCase` for paid gift checkouts. The
+/// redeemed term is a regular one-time subscription, stacked after whatever the recipient
+/// already has scheduled.
+pub struct GiftCodeUseCase<G, L, P, S>
+where
+    G: GiftCodeRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    gift_repo: Arc<G>,
+    live_following_repository: Arc<L>,
+    subscription_repo: Arc<S>,
+    plan_resolver: Arc<PlanResolver<P, S>>,
+}
+
+impl<G, L, P, S> GiftCodeUseCase<G, L, P, S>
+where
+    G: GiftCodeRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    pub fn new(
+        gift_repo: Arc<G>,
+        live_following_repository: Arc<L>,
+        subscription_repo: Arc<S>,
+        plan_resolver: Arc<PlanResolver<P, S>>,
+    ) -> Self {
+        Self {
+            gift_repo,
+            live_following_repository,
+            subscription_repo,
+            plan_resolver,
+        }
+    }
+
+    pub async fn redeem(
+        &self,
+        user_id: Uuid,
+        code: &str,
+        now: DateTime<Utc>,
+    ) -> UseCaseResult<GiftRedemptionDto> {
+        let code = normalize_gift_code(code).ok_or(GiftCodeError::InvalidCode)?;
+
+        let gift = self
+            .gift_repo
+            .find_by_code(&code)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "gift_codes: failed to load gift code"
+                );
+                GiftCodeError::Internal(err)
+            })?
+            .ok_or(GiftCodeError::NotFound)?;
+
+        let claimed = self
+            .gift_repo
+            .claim_gift_code(gift.id, user_id, now)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    gift_code_id = %gift.id,
+                    db_error = ?err,
+                    "gift_codes: failed to claim gift code"
+                );
+                GiftCodeError::Internal(err)
+            })?;
+
+        let Some(gift) = claimed else {
+            let err = Self::claim_rejection(&gift, now);
+            warn!(
+                %user_id,
+                gift_code_id = %gift.id,
+                gift_status = %gift.status,
+                status = err.status_code().as_u16(),
+                "gift_codes: gift code cannot be redeemed"
+            );
+            return Err(err);
+        };
+
+        let subscription = self
+            .subscription_for_claimed_gift(&gift, user_id, now)
+            .await?;
+
+        self.gift_repo
+            .attach_subscription(gift.id, subscription.id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    gift_code_id = %gift.id,
+                    subscription_id = %subscription.id,
+                    db_error = ?err,
+                    "gift_codes: failed to link subscription to gift code"
+                );
+                GiftCodeError::Internal(err)
+            })?;
+
+        info!(
+            %user_id,
+            gift_code_id = %gift.id,
+            purchaser_id = %gift.purchaser_id,
+            subscription_id = %subscription.id,
+            starts_at = %subscription.starts_at,
+            ends_at = %subscription.ends_at,
+            "gift_codes: gift code redeemed"
+        );
+        self.plan_resolver.invalidate_user(user_id);
+
+        // Follows paused by an earlier downgrade come back once the gifted term is running.
+        if subscription.starts_at <= now
+            && let Err(err) = self.restore_follows(user_id).await
+        {
+            error!(
+                %user_id,
+                error = ?err,
+                "gift_codes: failed to restore follows after redemption"
+            );
+        }
+
+        Ok(GiftRedemptionDto::from((&gift, &subscription)))
+    }
+
+    pub async fn list_purchased(&self, purchaser_id: Uuid) -> UseCaseResult<Vec<GiftCodeDto>> {
+        let gifts = self
+            .gift_repo
+            .list_by_purchaser(purchaser_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %purchaser_id,
+                    db_error = ?err,
+                    "gift_codes: failed to list purchased gift codes"
+                );
+                GiftCodeError::Internal(err)
+            })?;
+
+        Ok(gifts.into_iter().map(GiftCodeDto::from).collect())
+    }
+
+    fn claim_rejection(gift: &GiftCodeEntity, now: DateTime<Utc>) -> GiftCodeError {
+        if gift.status == GiftCodeStatus::Pending.as_str() {
+            GiftCodeError::NotPaid
+        } else if gift.status == GiftCodeStatus::Redeemed.as_str() {
+            GiftCodeError::AlreadyRedeemed
+        } else if gift.status == GiftCodeStatus::Active.as_str() && gift.expires_at <= now {
+            GiftCodeError::Expired(gift.expires_at)
+        } else {
+            // Voided codes are indistinguishable from unknown ones to the redeemer.
+            GiftCodeError::NotFound
+        }
+    }
+
+    /// Creates the recipient's subscription, or returns the one created by an earlier attempt
+    /// that failed before it was linked to the code.
+    async fn subscription_for_claimed_gift(
+        &self,
+        gift: &GiftCodeEntity,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> UseCaseResult<SubscriptionEntity> {
+        let provider_reference = format!("gift_{}", gift.id);
+        let existing = self
+            .subscription_repo
+            .find_by_provider_subscription_id(&provider_reference)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    gift_code_id = %gift.id,
+                    db_error = ?err,
+                    "gift_codes: failed to look up subscription for gift code"
+                );
+                GiftCodeError::Internal(err)
+            })?;
+        if let Some(existing) = existing {
+            return Ok(existing);
+        }
+
+        let scheduled = self
+            .subscription_repo
+            .list_subscriptions_ending_after(user_id, now)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "gift_codes: failed to load scheduled subscriptions"
+                );
+                GiftCodeError::Internal(err)
+            })?;
+        let term = plan_one_time_term(
+            &scheduled,
+            gift.plan_id,
+            gift.term_months.unsigned_abs(),
+            now,
+        )?;
+
+        let subscription_id = self
+            .subscription_repo
+            .create_or_update_subscription_after_checkout(
+                user_id,
+                gift.plan_id,
+                BillingMode::OneTime,
+                term.starts_at,
+                term.ends_at,
+                SubscriptionStatus::Active,
+                Some(provider_reference.clone()),
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    gift_code_id = %gift.id,
+                    db_error = ?err,
+                    "gift_codes: failed to create subscription for gift code"
+                );
+                GiftCodeError::Internal(err)
+            })?;
+
+        Ok(SubscriptionEntity {
+            id: subscription_id,
+            user_id,
+            plan_id: gift.plan_id,
+            starts_at: term.starts_at,
+            ends_at: term.ends_at,
+            billing_mode: BillingMode::OneTime.to_string(),
+            default_payment_method_id: None,
+            cancel_at_period_end: false,
+            canceled_at: None,
+            provider_subscription_id: Some(provider_reference),
+            status: SubscriptionStatus::Active.to_string(),
+            created_at: now,
+            ended_reason: None,
+        })
+    }
+
+    async fn restore_follows(&self, user_id: Uuid) -> anyhow::Result<()> {
+        let plan = self
+            .plan_resolver
+            .resolve_effective_plan_for_user(user_id)
+            .await?;
+        self.live_following_repository
+            .restore_temporary_inactive_follows(user_id, plan.features.max_follows_or_default())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use crates::domain::repositories::{
+        gift_codes::MockGiftCodeRepository, live_following::MockLiveFollowingRepository,
+        plans::MockPlanRepository, subscriptions::MockSubscriptionRepository,
+    };
+    use crates::domain::value_objects::plans::FREE_PLAN_ID;
+    use mockall::predicate::eq;
+
+    fn gift(status: GiftCodeStatus, expires_at: DateTime<Utc>) -> GiftCodeEntity {
+        GiftCodeEntity {
+            id: Uuid::new_v4(),
+            code: "ABCD-EFGH-JKMN-PQRS".to_string(),
+            plan_id: Uuid::new_v4(),
+            purchaser_id: Uuid::new_v4(),
+            term_months: 3,
+            status: status.to_string(),
+            provider_payment_ref: "pi_gift".to_string(),
+            invoice_id: Uuid::new_v4(),
+            expires_at,
+            redeemed_by: None,
+            redeemed_at: None,
+            subscription_id: None,
+            void_reason: None,
+            created_at: expires_at - Duration::days(365),
+        }
+    }
+
+    fn build_usecase(
+        gift_repo: MockGiftCodeRepository,
+        subscription_repo: MockSubscriptionRepository,
+    ) -> GiftCodeUseCase<
+        MockGiftCodeRepository,
+        MockLiveFollowingRepository,
+        MockPlanRepository,
+        MockSubscriptionRepository,
+    > {
+        let subscription_repo = Arc::new(subscription_repo);
+        let plan_resolver = Arc::new(PlanResolver::new(
+            Arc::new(MockPlanRepository::new()),
+            Arc::clone(&subscription_repo),
+            FREE_PLAN_ID,
+        ));
+        GiftCodeUseCase::new(
+            Arc::new(gift_repo),
+            Arc::new(MockLiveFollowingRepository::new()),
+            subscription_repo,
+            plan_resolver,
+        )
+    }
+
+    #[test]
+    fn generated_codes_survive_normalization() {
+        let code = generate_gift_code();
+        assert_eq!(normalize_gift_code(&code).as_deref(), Some(code.as_str()));
+        assert_eq!(
+            normalize_gift_code(&code.replace('-', "").to_lowercase()).as_deref(),
+            Some(code.as_str())
+        );
+        assert_eq!(normalize_gift_code("ABCD-EFGH-JKMN-PQR0"), None);
+    }
+
+    #[tokio::test]
+    async fn redeem_queues_gifted_term_after_scheduled_subscription() {
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+        let active = gift(GiftCodeStatus::Active, now + Duration::days(30));
+        let gift_id = active.id;
+        let plan_id = active.plan_id;
+        let scheduled_end = now + Duration::days(10);
+
+        let mut gift_repo = MockGiftCodeRepository::new();
+        let found = active.clone();
+        gift_repo
+            .expect_find_by_code()
+            .with(eq("ABCD-EFGH-JKMN-PQRS"))
+            .returning(move |_| {
+                let found = found.clone();
+                Box::pin(async move { Ok(Some(found)) })
+            });
+        gift_repo
+            .expect_claim_gift_code()
+            .with(eq(gift_id), eq(user_id), eq(now))
+            .times(1)
+            .returning(move |_, user_id, now| {
+                let mut claimed = active.clone();
+                claimed.status = GiftCodeStatus::Redeemed.to_string();
+                claimed.redeemed_by = Some(user_id);
+                claimed.redeemed_at = Some(now);
+                Box::pin(async move { Ok(Some(claimed)) })
+            });
+        gift_repo
+            .expect_attach_subscription()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        subscription_repo
+            .expect_find_by_provider_subscription_id()
+            .with(eq(format!("gift_{gift_id}")))
+            .returning(|_| Box::pin(async { Ok(None) }));
+        subscription_repo
+            .expect_list_subscriptions_ending_after()
+            .returning(move |user_id, _| {
+                let queued = SubscriptionEntity {
+                    id: Uuid::new_v4(),
+                    user_id,
+                    plan_id: Uuid::new_v4(),
+                    starts_at: now - Duration::days(20),
+                    ends_at: scheduled_end,
+                    billing_mode: BillingMode::Recurring.to_string(),
+                    default_payment_method_id: None,
+                    cancel_at_period_end: false,
+                    canceled_at: None,
+                    provider_subscription_id: Some("sub_123".to_string()),
+                    status: SubscriptionStatus::Active.to_string(),
+                    created_at: now - Duration::days(20),
+                    ended_reason: None,
+                };
+                Box::pin(async move { Ok(vec![queued]) })
+            });
+        let expected_end = scheduled_end
+            .checked_add_months(chrono::Months::new(3))
+            .unwrap();
+        let expected_ref = Some(format!("gift_{gift_id}"));
+        subscription_repo
+            .expect_create_or_update_subscription_after_checkout()
+            .withf(move |uid, pid, mode, start, end, status, reference| {
+                *uid == user_id
+                    && *pid == plan_id
+                    && *mode == BillingMode::OneTime
+                    && *start == scheduled_end
+                    && *end == expected_end
+                    && *status == SubscriptionStatus::Active
+                    && *reference == expected_ref
+            })
+            .times(1)
+            .returning(|_, _, _, _, _, _, _| Box::pin(async { Ok(Uuid::new_v4()) }));
+
+        let usecase = build_usecase(gift_repo, subscription_repo);
+        let redemption = usecase
+            .redeem(user_id, " abcd efgh-jkmn-pqrs ", now)
+            .await
+            .unwrap();
+
+        assert_eq!(redemption.gift_code_id, gift_id);
+        assert_eq!(redemption.starts_at, scheduled_end);
+        assert_eq!(redemption.ends_at, expected_end);
+    }
+
+    #[tokio::test]
+    async fn redeem_rejects_expired_and_used_codes() {
+        let now = Utc::now();
+        for (status, expires_at, expected) in [
+            (
+                GiftCodeStatus::Active,
+                now - Duration::seconds(1),
+                axum::http::StatusCode::GONE,
+            ),
+            (
+                GiftCodeStatus::Redeemed,
+                now + Duration::days(30),
+                axum::http::StatusCode::CONFLICT,
+            ),
+        ] {
+            let stored = gift(status, expires_at);
+            let mut gift_repo = MockGiftCodeRepository::new();
+            gift_repo.expect_find_by_code().returning(move |_| {
+                let stored = stored.clone();
+                Box::pin(async move { Ok(Some(stored)) })
+            });
+            gift_repo
+                .expect_claim_gift_code()
+                .returning(|_, _, _| Box::pin(async { Ok(None) }));
+            let mut subscription_repo = MockSubscriptionRepository::new();
+            subscription_repo
+                .expect_create_or_update_subscription_after_checkout()
+                .never();
+
+            let err = build_usecase(gift_repo, subscription_repo)
+                .redeem(Uuid::new_v4(), "ABCD-EFGH-JKMN-PQRS", now)
+                .await
+                .unwrap_err();
+            assert_eq!(err.status_code(), expected);
+        }
+    }
+}
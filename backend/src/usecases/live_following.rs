@@ -348,7 +348,7 @@ where
                 err
             })?;
 
-        let max_follows = features.max_follows.unwrap_or(0);
+        let max_follows = features.max_follows_or_default();
 
         if max_follows <= 0 || current >= max_follows {
             warn!(
@@ -405,9 +405,7 @@ mod tests {
                 ..PlanFeatures::default()
             },
             is_active: true,
-            stripe_price_recurring: None,
-            stripe_price_one_time_card: None,
-            stripe_price_one_time_promptpay: None,
+            prices: Vec::new(),
         }
     }
 
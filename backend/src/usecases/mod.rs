@@ -1,5 +1,11 @@
+pub mod gift_codes;
 pub mod live_following;
+pub mod one_time_terms;
+pub mod plan_cache;
+pub mod plan_features;
 pub mod plan_resolver;
 pub mod recordings;
+pub mod subscription_grants;
+pub mod subscription_lifecycle;
 pub mod subscriptions;
 pub mod watch_url;
@@ -0,0 +1,170 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Months, Utc};
+use crates::domain::{
+    entities::subscriptions::SubscriptionEntity,
+    value_objects::enums::subscription_statuses::SubscriptionStatus,
+};
+use uuid::Uuid;
+
+/// How a newly bought one-time term relates to what the user already has scheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermStacking {
+    /// Nothing runs past `now`; the term starts immediately.
+    Immediate,
+    /// The last scheduled period is on the same plan; the term continues it back-to-back.
+    Extend,
+    /// The last scheduled period is on another plan; the term waits until it ends.
+    Queue,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OneTimeTerm {
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub stacking: TermStacking,
+}
+
+/// Places `months` of `plan_id` after every period in `scheduled` that runs past `now`.
+/// Canceled and expired periods are ignored: a refunded term that had not started keeps its
+/// dates but is never served, so nothing should wait behind it.
+///
+/// Both extending and queueing start at the latest scheduled `ends_at`: each purchase keeps its
+/// own subscription row and payment, so an existing period is never rewritten and periods never
+/// overlap. Months are calendar months, clamped to the end of shorter months.
+pub fn plan_one_time_term(
+    scheduled: &[SubscriptionEntity],
+    plan_id: Uuid,
+    months: u32,
+    now: DateTime<Utc>,
+) -> Result<OneTimeTerm> {
+    let last = scheduled
+        .iter()
+        .filter(|subscription| subscription.ends_at > now)
+        .filter(|subscription| {
+            !matches!(
+                SubscriptionStatus::from_str(&subscription.status),
+                SubscriptionStatus::Canceled | SubscriptionStatus::Expired
+            )
+        })
+        .max_by_key(|subscription| subscription.ends_at);
+
+    let (starts_at, stacking) = match last {
+        None => (now, TermStacking::Immediate),
+        Some(last) if last.plan_id == plan_id => (last.ends_at, TermStacking::Extend),
+        Some(last) => (last.ends_at, TermStacking::Queue),
+    };
+
+    let ends_at = starts_at
+        .checked_add_months(Months::new(months))
+        .context("failed to compute one-time term end date")?;
+
+    Ok(OneTimeTerm {
+        starts_at,
+        ends_at,
+        stacking,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+    use crates::domain::value_objects::enums::billing_modes::BillingMode;
+
+    fn scheduled(
+        plan_id: Uuid,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> SubscriptionEntity {
+        SubscriptionEntity {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            plan_id,
+            starts_at,
+            ends_at,
+            billing_mode: BillingMode::OneTime.to_string(),
+            default_payment_method_id: None,
+            cancel_at_period_end: false,
+            canceled_at: None,
+            provider_subscription_id: Some("pi_123".to_string()),
+            status: SubscriptionStatus::Active.to_string(),
+            created_at: starts_at,
+            ended_reason: None,
+        }
+    }
+
+    fn at(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn starts_now_when_the_last_period_ends_exactly_now() {
+        let plan_id = Uuid::new_v4();
+        let now = at(2026, 3, 10);
+        let current = scheduled(plan_id, now - Duration::days(30), now);
+
+        let term = plan_one_time_term(&[current], plan_id, 1, now).unwrap();
+
+        assert_eq!(term.stacking, TermStacking::Immediate);
+        assert_eq!(term.starts_at, now);
+        assert_eq!(term.ends_at, at(2026, 4, 10));
+    }
+
+    #[test]
+    fn extends_same_plan_from_its_end_even_one_second_ahead() {
+        let plan_id = Uuid::new_v4();
+        let now = at(2026, 3, 10);
+        let ends_at = now + Duration::seconds(1);
+        let current = scheduled(plan_id, now - Duration::days(30), ends_at);
+
+        let term = plan_one_time_term(&[current], plan_id, 3 * 2, now).unwrap();
+
+        assert_eq!(term.stacking, TermStacking::Extend);
+        assert_eq!(term.starts_at, ends_at);
+        assert_eq!(term.ends_at, at(2026, 9, 10) + Duration::seconds(1));
+    }
+
+    #[test]
+    fn queues_a_different_plan_after_the_latest_scheduled_period() {
+        let plan_id = Uuid::new_v4();
+        let other_plan_id = Uuid::new_v4();
+        let now = at(2026, 3, 10);
+        let current = scheduled(plan_id, now - Duration::days(10), at(2026, 4, 1));
+        let queued = scheduled(other_plan_id, at(2026, 4, 1), at(2026, 5, 1));
+
+        let term = plan_one_time_term(&[queued, current], plan_id, 12, now).unwrap();
+
+        assert_eq!(term.stacking, TermStacking::Queue);
+        assert_eq!(term.starts_at, at(2026, 5, 1));
+        assert_eq!(term.ends_at, at(2027, 5, 1));
+    }
+
+    #[test]
+    fn ignores_a_canceled_term_that_never_started() {
+        let plan_id = Uuid::new_v4();
+        let other_plan_id = Uuid::new_v4();
+        let now = at(2026, 3, 10);
+        let current = scheduled(plan_id, now - Duration::days(10), at(2026, 4, 1));
+        let mut refunded = scheduled(other_plan_id, at(2026, 4, 1), at(2026, 5, 1));
+        refunded.status = SubscriptionStatus::Canceled.to_string();
+
+        let term = plan_one_time_term(&[refunded, current], plan_id, 1, now).unwrap();
+
+        assert_eq!(term.stacking, TermStacking::Extend);
+        assert_eq!(term.starts_at, at(2026, 4, 1));
+        assert_eq!(term.ends_at, at(2026, 5, 1));
+    }
+
+    #[test]
+    fn clamps_month_end_dates() {
+        let plan_id = Uuid::new_v4();
+        let now = at(2028, 1, 31);
+
+        let one_month = plan_one_time_term(&[], plan_id, 1, now).unwrap();
+        assert_eq!(one_month.ends_at, at(2028, 2, 29));
+
+        let leap_day = at(2028, 2, 29);
+        let one_year = plan_one_time_term(&[], plan_id, 12, leap_day).unwrap();
+        assert_eq!(one_year.ends_at, at(2029, 2, 28));
+    }
+}
@@ -0,0 +1,180 @@
+use crates::domain::entities::plans::PlanEntity;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+use uuid::Uuid;
+
+/// Entries kept per map before expired ones are swept on insert.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+/// In-process TTL cache of `plans` rows and of each user's resolved effective plan, shared by
+/// every `PlanResolver` in the backend process. Anything that changes a user's subscriptions
+/// must call [`PlanCache::invalidate_user`].
+pub struct PlanCache {
+    ttl: Duration,
+    plans: Mutex<HashMap<Uuid, CachedPlan>>,
+    effective_plans: Mutex<HashMap<Uuid, CachedPlan>>,
+    /// Bumped on every invalidation so a lookup that raced one does not store its result.
+    generation: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+struct CachedPlan {
+    plan: PlanEntity,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanCacheStats {
+    pub ttl_secs: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    /// Share of lookups served from the cache, 0.0 before the first lookup.
+    pub hit_rate: f64,
+    pub cached_plans: usize,
+    pub cached_users: usize,
+}
+
+impl PlanCache {
+    /// A zero `ttl` disables caching: every lookup misses and nothing is stored.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            plans: Mutex::new(HashMap::new()),
+            effective_plans: Mutex::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            invalidations: AtomicU64::new(0),
+        }
+    }
+
+    pub fn disabled() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// Token to pass to the `put_*` methods; take it before reading from the database.
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub fn plan(&self, plan_id: Uuid) -> Option<PlanEntity> {
+        self.lookup(&self.plans, plan_id)
+    }
+
+    pub fn put_plan(&self, plan: PlanEntity, generation: u64) {
+        self.store(&self.plans, plan.id, plan, generation);
+    }
+
+    pub fn effective_plan(&self, user_id: Uuid) -> Option<PlanEntity> {
+        self.lookup(&self.effective_plans, user_id)
+    }
+
+    pub fn put_effective_plan(&self, user_id: Uuid, plan: PlanEntity, generation: u64) {
+        self.store(&self.effective_plans, user_id, plan, generation);
+    }
+
+    /// Drops the user's resolved plan so the next lookup reads their subscriptions again.
+    pub fn invalidate_user(&self, user_id: Uuid) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.invalidations.fetch_add(1, Ordering::Relaxed);
+        self.effective_plans
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(&user_id);
+    }
+
+    pub fn stats(&self) -> PlanCacheStats {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+
+        PlanCacheStats {
+            ttl_secs: self.ttl.as_secs(),
+            hits,
+            misses,
+            invalidations: self.invalidations.load(Ordering::Relaxed),
+            hit_rate: if lookups == 0 {
+                0.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+            cached_plans: Self::len(&self.plans),
+            cached_users: Self::len(&self.effective_plans),
+        }
+    }
+
+    fn lookup(&self, map: &Mutex<HashMap<Uuid, CachedPlan>>, key: Uuid) -> Option<PlanEntity> {
+        if !self.is_enabled() {
+            return None;
+        }
+
+        let now = Instant::now();
+        let found = map
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&key)
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| entry.plan.clone());
+
+        let counter = if found.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    fn store(
+        &self,
+        map: &Mutex<HashMap<Uuid, CachedPlan>>,
+        key: Uuid,
+        plan: PlanEntity,
+        generation: u64,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = map.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Checked under the lock: an invalidation after this point removes the new entry.
+        if self.generation() != generation {
+            return;
+        }
+        if entries.len() >= SWEEP_THRESHOLD {
+            entries.retain(|_, entry| entry.expires_at > now);
+        }
+        entries.insert(
+            key,
+            CachedPlan {
+                plan,
+                expires_at: now + self.ttl,
+            },
+        );
+    }
+
+    fn len(map: &Mutex<HashMap<Uuid, CachedPlan>>) -> usize {
+        map.lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+}
@@ -0,0 +1,369 @@
+use chrono::Utc;
+use crates::domain::{
+    repositories::{
+        live_following::LiveFollowingRepository, plans::PlanRepository,
+        recording_view::RecordingViewRepository, subscriptions::SubscriptionRepository,
+    },
+    value_objects::{
+        enums::quota_enforcement_policies::QuotaEnforcementPolicy,
+        plans::{EffectivePlanFeaturesDto, PlanComparisonDto, PlanFeatureKey, PlanFeatureUsageDto},
+        recordings::usage_month_start,
+    },
+};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::usecases::plan_resolver::PlanResolver;
+
+#[derive(Debug, Error)]
+pub enum PlanFeaturesError {
+    #[error("plan not found")]
+    PlanNotFound,
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl PlanFeaturesError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+        match self {
+            PlanFeaturesError::PlanNotFound => StatusCode::NOT_FOUND,
+            PlanFeaturesError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+pub type UseCaseResult<T> = std::result::Result<T, PlanFeaturesError>;
+
+/// Reports the features of a user's effective plan and how they compare to other plans.
+pub struct PlanFeaturesUseCase<P, S, L, R>
+where
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    R: RecordingViewRepository + Send + Sync + 'static,
+{
+    plan_repository: Arc<P>,
+    live_following_repository: Arc<L>,
+    recording_view_repository: Arc<R>,
+    plan_resolver: Arc<PlanResolver<P, S>>,
+    quota_enforcement: QuotaEnforcementPolicy,
+}
+
+impl<P, S, L, R> PlanFeaturesUseCase<P, S, L, R>
+where
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    R: RecordingViewRepository + Send + Sync + 'static,
+{
+    pub fn new(
+        plan_repository: Arc<P>,
+        live_following_repository: Arc<L>,
+        recording_view_repository: Arc<R>,
+        plan_resolver: Arc<PlanResolver<P, S>>,
+        quota_enforcement: QuotaEnforcementPolicy,
+    ) -> Self {
+        Self {
+            plan_repository,
+            live_following_repository,
+            recording_view_repository,
+            plan_resolver,
+            quota_enforcement,
+        }
+    }
+
+    /// Every catalog feature of the user's effective plan, with usage for metered limits.
+    pub async fn effective_features(
+        &self,
+        user_id: Uuid,
+    ) -> UseCaseResult<EffectivePlanFeaturesDto> {
+        let plan = self
+            .plan_resolver
+            .resolve_effective_plan_for_user(user_id)
+            .await?;
+
+        let recording_usage = self
+            .recording_view_repository
+            .find_recording_usage(
+                user_id,
+                i64::from(plan.features.retention_days_or_default().max(0)),
+                usage_month_start(Utc::now()),
+            )
+            .await?;
+
+        let mut features = Vec::with_capacity(PlanFeatureKey::ALL.len());
+        for key in PlanFeatureKey::ALL {
+            let used = match key {
+                PlanFeatureKey::MaxFollows => Some(
+                    self.live_following_repository
+                        .count_active_follows(user_id)
+                        .await?,
+                ),
+                PlanFeatureKey::MaxConcurrentRecordings => Some(
+                    self.live_following_repository
+                        .count_recording_follows(user_id)
+                        .await?,
+                ),
+                PlanFeatureKey::MaxStorageBytes => Some(recording_usage.storage_bytes),
+                PlanFeatureKey::MaxRecordingHoursPerMonth => Some(recording_usage.recorded_hours()),
+                PlanFeatureKey::RetentionDays
+                | PlanFeatureKey::PrioritySupport
+                | PlanFeatureKey::CustomBranding => None,
+            };
+
+            features.push(PlanFeatureUsageDto {
+                key,
+                value: plan.features.value(key),
+                used,
+            });
+        }
+
+        let quota_exceeded = recording_usage.reached_storage_limit(plan.features.max_storage_bytes)
+            || recording_usage
+                .reached_recording_hours_limit(plan.features.max_recording_hours_per_month);
+
+        Ok(EffectivePlanFeaturesDto {
+            plan_id: plan.id,
+            plan_name: plan.name,
+            features,
+            recording_usage,
+            quota_exceeded,
+            quota_enforcement: self.quota_enforcement,
+        })
+    }
+
+    /// Feature changes the user would see moving from their effective plan to `plan_id`.
+    pub async fn compare_with(
+        &self,
+        user_id: Uuid,
+        plan_id: Uuid,
+    ) -> UseCaseResult<PlanComparisonDto> {
+        let current = self
+            .plan_resolver
+            .resolve_effective_plan_for_user(user_id)
+            .await?;
+
+        let target = self
+            .plan_repository
+            .find_active_plan_by_id(plan_id)
+            .await
+            .map_err(|err| {
+                if err.downcast_ref::<diesel::result::Error>()
+                    == Some(&diesel::result::Error::NotFound)
+                {
+                    PlanFeaturesError::PlanNotFound
+                } else {
+                    error!(
+                        %user_id,
+                        %plan_id,
+                        db_error = ?err,
+                        "plan_features: failed to load target plan"
+                    );
+                    PlanFeaturesError::Internal(err)
+                }
+            })?;
+
+        Ok(PlanComparisonDto {
+            from_plan_id: current.id,
+            to_plan_id: target.id,
+            changes: current.features.compare(&target.features),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crates::domain::{
+        entities::plans::PlanEntity,
+        repositories::{
+            live_following::MockLiveFollowingRepository, plans::MockPlanRepository,
+            recording_view::MockRecordingViewRepository, subscriptions::MockSubscriptionRepository,
+        },
+        value_objects::{
+            plans::{FREE_PLAN_ID, PlanFeatureChangeDirection, PlanFeatureValue, PlanFeatures},
+            recordings::RecordingUsage,
+        },
+    };
+
+    fn free_plan() -> PlanEntity {
+        PlanEntity {
+            id: FREE_PLAN_ID,
+            name: Some("Free".to_string()),
+            price_minor: 0,
+            duration_days: 30,
+            features: PlanFeatures {
+                max_follows: Some(3),
+                retention_days: Some(7),
+                ..PlanFeatures::default()
+            },
+            is_active: true,
+            prices: Vec::new(),
+        }
+    }
+
+    fn usecase(
+        plan_repo: MockPlanRepository,
+        live_following_repo: MockLiveFollowingRepository,
+        usage: RecordingUsage,
+    ) -> PlanFeaturesUseCase<
+        MockPlanRepository,
+        MockSubscriptionRepository,
+        MockLiveFollowingRepository,
+        MockRecordingViewRepository,
+    > {
+        let mut recording_view_repo = MockRecordingViewRepository::new();
+        recording_view_repo
+            .expect_find_recording_usage()
+            .returning(move |_, _, _| Box::pin(async move { Ok(usage) }));
+
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+
+        let plan_repo = Arc::new(plan_repo);
+        PlanFeaturesUseCase::new(
+            Arc::clone(&plan_repo),
+            Arc::new(live_following_repo),
+            Arc::new(recording_view_repo),
+            Arc::new(PlanResolver::new(
+                plan_repo,
+                Arc::new(subscription_repo),
+                FREE_PLAN_ID,
+            )),
+            QuotaEnforcementPolicy::Warn,
+        )
+    }
+
+    #[tokio::test]
+    async fn effective_features_reports_usage_against_limits() {
+        let user_id = Uuid::new_v4();
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo
+            .expect_find_by_id()
+            .returning(|_| Box::pin(async { Ok(free_plan()) }));
+        let mut live_following_repo = MockLiveFollowingRepository::new();
+        live_following_repo
+            .expect_count_active_follows()
+            .returning(|_| Box::pin(async { Ok(2) }));
+        live_following_repo
+            .expect_count_recording_follows()
+            .returning(|_| Box::pin(async { Ok(1) }));
+
+        let usage = RecordingUsage {
+            storage_bytes: 1_000,
+            recorded_seconds: 7_300,
+        };
+        let dto = usecase(plan_repo, live_following_repo, usage)
+            .effective_features(user_id)
+            .await
+            .unwrap();
+
+        assert_eq!(dto.plan_id, FREE_PLAN_ID);
+        assert_eq!(
+            dto.features[0],
+            PlanFeatureUsageDto {
+                key: PlanFeatureKey::MaxFollows,
+                value: PlanFeatureValue::Limit(3),
+                used: Some(2),
+            }
+        );
+        assert_eq!(dto.features[1].used, None);
+        assert_eq!(dto.features[2].used, Some(1));
+        assert_eq!(dto.features[3].value, PlanFeatureValue::Unlimited);
+        assert_eq!(dto.features[3].used, Some(1_000));
+        assert_eq!(dto.features[4].used, Some(2));
+        assert!(!dto.quota_exceeded);
+    }
+
+    #[tokio::test]
+    async fn effective_features_flags_reached_storage_quota() {
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo.expect_find_by_id().returning(|_| {
+            let mut plan = free_plan();
+            plan.features.max_storage_bytes = Some(1_000);
+            Box::pin(async move { Ok(plan) })
+        });
+        let mut live_following_repo = MockLiveFollowingRepository::new();
+        live_following_repo
+            .expect_count_active_follows()
+            .returning(|_| Box::pin(async { Ok(0) }));
+        live_following_repo
+            .expect_count_recording_follows()
+            .returning(|_| Box::pin(async { Ok(0) }));
+        let usage = RecordingUsage {
+            storage_bytes: 1_000,
+            recorded_seconds: 0,
+        };
+
+        let dto = usecase(plan_repo, live_following_repo, usage)
+            .effective_features(Uuid::new_v4())
+            .await
+            .unwrap();
+
+        assert_eq!(dto.features[3].value, PlanFeatureValue::Limit(1_000));
+        assert!(dto.quota_exceeded);
+        assert_eq!(dto.quota_enforcement, QuotaEnforcementPolicy::Warn);
+    }
+
+    #[tokio::test]
+    async fn compare_with_unknown_plan_is_not_found() {
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo
+            .expect_find_by_id()
+            .returning(|_| Box::pin(async { Ok(free_plan()) }));
+        plan_repo
+            .expect_find_active_plan_by_id()
+            .returning(|_| Box::pin(async { Err(diesel::result::Error::NotFound.into()) }));
+
+        let err = usecase(
+            plan_repo,
+            MockLiveFollowingRepository::new(),
+            RecordingUsage::default(),
+        )
+        .compare_with(Uuid::new_v4(), Uuid::new_v4())
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, PlanFeaturesError::PlanNotFound));
+        assert_eq!(err.status_code(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn compare_with_lists_upgraded_features() {
+        let paid_plan_id = Uuid::new_v4();
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo
+            .expect_find_by_id()
+            .returning(|_| Box::pin(async { Ok(free_plan()) }));
+        plan_repo
+            .expect_find_active_plan_by_id()
+            .returning(move |_| {
+                let mut plan = free_plan();
+                plan.id = paid_plan_id;
+                plan.features.max_follows = Some(50);
+                Box::pin(async move { Ok(plan) })
+            });
+
+        let dto = usecase(
+            plan_repo,
+            MockLiveFollowingRepository::new(),
+            RecordingUsage::default(),
+        )
+        .compare_with(Uuid::new_v4(), paid_plan_id)
+        .await
+        .unwrap();
+
+        assert_eq!(dto.to_plan_id, paid_plan_id);
+        assert_eq!(dto.changes.len(), 1);
+        assert_eq!(dto.changes[0].key, PlanFeatureKey::MaxFollows);
+        assert_eq!(
+            dto.changes[0].direction,
+            PlanFeatureChangeDirection::Upgrade
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::usecases::plan_cache::PlanCache;
 use anyhow::Result;
 use crates::domain::{
     entities::plans::PlanEntity,
@@ -16,6 +17,7 @@ where
     plan_repo: Arc<P>,
     subscription_repo: Arc<S>,
     free_plan_id: Uuid,
+    cache: Arc<PlanCache>,
 }
 
 impl<P, S> PlanResolver<P, S>
@@ -28,10 +30,65 @@ where
             plan_repo,
             subscription_repo,
             free_plan_id,
+            cache: Arc::new(PlanCache::disabled()),
         }
     }
 
+    /// Serves resolved plans from `cache`, which should be shared process-wide so
+    /// invalidations from subscription changes reach every resolver.
+    pub fn with_cache(mut self, cache: Arc<PlanCache>) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn free_plan_id(&self) -> Uuid {
+        self.free_plan_id
+    }
+
+    /// Call after changing a user's subscriptions so the next resolution sees the change.
+    pub fn invalidate_user(&self, user_id: Uuid) {
+        self.cache.invalidate_user(user_id);
+    }
+
     pub async fn resolve_effective_plan_for_user(&self, user_id: Uuid) -> Result<PlanEntity> {
+        if let Some(plan) = self.cache.effective_plan(user_id) {
+            debug!(%user_id, plan_id = %plan.id, "plan_resolver: using cached plan");
+            return Ok(plan);
+        }
+
+        let generation = self.cache.generation();
+        let plan = self.resolve_uncached(user_id).await?;
+        self.cache
+            .put_effective_plan(user_id, plan.clone(), generation);
+        Ok(plan)
+    }
+
+    /// Smallest follow limit any user can resolve to, i.e. across the active plans and the
+    /// free plan. Users with no more active follows than this are within their limit.
+    pub async fn lowest_follow_limit(&self) -> Result<i64> {
+        let free_plan = self.find_plan(self.free_plan_id).await?;
+        let active_plans = self.plan_repo.list_active_plans().await?;
+
+        Ok(active_plans
+            .iter()
+            .chain(std::iter::once(&free_plan))
+            .map(|plan| plan.features.max_follows_or_default())
+            .min()
+            .unwrap_or_else(|| free_plan.features.max_follows_or_default()))
+    }
+
+    async fn find_plan(&self, plan_id: Uuid) -> Result<PlanEntity> {
+        if let Some(plan) = self.cache.plan(plan_id) {
+            return Ok(plan);
+        }
+
+        let generation = self.cache.generation();
+        let plan = self.plan_repo.find_by_id(plan_id).await?;
+        self.cache.put_plan(plan.clone(), generation);
+        Ok(plan)
+    }
+
+    async fn resolve_uncached(&self, user_id: Uuid) -> Result<PlanEntity> {
         if let Some(subscription) = self
             .subscription_repo
             .find_current_active_non_free_subscription(user_id, self.free_plan_id)
@@ -50,34 +107,27 @@ where
                 plan_id = %subscription.plan_id,
                 "plan_resolver: using active subscription plan"
             );
-            return self
-                .plan_repo
-                .find_by_id(subscription.plan_id)
-                .await
-                .map_err(|err| {
-                    error!(
-                        %user_id,
-                        plan_id = %subscription.plan_id,
-                        db_error = ?err,
-                        "plan_resolver: failed to load plan by id"
-                    );
-                    err
-                });
-        }
-
-        debug!(%user_id, "plan_resolver: falling back to free plan");
-        self.plan_repo
-            .find_by_id(self.free_plan_id)
-            .await
-            .map_err(|err| {
+            return self.find_plan(subscription.plan_id).await.map_err(|err| {
                 error!(
                     %user_id,
-                    plan_id = %self.free_plan_id,
+                    plan_id = %subscription.plan_id,
                     db_error = ?err,
-                    "plan_resolver: failed to load free plan"
+                    "plan_resolver: failed to load plan by id"
                 );
                 err
-            })
+            });
+        }
+
+        debug!(%user_id, "plan_resolver: falling back to free plan");
+        self.find_plan(self.free_plan_id).await.map_err(|err| {
+            error!(
+                %user_id,
+                plan_id = %self.free_plan_id,
+                db_error = ?err,
+                "plan_resolver: failed to load free plan"
+            );
+            err
+        })
     }
 }
 
@@ -103,9 +153,7 @@ mod tests {
             duration_days: 30,
             features: PlanFeatures::default(),
             is_active: true,
-            stripe_price_recurring: None,
-            stripe_price_one_time_card: None,
-            stripe_price_one_time_promptpay: None,
+            prices: Vec::new(),
         }
     }
 
@@ -124,6 +172,7 @@ mod tests {
             provider_subscription_id: None,
             status: SubscriptionStatus::Active.to_string(),
             created_at: now,
+            ended_reason: None,
         }
     }
 
@@ -203,4 +252,50 @@ mod tests {
 
         assert_eq!(plan.id, FREE_PLAN_ID);
     }
+
+    #[tokio::test]
+    async fn cached_plan_is_reused_until_user_is_invalidated() {
+        let user_id = Uuid::new_v4();
+
+        let mut plan_repo = MockPlanRepository::new();
+        let mut subscription_repo = MockSubscriptionRepository::new();
+
+        let free_plan = sample_plan(FREE_PLAN_ID);
+
+        subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .times(2)
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+
+        plan_repo.expect_find_by_id().times(1).returning(move |_| {
+            let plan = free_plan.clone();
+            Box::pin(async move { Ok(plan) })
+        });
+
+        let cache = Arc::new(PlanCache::new(std::time::Duration::from_secs(60)));
+        let resolver = PlanResolver::new(
+            Arc::new(plan_repo),
+            Arc::new(subscription_repo),
+            FREE_PLAN_ID,
+        )
+        .with_cache(Arc::clone(&cache));
+
+        for _ in 0..2 {
+            resolver
+                .resolve_effective_plan_for_user(user_id)
+                .await
+                .unwrap();
+        }
+        resolver.invalidate_user(user_id);
+        // The subscription is read again; the plan row itself is still cached.
+        let plan = resolver
+            .resolve_effective_plan_for_user(user_id)
+            .await
+            .unwrap();
+
+        assert_eq!(plan.id, FREE_PLAN_ID);
+        let stats = cache.stats();
+        assert_eq!(stats.invalidations, 1);
+        assert_eq!(stats.hits, 2);
+    }
 }
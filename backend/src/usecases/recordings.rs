@@ -6,6 +6,9 @@ use crates::domain::{
         plans::PlanRepository, recording_view::RecordingViewRepository,
         subscriptions::SubscriptionRepository,
     },
+    value_objects::{
+        enums::quota_enforcement_policies::QuotaEnforcementPolicy, recordings::RecordingViewWindow,
+    },
 };
 use serde::Serialize;
 use std::sync::Arc;
@@ -29,6 +32,12 @@ pub struct RecordingDto {
     pub poster_storage_path: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bitrate_bps: Option<i64>,
+    pub fps: Option<f64>,
 }
 
 impl From<RecordingEntity> for RecordingDto {
@@ -48,6 +57,12 @@ impl From<RecordingEntity> for RecordingDto {
             poster_storage_path: value.poster_storage_path,
             created_at: value.created_at,
             updated_at: value.updated_at,
+            width: value.width,
+            height: value.height,
+            video_codec: value.video_codec,
+            audio_codec: value.audio_codec,
+            bitrate_bps: value.bitrate_bps,
+            fps: value.fps,
         }
     }
 }
@@ -128,6 +143,7 @@ where
 {
     recording_view_repo: Arc<R>,
     plan_resolver: Arc<PlanResolver<P, S>>,
+    quota_enforcement: QuotaEnforcementPolicy,
 }
 
 impl<R, P, S> RecordingsUseCase<R, P, S>
@@ -136,10 +152,15 @@ where
     P: PlanRepository + Send + Sync + 'static,
     S: SubscriptionRepository + Send + Sync + 'static,
 {
-    pub fn new(recording_view_repo: Arc<R>, plan_resolver: Arc<PlanResolver<P, S>>) -> Self {
+    pub fn new(
+        recording_view_repo: Arc<R>,
+        plan_resolver: Arc<PlanResolver<P, S>>,
+        quota_enforcement: QuotaEnforcementPolicy,
+    ) -> Self {
         Self {
             recording_view_repo,
             plan_resolver,
+            quota_enforcement,
         }
     }
 
@@ -149,7 +170,7 @@ where
         limit: i64,
         cursor: Option<HomeRecordingsCursor>,
     ) -> Result<HomeRecordingsPageDto> {
-        let retention_days = self.effective_retention_days(user_id).await?;
+        let window = self.effective_view_window(user_id).await?;
         let fetch_limit = limit.saturating_add(1);
         let (cursor_started_at, cursor_id) = match cursor {
             Some(cursor) => (Some(cursor.started_at), Some(cursor.id)),
@@ -160,7 +181,7 @@ where
             .recording_view_repo
             .list_home_entitled_recordings(
                 user_id,
-                retention_days,
+                window,
                 fetch_limit,
                 cursor_started_at,
                 cursor_id,
@@ -197,10 +218,10 @@ where
         user_id: Uuid,
         live_account_id: Option<Uuid>,
     ) -> Result<Vec<RecordingDto>> {
-        let retention_days = self.effective_retention_days(user_id).await?;
+        let window = self.effective_view_window(user_id).await?;
         let recordings = self
             .recording_view_repo
-            .list_follows_entitled_recordings(user_id, retention_days, live_account_id)
+            .list_follows_entitled_recordings(user_id, window, live_account_id)
             .await?;
 
         Ok(recordings.into_iter().map(RecordingDto::from).collect())
@@ -210,10 +231,10 @@ where
         &self,
         user_id: Uuid,
     ) -> Result<FollowsRecordingCountsDto> {
-        let retention_days = self.effective_retention_days(user_id).await?;
+        let window = self.effective_view_window(user_id).await?;
         let counts = self
             .recording_view_repo
-            .count_follows_entitled_recordings(user_id, retention_days)
+            .count_follows_entitled_recordings(user_id, window)
             .await?;
 
         let items = counts
@@ -228,10 +249,10 @@ where
     }
 
     pub async fn home_stats(&self, user_id: Uuid) -> Result<HomeRecordingStatsDto> {
-        let retention_days = self.effective_retention_days(user_id).await?;
+        let window = self.effective_view_window(user_id).await?;
         let total_recordings = self
             .recording_view_repo
-            .count_home_entitled_recordings(user_id, retention_days)
+            .count_home_entitled_recordings(user_id, window)
             .await?;
         let currently_recording = self
             .recording_view_repo
@@ -256,11 +277,21 @@ where
         Ok(CurrentlyRecordingLiveAccountsDto { live_account_ids })
     }
 
-    async fn effective_retention_days(&self, user_id: Uuid) -> Result<i64> {
+    /// Under `hide_oldest`, recordings past the plan's storage quota drop out of view.
+    async fn effective_view_window(&self, user_id: Uuid) -> Result<RecordingViewWindow> {
         let plan = self
             .plan_resolver
             .resolve_effective_plan_for_user(user_id)
             .await?;
-        Ok(i64::from(plan.features.retention_days_or_default().max(0)))
+
+        let storage_limit_bytes = match self.quota_enforcement {
+            QuotaEnforcementPolicy::HideOldest => plan.features.max_storage_bytes,
+            QuotaEnforcementPolicy::Warn | QuotaEnforcementPolicy::StopRecording => None,
+        };
+
+        Ok(RecordingViewWindow {
+            retention_days: i64::from(plan.features.retention_days_or_default().max(0)),
+            storage_limit_bytes,
+        })
     }
 }
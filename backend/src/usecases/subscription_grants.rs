@@ -0,0 +1,542 @@
+use chrono::{DateTime, Duration, Utc};
+use crates::domain::{
+    entities::subscriptions::InsertSubscriptionEntity,
+    repositories::{
+        live_following::LiveFollowingRepository, plans::PlanRepository,
+        subscription_grants::SubscriptionGrantRepository, subscriptions::SubscriptionRepository,
+    },
+    value_objects::{
+        enums::{billing_modes::BillingMode, subscription_statuses::SubscriptionStatus},
+        subscription_grants::{
+            CreateSubscriptionGrantRequest, RevokeSubscriptionGrantRequest, SubscriptionGrantDto,
+        },
+    },
+};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::usecases::plan_resolver::PlanResolver;
+
+#[derive(Debug, Error)]
+pub enum SubscriptionGrantError {
+    #[error("plan not found")]
+    PlanNotFound,
+    #[error("invalid grant: {0}")]
+    InvalidGrant(String),
+    #[error("user already has a subscription ending at {0}")]
+    OverlappingSubscription(DateTime<Utc>),
+    #[error("grant not found or already revoked")]
+    GrantNotFound,
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl SubscriptionGrantError {
+    pub fn status_code(&self) -> axum::http::StatusCode {
+        use axum::http::StatusCode;
+        match self {
+            SubscriptionGrantError::PlanNotFound | SubscriptionGrantError::GrantNotFound => {
+                StatusCode::NOT_FOUND
+            }
+            SubscriptionGrantError::InvalidGrant(_) => StatusCode::BAD_REQUEST,
+            SubscriptionGrantError::OverlappingSubscription(_) => StatusCode::CONFLICT,
+            SubscriptionGrantError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+pub type UseCaseResult<T> = std::result::Result<T, SubscriptionGrantError>;
+
+/// Admin-granted complimentary subscriptions. Grants are regular `subscriptions` rows with
+/// `BillingMode::Complimentary`, so `PlanResolver` picks them up without Stripe involvement.
+pub struct SubscriptionGrantUseCase<G, L, P, S>
+where
+    G: SubscriptionGrantRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    grant_repo: Arc<G>,
+    live_following_repository: Arc<L>,
+    plan_repo: Arc<P>,
+    subscription_repo: Arc<S>,
+    plan_resolver: Arc<PlanResolver<P, S>>,
+    free_plan_id: Uuid,
+}
+
+impl<G, L, P, S> SubscriptionGrantUseCase<G, L, P, S>
+where
+    G: SubscriptionGrantRepository + Send + Sync + 'static,
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+{
+    pub fn new(
+        grant_repo: Arc<G>,
+        live_following_repository: Arc<L>,
+        plan_repo: Arc<P>,
+        subscription_repo: Arc<S>,
+        plan_resolver: Arc<PlanResolver<P, S>>,
+        free_plan_id: Uuid,
+    ) -> Self {
+        Self {
+            grant_repo,
+            live_following_repository,
+            plan_repo,
+            subscription_repo,
+            plan_resolver,
+            free_plan_id,
+        }
+    }
+
+    pub async fn grant(
+        &self,
+        granted_by: Uuid,
+        request: CreateSubscriptionGrantRequest,
+        now: DateTime<Utc>,
+    ) -> UseCaseResult<SubscriptionGrantDto> {
+        let reason = request.reason.trim().to_string();
+        if reason.is_empty() {
+            return Err(SubscriptionGrantError::InvalidGrant(
+                "reason is required".to_string(),
+            ));
+        }
+        if request.plan_id == self.free_plan_id {
+            return Err(SubscriptionGrantError::InvalidGrant(
+                "the free plan cannot be granted".to_string(),
+            ));
+        }
+
+        let plan = self
+            .plan_repo
+            .list_active_plans()
+            .await
+            .map_err(|err| {
+                error!(
+                    plan_id = %request.plan_id,
+                    db_error = ?err,
+                    "subscription_grants: failed to load plans"
+                );
+                SubscriptionGrantError::Internal(err)
+            })?
+            .into_iter()
+            .find(|plan| plan.id == request.plan_id)
+            .ok_or(SubscriptionGrantError::PlanNotFound)?;
+
+        let ends_at = match (request.ends_at, request.duration_days) {
+            (Some(_), Some(_)) => {
+                return Err(SubscriptionGrantError::InvalidGrant(
+                    "use either ends_at or duration_days".to_string(),
+                ));
+            }
+            (Some(ends_at), None) => ends_at,
+            (None, Some(days)) if days > 0 => now + Duration::days(days),
+            (None, Some(_)) => {
+                return Err(SubscriptionGrantError::InvalidGrant(
+                    "duration_days must be positive".to_string(),
+                ));
+            }
+            (None, None) => now + Duration::days(plan.duration_days.into()),
+        };
+        if ends_at <= now {
+            return Err(SubscriptionGrantError::InvalidGrant(
+                "ends_at must be in the future".to_string(),
+            ));
+        }
+
+        if let Some(existing) = self
+            .subscription_repo
+            .find_overlapping_subscription(request.user_id, now, ends_at)
+            .await
+            .map_err(|err| {
+                error!(
+                    user_id = %request.user_id,
+                    db_error = ?err,
+                    "subscription_grants: failed to check overlapping subscriptions"
+                );
+                SubscriptionGrantError::Internal(err)
+            })?
+        {
+            let err = SubscriptionGrantError::OverlappingSubscription(existing.ends_at);
+            warn!(
+                user_id = %request.user_id,
+                existing_subscription_id = %existing.id,
+                status = err.status_code().as_u16(),
+                "subscription_grants: grant overlaps an existing subscription"
+            );
+            return Err(err);
+        }
+
+        let created = self
+            .grant_repo
+            .create_grant(
+                InsertSubscriptionEntity {
+                    user_id: request.user_id,
+                    plan_id: plan.id,
+                    starts_at: now,
+                    ends_at,
+                    billing_mode: BillingMode::Complimentary.to_string(),
+                    default_payment_method_id: None,
+                    cancel_at_period_end: false,
+                    canceled_at: None,
+                    provider_subscription_id: None,
+                    status: SubscriptionStatus::Active.to_string(),
+                },
+                reason,
+                granted_by,
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    user_id = %request.user_id,
+                    plan_id = %plan.id,
+                    db_error = ?err,
+                    "subscription_grants: failed to create grant"
+                );
+                SubscriptionGrantError::Internal(err)
+            })?;
+
+        info!(
+            subscription_id = %created.0.id,
+            user_id = %created.0.user_id,
+            plan_id = %created.0.plan_id,
+            ends_at = %created.0.ends_at,
+            %granted_by,
+            "subscription_grants: complimentary subscription granted"
+        );
+        self.plan_resolver.invalidate_user(request.user_id);
+
+        // Follows paused by an earlier downgrade come back within the granted plan's limit.
+        let max_follows = plan.features.max_follows_or_default();
+        if let Err(err) = self
+            .live_following_repository
+            .restore_temporary_inactive_follows(request.user_id, max_follows)
+            .await
+        {
+            error!(
+                user_id = %request.user_id,
+                db_error = ?err,
+                "subscription_grants: failed to restore follows after grant"
+            );
+        }
+
+        Ok(created.into())
+    }
+
+    pub async fn revoke(
+        &self,
+        subscription_id: Uuid,
+        revoked_by: Uuid,
+        request: RevokeSubscriptionGrantRequest,
+        now: DateTime<Utc>,
+    ) -> UseCaseResult<SubscriptionGrantDto> {
+        let revoke_reason = request
+            .reason
+            .map(|reason| reason.trim().to_string())
+            .filter(|reason| !reason.is_empty());
+
+        let revoked = self
+            .grant_repo
+            .revoke_grant(subscription_id, revoked_by, revoke_reason, now)
+            .await
+            .map_err(|err| {
+                error!(
+                    %subscription_id,
+                    db_error = ?err,
+                    "subscription_grants: failed to revoke grant"
+                );
+                SubscriptionGrantError::Internal(err)
+            })?
+            .ok_or(SubscriptionGrantError::GrantNotFound)?;
+
+        let user_id = revoked.0.user_id;
+        info!(
+            %subscription_id,
+            %user_id,
+            %revoked_by,
+            "subscription_grants: complimentary subscription revoked"
+        );
+        self.plan_resolver.invalidate_user(user_id);
+
+        if let Err(err) = self.enforce_follow_limit(user_id).await {
+            error!(
+                %subscription_id,
+                %user_id,
+                error = ?err,
+                "subscription_grants: failed to enforce follow limit after revoke"
+            );
+        }
+
+        Ok(revoked.into())
+    }
+
+    pub async fn list(
+        &self,
+        user_id: Option<Uuid>,
+        include_revoked: bool,
+    ) -> UseCaseResult<Vec<SubscriptionGrantDto>> {
+        let grants = self
+            .grant_repo
+            .list_grants(user_id, include_revoked)
+            .await
+            .map_err(|err| {
+                error!(
+                    user_id = ?user_id,
+                    db_error = ?err,
+                    "subscription_grants: failed to list grants"
+                );
+                SubscriptionGrantError::Internal(err)
+            })?;
+
+        Ok(grants.into_iter().map(SubscriptionGrantDto::from).collect())
+    }
+
+    async fn enforce_follow_limit(&self, user_id: Uuid) -> anyhow::Result<()> {
+        let plan = self
+            .plan_resolver
+            .resolve_effective_plan_for_user(user_id)
+            .await?;
+        let deactivated = self
+            .live_following_repository
+            .deactivate_follows_over_limit(user_id, plan.features.max_follows_or_default())
+            .await?;
+        if deactivated > 0 {
+            info!(
+                %user_id,
+                deactivated,
+                "subscription_grants: follows set to temporary inactive"
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crates::domain::{
+        entities::{
+            plans::PlanEntity, subscription_grants::SubscriptionGrantEntity,
+            subscriptions::SubscriptionEntity,
+        },
+        repositories::{
+            live_following::MockLiveFollowingRepository, plans::MockPlanRepository,
+            subscription_grants::MockSubscriptionGrantRepository,
+            subscriptions::MockSubscriptionRepository,
+        },
+        value_objects::plans::{FREE_PLAN_ID, PlanFeatures},
+    };
+    use mockall::predicate::eq;
+
+    fn premium_plan() -> PlanEntity {
+        PlanEntity {
+            id: Uuid::new_v4(),
+            name: Some("Premium".to_string()),
+            price_minor: 29900,
+            duration_days: 30,
+            features: PlanFeatures {
+                max_follows: Some(20),
+                ..PlanFeatures::default()
+            },
+            is_active: true,
+            prices: Vec::new(),
+        }
+    }
+
+    fn created_grant(
+        insert: InsertSubscriptionEntity,
+        reason: String,
+        granted_by: Uuid,
+    ) -> (SubscriptionEntity, SubscriptionGrantEntity) {
+        let subscription = SubscriptionEntity {
+            id: Uuid::new_v4(),
+            user_id: insert.user_id,
+            plan_id: insert.plan_id,
+            starts_at: insert.starts_at,
+            ends_at: insert.ends_at,
+            billing_mode: insert.billing_mode,
+            default_payment_method_id: None,
+            cancel_at_period_end: false,
+            canceled_at: None,
+            provider_subscription_id: None,
+            status: insert.status,
+            created_at: insert.starts_at,
+            ended_reason: None,
+        };
+        let grant = SubscriptionGrantEntity {
+            subscription_id: subscription.id,
+            reason,
+            granted_by,
+            created_at: insert.starts_at,
+            revoked_at: None,
+            revoked_by: None,
+            revoke_reason: None,
+        };
+        (subscription, grant)
+    }
+
+    fn build_usecase(
+        grant_repo: MockSubscriptionGrantRepository,
+        live_following_repository: MockLiveFollowingRepository,
+        plan_repo: MockPlanRepository,
+        subscription_repo: MockSubscriptionRepository,
+    ) -> SubscriptionGrantUseCase<
+        MockSubscriptionGrantRepository,
+        MockLiveFollowingRepository,
+        MockPlanRepository,
+        MockSubscriptionRepository,
+    > {
+        let plan_repo = Arc::new(plan_repo);
+        let subscription_repo = Arc::new(subscription_repo);
+        let plan_resolver = Arc::new(PlanResolver::new(
+            Arc::clone(&plan_repo),
+            Arc::clone(&subscription_repo),
+            FREE_PLAN_ID,
+        ));
+        SubscriptionGrantUseCase::new(
+            Arc::new(grant_repo),
+            Arc::new(live_following_repository),
+            plan_repo,
+            subscription_repo,
+            plan_resolver,
+            FREE_PLAN_ID,
+        )
+    }
+
+    #[tokio::test]
+    async fn grant_creates_complimentary_subscription_for_requested_days() {
+        let now = Utc::now();
+        let admin_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+        let plan = premium_plan();
+        let plan_id = plan.id;
+
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo.expect_list_active_plans().returning(move || {
+            let plans = vec![plan.clone()];
+            Box::pin(async move { Ok(plans) })
+        });
+
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        subscription_repo
+            .expect_find_overlapping_subscription()
+            .with(eq(user_id), eq(now), eq(now + Duration::days(14)))
+            .returning(|_, _, _| Box::pin(async { Ok(None) }));
+
+        let mut grant_repo = MockSubscriptionGrantRepository::new();
+        grant_repo
+            .expect_create_grant()
+            .withf(move |insert, reason, granted_by| {
+                insert.billing_mode == BillingMode::Complimentary.as_str()
+                    && insert.plan_id == plan_id
+                    && reason == "streamer partnership"
+                    && *granted_by == admin_id
+            })
+            .times(1)
+            .returning(|insert, reason, granted_by| {
+                let created = created_grant(insert, reason, granted_by);
+                Box::pin(async move { Ok(created) })
+            });
+
+        let mut live_following_repository = MockLiveFollowingRepository::new();
+        live_following_repository
+            .expect_restore_temporary_inactive_follows()
+            .with(eq(user_id), eq(20))
+            .returning(|_, _| Box::pin(async { Ok(0) }));
+
+        let usecase = build_usecase(
+            grant_repo,
+            live_following_repository,
+            plan_repo,
+            subscription_repo,
+        );
+
+        let grant = usecase
+            .grant(
+                admin_id,
+                CreateSubscriptionGrantRequest {
+                    user_id,
+                    plan_id,
+                    reason: "  streamer partnership ".to_string(),
+                    duration_days: Some(14),
+                    ends_at: None,
+                },
+                now,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(grant.user_id, user_id);
+        assert_eq!(grant.ends_at, now + Duration::days(14));
+        assert_eq!(grant.granted_by, admin_id);
+    }
+
+    #[tokio::test]
+    async fn grant_rejects_overlapping_subscription() {
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+        let plan = premium_plan();
+        let plan_id = plan.id;
+
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo.expect_list_active_plans().returning(move || {
+            let plans = vec![plan.clone()];
+            Box::pin(async move { Ok(plans) })
+        });
+
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        subscription_repo
+            .expect_find_overlapping_subscription()
+            .returning(move |user_id, starts_at, ends_at| {
+                let (existing, _) = created_grant(
+                    InsertSubscriptionEntity {
+                        user_id,
+                        plan_id,
+                        starts_at,
+                        ends_at,
+                        billing_mode: BillingMode::Recurring.to_string(),
+                        default_payment_method_id: None,
+                        cancel_at_period_end: false,
+                        canceled_at: None,
+                        provider_subscription_id: Some("sub_123".to_string()),
+                        status: SubscriptionStatus::Active.to_string(),
+                    },
+                    String::new(),
+                    Uuid::nil(),
+                );
+                Box::pin(async move { Ok(Some(existing)) })
+            });
+
+        let mut grant_repo = MockSubscriptionGrantRepository::new();
+        grant_repo.expect_create_grant().never();
+
+        let usecase = build_usecase(
+            grant_repo,
+            MockLiveFollowingRepository::new(),
+            plan_repo,
+            subscription_repo,
+        );
+
+        let err = usecase
+            .grant(
+                Uuid::new_v4(),
+                CreateSubscriptionGrantRequest {
+                    user_id,
+                    plan_id,
+                    reason: "tester".to_string(),
+                    duration_days: None,
+                    ends_at: None,
+                },
+                now,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            SubscriptionGrantError::OverlappingSubscription(_)
+        ));
+    }
+}
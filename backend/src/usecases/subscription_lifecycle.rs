@@ -0,0 +1,927 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use crates::domain::{
+    entities::subscriptions::SubscriptionEntity,
+    repositories::{
+        job::JobRepository, live_following::LiveFollowingRepository, plans::PlanRepository,
+        subscriptions::SubscriptionRepository,
+    },
+    value_objects::{
+        enums::subscription_statuses::SubscriptionStatus,
+        subscription_notifications::SubscriptionNotificationPayload,
+    },
+};
+use std::{collections::BTreeSet, sync::Arc};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::usecases::plan_resolver::PlanResolver;
+
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionLifecycleReport {
+    pub expired: usize,
+    pub canceled: usize,
+    pub follows_deactivated: usize,
+    pub follows_restored: usize,
+    pub notifications_enqueued: usize,
+    pub failed_user_ids: Vec<Uuid>,
+}
+
+/// Applies time-based subscription transitions and keeps follows within the effective plan limit.
+///
+/// Also enqueues the user-facing notifications for those transitions: a renewal reminder
+/// `renewal_reminder_lead` before a non-renewing subscription ends, and an expiry notice once
+/// it has ended. Job dedupe keys make repeated runs safe.
+pub struct SubscriptionLifecycleUseCase<L, P, S, J>
+where
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+    J: JobRepository + Send + Sync + 'static,
+{
+    live_following_repository: Arc<L>,
+    subscription_repo: Arc<S>,
+    plan_resolver: Arc<PlanResolver<P, S>>,
+    job_repo: Arc<J>,
+    past_due_grace: Duration,
+    renewal_reminder_lead: Duration,
+}
+
+impl<L, P, S, J> SubscriptionLifecycleUseCase<L, P, S, J>
+where
+    L: LiveFollowingRepository + Send + Sync + 'static,
+    P: PlanRepository + Send + Sync + 'static,
+    S: SubscriptionRepository + Send + Sync + 'static,
+    J: JobRepository + Send + Sync + 'static,
+{
+    pub fn new(
+        live_following_repository: Arc<L>,
+        subscription_repo: Arc<S>,
+        plan_resolver: Arc<PlanResolver<P, S>>,
+        job_repo: Arc<J>,
+        past_due_grace: Duration,
+        renewal_reminder_lead: Duration,
+    ) -> Self {
+        Self {
+            live_following_repository,
+            subscription_repo,
+            plan_resolver,
+            job_repo,
+            past_due_grace,
+            renewal_reminder_lead,
+        }
+    }
+
+    pub async fn run(&self, now: DateTime<Utc>) -> Result<SubscriptionLifecycleReport> {
+        let mut report = SubscriptionLifecycleReport::default();
+
+        let expired = self
+            .subscription_repo
+            .expire_ended_non_renewing_subscriptions(now)
+            .await
+            .map_err(|err| {
+                error!(
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to expire ended subscriptions"
+                );
+                err
+            })?;
+        Self::log_transitions(&expired, "expired");
+        report.expired = expired.len();
+        report.notifications_enqueued += self.enqueue_pending_expiry_notices(now).await;
+
+        let canceled = self
+            .subscription_repo
+            .cancel_past_due_subscriptions(now - self.past_due_grace)
+            .await
+            .map_err(|err| {
+                error!(
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to cancel past due subscriptions"
+                );
+                err
+            })?;
+        Self::log_transitions(&canceled, "canceled");
+        report.canceled = canceled.len();
+
+        // A user may hold several subscriptions; each is handled once against the plan
+        // that is effective after the transitions above.
+        let downgraded_users = expired
+            .iter()
+            .chain(canceled.iter())
+            .map(|subscription| subscription.user_id)
+            .collect::<BTreeSet<_>>();
+        for &user_id in &downgraded_users {
+            self.plan_resolver.invalidate_user(user_id);
+            match self.enforce_follow_limit(user_id).await {
+                Ok(deactivated) => report.follows_deactivated += deactivated,
+                Err(_) => report.failed_user_ids.push(user_id),
+            }
+        }
+
+        // Catches users whose limit dropped elsewhere (Stripe webhooks, refunds) when the
+        // enforcement there failed, so a missed downgrade is retried on every run.
+        let lowest_follow_limit =
+            self.plan_resolver
+                .lowest_follow_limit()
+                .await
+                .map_err(|err| {
+                    error!(
+                        db_error = ?err,
+                        "subscription_lifecycle: failed to resolve lowest follow limit"
+                    );
+                    err
+                })?;
+        let users_over_limit = self
+            .live_following_repository
+            .list_users_with_active_follows_over(lowest_follow_limit)
+            .await
+            .map_err(|err| {
+                error!(
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to list users over the follow limit"
+                );
+                err
+            })?;
+        for user_id in users_over_limit {
+            if downgraded_users.contains(&user_id) {
+                continue;
+            }
+            match self.enforce_follow_limit(user_id).await {
+                Ok(deactivated) => report.follows_deactivated += deactivated,
+                Err(_) => report.failed_user_ids.push(user_id),
+            }
+        }
+
+        let users_to_restore = self
+            .live_following_repository
+            .list_users_with_temporary_inactive_follows()
+            .await
+            .map_err(|err| {
+                error!(
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to list users with temporary inactive follows"
+                );
+                err
+            })?;
+        for user_id in users_to_restore {
+            match self.restore_follows(user_id).await {
+                Ok(restored) => report.follows_restored += restored,
+                Err(_) => report.failed_user_ids.push(user_id),
+            }
+        }
+
+        let ending_soon = self
+            .subscription_repo
+            .list_non_renewing_ending_between(now, now + self.renewal_reminder_lead)
+            .await
+            .map_err(|err| {
+                error!(
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to list subscriptions ending soon"
+                );
+                err
+            })?;
+        for subscription in &ending_soon {
+            if self.has_following_term(subscription).await {
+                continue;
+            }
+            if let Ok(true) = self
+                .enqueue_notification(SubscriptionNotificationPayload::renewal_reminder(
+                    subscription,
+                ))
+                .await
+            {
+                report.notifications_enqueued += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Enqueues the expiry notice of every expired subscription still waiting for one,
+    /// including those left over from runs where enqueueing failed, and returns how many new
+    /// jobs were created. A subscription is marked once its job exists, so it is not retried.
+    async fn enqueue_pending_expiry_notices(&self, now: DateTime<Utc>) -> usize {
+        let pending = match self.subscription_repo.list_expired_pending_notice().await {
+            Ok(pending) => pending,
+            Err(err) => {
+                error!(
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to list expired subscriptions pending a notice"
+                );
+                return 0;
+            }
+        };
+
+        let mut enqueued = 0;
+        for subscription in &pending {
+            let next_plan_name = self.next_paid_plan_name(subscription.user_id).await;
+            let payload = SubscriptionNotificationPayload::expired(subscription, next_plan_name);
+            let Ok(created) = self.enqueue_notification(payload).await else {
+                continue;
+            };
+            if created {
+                enqueued += 1;
+            }
+            if let Err(err) = self
+                .subscription_repo
+                .mark_expiry_notice_enqueued(subscription.id, now)
+                .await
+            {
+                error!(
+                    subscription_id = %subscription.id,
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to mark expiry notice enqueued"
+                );
+            }
+        }
+        enqueued
+    }
+
+    /// Whether another live term starts by the time `subscription` ends (e.g. a queued
+    /// one-time term), so the user does not need a reminder to renew. Lookup failures send
+    /// the reminder anyway.
+    async fn has_following_term(&self, subscription: &SubscriptionEntity) -> bool {
+        let later = match self
+            .subscription_repo
+            .list_subscriptions_ending_after(subscription.user_id, subscription.ends_at)
+            .await
+        {
+            Ok(later) => later,
+            Err(err) => {
+                error!(
+                    user_id = %subscription.user_id,
+                    subscription_id = %subscription.id,
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to list following terms"
+                );
+                return false;
+            }
+        };
+
+        later.iter().any(|next| {
+            next.id != subscription.id
+                && next.starts_at <= subscription.ends_at
+                && !matches!(
+                    SubscriptionStatus::from_str(&next.status),
+                    SubscriptionStatus::Canceled | SubscriptionStatus::Expired
+                )
+        })
+    }
+
+    /// Returns `Ok(true)` when a new job was enqueued and `Ok(false)` for a duplicate.
+    /// Failures are only logged so a notification problem never blocks state transitions.
+    async fn enqueue_notification(&self, payload: SubscriptionNotificationPayload) -> Result<bool> {
+        let dedupe_key = payload.dedupe_key.clone();
+        match self
+            .job_repo
+            .enqueue_subscription_notification_job(payload)
+            .await
+        {
+            Ok(Some(job_id)) => {
+                info!(
+                    %job_id,
+                    dedupe_key,
+                    "subscription_lifecycle: notification enqueued"
+                );
+                Ok(true)
+            }
+            Ok(None) => Ok(false),
+            Err(err) => {
+                error!(
+                    dedupe_key,
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to enqueue notification"
+                );
+                Err(err)
+            }
+        }
+    }
+
+    /// Names the paid plan that took over after an expiry (e.g. a queued term), or `None`
+    /// when the user is back on the free plan. Resolution failures fall back to `None`.
+    async fn next_paid_plan_name(&self, user_id: Uuid) -> Option<String> {
+        self.plan_resolver.invalidate_user(user_id);
+        let plan = self
+            .plan_resolver
+            .resolve_effective_plan_for_user(user_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to resolve plan for expiry notice"
+                );
+                err
+            })
+            .ok()?;
+
+        if plan.id == self.plan_resolver.free_plan_id() {
+            return None;
+        }
+        Some(plan.name.unwrap_or_else(|| "paid".to_string()))
+    }
+
+    async fn enforce_follow_limit(&self, user_id: Uuid) -> Result<usize> {
+        let max_follows = self.resolve_max_follows(user_id).await?;
+        let deactivated = self
+            .live_following_repository
+            .deactivate_follows_over_limit(user_id, max_follows)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to deactivate follows over limit"
+                );
+                err
+            })?;
+
+        if deactivated > 0 {
+            info!(
+                %user_id,
+                max_follows,
+                deactivated,
+                "subscription_lifecycle: follows set to temporary inactive"
+            );
+        }
+        Ok(deactivated)
+    }
+
+    async fn restore_follows(&self, user_id: Uuid) -> Result<usize> {
+        let max_follows = self.resolve_max_follows(user_id).await?;
+        let restored = self
+            .live_following_repository
+            .restore_temporary_inactive_follows(user_id, max_follows)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to restore temporary inactive follows"
+                );
+                err
+            })?;
+
+        if restored > 0 {
+            info!(
+                %user_id,
+                max_follows,
+                restored,
+                "subscription_lifecycle: temporary inactive follows restored"
+            );
+        }
+        Ok(restored)
+    }
+
+    async fn resolve_max_follows(&self, user_id: Uuid) -> Result<i64> {
+        let plan = self
+            .plan_resolver
+            .resolve_effective_plan_for_user(user_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "subscription_lifecycle: failed to resolve effective plan"
+                );
+                err
+            })?;
+
+        Ok(plan.features.max_follows_or_default())
+    }
+
+    fn log_transitions(subscriptions: &[SubscriptionEntity], to_status: &str) {
+        for subscription in subscriptions {
+            info!(
+                subscription_id = %subscription.id,
+                user_id = %subscription.user_id,
+                plan_id = %subscription.plan_id,
+                billing_mode = %subscription.billing_mode,
+                ends_at = %subscription.ends_at,
+                to_status,
+                "subscription_lifecycle: subscription transitioned"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crates::domain::{
+        entities::plans::PlanEntity,
+        repositories::{
+            job::MockJobRepository, live_following::MockLiveFollowingRepository,
+            plans::MockPlanRepository, subscriptions::MockSubscriptionRepository,
+        },
+        value_objects::{
+            enums::{
+                notification_kinds::NotificationKind, subscription_statuses::SubscriptionStatus,
+            },
+            plans::{FREE_PLAN_ID, PlanFeatures},
+        },
+    };
+    use mockall::predicate::eq;
+
+    fn free_plan(max_follows: i64) -> PlanEntity {
+        PlanEntity {
+            id: FREE_PLAN_ID,
+            name: Some("Free".to_string()),
+            price_minor: 0,
+            duration_days: 30,
+            features: PlanFeatures {
+                max_follows: Some(max_follows),
+                ..PlanFeatures::default()
+            },
+            is_active: true,
+            prices: Vec::new(),
+        }
+    }
+
+    fn ended_subscription(user_id: Uuid, now: DateTime<Utc>) -> SubscriptionEntity {
+        SubscriptionEntity {
+            id: Uuid::new_v4(),
+            user_id,
+            plan_id: Uuid::new_v4(),
+            starts_at: now - Duration::days(30),
+            ends_at: now - Duration::minutes(1),
+            billing_mode: "one_time".to_string(),
+            default_payment_method_id: None,
+            cancel_at_period_end: false,
+            canceled_at: None,
+            provider_subscription_id: None,
+            status: SubscriptionStatus::Expired.to_string(),
+            created_at: now - Duration::days(30),
+            ended_reason: None,
+        }
+    }
+
+    /// Expects the expiry notices of `pending` to be listed and each marked enqueued at `now`.
+    fn expect_pending_notices(
+        subscription_repo: &mut MockSubscriptionRepository,
+        pending: Vec<SubscriptionEntity>,
+        now: DateTime<Utc>,
+    ) {
+        let pending_ids = pending.iter().map(|pending| pending.id).collect::<Vec<_>>();
+        let marked = pending_ids.len();
+        subscription_repo
+            .expect_list_expired_pending_notice()
+            .returning(move || {
+                let pending = pending.clone();
+                Box::pin(async move { Ok(pending) })
+            });
+        subscription_repo
+            .expect_mark_expiry_notice_enqueued()
+            .withf(move |subscription_id, enqueued_at| {
+                pending_ids.contains(subscription_id) && *enqueued_at == now
+            })
+            .times(marked)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+    }
+
+    #[tokio::test]
+    async fn expiry_limits_follows_to_free_plan_and_restores_others() {
+        let now = Utc::now();
+        let expired_user = Uuid::new_v4();
+        let renewed_user = Uuid::new_v4();
+
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        let expired = ended_subscription(expired_user, now);
+        subscription_repo
+            .expect_expire_ended_non_renewing_subscriptions()
+            .with(eq(now))
+            .returning({
+                let expired = expired.clone();
+                move |_| {
+                    let expired = vec![expired.clone()];
+                    Box::pin(async move { Ok(expired) })
+                }
+            });
+        expect_pending_notices(&mut subscription_repo, vec![expired], now);
+        subscription_repo
+            .expect_cancel_past_due_subscriptions()
+            .with(eq(now - Duration::days(7)))
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        let mut ending_soon = ended_subscription(renewed_user, now);
+        ending_soon.ends_at = now + Duration::days(2);
+        ending_soon.status = SubscriptionStatus::Active.to_string();
+        subscription_repo
+            .expect_list_subscriptions_ending_after()
+            .with(eq(renewed_user), eq(ending_soon.ends_at))
+            .returning(|_, _| Box::pin(async { Ok(vec![]) }));
+        subscription_repo
+            .expect_list_non_renewing_ending_between()
+            .with(eq(now), eq(now + Duration::days(3)))
+            .returning(move |_, _| {
+                let ending_soon = vec![ending_soon.clone()];
+                Box::pin(async move { Ok(ending_soon) })
+            });
+
+        let mut job_repo = MockJobRepository::new();
+        job_repo
+            .expect_enqueue_subscription_notification_job()
+            .withf(move |payload| {
+                payload.kind == NotificationKind::SubscriptionExpired
+                    && payload.user_id == expired_user
+                    && payload.next_plan_name.is_none()
+                    && payload.body().contains("free plan")
+            })
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Some(Uuid::new_v4())) }));
+        job_repo
+            .expect_enqueue_subscription_notification_job()
+            .withf(move |payload| {
+                payload.kind == NotificationKind::RenewalReminder && payload.user_id == renewed_user
+            })
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(None) }));
+
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo
+            .expect_find_by_id()
+            .with(eq(FREE_PLAN_ID))
+            .returning(|_| Box::pin(async { Ok(free_plan(3)) }));
+        plan_repo
+            .expect_list_active_plans()
+            .returning(|| Box::pin(async { Ok(vec![free_plan(3)]) }));
+
+        let mut live_following_repository = MockLiveFollowingRepository::new();
+        live_following_repository
+            .expect_deactivate_follows_over_limit()
+            .with(eq(expired_user), eq(3))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(2) }));
+        live_following_repository
+            .expect_list_users_with_active_follows_over()
+            .with(eq(3))
+            .returning(move |_| Box::pin(async move { Ok(vec![expired_user]) }));
+        live_following_repository
+            .expect_list_users_with_temporary_inactive_follows()
+            .returning(move || Box::pin(async move { Ok(vec![expired_user, renewed_user]) }));
+        live_following_repository
+            .expect_restore_temporary_inactive_follows()
+            .with(eq(expired_user), eq(3))
+            .returning(|_, _| Box::pin(async { Ok(0) }));
+        live_following_repository
+            .expect_restore_temporary_inactive_follows()
+            .with(eq(renewed_user), eq(3))
+            .returning(|_, _| Box::pin(async { Ok(1) }));
+
+        let subscription_repo = Arc::new(subscription_repo);
+        let plan_resolver = Arc::new(PlanResolver::new(
+            Arc::new(plan_repo),
+            Arc::clone(&subscription_repo),
+            FREE_PLAN_ID,
+        ));
+        let usecase = SubscriptionLifecycleUseCase::new(
+            Arc::new(live_following_repository),
+            subscription_repo,
+            plan_resolver,
+            Arc::new(job_repo),
+            Duration::days(7),
+            Duration::days(3),
+        );
+
+        let report = usecase.run(now).await.unwrap();
+
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.canceled, 0);
+        assert_eq!(report.follows_deactivated, 2);
+        assert_eq!(report.follows_restored, 1);
+        assert_eq!(report.notifications_enqueued, 1);
+        assert!(report.failed_user_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn expiry_notice_names_queued_term_that_takes_over() {
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+        let queued_plan_id = Uuid::new_v4();
+
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        let expired = ended_subscription(user_id, now);
+        subscription_repo
+            .expect_expire_ended_non_renewing_subscriptions()
+            .returning({
+                let expired = expired.clone();
+                move |_| {
+                    let expired = vec![expired.clone()];
+                    Box::pin(async move { Ok(expired) })
+                }
+            });
+        expect_pending_notices(&mut subscription_repo, vec![expired], now);
+        subscription_repo
+            .expect_cancel_past_due_subscriptions()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        let mut queued = ended_subscription(user_id, now);
+        queued.plan_id = queued_plan_id;
+        queued.starts_at = now - Duration::minutes(1);
+        queued.ends_at = now + Duration::days(30);
+        queued.status = SubscriptionStatus::Active.to_string();
+        subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .with(eq(user_id), eq(FREE_PLAN_ID))
+            .returning(move |_, _| {
+                let queued = queued.clone();
+                Box::pin(async move { Ok(Some(queued)) })
+            });
+        subscription_repo
+            .expect_list_non_renewing_ending_between()
+            .returning(|_, _| Box::pin(async { Ok(vec![]) }));
+
+        let mut job_repo = MockJobRepository::new();
+        job_repo
+            .expect_enqueue_subscription_notification_job()
+            .withf(move |payload| {
+                let body = payload.body();
+                payload.kind == NotificationKind::SubscriptionExpired
+                    && payload.next_plan_name.as_deref() == Some("Pro")
+                    && body.contains("Pro plan is now active")
+                    && !body.contains("free plan")
+            })
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Some(Uuid::new_v4())) }));
+
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo
+            .expect_find_by_id()
+            .with(eq(queued_plan_id))
+            .returning(move |_| {
+                Box::pin(async move {
+                    Ok(PlanEntity {
+                        id: queued_plan_id,
+                        name: Some("Pro".to_string()),
+                        price_minor: 9_900,
+                        ..free_plan(10)
+                    })
+                })
+            });
+        plan_repo
+            .expect_find_by_id()
+            .with(eq(FREE_PLAN_ID))
+            .returning(|_| Box::pin(async { Ok(free_plan(3)) }));
+        plan_repo
+            .expect_list_active_plans()
+            .returning(|| Box::pin(async { Ok(vec![]) }));
+
+        let mut live_following_repository = MockLiveFollowingRepository::new();
+        live_following_repository
+            .expect_deactivate_follows_over_limit()
+            .with(eq(user_id), eq(10))
+            .returning(|_, _| Box::pin(async { Ok(0) }));
+        live_following_repository
+            .expect_list_users_with_temporary_inactive_follows()
+            .returning(|| Box::pin(async { Ok(vec![]) }));
+        live_following_repository
+            .expect_list_users_with_active_follows_over()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+
+        let subscription_repo = Arc::new(subscription_repo);
+        let plan_resolver = Arc::new(PlanResolver::new(
+            Arc::new(plan_repo),
+            Arc::clone(&subscription_repo),
+            FREE_PLAN_ID,
+        ));
+        let usecase = SubscriptionLifecycleUseCase::new(
+            Arc::new(live_following_repository),
+            subscription_repo,
+            plan_resolver,
+            Arc::new(job_repo),
+            Duration::days(7),
+            Duration::days(3),
+        );
+
+        let report = usecase.run(now).await.unwrap();
+
+        assert_eq!(report.expired, 1);
+        assert_eq!(report.notifications_enqueued, 1);
+        assert!(report.failed_user_ids.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sweep_enforces_follow_limit_missed_by_earlier_downgrades() {
+        let now = Utc::now();
+        let user_id = Uuid::new_v4();
+
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        subscription_repo
+            .expect_expire_ended_non_renewing_subscriptions()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        expect_pending_notices(&mut subscription_repo, vec![], now);
+        subscription_repo
+            .expect_cancel_past_due_subscriptions()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .with(eq(user_id), eq(FREE_PLAN_ID))
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        subscription_repo
+            .expect_list_non_renewing_ending_between()
+            .returning(|_, _| Box::pin(async { Ok(vec![]) }));
+
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo
+            .expect_find_by_id()
+            .with(eq(FREE_PLAN_ID))
+            .returning(|_| Box::pin(async { Ok(free_plan(3)) }));
+        plan_repo.expect_list_active_plans().returning(|| {
+            Box::pin(async {
+                Ok(vec![PlanEntity {
+                    id: Uuid::new_v4(),
+                    ..free_plan(10)
+                }])
+            })
+        });
+
+        let mut live_following_repository = MockLiveFollowingRepository::new();
+        live_following_repository
+            .expect_list_users_with_active_follows_over()
+            .with(eq(3))
+            .times(1)
+            .returning(move |_| Box::pin(async move { Ok(vec![user_id]) }));
+        live_following_repository
+            .expect_deactivate_follows_over_limit()
+            .with(eq(user_id), eq(3))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(4) }));
+        live_following_repository
+            .expect_list_users_with_temporary_inactive_follows()
+            .returning(|| Box::pin(async { Ok(vec![]) }));
+
+        let subscription_repo = Arc::new(subscription_repo);
+        let plan_resolver = Arc::new(PlanResolver::new(
+            Arc::new(plan_repo),
+            Arc::clone(&subscription_repo),
+            FREE_PLAN_ID,
+        ));
+        let usecase = SubscriptionLifecycleUseCase::new(
+            Arc::new(live_following_repository),
+            subscription_repo,
+            plan_resolver,
+            Arc::new(MockJobRepository::new()),
+            Duration::days(7),
+            Duration::days(3),
+        );
+
+        let report = usecase.run(now).await.unwrap();
+
+        assert_eq!(report.expired, 0);
+        assert_eq!(report.follows_deactivated, 4);
+        assert!(report.failed_user_ids.is_empty());
+    }
+
+    /// Repositories for a run with nothing to downgrade or restore.
+    fn quiet_repos(
+        now: DateTime<Utc>,
+    ) -> (
+        MockSubscriptionRepository,
+        MockPlanRepository,
+        MockLiveFollowingRepository,
+    ) {
+        let mut subscription_repo = MockSubscriptionRepository::new();
+        subscription_repo
+            .expect_expire_ended_non_renewing_subscriptions()
+            .with(eq(now))
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        subscription_repo
+            .expect_cancel_past_due_subscriptions()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+
+        let mut plan_repo = MockPlanRepository::new();
+        plan_repo
+            .expect_find_by_id()
+            .with(eq(FREE_PLAN_ID))
+            .returning(|_| Box::pin(async { Ok(free_plan(3)) }));
+        plan_repo
+            .expect_list_active_plans()
+            .returning(|| Box::pin(async { Ok(vec![]) }));
+
+        let mut live_following_repository = MockLiveFollowingRepository::new();
+        live_following_repository
+            .expect_list_users_with_active_follows_over()
+            .returning(|_| Box::pin(async { Ok(vec![]) }));
+        live_following_repository
+            .expect_list_users_with_temporary_inactive_follows()
+            .returning(|| Box::pin(async { Ok(vec![]) }));
+
+        (subscription_repo, plan_repo, live_following_repository)
+    }
+
+    fn lifecycle_usecase(
+        subscription_repo: MockSubscriptionRepository,
+        plan_repo: MockPlanRepository,
+        live_following_repository: MockLiveFollowingRepository,
+        job_repo: MockJobRepository,
+    ) -> SubscriptionLifecycleUseCase<
+        MockLiveFollowingRepository,
+        MockPlanRepository,
+        MockSubscriptionRepository,
+        MockJobRepository,
+    > {
+        let subscription_repo = Arc::new(subscription_repo);
+        let plan_resolver = Arc::new(PlanResolver::new(
+            Arc::new(plan_repo),
+            Arc::clone(&subscription_repo),
+            FREE_PLAN_ID,
+        ));
+        SubscriptionLifecycleUseCase::new(
+            Arc::new(live_following_repository),
+            subscription_repo,
+            plan_resolver,
+            Arc::new(job_repo),
+            Duration::days(7),
+            Duration::days(3),
+        )
+    }
+
+    #[tokio::test]
+    async fn failed_expiry_notice_stays_pending_for_the_next_run() {
+        let now = Utc::now();
+        let (mut subscription_repo, plan_repo, live_following_repository) = quiet_repos(now);
+        let expired = ended_subscription(Uuid::new_v4(), now - Duration::hours(1));
+        subscription_repo
+            .expect_list_expired_pending_notice()
+            .returning(move || {
+                let pending = vec![expired.clone()];
+                Box::pin(async move { Ok(pending) })
+            });
+        subscription_repo
+            .expect_mark_expiry_notice_enqueued()
+            .never();
+        subscription_repo
+            .expect_list_non_renewing_ending_between()
+            .returning(|_, _| Box::pin(async { Ok(vec![]) }));
+
+        let mut job_repo = MockJobRepository::new();
+        job_repo
+            .expect_enqueue_subscription_notification_job()
+            .times(1)
+            .returning(|_| Box::pin(async { Err(anyhow::anyhow!("queue unavailable")) }));
+
+        let report = lifecycle_usecase(
+            subscription_repo,
+            plan_repo,
+            live_following_repository,
+            job_repo,
+        )
+        .run(now)
+        .await
+        .unwrap();
+
+        assert_eq!(report.notifications_enqueued, 0);
+    }
+
+    #[tokio::test]
+    async fn renewal_reminder_skipped_when_a_queued_term_follows() {
+        let now = Utc::now();
+        let (mut subscription_repo, plan_repo, live_following_repository) = quiet_repos(now);
+        expect_pending_notices(&mut subscription_repo, vec![], now);
+
+        let user_id = Uuid::new_v4();
+        let mut ending_soon = ended_subscription(user_id, now);
+        ending_soon.ends_at = now + Duration::days(2);
+        ending_soon.status = SubscriptionStatus::Active.to_string();
+        let mut queued = ended_subscription(user_id, now);
+        queued.plan_id = Uuid::new_v4();
+        queued.starts_at = ending_soon.ends_at;
+        queued.ends_at = ending_soon.ends_at + Duration::days(30);
+        queued.status = SubscriptionStatus::Active.to_string();
+        subscription_repo
+            .expect_list_non_renewing_ending_between()
+            .returning(move |_, _| {
+                let ending_soon = vec![ending_soon.clone()];
+                Box::pin(async move { Ok(ending_soon) })
+            });
+        subscription_repo
+            .expect_list_subscriptions_ending_after()
+            .returning(move |_, _| {
+                let later = vec![queued.clone()];
+                Box::pin(async move { Ok(later) })
+            });
+
+        let mut job_repo = MockJobRepository::new();
+        job_repo
+            .expect_enqueue_subscription_notification_job()
+            .never();
+
+        let report = lifecycle_usecase(
+            subscription_repo,
+            plan_repo,
+            live_following_repository,
+            job_repo,
+        )
+        .run(now)
+        .await
+        .unwrap();
+
+        assert_eq!(report.notifications_enqueued, 0);
+    }
+}
@@ -5,23 +5,35 @@ use async_trait::async_trait;
 use chrono::{DateTime, Duration, TimeZone, Utc};
 use crates::{
     domain::{
-        entities::{plans::PlanEntity, subscriptions::SubscriptionEntity},
+        entities::{
+            gift_codes::InsertGiftCodeEntity, payments::PaymentEntity, plans::PlanEntity,
+            stripe_events::InsertStripeEventEntity, subscriptions::SubscriptionEntity,
+        },
         repositories::{
-            invoices::InvoiceRepository,
+            gift_codes::GiftCodeRepository, invoices::InvoiceRepository, job::JobRepository,
+            live_following::LiveFollowingRepository,
             payment_provider_customers::PaymentProviderCustomerRepository,
             payments::PaymentRepository, plans::PlanRepository,
-            subscriptions::SubscriptionRepository,
+            stripe_events::StripeEventRepository, subscriptions::SubscriptionRepository,
         },
         value_objects::{
             enums::{
-                billing_modes::BillingMode, payment_methods::PaymentMethod,
-                payment_statuses::PaymentStatus, subscription_statuses::SubscriptionStatus,
+                billing_modes::BillingMode, gift_code_statuses::GiftCodeStatus,
+                payment_methods::PaymentMethod, payment_statuses::PaymentStatus,
+                subscription_statuses::SubscriptionStatus,
+            },
+            gift_codes::GIFT_CODE_VALIDITY_DAYS,
+            plans::DEFAULT_CURRENCY,
+            subscription_notifications::SubscriptionNotificationPayload,
+            subscriptions::{
+                BillingHistoryCursor, BillingHistoryPageDto, CheckoutPriceSelection,
+                CheckoutPromotion, CurrentSubscriptionDto, InvoiceHistoryDto, InvoiceReceiptDto,
+                PaymentHistoryDto, PlanDto, SubscriptionDiscountDto,
             },
-            subscriptions::{CurrentSubscriptionDto, PlanDto},
         },
     },
     payments::stripe_client::{
-        StripeCheckoutSession, StripeClient, StripeEvent, StripeSubscription,
+        StripeCheckoutSession, StripeClient, StripeEvent, StripePromotionCode, StripeSubscription,
     },
 };
 use serde::Deserialize;
@@ -29,19 +41,35 @@ use thiserror::Error;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+use crate::usecases::{
+    gift_codes::generate_gift_code,
+    one_time_terms::{OneTimeTerm, plan_one_time_term},
+    plan_cache::PlanCache,
+    plan_resolver::PlanResolver,
+};
+
+/// A ledger claim older than this is treated as abandoned by a crashed run and can be taken.
+const STRIPE_EVENT_CLAIM_TIMEOUT_MINUTES: i64 = 15;
+
 #[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait StripeGateway: Send + Sync {
     async fn create_checkout_session(
         &self,
         price_id: &str,
+        quantity: i32,
         mode: &str,
         customer_id: Option<String>,
         metadata: HashMap<String, String>,
+        promotion: CheckoutPromotion,
     ) -> AnyResult<String>;
 
+    async fn find_promotion_code(&self, code: &str) -> AnyResult<Option<StripePromotionCode>>;
+
     async fn cancel_subscription(&self, provider_subscription_id: &str) -> AnyResult<()>;
 
+    async fn cancel_subscription_now(&self, provider_subscription_id: &str) -> AnyResult<()>;
+
     fn verify_webhook_signature(&self, payload: &[u8], signature: &str) -> AnyResult<StripeEvent>;
 
     async fn retrieve_subscription(&self, subscription_id: &str) -> AnyResult<StripeSubscription>;
@@ -52,18 +80,28 @@ impl StripeGateway for StripeClient {
     async fn create_checkout_session(
         &self,
         price_id: &str,
+        quantity: i32,
         mode: &str,
         customer_id: Option<String>,
         metadata: HashMap<String, String>,
+        promotion: CheckoutPromotion,
     ) -> AnyResult<String> {
-        self.create_checkout_session(price_id, mode, customer_id, metadata)
+        self.create_checkout_session(price_id, quantity, mode, customer_id, metadata, promotion)
             .await
     }
 
+    async fn find_promotion_code(&self, code: &str) -> AnyResult<Option<StripePromotionCode>> {
+        self.find_promotion_code(code).await
+    }
+
     async fn cancel_subscription(&self, provider_subscription_id: &str) -> AnyResult<()> {
         self.cancel_subscription(provider_subscription_id).await
     }
 
+    async fn cancel_subscription_now(&self, provider_subscription_id: &str) -> AnyResult<()> {
+        self.cancel_subscription_now(provider_subscription_id).await
+    }
+
     fn verify_webhook_signature(&self, payload: &[u8], signature: &str) -> AnyResult<StripeEvent> {
         self.verify_webhook_signature(payload, signature)
     }
@@ -78,7 +116,7 @@ pub enum SubscriptionError {
     #[error("plan not found")]
     PlanNotFound,
     #[error("missing or inactive plan price: {0}")]
-    MissingPrice(&'static str),
+    MissingPrice(String),
     #[error("invalid payment combination: {0}")]
     InvalidCombination(String),
     #[error("user email is required for checkout")]
@@ -89,6 +127,12 @@ pub enum SubscriptionError {
     WebhookRetry(&'static str),
     #[error("no active subscription to cancel")]
     SubscriptionNotFound,
+    #[error("invoice not found")]
+    InvoiceNotFound,
+    #[error("invalid promotion code: {0}")]
+    InvalidPromotionCode(&'static str),
+    #[error("stripe event not found")]
+    StripeEventNotFound,
     #[error(transparent)]
     Internal(#[from] anyhow::Error),
 }
@@ -101,9 +145,12 @@ impl SubscriptionError {
             SubscriptionError::MissingPrice(_)
             | SubscriptionError::InvalidCombination(_)
             | SubscriptionError::MissingEmail
+            | SubscriptionError::InvalidPromotionCode(_)
             | SubscriptionError::InvalidWebhook(_) => StatusCode::BAD_REQUEST,
             SubscriptionError::WebhookRetry(_) => StatusCode::CONFLICT,
-            SubscriptionError::SubscriptionNotFound => StatusCode::NOT_FOUND,
+            SubscriptionError::SubscriptionNotFound
+            | SubscriptionError::InvoiceNotFound
+            | SubscriptionError::StripeEventNotFound => StatusCode::NOT_FOUND,
             SubscriptionError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -111,6 +158,33 @@ impl SubscriptionError {
 
 pub type UseCaseResult<T> = std::result::Result<T, SubscriptionError>;
 
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionReconciliationReport {
+    pub scanned: usize,
+    pub corrected: usize,
+    pub unchanged: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    pub corrected_ids: Vec<Uuid>,
+    pub failed_ids: Vec<Uuid>,
+}
+
+/// What happens to a subscription when the payment behind it is taken back.
+#[derive(Debug, Clone, Copy)]
+pub struct PaymentReversalPolicy {
+    pub end_on_full_refund: bool,
+    pub end_on_lost_dispute: bool,
+}
+
+impl Default for PaymentReversalPolicy {
+    fn default() -> Self {
+        Self {
+            end_on_full_refund: true,
+            end_on_lost_dispute: true,
+        }
+    }
+}
+
 struct InvoiceContext {
     invoice_id: Option<String>,
     subscription_id: String,
@@ -122,6 +196,9 @@ struct InvoiceContext {
     period_end: Option<DateTime<Utc>>,
     amount_due: Option<i64>,
     amount_paid: Option<i64>,
+    hosted_invoice_url: Option<String>,
+    invoice_pdf: Option<String>,
+    discount_minor: Option<i64>,
 }
 
 impl InvoiceContext {
@@ -134,15 +211,38 @@ impl InvoiceContext {
             .or(self.amount_due)
             .and_then(|value| i32::try_from(value).ok())
     }
+
+    fn discount_minor(&self) -> i32 {
+        self.discount_minor
+            .and_then(|value| i32::try_from(value).ok())
+            .unwrap_or(0)
+    }
+}
+
+/// The stores [`SubscriptionUseCase`] reads and writes.
+pub struct SubscriptionRepositories<P, S, Pay, Cust, Inv, Evt, Job, Gift, Live> {
+    pub plan_repo: Arc<P>,
+    pub subscription_repo: Arc<S>,
+    pub payment_repo: Arc<Pay>,
+    pub customer_repo: Arc<Cust>,
+    pub invoice_repo: Arc<Inv>,
+    pub stripe_event_repo: Arc<Evt>,
+    pub job_repo: Arc<Job>,
+    pub gift_repo: Arc<Gift>,
+    pub live_following_repo: Arc<Live>,
 }
 
-pub struct SubscriptionUseCase<P, S, Pay, Cust, Inv, Stripe>
+pub struct SubscriptionUseCase<P, S, Pay, Cust, Inv, Evt, Job, Gift, Live, Stripe>
 where
     P: PlanRepository + Send + Sync + 'static,
     S: SubscriptionRepository + Send + Sync + 'static,
     Pay: PaymentRepository + Send + Sync + 'static,
     Cust: PaymentProviderCustomerRepository + Send + Sync + 'static,
     Inv: InvoiceRepository + Send + Sync + 'static,
+    Evt: StripeEventRepository + Send + Sync + 'static,
+    Job: JobRepository + Send + Sync + 'static,
+    Gift: GiftCodeRepository + Send + Sync + 'static,
+    Live: LiveFollowingRepository + Send + Sync + 'static,
     Stripe: StripeGateway + Send + Sync + 'static,
 {
     plan_repo: Arc<P>,
@@ -150,36 +250,110 @@ where
     payment_repo: Arc<Pay>,
     customer_repo: Arc<Cust>,
     invoice_repo: Arc<Inv>,
+    stripe_event_repo: Arc<Evt>,
+    job_repo: Arc<Job>,
+    gift_repo: Arc<Gift>,
     stripe_client: Arc<Stripe>,
     free_plan_id: Uuid,
+    reversal_policy: PaymentReversalPolicy,
+    plan_cache: Arc<PlanCache>,
+    live_following_repo: Arc<Live>,
 }
 
-impl<P, S, Pay, Cust, Inv, Stripe> SubscriptionUseCase<P, S, Pay, Cust, Inv, Stripe>
+impl<P, S, Pay, Cust, Inv, Evt, Job, Gift, Live, Stripe>
+    SubscriptionUseCase<P, S, Pay, Cust, Inv, Evt, Job, Gift, Live, Stripe>
 where
     P: PlanRepository + Send + Sync + 'static,
     S: SubscriptionRepository + Send + Sync + 'static,
     Pay: PaymentRepository + Send + Sync + 'static,
     Cust: PaymentProviderCustomerRepository + Send + Sync + 'static,
     Inv: InvoiceRepository + Send + Sync + 'static,
+    Evt: StripeEventRepository + Send + Sync + 'static,
+    Job: JobRepository + Send + Sync + 'static,
+    Gift: GiftCodeRepository + Send + Sync + 'static,
+    Live: LiveFollowingRepository + Send + Sync + 'static,
     Stripe: StripeGateway + Send + Sync + 'static,
 {
     pub fn new(
-        plan_repo: Arc<P>,
-        subscription_repo: Arc<S>,
-        payment_repo: Arc<Pay>,
-        customer_repo: Arc<Cust>,
-        invoice_repo: Arc<Inv>,
+        repositories: SubscriptionRepositories<P, S, Pay, Cust, Inv, Evt, Job, Gift, Live>,
         stripe_client: Arc<Stripe>,
         free_plan_id: Uuid,
     ) -> Self {
+        let SubscriptionRepositories {
+            plan_repo,
+            subscription_repo,
+            payment_repo,
+            customer_repo,
+            invoice_repo,
+            stripe_event_repo,
+            job_repo,
+            gift_repo,
+            live_following_repo,
+        } = repositories;
         Self {
             plan_repo,
             subscription_repo,
             payment_repo,
             customer_repo,
             invoice_repo,
+            stripe_event_repo,
+            job_repo,
+            gift_repo,
             stripe_client,
             free_plan_id,
+            reversal_policy: PaymentReversalPolicy::default(),
+            plan_cache: Arc::new(PlanCache::disabled()),
+            live_following_repo,
+        }
+    }
+
+    /// Decides whether refunds and lost disputes end the subscription they paid for.
+    pub fn with_reversal_policy(mut self, reversal_policy: PaymentReversalPolicy) -> Self {
+        self.reversal_policy = reversal_policy;
+        self
+    }
+
+    /// Invalidates users in the shared resolver cache whenever their subscriptions change.
+    pub fn with_plan_cache(mut self, plan_cache: Arc<PlanCache>) -> Self {
+        self.plan_cache = plan_cache;
+        self
+    }
+
+    fn invalidate_effective_plan(&self, user_id: Uuid) {
+        self.plan_cache.invalidate_user(user_id);
+    }
+
+    /// Sets follows over the plan limit to temporary inactive after a subscription ends.
+    /// Best effort: the subscription change is already stored, and the lifecycle job's
+    /// over-limit sweep retries users whose enforcement failed here.
+    async fn enforce_follow_limit(&self, user_id: Uuid) {
+        let plan_resolver = PlanResolver::new(
+            Arc::clone(&self.plan_repo),
+            Arc::clone(&self.subscription_repo),
+            self.free_plan_id,
+        )
+        .with_cache(Arc::clone(&self.plan_cache));
+
+        let deactivated = match plan_resolver.resolve_effective_plan_for_user(user_id).await {
+            Ok(plan) => {
+                self.live_following_repo
+                    .deactivate_follows_over_limit(user_id, plan.features.max_follows_or_default())
+                    .await
+            }
+            Err(err) => Err(err),
+        };
+        match deactivated {
+            Ok(0) => {}
+            Ok(deactivated) => info!(
+                %user_id,
+                deactivated,
+                "subscriptions: follows set to temporary inactive"
+            ),
+            Err(err) => error!(
+                %user_id,
+                error = ?err,
+                "subscriptions: failed to enforce follow limit"
+            ),
         }
     }
 
@@ -235,6 +409,27 @@ where
                 SubscriptionError::Internal(err)
             })?;
 
+        let latest_invoice = self
+            .invoice_repo
+            .find_latest_by_subscription_id(subscription.id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    subscription_id = %subscription.id,
+                    db_error = ?err,
+                    "subscriptions: failed to load latest invoice for current subscription"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+        let discount = latest_invoice
+            .filter(|invoice| invoice.discount_minor > 0)
+            .map(|invoice| SubscriptionDiscountDto {
+                amount_minor: invoice.discount_minor,
+                currency: invoice.currency,
+                promotion_code: invoice.promotion_code,
+            });
+
         Ok(Some(CurrentSubscriptionDto {
             plan_id: plan.id,
             plan_name: plan.name,
@@ -244,6 +439,7 @@ where
             starts_at: subscription.starts_at,
             ends_at: subscription.ends_at,
             features: plan.features,
+            discount,
         }))
     }
 
@@ -252,14 +448,25 @@ where
         user_id: Uuid,
         user_email: Option<String>,
         plan_id: Uuid,
-        billing_mode: BillingMode,
-        payment_method: PaymentMethod,
+        selection: CheckoutPriceSelection,
+        promotion: CheckoutPromotion,
     ) -> UseCaseResult<String> {
+        let CheckoutPriceSelection {
+            billing_mode,
+            payment_method,
+            currency,
+            term_months,
+            quantity,
+        } = selection;
         info!(
             %user_id,
             %plan_id,
             billing_mode = %billing_mode,
             payment_method = %payment_method,
+            %currency,
+            term_months,
+            quantity,
+            promotion = ?promotion,
             "subscriptions: create checkout session requested"
         );
 
@@ -290,6 +497,34 @@ where
             return Err(err);
         }
 
+        if billing_mode == BillingMode::Complimentary {
+            let err = SubscriptionError::InvalidCombination(
+                "complimentary subscriptions are granted, not purchased".to_string(),
+            );
+            warn!(
+                %user_id,
+                %plan_id,
+                status = err.status_code().as_u16(),
+                "subscriptions: complimentary checkout attempted"
+            );
+            return Err(err);
+        }
+
+        if billing_mode == BillingMode::Recurring && (term_months != 1 || quantity != 1) {
+            let err = SubscriptionError::InvalidCombination(
+                "multi-month terms are only sold as one-time payments".to_string(),
+            );
+            warn!(
+                %user_id,
+                %plan_id,
+                term_months,
+                quantity,
+                status = err.status_code().as_u16(),
+                "subscriptions: recurring checkout with term options"
+            );
+            return Err(err);
+        }
+
         let plan = self
             .plan_repo
             .find_active_plan_by_id(plan_id)
@@ -363,39 +598,25 @@ where
                         );
                         SubscriptionError::Internal(err)
                     })?;
+                self.invalidate_effective_plan(user_id);
             }
         }
 
         let one_time_period = if billing_mode == BillingMode::OneTime {
-            let now = Utc::now();
-            let starts_at = match current_subscription.as_ref() {
-                Some(current) => {
-                    let current_billing_mode = BillingMode::from_str(&current.billing_mode)
-                        .unwrap_or(BillingMode::Recurring);
-                    match current_billing_mode {
-                        BillingMode::Recurring => current.ends_at,
-                        BillingMode::OneTime => {
-                            if current.ends_at > now {
-                                current.ends_at
-                            } else {
-                                now
-                            }
-                        }
-                    }
-                }
-                None => now,
-            };
-
-            let ends_at = starts_at
-                .checked_add_signed(Duration::days(plan.duration_days.into()))
-                .context("failed to compute subscription end date")?;
-
-            Some((starts_at, ends_at))
+            // Quote only; the period is placed again when the payment is fulfilled so that
+            // purchases completed in a different order still stack without overlapping.
+            let term = self
+                .plan_one_time_term(user_id, plan.id, term_months * quantity)
+                .await?;
+            Some((term.starts_at, term.ends_at))
         } else {
             None
         };
 
-        let price_id = Self::pick_price_id(&plan, billing_mode, payment_method)?;
+        let price_id =
+            Self::pick_price_id(&plan, billing_mode, payment_method, &currency, term_months)?;
+        let (promotion, promotion_code) =
+            self.resolve_checkout_promotion(user_id, promotion).await?;
         let customer_id = self
             .customer_repo
             .find_or_create_stripe_customer_id(user_id, &email)
@@ -415,8 +636,15 @@ where
             ("plan_id".to_string(), plan_id.to_string()),
             ("billing_mode".to_string(), billing_mode.to_string()),
             ("payment_method".to_string(), payment_method.to_string()),
+            ("currency".to_string(), currency.clone()),
+            ("term_months".to_string(), term_months.to_string()),
+            ("term_quantity".to_string(), quantity.to_string()),
         ]);
 
+        if let Some(code) = promotion_code {
+            metadata.insert("promotion_code".to_string(), code);
+        }
+
         if let Some((one_time_starts_at, one_time_ends_at)) = one_time_period {
             metadata.insert(
                 "one_time_starts_at".to_string(),
@@ -428,9 +656,10 @@ where
             );
         }
 
+        // Complimentary never reaches this point; `pick_price_id` rejects it.
         let mode = match billing_mode {
             BillingMode::Recurring => "subscription",
-            BillingMode::OneTime => "payment",
+            BillingMode::OneTime | BillingMode::Complimentary => "payment",
         };
 
         info!(
@@ -446,7 +675,14 @@ where
 
         let checkout_url = self
             .stripe_client
-            .create_checkout_session(&price_id, mode, Some(customer_id.clone()), metadata)
+            .create_checkout_session(
+                &price_id,
+                quantity,
+                mode,
+                Some(customer_id.clone()),
+                metadata,
+                promotion,
+            )
             .await
             .map_err(|err| {
                 error!(
@@ -472,6 +708,132 @@ where
         Ok(checkout_url)
     }
 
+    /// Starts a one-time checkout whose term is not attached to the buyer. The paid checkout
+    /// issues a gift code instead (see `handle_checkout_completed_gift`), and the term is only
+    /// placed on a timeline when someone redeems it.
+    pub async fn create_gift_checkout_session(
+        &self,
+        purchaser_id: Uuid,
+        user_email: Option<String>,
+        plan_id: Uuid,
+        selection: CheckoutPriceSelection,
+    ) -> UseCaseResult<String> {
+        let CheckoutPriceSelection {
+            billing_mode,
+            payment_method,
+            currency,
+            term_months,
+            quantity,
+        } = selection;
+        info!(
+            %purchaser_id,
+            %plan_id,
+            payment_method = %payment_method,
+            %currency,
+            term_months,
+            quantity,
+            "subscriptions: create gift checkout session requested"
+        );
+
+        let email = user_email.ok_or_else(|| {
+            let err = SubscriptionError::MissingEmail;
+            warn!(
+                %purchaser_id,
+                %plan_id,
+                status = err.status_code().as_u16(),
+                "subscriptions: missing email for gift checkout"
+            );
+            err
+        })?;
+
+        if plan_id == self.free_plan_id || billing_mode != BillingMode::OneTime {
+            let err = SubscriptionError::InvalidCombination(
+                "gifts are one-time purchases of a paid plan".to_string(),
+            );
+            warn!(
+                %purchaser_id,
+                %plan_id,
+                billing_mode = %billing_mode,
+                status = err.status_code().as_u16(),
+                "subscriptions: invalid gift checkout"
+            );
+            return Err(err);
+        }
+
+        let plan = self
+            .plan_repo
+            .find_active_plan_by_id(plan_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %purchaser_id,
+                    %plan_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load plan for gift checkout"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let price_id =
+            Self::pick_price_id(&plan, billing_mode, payment_method, &currency, term_months)?;
+        let customer_id = self
+            .customer_repo
+            .find_or_create_stripe_customer_id(purchaser_id, &email)
+            .await
+            .map_err(|err| {
+                error!(
+                    %purchaser_id,
+                    %plan_id,
+                    error = ?err,
+                    "subscriptions: failed to resolve stripe customer id for gift checkout"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let metadata = HashMap::from([
+            ("user_id".to_string(), purchaser_id.to_string()),
+            ("plan_id".to_string(), plan_id.to_string()),
+            ("billing_mode".to_string(), billing_mode.to_string()),
+            ("payment_method".to_string(), payment_method.to_string()),
+            ("currency".to_string(), currency.clone()),
+            ("term_months".to_string(), term_months.to_string()),
+            ("term_quantity".to_string(), quantity.to_string()),
+            ("gift".to_string(), "true".to_string()),
+        ]);
+
+        let checkout_url = self
+            .stripe_client
+            .create_checkout_session(
+                &price_id,
+                quantity,
+                "payment",
+                Some(customer_id.clone()),
+                metadata,
+                CheckoutPromotion::None,
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    %purchaser_id,
+                    %plan_id,
+                    price_id = %price_id,
+                    customer_id = %customer_id,
+                    error = ?err,
+                    "subscriptions: stripe gift checkout session creation failed"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        info!(
+            %purchaser_id,
+            %plan_id,
+            checkout_url = %checkout_url,
+            "subscriptions: gift checkout session created successfully"
+        );
+
+        Ok(checkout_url)
+    }
+
     pub async fn handle_stripe_webhook(
         &self,
         payload: &[u8],
@@ -505,276 +867,862 @@ where
             "subscriptions: stripe webhook verified"
         );
 
-        match event_type.as_str() {
-            "checkout.session.completed" => self.handle_checkout_completed(&event).await?,
-            "checkout.session.expired" => self.handle_checkout_expired(&event).await?,
-            "customer.subscription.deleted" => self.handle_subscription_deleted(&event).await?,
-            "invoice.payment_succeeded" => {
-                self.handle_invoice_payment_succeeded(&event).await?
-            }
-            "invoice.payment_failed" => self.handle_invoice_payment_failed(&event).await?,
-            "payment_intent.succeeded" => self.handle_payment_intent_succeeded(&event).await?,
-            "payment_intent.payment_failed" => {
-                self.handle_payment_intent_failed(&event, PaymentStatus::Failed, "failed")
-                    .await?
-            }
-            "payment_intent.canceled" => {
-                self.handle_payment_intent_failed(&event, PaymentStatus::Canceled, "void")
-                    .await?
-            }
-            _ => {
+        let Some(event_id) = event.id.clone() else {
+            warn!(
+                event_type = %event_type,
+                "subscriptions: stripe event has no id; processing without ledger"
+            );
+            return self.process_stripe_event(&event).await;
+        };
+
+        let raw_payload: serde_json::Value = serde_json::from_slice(payload).map_err(|err| {
+            SubscriptionError::InvalidWebhook(format!("payload is not valid json: {err}"))
+        })?;
+        let ledger_entry = self
+            .stripe_event_repo
+            .record_received(InsertStripeEventEntity {
+                id: event_id.clone(),
+                type_: event_type.clone(),
+                payload: raw_payload,
+            })
+            .await
+            .map_err(|err| {
                 error!(
-                    stripe_event_id = ?event.id,
-                    event_type = %event.type_,
-                    created = ?event.created,
-                    livemode = ?event.livemode,
-                    api_version = ?event.api_version,
-                    request = ?event.request,
-                    "subscriptions: unhandled stripe event type"
+                    stripe_event_id = %event_id,
+                    db_error = %err,
+                    "subscriptions: failed to record stripe event"
                 );
-            }
+                SubscriptionError::Internal(err)
+            })?;
+
+        if ledger_entry.processed_at.is_some() {
+            info!(
+                stripe_event_id = %event_id,
+                event_type = %event_type,
+                processed_at = ?ledger_entry.processed_at,
+                "subscriptions: stripe event already processed; skipping"
+            );
+            return Ok(());
         }
 
-        Ok(())
+        self.process_recorded_event(&event_id, &event, false).await
     }
 
-    pub async fn cancel_recurring_subscription(&self, user_id: Uuid) -> UseCaseResult<()> {
-        let subscription = self
-            .subscription_repo
-            .find_current_active_non_free_subscription(user_id, self.free_plan_id)
+    /// Re-runs a stored stripe event regardless of whether it was already processed.
+    pub async fn replay_stripe_event(&self, event_id: &str) -> UseCaseResult<()> {
+        let stored = self
+            .stripe_event_repo
+            .find_by_id(event_id)
             .await
             .map_err(|err| {
                 error!(
-                    %user_id,
-                    db_error = ?err,
-                    "subscriptions: failed to load current subscription for cancel"
+                    stripe_event_id = %event_id,
+                    db_error = %err,
+                    "subscriptions: failed to load stripe event for replay"
                 );
                 SubscriptionError::Internal(err)
             })?
-            .ok_or_else(|| {
-                let err = SubscriptionError::SubscriptionNotFound;
-                warn!(
-                    %user_id,
-                    status = err.status_code().as_u16(),
-                    "subscriptions: no active recurring subscription to cancel"
-                );
-                err
-            })?;
+            .ok_or(SubscriptionError::StripeEventNotFound)?;
 
-        let billing_mode =
-            BillingMode::from_str(&subscription.billing_mode).unwrap_or(BillingMode::Recurring);
-        if billing_mode != BillingMode::Recurring {
-            let err = SubscriptionError::InvalidCombination(
-                "only recurring subscriptions can be canceled".to_string(),
-            );
-            warn!(
-                %user_id,
-                status = err.status_code().as_u16(),
-                billing_mode = %subscription.billing_mode,
-                "subscriptions: attempted to cancel non-recurring subscription"
-            );
-            return Err(err);
-        }
+        let event: StripeEvent = serde_json::from_value(stored.payload).map_err(|err| {
+            SubscriptionError::InvalidWebhook(format!("stored stripe event is invalid: {err}"))
+        })?;
 
-        let provider_subscription_id =
-            subscription
-                .provider_subscription_id
-                .clone()
-                .ok_or_else(|| {
-                    let err = SubscriptionError::SubscriptionNotFound;
-                    warn!(
-                        %user_id,
-                        status = err.status_code().as_u16(),
-                        "subscriptions: recurring subscription missing provider id"
-                    );
-                    err
-                })?;
+        info!(
+            stripe_event_id = %event_id,
+            event_type = %event.type_,
+            attempts = stored.attempts,
+            "subscriptions: replaying stripe event"
+        );
+        self.process_recorded_event(event_id, &event, true).await
+    }
 
-        info!(%user_id, "subscriptions: canceling recurring subscription at Stripe");
-        self.stripe_client
-            .cancel_subscription(&provider_subscription_id)
+    /// Retries unprocessed ledger events older than `min_age`; returns how many succeeded.
+    pub async fn retry_failed_stripe_events(
+        &self,
+        max_attempts: i32,
+        min_age: Duration,
+        limit: i64,
+    ) -> UseCaseResult<usize> {
+        let events = self
+            .stripe_event_repo
+            .list_retryable(max_attempts, Utc::now() - min_age, limit)
             .await
             .map_err(|err| {
                 error!(
-                    %user_id,
-                    %provider_subscription_id,
-                    error = ?err,
-                    "subscriptions: stripe cancel subscription failed"
+                    db_error = %err,
+                    "subscriptions: failed to list retryable stripe events"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        self.subscription_repo
-            .cancel_recurring_subscription(user_id)
-            .await
-            .map_err(|err| {
-                error!(
-                    %user_id,
-                    %provider_subscription_id,
-                    db_error = ?err,
-                    "subscriptions: failed to mark recurring subscription canceled"
+        let mut succeeded = 0;
+        for stored in events {
+            let event: StripeEvent = match serde_json::from_value(stored.payload) {
+                Ok(event) => event,
+                Err(err) => {
+                    let message = format!("stored stripe event is invalid: {err}");
+                    error!(
+                        stripe_event_id = %stored.id,
+                        error = %message,
+                        "subscriptions: skipping undecodable stripe event"
+                    );
+                    self.mark_stripe_event_failed(&stored.id, &message).await;
+                    continue;
+                }
+            };
+
+            match self.process_recorded_event(&stored.id, &event, false).await {
+                Ok(()) => succeeded += 1,
+                Err(err) => {
+                    warn!(
+                        stripe_event_id = %stored.id,
+                        event_type = %event.type_,
+                        attempts = stored.attempts + 1,
+                        error = %err,
+                        "subscriptions: stripe event retry failed"
+                    );
+                }
+            }
+        }
+
+        Ok(succeeded)
+    }
+
+    /// Compares active recurring subscriptions with Stripe and corrects status/period drift.
+    pub async fn reconcile_recurring_subscriptions(
+        &self,
+    ) -> UseCaseResult<SubscriptionReconciliationReport> {
+        let subscriptions = self
+            .subscription_repo
+            .list_active_subscriptions()
+            .await
+            .map_err(|err| {
+                error!(
+                    db_error = %err,
+                    "subscriptions: failed to list active subscriptions for reconciliation"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        info!(
-            %user_id,
-            %provider_subscription_id,
-            "subscriptions: recurring subscription cancellation completed"
-        );
+        let mut report = SubscriptionReconciliationReport::default();
+        for subscription in subscriptions {
+            if BillingMode::from_str(&subscription.billing_mode) != Some(BillingMode::Recurring) {
+                continue;
+            }
+            report.scanned += 1;
 
-        Ok(())
-    }
+            let Some(provider_subscription_id) = subscription.provider_subscription_id.clone()
+            else {
+                warn!(
+                    subscription_id = %subscription.id,
+                    user_id = %subscription.user_id,
+                    "subscriptions: recurring subscription has no provider id; skipping reconciliation"
+                );
+                report.skipped += 1;
+                continue;
+            };
 
-    fn pick_price_id(
-        plan: &PlanEntity,
-        billing_mode: BillingMode,
-        payment_method: PaymentMethod,
-    ) -> UseCaseResult<String> {
-        match billing_mode {
-            BillingMode::Recurring => {
-                if payment_method != PaymentMethod::Card {
-                    let err = SubscriptionError::InvalidCombination(
-                        "recurring billing is card-only".to_string(),
-                    );
-                    warn!(
-                        status = err.status_code().as_u16(),
-                        billing_mode = %billing_mode,
-                        payment_method = %payment_method,
-                        "subscriptions: invalid recurring payment combination"
-                    );
-                    return Err(err);
+            match self
+                .reconcile_subscription(&subscription, &provider_subscription_id)
+                .await
+            {
+                Ok(true) => {
+                    report.corrected += 1;
+                    report.corrected_ids.push(subscription.id);
                 }
-                plan.stripe_price_recurring.clone().ok_or_else(|| {
-                    let err = SubscriptionError::MissingPrice("stripe_price_recurring");
-                    warn!(
-                        status = err.status_code().as_u16(),
-                        plan_id = %plan.id,
-                        "subscriptions: missing recurring price"
+                Ok(false) => report.unchanged += 1,
+                Err(err) => {
+                    error!(
+                        subscription_id = %subscription.id,
+                        provider_subscription_id = %provider_subscription_id,
+                        error = %err,
+                        "subscriptions: failed to reconcile subscription"
                     );
-                    err
-                })
+                    report.failed += 1;
+                    report.failed_ids.push(subscription.id);
+                }
             }
-            BillingMode::OneTime => match payment_method {
-                PaymentMethod::Card => plan.stripe_price_one_time_card.clone().ok_or_else(|| {
-                    let err = SubscriptionError::MissingPrice("stripe_price_one_time_card");
+        }
+
+        Ok(report)
+    }
+
+    async fn reconcile_subscription(
+        &self,
+        subscription: &SubscriptionEntity,
+        provider_subscription_id: &str,
+    ) -> UseCaseResult<bool> {
+        let remote = self
+            .stripe_client
+            .retrieve_subscription(provider_subscription_id)
+            .await
+            .map_err(SubscriptionError::Internal)?;
+
+        let local_status = SubscriptionStatus::from_str(&subscription.status);
+        let remote_status = match remote.status.as_deref() {
+            Some(status) => match Self::status_from_stripe(status) {
+                Some(mapped) => mapped,
+                None => {
                     warn!(
-                        status = err.status_code().as_u16(),
-                        plan_id = %plan.id,
-                        "subscriptions: missing one-time card price"
+                        subscription_id = %subscription.id,
+                        provider_subscription_id,
+                        stripe_status = status,
+                        "subscriptions: unknown stripe subscription status; keeping local status"
                     );
-                    err
-                }),
-                PaymentMethod::PromptPay => {
-                    plan.stripe_price_one_time_promptpay.clone().ok_or_else(|| {
-                        let err =
-                            SubscriptionError::MissingPrice("stripe_price_one_time_promptpay");
-                        warn!(
-                            status = err.status_code().as_u16(),
-                            plan_id = %plan.id,
-                            "subscriptions: missing promptpay price"
-                        );
-                        err
-                    })
+                    local_status
                 }
             },
+            None => local_status,
+        };
+        let remote_starts_at = remote
+            .period_start()
+            .and_then(Self::ts_to_datetime)
+            .unwrap_or(subscription.starts_at);
+        let remote_ends_at = remote
+            .period_end()
+            .and_then(Self::ts_to_datetime)
+            .unwrap_or(subscription.ends_at);
+
+        if remote_status == local_status
+            && remote_starts_at == subscription.starts_at
+            && remote_ends_at == subscription.ends_at
+        {
+            return Ok(false);
         }
-    }
 
-    async fn handle_checkout_completed(&self, event: &StripeEvent) -> UseCaseResult<()> {
-        info!(
-            stripe_event_id = ?event.id,
-            event_type = %event.type_,
-            payload = ?event.data.object,
-            created = ?event.created,
-            livemode = ?event.livemode,
-            api_version = ?event.api_version,
-            request = ?event.request,
-            "subscriptions: processing checkout completed webhook"
+        // Logged at error level on purpose: drift means a webhook was missed or the
+        // subscription was edited in the dashboard, and should reach the notifier.
+        error!(
+            subscription_id = %subscription.id,
+            user_id = %subscription.user_id,
+            provider_subscription_id,
+            local_status = %local_status,
+            stripe_status = %remote_status,
+            local_starts_at = %subscription.starts_at,
+            stripe_starts_at = %remote_starts_at,
+            local_ends_at = %subscription.ends_at,
+            stripe_ends_at = %remote_ends_at,
+            "subscriptions: correcting subscription drift from stripe"
         );
 
-        let session = StripeClient::extract_checkout_session(event).ok_or_else(|| {
-            let err = SubscriptionError::InvalidWebhook("missing checkout session".to_string());
-            error!(
-                stripe_event_id = ?event.id,
-                status = err.status_code().as_u16(),
-                "subscriptions: checkout session missing in webhook"
+        self.subscription_repo
+            .update_status_and_period_by_provider_subscription_id(
+                provider_subscription_id,
+                remote_status,
+                remote_starts_at,
+                remote_ends_at,
+            )
+            .await
+            .map_err(SubscriptionError::Internal)?;
+        self.invalidate_effective_plan(subscription.user_id);
+        if matches!(
+            remote_status,
+            SubscriptionStatus::Canceled | SubscriptionStatus::Expired
+        ) {
+            self.enforce_follow_limit(subscription.user_id).await;
+        }
+
+        Ok(true)
+    }
+
+    fn status_from_stripe(status: &str) -> Option<SubscriptionStatus> {
+        match status {
+            "active" | "trialing" => Some(SubscriptionStatus::Active),
+            "past_due" | "unpaid" => Some(SubscriptionStatus::PastDue),
+            "canceled" => Some(SubscriptionStatus::Canceled),
+            "incomplete" => Some(SubscriptionStatus::Pending),
+            "incomplete_expired" => Some(SubscriptionStatus::Expired),
+            _ => None,
+        }
+    }
+
+    /// Processes a ledger event under a claim so a redelivery, the retry loop and a replay
+    /// cannot run it concurrently; `reprocess` allows claiming an already processed event.
+    async fn process_recorded_event(
+        &self,
+        event_id: &str,
+        event: &StripeEvent,
+        reprocess: bool,
+    ) -> UseCaseResult<()> {
+        let stale_before = Utc::now() - Duration::minutes(STRIPE_EVENT_CLAIM_TIMEOUT_MINUTES);
+        let claimed = self
+            .stripe_event_repo
+            .claim(event_id, stale_before, reprocess)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = %event_id,
+                    db_error = %err,
+                    "subscriptions: failed to claim stripe event"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+        if !claimed {
+            warn!(
+                stripe_event_id = %event_id,
+                event_type = %event.type_,
+                "subscriptions: stripe event is already being processed or was processed"
             );
-            err
-        })?;
+            return Err(SubscriptionError::WebhookRetry(
+                "stripe event is already being processed",
+            ));
+        }
 
-        let metadata = session.metadata.clone().ok_or_else(|| {
-            let err = SubscriptionError::InvalidWebhook("missing metadata".to_string());
+        match self.process_stripe_event(event).await {
+            Ok(()) => {
+                if let Err(err) = self.stripe_event_repo.mark_processed(event_id).await {
+                    error!(
+                        stripe_event_id = %event_id,
+                        db_error = %err,
+                        "subscriptions: failed to mark stripe event processed"
+                    );
+                }
+                Ok(())
+            }
+            Err(err) => {
+                self.mark_stripe_event_failed(event_id, &err.to_string())
+                    .await;
+                Err(err)
+            }
+        }
+    }
+
+    async fn mark_stripe_event_failed(&self, event_id: &str, message: &str) {
+        if let Err(err) = self.stripe_event_repo.mark_failed(event_id, message).await {
             error!(
-                stripe_event_id = ?event.id,
-                status = err.status_code().as_u16(),
-                "subscriptions: missing metadata on checkout session"
+                stripe_event_id = %event_id,
+                db_error = %err,
+                "subscriptions: failed to mark stripe event failed"
             );
-            err
-        })?;
+        }
+    }
 
-        let user_id = metadata
-            .get("user_id")
-            .and_then(|v| Uuid::parse_str(v).ok())
-            .ok_or_else(|| {
-                let err = SubscriptionError::InvalidWebhook("missing user_id".to_string());
+    async fn process_stripe_event(&self, event: &StripeEvent) -> UseCaseResult<()> {
+        match event.type_.as_str() {
+            "checkout.session.completed" => self.handle_checkout_completed(event).await?,
+            "checkout.session.expired" => self.handle_checkout_expired(event).await?,
+            "customer.subscription.deleted" => self.handle_subscription_deleted(event).await?,
+            "invoice.payment_succeeded" => self.handle_invoice_payment_succeeded(event).await?,
+            "invoice.payment_failed" => self.handle_invoice_payment_failed(event).await?,
+            "payment_intent.succeeded" => self.handle_payment_intent_succeeded(event).await?,
+            "payment_intent.payment_failed" => {
+                self.handle_payment_intent_failed(event, PaymentStatus::Failed, "failed")
+                    .await?
+            }
+            "payment_intent.canceled" => {
+                self.handle_payment_intent_failed(event, PaymentStatus::Canceled, "void")
+                    .await?
+            }
+            "charge.succeeded" => self.handle_charge_succeeded(event).await?,
+            "charge.refunded" => self.handle_charge_refunded(event).await?,
+            "charge.dispute.created" => self.handle_charge_dispute(event, false).await?,
+            "charge.dispute.closed" => self.handle_charge_dispute(event, true).await?,
+            _ => {
                 error!(
                     stripe_event_id = ?event.id,
-                    status = err.status_code().as_u16(),
-                    "subscriptions: missing user_id in checkout metadata"
+                    event_type = %event.type_,
+                    created = ?event.created,
+                    livemode = ?event.livemode,
+                    api_version = ?event.api_version,
+                    request = ?event.request,
+                    "subscriptions: unhandled stripe event type"
                 );
-                err
-            })?;
-        let plan_id = metadata
-            .get("plan_id")
-            .and_then(|v| Uuid::parse_str(v).ok())
-            .ok_or_else(|| {
-                let err = SubscriptionError::InvalidWebhook("missing plan_id".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn cancel_recurring_subscription(&self, user_id: Uuid) -> UseCaseResult<()> {
+        let subscription = self
+            .subscription_repo
+            .find_current_active_non_free_subscription(user_id, self.free_plan_id)
+            .await
+            .map_err(|err| {
                 error!(
-                    stripe_event_id = ?event.id,
+                    %user_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load current subscription for cancel"
+                );
+                SubscriptionError::Internal(err)
+            })?
+            .ok_or_else(|| {
+                let err = SubscriptionError::SubscriptionNotFound;
+                warn!(
                     %user_id,
                     status = err.status_code().as_u16(),
-                    "subscriptions: missing plan_id in checkout metadata"
+                    "subscriptions: no active recurring subscription to cancel"
                 );
                 err
             })?;
-        let payment_method = metadata
-            .get("payment_method")
-            .and_then(|v| PaymentMethod::from_str(v))
-            .unwrap_or(PaymentMethod::Card);
 
-        if plan_id == self.free_plan_id {
-            let err =
-                SubscriptionError::InvalidWebhook("free plan cannot be purchased".to_string());
-            error!(
-                stripe_event_id = ?event.id,
+        let billing_mode =
+            BillingMode::from_str(&subscription.billing_mode).unwrap_or(BillingMode::Recurring);
+        if billing_mode != BillingMode::Recurring {
+            let err = SubscriptionError::InvalidCombination(
+                "only recurring subscriptions can be canceled".to_string(),
+            );
+            warn!(
                 %user_id,
-                %plan_id,
                 status = err.status_code().as_u16(),
-                "subscriptions: free plan cannot be purchased from webhook"
+                billing_mode = %subscription.billing_mode,
+                "subscriptions: attempted to cancel non-recurring subscription"
             );
             return Err(err);
         }
 
-        let plan = self
-            .plan_repo
-            .find_active_plan_by_id(plan_id)
+        let provider_subscription_id =
+            subscription
+                .provider_subscription_id
+                .clone()
+                .ok_or_else(|| {
+                    let err = SubscriptionError::SubscriptionNotFound;
+                    warn!(
+                        %user_id,
+                        status = err.status_code().as_u16(),
+                        "subscriptions: recurring subscription missing provider id"
+                    );
+                    err
+                })?;
+
+        info!(%user_id, "subscriptions: canceling recurring subscription at Stripe");
+        self.stripe_client
+            .cancel_subscription(&provider_subscription_id)
             .await
             .map_err(|err| {
                 error!(
                     %user_id,
-                    %plan_id,
+                    %provider_subscription_id,
+                    error = ?err,
+                    "subscriptions: stripe cancel subscription failed"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        self.subscription_repo
+            .cancel_recurring_subscription(user_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    %provider_subscription_id,
                     db_error = ?err,
-                    "subscriptions: failed to load plan during webhook"
+                    "subscriptions: failed to mark recurring subscription canceled"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        if let Some(customer) = session.customer.as_deref() {
-            self.customer_repo
-                .upsert_customer_ref(user_id, "stripe", customer)
-                .await
-                .map_err(|err| {
+        self.invalidate_effective_plan(user_id);
+
+        info!(
+            %user_id,
+            %provider_subscription_id,
+            "subscriptions: recurring subscription cancellation completed"
+        );
+
+        Ok(())
+    }
+
+    pub async fn list_invoices(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        cursor: Option<BillingHistoryCursor>,
+    ) -> UseCaseResult<BillingHistoryPageDto<InvoiceHistoryDto>> {
+        info!(%user_id, limit, "subscriptions: listing invoices for user");
+        let (cursor_created_at, cursor_id) = match cursor {
+            Some(cursor) => (Some(cursor.created_at), Some(cursor.id)),
+            None => (None, None),
+        };
+
+        let invoices = self
+            .invoice_repo
+            .list_by_user(
+                user_id,
+                limit.saturating_add(1),
+                cursor_created_at,
+                cursor_id,
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "subscriptions: failed to list invoices"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let has_more = invoices.len() > limit as usize;
+        let invoices: Vec<_> = invoices.into_iter().take(limit as usize).collect();
+
+        let payments = self
+            .payment_repo
+            .list_by_invoice_ids(invoices.iter().map(|invoice| invoice.id).collect())
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load payments for invoices"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        // Payments come back newest first, so the first hit per invoice is the latest attempt.
+        let mut latest_payments = HashMap::new();
+        for payment in &payments {
+            latest_payments.entry(payment.invoice_id).or_insert(payment);
+        }
+
+        let items: Vec<InvoiceHistoryDto> = invoices
+            .into_iter()
+            .map(|invoice| {
+                let payment = latest_payments.get(&invoice.id).copied();
+                InvoiceHistoryDto::from_entities(invoice, payment)
+            })
+            .collect();
+
+        let next_cursor = if has_more {
+            items.last().map(|item| BillingHistoryCursor {
+                created_at: item.created_at,
+                id: item.id,
+            })
+        } else {
+            None
+        };
+
+        Ok(BillingHistoryPageDto {
+            items,
+            next_cursor,
+            has_more,
+        })
+    }
+
+    pub async fn list_payments(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        cursor: Option<BillingHistoryCursor>,
+    ) -> UseCaseResult<BillingHistoryPageDto<PaymentHistoryDto>> {
+        info!(%user_id, limit, "subscriptions: listing payments for user");
+        let (cursor_created_at, cursor_id) = match cursor {
+            Some(cursor) => (Some(cursor.created_at), Some(cursor.id)),
+            None => (None, None),
+        };
+
+        let payments = self
+            .payment_repo
+            .list_by_user(
+                user_id,
+                limit.saturating_add(1),
+                cursor_created_at,
+                cursor_id,
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    db_error = ?err,
+                    "subscriptions: failed to list payments"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let has_more = payments.len() > limit as usize;
+        let items: Vec<PaymentHistoryDto> = payments
+            .into_iter()
+            .take(limit as usize)
+            .map(|(payment, invoice)| PaymentHistoryDto::from_entities(payment, invoice))
+            .collect();
+
+        let next_cursor = if has_more {
+            items.last().map(|item| BillingHistoryCursor {
+                created_at: item.created_at,
+                id: item.id,
+            })
+        } else {
+            None
+        };
+
+        Ok(BillingHistoryPageDto {
+            items,
+            next_cursor,
+            has_more,
+        })
+    }
+
+    pub async fn get_invoice_receipt(
+        &self,
+        user_id: Uuid,
+        invoice_id: Uuid,
+    ) -> UseCaseResult<InvoiceReceiptDto> {
+        info!(%user_id, %invoice_id, "subscriptions: loading invoice receipt");
+        let invoice = self
+            .invoice_repo
+            .find_by_id_and_user(invoice_id, user_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    %invoice_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load invoice for receipt"
+                );
+                SubscriptionError::Internal(err)
+            })?
+            .ok_or_else(|| {
+                let err = SubscriptionError::InvoiceNotFound;
+                warn!(
+                    %user_id,
+                    %invoice_id,
+                    status = err.status_code().as_u16(),
+                    "subscriptions: invoice not found for receipt"
+                );
+                err
+            })?;
+
+        let payments = self
+            .payment_repo
+            .list_by_invoice_ids(vec![invoice.id])
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    %invoice_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load payments for receipt"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let receipt_url = payments.into_iter().find_map(|payment| payment.receipt_url);
+
+        Ok(InvoiceReceiptDto {
+            invoice_id: invoice.id,
+            hosted_invoice_url: invoice.hosted_invoice_url,
+            invoice_pdf_url: invoice.invoice_pdf_url,
+            receipt_url,
+        })
+    }
+
+    /// Validates a requested promotion code and swaps it for the provider's promotion code id,
+    /// also returning the customer-facing code for the session metadata.
+    async fn resolve_checkout_promotion(
+        &self,
+        user_id: Uuid,
+        promotion: CheckoutPromotion,
+    ) -> UseCaseResult<(CheckoutPromotion, Option<String>)> {
+        let code = match promotion {
+            CheckoutPromotion::Code(code) => code,
+            other => return Ok((other, None)),
+        };
+
+        let promotion_code = self
+            .stripe_client
+            .find_promotion_code(&code)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    promotion_code = %code,
+                    error = ?err,
+                    "subscriptions: failed to look up promotion code"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let Some(promotion_code) = promotion_code.filter(|promotion| promotion.active) else {
+            let err = SubscriptionError::InvalidPromotionCode("code not found or inactive");
+            warn!(
+                %user_id,
+                promotion_code = %code,
+                status = err.status_code().as_u16(),
+                "subscriptions: unknown or inactive promotion code"
+            );
+            return Err(err);
+        };
+
+        if promotion_code.coupon.valid == Some(false) {
+            let err = SubscriptionError::InvalidPromotionCode("coupon is no longer valid");
+            warn!(
+                %user_id,
+                promotion_code = %code,
+                coupon_id = %promotion_code.coupon.id,
+                status = err.status_code().as_u16(),
+                "subscriptions: promotion code coupon is no longer valid"
+            );
+            return Err(err);
+        }
+
+        info!(
+            %user_id,
+            promotion_code = %promotion_code.code,
+            promotion_code_id = %promotion_code.id,
+            coupon_id = %promotion_code.coupon.id,
+            percent_off = ?promotion_code.coupon.percent_off,
+            amount_off = ?promotion_code.coupon.amount_off,
+            "subscriptions: promotion code validated for checkout"
+        );
+
+        Ok((
+            CheckoutPromotion::Code(promotion_code.id),
+            Some(promotion_code.code),
+        ))
+    }
+
+    fn pick_price_id(
+        plan: &PlanEntity,
+        billing_mode: BillingMode,
+        payment_method: PaymentMethod,
+        currency: &str,
+        term_months: i32,
+    ) -> UseCaseResult<String> {
+        match billing_mode {
+            BillingMode::Recurring | BillingMode::OneTime => {
+                if billing_mode == BillingMode::Recurring && payment_method != PaymentMethod::Card {
+                    let err = SubscriptionError::InvalidCombination(
+                        "recurring billing is card-only".to_string(),
+                    );
+                    warn!(
+                        status = err.status_code().as_u16(),
+                        billing_mode = %billing_mode,
+                        payment_method = %payment_method,
+                        "subscriptions: invalid recurring payment combination"
+                    );
+                    return Err(err);
+                }
+                if payment_method == PaymentMethod::PromptPay && currency != DEFAULT_CURRENCY {
+                    let err = SubscriptionError::InvalidCombination(format!(
+                        "promptpay only supports {DEFAULT_CURRENCY}"
+                    ));
+                    warn!(
+                        status = err.status_code().as_u16(),
+                        %currency,
+                        payment_method = %payment_method,
+                        "subscriptions: invalid promptpay currency"
+                    );
+                    return Err(err);
+                }
+                plan.find_price(currency, billing_mode, payment_method, term_months)
+                    .map(|price| price.stripe_price_id.clone())
+                    .ok_or_else(|| {
+                        let err = SubscriptionError::MissingPrice(format!(
+                            "{billing_mode}/{payment_method}/{currency}/{term_months}m"
+                        ));
+                        warn!(
+                            status = err.status_code().as_u16(),
+                            plan_id = %plan.id,
+                            billing_mode = %billing_mode,
+                            payment_method = %payment_method,
+                            %currency,
+                            term_months,
+                            "subscriptions: missing plan price"
+                        );
+                        err
+                    })
+            }
+            BillingMode::Complimentary => Err(SubscriptionError::InvalidCombination(
+                "complimentary subscriptions have no price".to_string(),
+            )),
+        }
+    }
+
+    async fn handle_checkout_completed(&self, event: &StripeEvent) -> UseCaseResult<()> {
+        info!(
+            stripe_event_id = ?event.id,
+            event_type = %event.type_,
+            payload = ?event.data.object,
+            created = ?event.created,
+            livemode = ?event.livemode,
+            api_version = ?event.api_version,
+            request = ?event.request,
+            "subscriptions: processing checkout completed webhook"
+        );
+
+        let session = StripeClient::extract_checkout_session(event).ok_or_else(|| {
+            let err = SubscriptionError::InvalidWebhook("missing checkout session".to_string());
+            error!(
+                stripe_event_id = ?event.id,
+                status = err.status_code().as_u16(),
+                "subscriptions: checkout session missing in webhook"
+            );
+            err
+        })?;
+
+        let metadata = session.metadata.clone().ok_or_else(|| {
+            let err = SubscriptionError::InvalidWebhook("missing metadata".to_string());
+            error!(
+                stripe_event_id = ?event.id,
+                status = err.status_code().as_u16(),
+                "subscriptions: missing metadata on checkout session"
+            );
+            err
+        })?;
+
+        let user_id = metadata
+            .get("user_id")
+            .and_then(|v| Uuid::parse_str(v).ok())
+            .ok_or_else(|| {
+                let err = SubscriptionError::InvalidWebhook("missing user_id".to_string());
+                error!(
+                    stripe_event_id = ?event.id,
+                    status = err.status_code().as_u16(),
+                    "subscriptions: missing user_id in checkout metadata"
+                );
+                err
+            })?;
+        let plan_id = metadata
+            .get("plan_id")
+            .and_then(|v| Uuid::parse_str(v).ok())
+            .ok_or_else(|| {
+                let err = SubscriptionError::InvalidWebhook("missing plan_id".to_string());
+                error!(
+                    stripe_event_id = ?event.id,
+                    %user_id,
+                    status = err.status_code().as_u16(),
+                    "subscriptions: missing plan_id in checkout metadata"
+                );
+                err
+            })?;
+        let payment_method = metadata
+            .get("payment_method")
+            .and_then(|v| PaymentMethod::from_str(v))
+            .unwrap_or(PaymentMethod::Card);
+
+        if plan_id == self.free_plan_id {
+            let err =
+                SubscriptionError::InvalidWebhook("free plan cannot be purchased".to_string());
+            error!(
+                stripe_event_id = ?event.id,
+                %user_id,
+                %plan_id,
+                status = err.status_code().as_u16(),
+                "subscriptions: free plan cannot be purchased from webhook"
+            );
+            return Err(err);
+        }
+
+        let plan = self
+            .plan_repo
+            .find_active_plan_by_id(plan_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    %plan_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load plan during webhook"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        if let Some(customer) = session.customer.as_deref() {
+            self.customer_repo
+                .upsert_customer_ref(user_id, "stripe", customer)
+                .await
+                .map_err(|err| {
                     error!(
                         %user_id,
                         %plan_id,
@@ -787,6 +1735,17 @@ where
         }
 
         match session.mode.as_deref() {
+            Some("payment") if metadata.get("gift").map(String::as_str) == Some("true") => {
+                self.handle_checkout_completed_gift(
+                    event,
+                    &session,
+                    &metadata,
+                    user_id,
+                    &plan,
+                    payment_method,
+                )
+                .await?;
+            }
             Some("payment") => {
                 self.handle_checkout_completed_one_time(
                     event,
@@ -829,13 +1788,17 @@ where
         plan: &PlanEntity,
         payment_method: PaymentMethod,
     ) -> UseCaseResult<()> {
-        let (starts_at, ends_at) =
-            Self::one_time_period_from_metadata(metadata, plan.duration_days)?;
-        let currency = session.currency.clone().unwrap_or_else(|| "thb".to_string());
+        let currency = session
+            .currency
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
         let amount_minor = session
             .amount_total
             .and_then(|value| i32::try_from(value).ok())
-            .unwrap_or(plan.price_minor);
+            .unwrap_or_else(|| plan.price_minor_in(&currency));
+
+        let discount_minor = Self::checkout_discount_minor(session);
+        let promotion_code = metadata.get("promotion_code").cloned();
 
         let provider_session_ref = session.id.clone().unwrap_or_default();
         let provider_payment_id = session.payment_intent.clone();
@@ -872,6 +1835,14 @@ where
         let subscription = match existing_subscription {
             Some(existing) => existing,
             None => {
+                let (starts_at, ends_at) = match Self::term_months_from_metadata(metadata) {
+                    Some(months) => {
+                        let term = self.plan_one_time_term(user_id, plan.id, months).await?;
+                        (term.starts_at, term.ends_at)
+                    }
+                    // Sessions created before term options carry a fixed period.
+                    None => Self::one_time_period_from_metadata(metadata, plan.duration_days)?,
+                };
                 let subscription_id = self
                     .subscription_repo
                     .create_or_update_subscription_after_checkout(
@@ -907,6 +1878,7 @@ where
                     provider_subscription_id: Some(provider_reference.clone()),
                     status: SubscriptionStatus::Pending.to_string(),
                     created_at: Utc::now(),
+                    ended_reason: None,
                 }
             }
         };
@@ -914,13 +1886,16 @@ where
         let invoice_id = self
             .ensure_invoice_for_subscription(
                 &subscription,
-                starts_at,
-                ends_at,
+                subscription.starts_at,
+                subscription.ends_at,
                 amount_minor,
                 currency.clone(),
             )
             .await?;
 
+        self.store_invoice_discount(event, invoice_id, discount_minor, promotion_code.clone())
+            .await?;
+
         if let Some(payment_intent_id) = provider_payment_id.as_deref() {
             let payment_exists = self
                 .payment_repo
@@ -951,6 +1926,8 @@ where
                         provider_payment_id: provider_payment_id.clone(),
                         provider_session_ref: Some(provider_session_ref),
                         error: None,
+                        discount_minor,
+                        promotion_code: promotion_code.clone(),
                     })
                     .await
                     .map_err(|err| {
@@ -996,49 +1973,280 @@ where
                     SubscriptionError::Internal(err)
                 })?;
 
-            self.invoice_repo
-                .mark_invoice_paid(invoice_id)
+            self.invalidate_effective_plan(user_id);
+
+            self.invoice_repo
+                .mark_invoice_paid(invoice_id)
+                .await
+                .map_err(|err| {
+                    error!(
+                        stripe_event_id = ?event.id,
+                        %user_id,
+                        invoice_id = %invoice_id,
+                        db_error = ?err,
+                        "subscriptions: failed to mark invoice paid after paid checkout"
+                    );
+                    SubscriptionError::Internal(err)
+                })?;
+
+            if let Some(payment_intent_id) = provider_payment_id.as_deref() {
+                self.payment_repo
+                    .update_status_by_provider_payment_id(
+                        payment_intent_id,
+                        PaymentStatus::Succeeded,
+                    )
+                    .await
+                    .map_err(|err| {
+                        error!(
+                            stripe_event_id = ?event.id,
+                            %user_id,
+                            payment_intent_id = %payment_intent_id,
+                            db_error = ?err,
+                            "subscriptions: failed to update payment after paid checkout"
+                        );
+                        SubscriptionError::Internal(err)
+                    })?;
+            }
+        }
+
+        info!(
+            stripe_event_id = ?event.id,
+            %user_id,
+            "subscriptions: processed one-time checkout webhook"
+        );
+
+        Ok(())
+    }
+
+    /// Issues the gift code for a paid gift checkout. Nothing is added to the buyer's own
+    /// subscriptions; the invoice is theirs but has no subscription attached.
+    async fn handle_checkout_completed_gift(
+        &self,
+        event: &StripeEvent,
+        session: &StripeCheckoutSession,
+        metadata: &HashMap<String, String>,
+        purchaser_id: Uuid,
+        plan: &PlanEntity,
+        payment_method: PaymentMethod,
+    ) -> UseCaseResult<()> {
+        let currency = session
+            .currency
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
+        let amount_minor = session
+            .amount_total
+            .and_then(|value| i32::try_from(value).ok())
+            .unwrap_or_else(|| plan.price_minor_in(&currency));
+        let term_months = Self::term_months_from_metadata(metadata).ok_or_else(|| {
+            let err = SubscriptionError::InvalidWebhook("missing gift term".to_string());
+            error!(
+                stripe_event_id = ?event.id,
+                %purchaser_id,
+                status = err.status_code().as_u16(),
+                "subscriptions: missing term in gift checkout metadata"
+            );
+            err
+        })?;
+
+        let provider_payment_id = session.payment_intent.clone();
+        let provider_reference = session
+            .payment_intent
+            .clone()
+            .or_else(|| session.id.clone())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                let err =
+                    SubscriptionError::InvalidWebhook("missing payment reference".to_string());
+                error!(
+                    stripe_event_id = ?event.id,
+                    status = err.status_code().as_u16(),
+                    "subscriptions: missing payment reference in gift checkout"
+                );
+                err
+            })?;
+
+        let existing_gift = self
+            .gift_repo
+            .find_by_provider_payment_ref(&provider_reference)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    %purchaser_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load gift code after checkout"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+        let gift = match existing_gift {
+            Some(gift) => gift,
+            None => {
+                let now = Utc::now();
+                let invoice = crates::domain::entities::invoices::InsertInvoiceEntity {
+                    user_id: purchaser_id,
+                    subscription_id: None,
+                    plan_id: plan.id,
+                    amount_minor,
+                    currency: currency.clone(),
+                    period_start: now,
+                    period_end: now
+                        .checked_add_months(chrono::Months::new(term_months.unsigned_abs()))
+                        .context("failed to compute gift term end")?,
+                    due_at: now,
+                    status: "pending".to_string(),
+                    paid_at: None,
+                };
+
+                self.gift_repo
+                    .create_gift_code(
+                        invoice,
+                        InsertGiftCodeEntity {
+                            code: generate_gift_code(),
+                            plan_id: plan.id,
+                            purchaser_id,
+                            term_months,
+                            status: GiftCodeStatus::Pending.to_string(),
+                            provider_payment_ref: provider_reference.clone(),
+                            // Replaced by the invoice the repository creates alongside.
+                            invoice_id: Uuid::nil(),
+                            expires_at: now + Duration::days(GIFT_CODE_VALIDITY_DAYS),
+                        },
+                    )
+                    .await
+                    .map_err(|err| {
+                        error!(
+                            stripe_event_id = ?event.id,
+                            %purchaser_id,
+                            db_error = ?err,
+                            "subscriptions: failed to issue gift code with its invoice"
+                        );
+                        SubscriptionError::Internal(err)
+                    })?
+            }
+        };
+        let invoice_id = gift.invoice_id;
+
+        if let Some(payment_intent_id) = provider_payment_id.as_deref() {
+            let payment_exists = self
+                .payment_repo
+                .exists_by_provider_payment_id(payment_intent_id)
                 .await
                 .map_err(|err| {
                     error!(
                         stripe_event_id = ?event.id,
-                        %user_id,
-                        invoice_id = %invoice_id,
+                        %purchaser_id,
+                        payment_intent_id = %payment_intent_id,
                         db_error = ?err,
-                        "subscriptions: failed to mark invoice paid after paid checkout"
+                        "subscriptions: failed to check payment for gift checkout"
                     );
                     SubscriptionError::Internal(err)
                 })?;
 
-            if let Some(payment_intent_id) = provider_payment_id.as_deref() {
+            if !payment_exists {
                 self.payment_repo
-                    .update_status_by_provider_payment_id(
-                        payment_intent_id,
-                        PaymentStatus::Succeeded,
-                    )
+                    .record_payment(crates::domain::entities::payments::NewPaymentEntity {
+                        invoice_id,
+                        user_id: purchaser_id,
+                        provider: "stripe".to_string(),
+                        method_type: payment_method.to_string(),
+                        payment_method_id: None,
+                        amount_minor,
+                        currency: currency.clone(),
+                        status: PaymentStatus::Processing.to_string(),
+                        provider_payment_id: provider_payment_id.clone(),
+                        provider_session_ref: session.id.clone(),
+                        error: None,
+                        discount_minor: Self::checkout_discount_minor(session),
+                        promotion_code: None,
+                    })
                     .await
                     .map_err(|err| {
                         error!(
                             stripe_event_id = ?event.id,
-                            %user_id,
-                            payment_intent_id = %payment_intent_id,
+                            %purchaser_id,
                             db_error = ?err,
-                            "subscriptions: failed to update payment after paid checkout"
+                            "subscriptions: failed to record payment for gift checkout"
                         );
                         SubscriptionError::Internal(err)
                     })?;
             }
         }
 
+        if Self::checkout_paid(session.payment_status.as_deref()) {
+            self.activate_gift_code(event, &provider_reference, invoice_id)
+                .await?;
+        }
+
         info!(
             stripe_event_id = ?event.id,
-            %user_id,
-            "subscriptions: processed one-time checkout webhook"
+            %purchaser_id,
+            gift_code_id = %gift.id,
+            plan_id = %plan.id,
+            term_months,
+            "subscriptions: processed gift checkout webhook"
         );
 
         Ok(())
     }
 
+    /// Makes a gift code redeemable once its payment has cleared.
+    async fn activate_gift_code(
+        &self,
+        event: &StripeEvent,
+        provider_reference: &str,
+        invoice_id: Uuid,
+    ) -> UseCaseResult<()> {
+        let activated = self
+            .gift_repo
+            .activate_by_provider_payment_ref(provider_reference)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    %provider_reference,
+                    db_error = ?err,
+                    "subscriptions: failed to activate gift code"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+        if let Some(gift) = activated.as_ref() {
+            info!(
+                stripe_event_id = ?event.id,
+                gift_code_id = %gift.id,
+                purchaser_id = %gift.purchaser_id,
+                "subscriptions: gift code activated"
+            );
+        }
+
+        self.invoice_repo
+            .mark_invoice_paid(invoice_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    %invoice_id,
+                    db_error = ?err,
+                    "subscriptions: failed to mark gift invoice paid"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        self.payment_repo
+            .update_status_by_provider_payment_id(provider_reference, PaymentStatus::Succeeded)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    %provider_reference,
+                    db_error = ?err,
+                    "subscriptions: failed to update gift payment"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        Ok(())
+    }
+
     async fn handle_checkout_completed_recurring(
         &self,
         event: &StripeEvent,
@@ -1086,11 +2294,14 @@ where
             .ok_or_else(|| {
                 SubscriptionError::InvalidWebhook("period end missing on subscription".to_string())
             })?;
-        let currency = session.currency.clone().unwrap_or_else(|| "thb".to_string());
+        let currency = session
+            .currency
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
         let amount_minor = session
             .amount_total
             .and_then(|value| i32::try_from(value).ok())
-            .unwrap_or(plan.price_minor);
+            .unwrap_or_else(|| plan.price_minor_in(&currency));
 
         let existing_subscription = self
             .subscription_repo
@@ -1146,6 +2357,7 @@ where
                     provider_subscription_id: Some(subscription_id.clone()),
                     status: SubscriptionStatus::Pending.to_string(),
                     created_at: Utc::now(),
+                    ended_reason: None,
                 }
             }
         };
@@ -1160,6 +2372,18 @@ where
             )
             .await?;
 
+        let promotion_code = session
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("promotion_code").cloned());
+        self.store_invoice_discount(
+            event,
+            invoice_id,
+            Self::checkout_discount_minor(session),
+            promotion_code,
+        )
+        .await?;
+
         if Self::checkout_paid(session.payment_status.as_deref()) {
             info!(
                 stripe_event_id = ?event.id,
@@ -1186,6 +2410,8 @@ where
                     SubscriptionError::Internal(err)
                 })?;
 
+            self.invalidate_effective_plan(user_id);
+
             self.invoice_repo
                 .mark_invoice_paid(invoice_id)
                 .await
@@ -1293,6 +2519,8 @@ where
                 SubscriptionError::Internal(err)
             })?;
 
+        self.invalidate_effective_plan(subscription.user_id);
+
         if let Some(invoice) = self
             .invoice_repo
             .find_by_subscription_and_period_start(subscription.id, subscription.starts_at)
@@ -1339,20 +2567,225 @@ where
                 })?;
         }
 
-        Ok(())
+        Ok(())
+    }
+
+    async fn handle_payment_intent_succeeded(
+        &self,
+        event: &StripeEvent,
+    ) -> UseCaseResult<()> {
+        let (payment_intent_id, amount_minor, currency, payment_method) =
+            Self::parse_payment_intent_event(event)?;
+
+        info!(
+            stripe_event_id = ?event.id,
+            payment_intent_id = %payment_intent_id,
+            "subscriptions: payment_intent succeeded"
+        );
+
+        let subscription = self
+            .subscription_repo
+            .find_by_provider_subscription_id(&payment_intent_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    payment_intent_id = %payment_intent_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load subscription after payment_intent"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let Some(subscription) = subscription else {
+            return self
+                .handle_gift_payment_intent_succeeded(event, &payment_intent_id)
+                .await;
+        };
+
+        if BillingMode::from_str(&subscription.billing_mode) != Some(BillingMode::OneTime) {
+            info!(
+                stripe_event_id = ?event.id,
+                payment_intent_id = %payment_intent_id,
+                billing_mode = %subscription.billing_mode,
+                "subscriptions: ignoring payment_intent for non one-time subscription"
+            );
+            return Ok(());
+        }
+
+        self.subscription_repo
+            .update_status_by_provider_subscription_id(
+                &payment_intent_id,
+                SubscriptionStatus::Active,
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    payment_intent_id = %payment_intent_id,
+                    db_error = ?err,
+                    "subscriptions: failed to activate subscription after payment_intent"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        self.invalidate_effective_plan(subscription.user_id);
+
+        let currency = currency.unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
+
+        let plan_price_minor = match amount_minor {
+            Some(value) => value,
+            None => {
+                let plan = self
+                    .plan_repo
+                    .find_active_plan_by_id(subscription.plan_id)
+                    .await
+                    .map_err(|err| {
+                        error!(
+                            stripe_event_id = ?event.id,
+                            plan_id = %subscription.plan_id,
+                            db_error = ?err,
+                            "subscriptions: failed to load plan for payment_intent"
+                        );
+                        SubscriptionError::Internal(err)
+                    })?;
+                plan.price_minor_in(&currency)
+            }
+        };
+
+        let invoice_id = self
+            .ensure_invoice_for_subscription(
+                &subscription,
+                subscription.starts_at,
+                subscription.ends_at,
+                plan_price_minor,
+                currency.clone(),
+            )
+            .await?;
+
+        self.invoice_repo
+            .mark_invoice_paid(invoice_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    invoice_id = %invoice_id,
+                    db_error = ?err,
+                    "subscriptions: failed to mark invoice paid after payment_intent"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let payment_exists = self
+            .payment_repo
+            .exists_by_provider_payment_id(&payment_intent_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    payment_intent_id = %payment_intent_id,
+                    db_error = ?err,
+                    "subscriptions: failed to check payment after payment_intent"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        if payment_exists {
+            self.payment_repo
+                .update_status_by_provider_payment_id(
+                    &payment_intent_id,
+                    PaymentStatus::Succeeded,
+                )
+                .await
+                .map_err(|err| {
+                    error!(
+                        stripe_event_id = ?event.id,
+                        payment_intent_id = %payment_intent_id,
+                        db_error = ?err,
+                        "subscriptions: failed to update payment after payment_intent"
+                    );
+                    SubscriptionError::Internal(err)
+                })?;
+        } else {
+            let amount_minor = amount_minor.unwrap_or(plan_price_minor);
+            self.payment_repo
+                .record_payment(crates::domain::entities::payments::NewPaymentEntity {
+                    invoice_id,
+                    user_id: subscription.user_id,
+                    provider: "stripe".to_string(),
+                    method_type: payment_method.to_string(),
+                    payment_method_id: None,
+                    amount_minor,
+                    currency: currency.clone(),
+                    status: PaymentStatus::Succeeded.to_string(),
+                    provider_payment_id: Some(payment_intent_id.clone()),
+                    provider_session_ref: None,
+                    error: None,
+                    discount_minor: 0,
+                    promotion_code: None,
+                })
+                .await
+                .map_err(|err| {
+                    error!(
+                        stripe_event_id = ?event.id,
+                        payment_intent_id = %payment_intent_id,
+                        db_error = ?err,
+                        "subscriptions: failed to record payment after payment_intent"
+                    );
+                    SubscriptionError::Internal(err)
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Async payment methods (e.g. PromptPay) settle after checkout; gift codes bought with
+    /// them only become redeemable here.
+    async fn handle_gift_payment_intent_succeeded(
+        &self,
+        event: &StripeEvent,
+        payment_intent_id: &str,
+    ) -> UseCaseResult<()> {
+        let gift = self
+            .gift_repo
+            .find_by_provider_payment_ref(payment_intent_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    payment_intent_id = %payment_intent_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load gift code after payment_intent"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        let Some(gift) = gift else {
+            info!(
+                stripe_event_id = ?event.id,
+                payment_intent_id = %payment_intent_id,
+                "subscriptions: payment_intent succeeded without local subscription"
+            );
+            return Ok(());
+        };
+
+        self.activate_gift_code(event, payment_intent_id, gift.invoice_id)
+            .await
     }
 
-    async fn handle_payment_intent_succeeded(
+    async fn handle_payment_intent_failed(
         &self,
         event: &StripeEvent,
+        payment_status: PaymentStatus,
+        invoice_status: &'static str,
     ) -> UseCaseResult<()> {
         let (payment_intent_id, amount_minor, currency, payment_method) =
             Self::parse_payment_intent_event(event)?;
 
-        info!(
+        error!(
             stripe_event_id = ?event.id,
             payment_intent_id = %payment_intent_id,
-            "subscriptions: payment_intent succeeded"
+            "subscriptions: payment_intent failed"
         );
 
         let subscription = self
@@ -1364,7 +2797,7 @@ where
                     stripe_event_id = ?event.id,
                     payment_intent_id = %payment_intent_id,
                     db_error = ?err,
-                    "subscriptions: failed to load subscription after payment_intent"
+                    "subscriptions: failed to load subscription after payment_intent failure"
                 );
                 SubscriptionError::Internal(err)
             })?;
@@ -1373,7 +2806,7 @@ where
             info!(
                 stripe_event_id = ?event.id,
                 payment_intent_id = %payment_intent_id,
-                "subscriptions: payment_intent succeeded without local subscription"
+                "subscriptions: payment_intent failed without local subscription"
             );
             return Ok(());
         };
@@ -1383,7 +2816,7 @@ where
                 stripe_event_id = ?event.id,
                 payment_intent_id = %payment_intent_id,
                 billing_mode = %subscription.billing_mode,
-                "subscriptions: ignoring payment_intent for non one-time subscription"
+                "subscriptions: ignoring payment_intent failure for non one-time subscription"
             );
             return Ok(());
         }
@@ -1391,7 +2824,7 @@ where
         self.subscription_repo
             .update_status_by_provider_subscription_id(
                 &payment_intent_id,
-                SubscriptionStatus::Active,
+                SubscriptionStatus::Expired,
             )
             .await
             .map_err(|err| {
@@ -1399,12 +2832,14 @@ where
                     stripe_event_id = ?event.id,
                     payment_intent_id = %payment_intent_id,
                     db_error = ?err,
-                    "subscriptions: failed to activate subscription after payment_intent"
+                    "subscriptions: failed to expire subscription after payment_intent failure"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        let currency = currency.unwrap_or_else(|| "thb".to_string());
+        self.invalidate_effective_plan(subscription.user_id);
+
+        let currency = currency.unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
 
         let plan_price_minor = match amount_minor {
             Some(value) => value,
@@ -1418,11 +2853,11 @@ where
                             stripe_event_id = ?event.id,
                             plan_id = %subscription.plan_id,
                             db_error = ?err,
-                            "subscriptions: failed to load plan for payment_intent"
+                            "subscriptions: failed to load plan for payment_intent failure"
                         );
                         SubscriptionError::Internal(err)
                     })?;
-                plan.price_minor
+                plan.price_minor_in(&currency)
             }
         };
 
@@ -1437,14 +2872,14 @@ where
             .await?;
 
         self.invoice_repo
-            .mark_invoice_paid(invoice_id)
+            .update_status_by_id(invoice_id, invoice_status)
             .await
             .map_err(|err| {
                 error!(
                     stripe_event_id = ?event.id,
                     invoice_id = %invoice_id,
                     db_error = ?err,
-                    "subscriptions: failed to mark invoice paid after payment_intent"
+                    "subscriptions: failed to update invoice after payment_intent failure"
                 );
                 SubscriptionError::Internal(err)
             })?;
@@ -1458,24 +2893,21 @@ where
                     stripe_event_id = ?event.id,
                     payment_intent_id = %payment_intent_id,
                     db_error = ?err,
-                    "subscriptions: failed to check payment after payment_intent"
+                    "subscriptions: failed to check payment after payment_intent failure"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
         if payment_exists {
             self.payment_repo
-                .update_status_by_provider_payment_id(
-                    &payment_intent_id,
-                    PaymentStatus::Succeeded,
-                )
+                .update_status_by_provider_payment_id(&payment_intent_id, payment_status)
                 .await
                 .map_err(|err| {
                     error!(
                         stripe_event_id = ?event.id,
                         payment_intent_id = %payment_intent_id,
                         db_error = ?err,
-                        "subscriptions: failed to update payment after payment_intent"
+                        "subscriptions: failed to update payment after payment_intent failure"
                     );
                     SubscriptionError::Internal(err)
                 })?;
@@ -1490,10 +2922,12 @@ where
                     payment_method_id: None,
                     amount_minor,
                     currency: currency.clone(),
-                    status: PaymentStatus::Succeeded.to_string(),
+                    status: payment_status.to_string(),
                     provider_payment_id: Some(payment_intent_id.clone()),
                     provider_session_ref: None,
                     error: None,
+                    discount_minor: 0,
+                    promotion_code: None,
                 })
                 .await
                 .map_err(|err| {
@@ -1501,7 +2935,7 @@ where
                         stripe_event_id = ?event.id,
                         payment_intent_id = %payment_intent_id,
                         db_error = ?err,
-                        "subscriptions: failed to record payment after payment_intent"
+                        "subscriptions: failed to record payment after payment_intent failure"
                     );
                     SubscriptionError::Internal(err)
                 })?;
@@ -1510,168 +2944,476 @@ where
         Ok(())
     }
 
-    async fn handle_payment_intent_failed(
-        &self,
-        event: &StripeEvent,
-        payment_status: PaymentStatus,
-        invoice_status: &'static str,
-    ) -> UseCaseResult<()> {
-        let (payment_intent_id, amount_minor, currency, payment_method) =
-            Self::parse_payment_intent_event(event)?;
+    async fn handle_charge_succeeded(&self, event: &StripeEvent) -> UseCaseResult<()> {
+        #[derive(Deserialize)]
+        struct ChargeObject {
+            id: Option<String>,
+            payment_intent: Option<String>,
+            receipt_url: Option<String>,
+        }
 
-        error!(
+        let charge: ChargeObject =
+            serde_json::from_value(event.data.object.clone()).map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    error = %err,
+                    status = SubscriptionError::InvalidWebhook("".into()).status_code().as_u16(),
+                    "subscriptions: invalid charge payload in webhook"
+                );
+                SubscriptionError::InvalidWebhook("invalid charge payload".to_string())
+            })?;
+
+        let (Some(payment_intent_id), Some(receipt_url)) =
+            (charge.payment_intent, charge.receipt_url)
+        else {
+            info!(
+                stripe_event_id = ?event.id,
+                charge_id = ?charge.id,
+                "subscriptions: charge succeeded without payment_intent or receipt_url"
+            );
+            return Ok(());
+        };
+
+        let payment_exists = self
+            .payment_repo
+            .exists_by_provider_payment_id(&payment_intent_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    payment_intent_id = %payment_intent_id,
+                    db_error = ?err,
+                    "subscriptions: failed to check payment for charge receipt"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        if !payment_exists {
+            // Recurring payments are keyed by invoice; their receipts come from the hosted invoice.
+            info!(
+                stripe_event_id = ?event.id,
+                payment_intent_id = %payment_intent_id,
+                "subscriptions: charge succeeded without local payment; skipping receipt"
+            );
+            return Ok(());
+        }
+
+        self.payment_repo
+            .update_receipt_url_by_provider_payment_id(&payment_intent_id, &receipt_url)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    payment_intent_id = %payment_intent_id,
+                    db_error = ?err,
+                    "subscriptions: failed to store receipt url from charge webhook"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        info!(
             stripe_event_id = ?event.id,
             payment_intent_id = %payment_intent_id,
-            "subscriptions: payment_intent failed"
+            "subscriptions: stored receipt url from charge webhook"
         );
 
-        let subscription = self
-            .subscription_repo
-            .find_by_provider_subscription_id(&payment_intent_id)
+        Ok(())
+    }
+
+    async fn handle_charge_refunded(&self, event: &StripeEvent) -> UseCaseResult<()> {
+        #[derive(Deserialize)]
+        struct ChargeObject {
+            id: Option<String>,
+            payment_intent: Option<String>,
+            invoice: Option<String>,
+            amount: Option<i64>,
+            amount_refunded: Option<i64>,
+            #[serde(default)]
+            refunded: bool,
+            refunds: Option<RefundList>,
+        }
+
+        #[derive(Deserialize)]
+        struct RefundList {
+            #[serde(default)]
+            data: Vec<RefundObject>,
+        }
+
+        #[derive(Deserialize)]
+        struct RefundObject {
+            reason: Option<String>,
+        }
+
+        let charge: ChargeObject =
+            serde_json::from_value(event.data.object.clone()).map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    error = %err,
+                    status = SubscriptionError::InvalidWebhook("".into()).status_code().as_u16(),
+                    "subscriptions: invalid refunded charge payload in webhook"
+                );
+                SubscriptionError::InvalidWebhook("invalid charge payload".to_string())
+            })?;
+
+        let Some(provider_payment_id) = charge.payment_intent.or(charge.invoice) else {
+            info!(
+                stripe_event_id = ?event.id,
+                charge_id = ?charge.id,
+                "subscriptions: refunded charge without payment_intent or invoice"
+            );
+            return Ok(());
+        };
+
+        let Some(payment) = self
+            .find_payment_for_reversal(event, &provider_payment_id)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let refunded_minor = charge
+            .amount_refunded
+            .and_then(|value| i32::try_from(value).ok())
+            .unwrap_or(0);
+        let fully_refunded = charge.refunded
+            || charge
+                .amount
+                .is_some_and(|amount| charge.amount_refunded.unwrap_or(0) >= amount);
+        let status = if fully_refunded {
+            PaymentStatus::Refunded
+        } else {
+            PaymentStatus::PartiallyRefunded
+        };
+        let reason = match charge
+            .refunds
+            .and_then(|refunds| refunds.data.into_iter().find_map(|refund| refund.reason))
+        {
+            Some(refund_reason) => format!("refunded: {refund_reason}"),
+            None => "refunded".to_string(),
+        };
+
+        info!(
+            stripe_event_id = ?event.id,
+            provider_payment_id = %provider_payment_id,
+            refunded_minor,
+            fully_refunded,
+            reason = %reason,
+            "subscriptions: recording refund"
+        );
+
+        self.payment_repo
+            .record_refund(
+                &provider_payment_id,
+                status,
+                refunded_minor,
+                Some(reason.clone()),
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    provider_payment_id = %provider_payment_id,
+                    db_error = ?err,
+                    "subscriptions: failed to record refund"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        if !fully_refunded {
+            return Ok(());
+        }
+
+        self.update_invoice_status_for_reversal(event, &payment, "refunded")
+            .await?;
+        if self.reversal_policy.end_on_full_refund {
+            self.end_subscription_for_payment(event, &payment, reason)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn handle_charge_dispute(&self, event: &StripeEvent, closed: bool) -> UseCaseResult<()> {
+        #[derive(Deserialize)]
+        struct DisputeObject {
+            id: Option<String>,
+            charge: Option<String>,
+            payment_intent: Option<String>,
+            reason: Option<String>,
+            status: Option<String>,
+        }
+
+        let dispute: DisputeObject =
+            serde_json::from_value(event.data.object.clone()).map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    error = %err,
+                    status = SubscriptionError::InvalidWebhook("".into()).status_code().as_u16(),
+                    "subscriptions: invalid dispute payload in webhook"
+                );
+                SubscriptionError::InvalidWebhook("invalid dispute payload".to_string())
+            })?;
+
+        // Payments are keyed by payment intent; a dispute without one cannot be matched.
+        let Some(provider_payment_id) = dispute.payment_intent else {
+            warn!(
+                stripe_event_id = ?event.id,
+                dispute_id = ?dispute.id,
+                charge_id = ?dispute.charge,
+                "subscriptions: dispute without payment_intent; skipping"
+            );
+            return Ok(());
+        };
+
+        let Some(payment) = self
+            .find_payment_for_reversal(event, &provider_payment_id)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let dispute_reason = dispute.reason.as_deref().unwrap_or("unknown");
+        let dispute_status = dispute.status.as_deref().unwrap_or_default();
+        let (payment_status, invoice_status, reason, lost) = match (closed, dispute_status) {
+            (false, _) => (
+                PaymentStatus::Disputed,
+                "disputed",
+                format!("dispute opened: {dispute_reason}"),
+                false,
+            ),
+            (true, "lost") => (
+                PaymentStatus::Disputed,
+                "disputed",
+                format!("dispute lost: {dispute_reason}"),
+                true,
+            ),
+            (true, "won") => (
+                PaymentStatus::Succeeded,
+                "paid",
+                format!("dispute won: {dispute_reason}"),
+                false,
+            ),
+            (true, other) => (
+                PaymentStatus::Succeeded,
+                "paid",
+                format!("dispute closed ({other}): {dispute_reason}"),
+                false,
+            ),
+        };
+
+        // A closed dispute only undoes the dispute itself; a refund recorded before or
+        // during it keeps its status and refunded amount.
+        if payment_status == PaymentStatus::Succeeded
+            && payment.status != PaymentStatus::Disputed.to_string()
+        {
+            info!(
+                stripe_event_id = ?event.id,
+                dispute_id = ?dispute.id,
+                provider_payment_id = %provider_payment_id,
+                payment_status = %payment.status,
+                reason = %reason,
+                "subscriptions: dispute closed on a payment that is no longer disputed; keeping its status"
+            );
+            return Ok(());
+        }
+
+        info!(
+            stripe_event_id = ?event.id,
+            dispute_id = ?dispute.id,
+            provider_payment_id = %provider_payment_id,
+            reason = %reason,
+            "subscriptions: recording dispute"
+        );
+
+        self.payment_repo
+            .record_dispute(&provider_payment_id, payment_status, Some(reason.clone()))
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    provider_payment_id = %provider_payment_id,
+                    db_error = ?err,
+                    "subscriptions: failed to record dispute"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        self.update_invoice_status_for_reversal(event, &payment, invoice_status)
+            .await?;
+        if lost && self.reversal_policy.end_on_lost_dispute {
+            self.end_subscription_for_payment(event, &payment, reason)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn find_payment_for_reversal(
+        &self,
+        event: &StripeEvent,
+        provider_payment_id: &str,
+    ) -> UseCaseResult<Option<PaymentEntity>> {
+        let payment = self
+            .payment_repo
+            .find_by_provider_payment_id(provider_payment_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    provider_payment_id = %provider_payment_id,
+                    db_error = ?err,
+                    "subscriptions: failed to load payment for refund or dispute"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        if payment.is_none() {
+            info!(
+                stripe_event_id = ?event.id,
+                provider_payment_id = %provider_payment_id,
+                "subscriptions: refund or dispute for unknown payment; skipping"
+            );
+        }
+
+        Ok(payment)
+    }
+
+    async fn update_invoice_status_for_reversal(
+        &self,
+        event: &StripeEvent,
+        payment: &PaymentEntity,
+        status: &str,
+    ) -> UseCaseResult<()> {
+        self.invoice_repo
+            .update_status_by_id(payment.invoice_id, status)
             .await
             .map_err(|err| {
                 error!(
                     stripe_event_id = ?event.id,
-                    payment_intent_id = %payment_intent_id,
+                    invoice_id = %payment.invoice_id,
+                    status,
                     db_error = ?err,
-                    "subscriptions: failed to load subscription after payment_intent failure"
+                    "subscriptions: failed to update invoice status for refund or dispute"
                 );
                 SubscriptionError::Internal(err)
-            })?;
-
-        let Some(subscription) = subscription else {
-            info!(
-                stripe_event_id = ?event.id,
-                payment_intent_id = %payment_intent_id,
-                "subscriptions: payment_intent failed without local subscription"
-            );
-            return Ok(());
-        };
-
-        if BillingMode::from_str(&subscription.billing_mode) != Some(BillingMode::OneTime) {
-            info!(
-                stripe_event_id = ?event.id,
-                payment_intent_id = %payment_intent_id,
-                billing_mode = %subscription.billing_mode,
-                "subscriptions: ignoring payment_intent failure for non one-time subscription"
-            );
-            return Ok(());
-        }
+            })
+    }
 
-        self.subscription_repo
-            .update_status_by_provider_subscription_id(
-                &payment_intent_id,
-                SubscriptionStatus::Expired,
-            )
+    /// Ends the subscription paid for by `payment` right away and, for recurring billing, stops
+    /// Stripe from charging again. `reason` is kept on the subscription for support.
+    async fn end_subscription_for_payment(
+        &self,
+        event: &StripeEvent,
+        payment: &PaymentEntity,
+        reason: String,
+    ) -> UseCaseResult<()> {
+        let invoice = self
+            .invoice_repo
+            .find_by_id_and_user(payment.invoice_id, payment.user_id)
             .await
             .map_err(|err| {
                 error!(
                     stripe_event_id = ?event.id,
-                    payment_intent_id = %payment_intent_id,
+                    invoice_id = %payment.invoice_id,
                     db_error = ?err,
-                    "subscriptions: failed to expire subscription after payment_intent failure"
+                    "subscriptions: failed to load invoice for reversed payment"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        let currency = currency.unwrap_or_else(|| "thb".to_string());
-
-        let plan_price_minor = match amount_minor {
-            Some(value) => value,
-            None => {
-                let plan = self
-                    .plan_repo
-                    .find_active_plan_by_id(subscription.plan_id)
-                    .await
-                    .map_err(|err| {
-                        error!(
-                            stripe_event_id = ?event.id,
-                            plan_id = %subscription.plan_id,
-                            db_error = ?err,
-                            "subscriptions: failed to load plan for payment_intent failure"
-                        );
-                        SubscriptionError::Internal(err)
-                    })?;
-                plan.price_minor
-            }
+        let Some(subscription_id) = invoice.and_then(|invoice| invoice.subscription_id) else {
+            return self.void_gift_for_payment(event, payment, reason).await;
         };
 
-        let invoice_id = self
-            .ensure_invoice_for_subscription(
-                &subscription,
-                subscription.starts_at,
-                subscription.ends_at,
-                plan_price_minor,
-                currency.clone(),
-            )
-            .await?;
-
-        self.invoice_repo
-            .update_status_by_id(invoice_id, invoice_status)
+        let ended = self
+            .subscription_repo
+            .end_subscription_early(subscription_id, reason.clone(), Utc::now())
             .await
             .map_err(|err| {
                 error!(
                     stripe_event_id = ?event.id,
-                    invoice_id = %invoice_id,
+                    %subscription_id,
                     db_error = ?err,
-                    "subscriptions: failed to update invoice after payment_intent failure"
+                    "subscriptions: failed to end subscription for reversed payment"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        let payment_exists = self
-            .payment_repo
-            .exists_by_provider_payment_id(&payment_intent_id)
+        self.invalidate_effective_plan(payment.user_id);
+        self.enforce_follow_limit(payment.user_id).await;
+
+        let Some(subscription) = ended else {
+            info!(
+                stripe_event_id = ?event.id,
+                %subscription_id,
+                "subscriptions: subscription for reversed payment already ended"
+            );
+            return Ok(());
+        };
+
+        warn!(
+            stripe_event_id = ?event.id,
+            %subscription_id,
+            user_id = %subscription.user_id,
+            reason = %reason,
+            "subscriptions: subscription ended after payment reversal"
+        );
+
+        // The local subscription is already ended, so a retry would not get here again;
+        // log loudly instead of failing the event.
+        if BillingMode::from_str(&subscription.billing_mode) == Some(BillingMode::Recurring)
+            && let Some(provider_subscription_id) = subscription.provider_subscription_id.as_deref()
+            && let Err(err) = self
+                .stripe_client
+                .cancel_subscription_now(provider_subscription_id)
+                .await
+        {
+            error!(
+                stripe_event_id = ?event.id,
+                %subscription_id,
+                %provider_subscription_id,
+                error = ?err,
+                "subscriptions: failed to cancel provider subscription after payment reversal"
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Gift invoices have no subscription; an unredeemed code paid by a reversed payment is
+    /// voided instead. Codes already redeemed keep the recipient's subscription.
+    async fn void_gift_for_payment(
+        &self,
+        event: &StripeEvent,
+        payment: &PaymentEntity,
+        reason: String,
+    ) -> UseCaseResult<()> {
+        let voided = self
+            .gift_repo
+            .void_unredeemed_by_invoice_id(payment.invoice_id, reason)
             .await
             .map_err(|err| {
                 error!(
                     stripe_event_id = ?event.id,
-                    payment_intent_id = %payment_intent_id,
+                    invoice_id = %payment.invoice_id,
                     db_error = ?err,
-                    "subscriptions: failed to check payment after payment_intent failure"
+                    "subscriptions: failed to void gift code for reversed payment"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        if payment_exists {
-            self.payment_repo
-                .update_status_by_provider_payment_id(&payment_intent_id, payment_status)
-                .await
-                .map_err(|err| {
-                    error!(
-                        stripe_event_id = ?event.id,
-                        payment_intent_id = %payment_intent_id,
-                        db_error = ?err,
-                        "subscriptions: failed to update payment after payment_intent failure"
-                    );
-                    SubscriptionError::Internal(err)
-                })?;
-        } else {
-            let amount_minor = amount_minor.unwrap_or(plan_price_minor);
-            self.payment_repo
-                .record_payment(crates::domain::entities::payments::NewPaymentEntity {
-                    invoice_id,
-                    user_id: subscription.user_id,
-                    provider: "stripe".to_string(),
-                    method_type: payment_method.to_string(),
-                    payment_method_id: None,
-                    amount_minor,
-                    currency: currency.clone(),
-                    status: payment_status.to_string(),
-                    provider_payment_id: Some(payment_intent_id.clone()),
-                    provider_session_ref: None,
-                    error: None,
-                })
-                .await
-                .map_err(|err| {
-                    error!(
-                        stripe_event_id = ?event.id,
-                        payment_intent_id = %payment_intent_id,
-                        db_error = ?err,
-                        "subscriptions: failed to record payment after payment_intent failure"
-                    );
-                    SubscriptionError::Internal(err)
-                })?;
+        match voided {
+            Some(gift) => warn!(
+                stripe_event_id = ?event.id,
+                gift_code_id = %gift.id,
+                purchaser_id = %gift.purchaser_id,
+                "subscriptions: gift code voided after payment reversal"
+            ),
+            None => warn!(
+                stripe_event_id = ?event.id,
+                invoice_id = %payment.invoice_id,
+                "subscriptions: reversed payment has no linked subscription"
+            ),
         }
 
         Ok(())
@@ -1725,6 +3467,24 @@ where
                 SubscriptionError::Internal(err)
             })?;
 
+        match self
+            .subscription_repo
+            .find_by_provider_subscription_id(&subscription_id)
+            .await
+        {
+            Ok(Some(subscription)) => {
+                self.invalidate_effective_plan(subscription.user_id);
+                self.enforce_follow_limit(subscription.user_id).await;
+            }
+            Ok(None) => {}
+            // The status is already stored; a stale cached plan only lasts until its TTL.
+            Err(err) => warn!(
+                subscription_id = %subscription_id,
+                db_error = ?err,
+                "subscriptions: failed to load deleted subscription for plan cache invalidation"
+            ),
+        }
+
         Ok(())
     }
 
@@ -1798,7 +3558,12 @@ where
                 SubscriptionError::Internal(err)
             })?;
 
-        let currency = context.currency.clone().unwrap_or_else(|| "thb".to_string());
+        self.invalidate_effective_plan(subscription.user_id);
+
+        let currency = context
+            .currency
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
 
         let plan_price_minor = match context.amount_minor() {
             Some(value) => value,
@@ -1816,7 +3581,7 @@ where
                         );
                         SubscriptionError::Internal(err)
                     })?;
-                plan.price_minor
+                plan.price_minor_in(&currency)
             }
         };
 
@@ -1830,6 +3595,10 @@ where
             )
             .await?;
 
+        self.store_invoice_links(event, invoice_id, &context).await?;
+        self.store_invoice_discount(event, invoice_id, context.discount_minor(), None)
+            .await?;
+
         self.invoice_repo
             .mark_invoice_paid(invoice_id)
             .await
@@ -1894,6 +3663,8 @@ where
                         provider_payment_id: Some(provider_payment_id.to_string()),
                         provider_session_ref: None,
                         error: None,
+                        discount_minor: context.discount_minor(),
+                        promotion_code: None,
                     })
                     .await
                     .map_err(|err| {
@@ -1985,7 +3756,12 @@ where
                 SubscriptionError::Internal(err)
             })?;
 
-        let currency = context.currency.clone().unwrap_or_else(|| "thb".to_string());
+        self.invalidate_effective_plan(subscription.user_id);
+
+        let currency = context
+            .currency
+            .clone()
+            .unwrap_or_else(|| DEFAULT_CURRENCY.to_string());
 
         let plan_price_minor = match context.amount_minor() {
             Some(value) => value,
@@ -2003,7 +3779,7 @@ where
                         );
                         SubscriptionError::Internal(err)
                     })?;
-                plan.price_minor
+                plan.price_minor_in(&currency)
             }
         };
 
@@ -2017,6 +3793,10 @@ where
             )
             .await?;
 
+        self.store_invoice_links(event, invoice_id, &context).await?;
+        self.store_invoice_discount(event, invoice_id, context.discount_minor(), None)
+            .await?;
+
         self.invoice_repo
             .update_status_by_id(invoice_id, "past_due")
             .await
@@ -2081,6 +3861,8 @@ where
                         provider_payment_id: Some(provider_payment_id.to_string()),
                         provider_session_ref: None,
                         error: None,
+                        discount_minor: context.discount_minor(),
+                        promotion_code: None,
                     })
                     .await
                     .map_err(|err| {
@@ -2101,9 +3883,58 @@ where
             );
         }
 
+        let attempt_ref = context
+            .invoice_id
+            .clone()
+            .or_else(|| event.id.clone())
+            .unwrap_or_else(|| period.1.timestamp().to_string());
+        let mut notification =
+            SubscriptionNotificationPayload::payment_failed(&subscription, &attempt_ref);
+        notification.ends_at = period.1;
+        self.enqueue_subscription_notification(event, notification)
+            .await;
+
         Ok(())
     }
 
+    /// Notification failures are logged only; the billing state is already persisted.
+    async fn enqueue_subscription_notification(
+        &self,
+        event: &StripeEvent,
+        notification: SubscriptionNotificationPayload,
+    ) {
+        let dedupe_key = notification.dedupe_key.clone();
+        match self
+            .job_repo
+            .enqueue_subscription_notification_job(notification)
+            .await
+        {
+            Ok(Some(job_id)) => {
+                info!(
+                    stripe_event_id = ?event.id,
+                    %job_id,
+                    dedupe_key,
+                    "subscriptions: subscription notification enqueued"
+                );
+            }
+            Ok(None) => {
+                info!(
+                    stripe_event_id = ?event.id,
+                    dedupe_key,
+                    "subscriptions: subscription notification already enqueued"
+                );
+            }
+            Err(err) => {
+                error!(
+                    stripe_event_id = ?event.id,
+                    dedupe_key,
+                    db_error = ?err,
+                    "subscriptions: failed to enqueue subscription notification"
+                );
+            }
+        }
+    }
+
     fn parse_payment_intent_event(
         event: &StripeEvent,
     ) -> UseCaseResult<(String, Option<i32>, Option<String>, PaymentMethod)> {
@@ -2170,10 +4001,18 @@ where
             amount_paid: Option<i64>,
             currency: Option<String>,
             payment_intent: Option<String>,
+            hosted_invoice_url: Option<String>,
+            invoice_pdf: Option<String>,
+            total_discount_amounts: Option<Vec<InvoiceDiscountAmount>>,
             parent: Option<InvoiceParent>,
             lines: Option<InvoiceLines>,
         }
 
+        #[derive(Deserialize)]
+        struct InvoiceDiscountAmount {
+            amount: i64,
+        }
+
         #[derive(Deserialize)]
         struct InvoiceParent {
             subscription_details: Option<InvoiceSubscriptionDetails>,
@@ -2310,6 +4149,11 @@ where
             period_end: invoice_period.map(|value| value.1),
             amount_due: invoice.amount_due,
             amount_paid: invoice.amount_paid,
+            hosted_invoice_url: invoice.hosted_invoice_url,
+            invoice_pdf: invoice.invoice_pdf,
+            discount_minor: invoice
+                .total_discount_amounts
+                .map(|amounts| amounts.iter().map(|discount| discount.amount).sum()),
         })
     }
 
@@ -2382,39 +4226,151 @@ where
                 SubscriptionError::Internal(err)
             })?;
 
-        if let Some(invoice) = existing {
-            return Ok(invoice.id);
-        }
+        if let Some(invoice) = existing {
+            return Ok(invoice.id);
+        }
+
+        let invoice_id = self
+            .invoice_repo
+            .create_invoice(crates::domain::entities::invoices::InsertInvoiceEntity {
+                user_id: subscription.user_id,
+                subscription_id: Some(subscription.id),
+                plan_id: subscription.plan_id,
+                amount_minor,
+                currency,
+                period_start,
+                period_end,
+                due_at: period_start,
+                status: "pending".to_string(),
+                paid_at: None,
+            })
+            .await
+            .map_err(|err| {
+                error!(
+                    subscription_id = %subscription.id,
+                    db_error = ?err,
+                    "subscriptions: failed to create invoice for subscription"
+                );
+                SubscriptionError::Internal(err)
+            })?;
+
+        Ok(invoice_id)
+    }
+
+    async fn store_invoice_links(
+        &self,
+        event: &StripeEvent,
+        invoice_id: Uuid,
+        context: &InvoiceContext,
+    ) -> UseCaseResult<()> {
+        self.invoice_repo
+            .update_provider_links(
+                invoice_id,
+                context.invoice_id.clone(),
+                context.hosted_invoice_url.clone(),
+                context.invoice_pdf.clone(),
+            )
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    invoice_id = %invoice_id,
+                    db_error = ?err,
+                    "subscriptions: failed to store invoice links from invoice webhook"
+                );
+                SubscriptionError::Internal(err)
+            })
+    }
+
+    async fn store_invoice_discount(
+        &self,
+        event: &StripeEvent,
+        invoice_id: Uuid,
+        discount_minor: i32,
+        promotion_code: Option<String>,
+    ) -> UseCaseResult<()> {
+        if discount_minor <= 0 && promotion_code.is_none() {
+            return Ok(());
+        }
+
+        info!(
+            stripe_event_id = ?event.id,
+            invoice_id = %invoice_id,
+            discount_minor,
+            promotion_code = ?promotion_code,
+            "subscriptions: recording discount on invoice"
+        );
+
+        self.invoice_repo
+            .update_discount(invoice_id, discount_minor.max(0), promotion_code)
+            .await
+            .map_err(|err| {
+                error!(
+                    stripe_event_id = ?event.id,
+                    invoice_id = %invoice_id,
+                    db_error = ?err,
+                    "subscriptions: failed to record discount on invoice"
+                );
+                SubscriptionError::Internal(err)
+            })
+    }
+
+    fn checkout_discount_minor(session: &StripeCheckoutSession) -> i32 {
+        session
+            .total_details
+            .as_ref()
+            .and_then(|details| details.amount_discount)
+            .and_then(|value| i32::try_from(value).ok())
+            .unwrap_or(0)
+    }
+
+    fn checkout_paid(status: Option<&str>) -> bool {
+        matches!(status, Some("paid") | Some("no_payment_required"))
+    }
 
-        let invoice_id = self
-            .invoice_repo
-            .create_invoice(crates::domain::entities::invoices::InsertInvoiceEntity {
-                user_id: subscription.user_id,
-                subscription_id: Some(subscription.id),
-                plan_id: subscription.plan_id,
-                amount_minor,
-                currency,
-                period_start,
-                period_end,
-                due_at: period_start,
-                status: "pending".to_string(),
-                paid_at: None,
-            })
+    /// Places a one-time term of `months` after everything the user has scheduled.
+    async fn plan_one_time_term(
+        &self,
+        user_id: Uuid,
+        plan_id: Uuid,
+        months: i32,
+    ) -> UseCaseResult<OneTimeTerm> {
+        let now = Utc::now();
+        let scheduled = self
+            .subscription_repo
+            .list_subscriptions_ending_after(user_id, now)
             .await
             .map_err(|err| {
                 error!(
-                    subscription_id = %subscription.id,
+                    %user_id,
                     db_error = ?err,
-                    "subscriptions: failed to create invoice for subscription"
+                    "subscriptions: failed to load scheduled subscriptions for one-time term"
                 );
                 SubscriptionError::Internal(err)
             })?;
 
-        Ok(invoice_id)
+        let months = u32::try_from(months).context("invalid one-time term length")?;
+        let term = plan_one_time_term(&scheduled, plan_id, months, now)?;
+        info!(
+            %user_id,
+            %plan_id,
+            months,
+            stacking = ?term.stacking,
+            starts_at = %term.starts_at,
+            ends_at = %term.ends_at,
+            "subscriptions: placed one-time term"
+        );
+        Ok(term)
     }
 
-    fn checkout_paid(status: Option<&str>) -> bool {
-        matches!(status, Some("paid") | Some("no_payment_required"))
+    /// Total months bought by a checkout, when it was created with term options.
+    fn term_months_from_metadata(metadata: &HashMap<String, String>) -> Option<i32> {
+        let term_months = metadata.get("term_months")?.parse::<i32>().ok()?;
+        let quantity = metadata
+            .get("term_quantity")
+            .and_then(|value| value.parse::<i32>().ok())
+            .unwrap_or(1);
+        Some(term_months * quantity)
     }
 
     fn one_time_period_from_metadata(
@@ -2446,3 +4402,1261 @@ where
         Utc.timestamp_opt(ts, 0).single()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crates::domain::{
+        entities::{
+            invoices::InvoiceEntity, payments::PaymentEntity, plan_prices::PlanPriceEntity,
+            plans::PlanEntity, stripe_events::StripeEventEntity,
+        },
+        repositories::{
+            gift_codes::MockGiftCodeRepository, invoices::MockInvoiceRepository,
+            job::MockJobRepository, live_following::MockLiveFollowingRepository,
+            payment_provider_customers::MockPaymentProviderCustomerRepository,
+            payments::MockPaymentRepository, plans::MockPlanRepository,
+            stripe_events::MockStripeEventRepository, subscriptions::MockSubscriptionRepository,
+        },
+        value_objects::plans::FREE_PLAN_ID,
+    };
+    use mockall::predicate::eq;
+
+    type TestUseCase = SubscriptionUseCase<
+        MockPlanRepository,
+        MockSubscriptionRepository,
+        MockPaymentRepository,
+        MockPaymentProviderCustomerRepository,
+        MockInvoiceRepository,
+        MockStripeEventRepository,
+        MockJobRepository,
+        MockGiftCodeRepository,
+        MockLiveFollowingRepository,
+        MockStripeGateway,
+    >;
+
+    struct Mocks {
+        plan_repo: MockPlanRepository,
+        subscription_repo: MockSubscriptionRepository,
+        payment_repo: MockPaymentRepository,
+        customer_repo: MockPaymentProviderCustomerRepository,
+        invoice_repo: MockInvoiceRepository,
+        stripe_event_repo: MockStripeEventRepository,
+        job_repo: MockJobRepository,
+        gift_repo: MockGiftCodeRepository,
+        stripe: MockStripeGateway,
+        live_following_repo: MockLiveFollowingRepository,
+    }
+
+    impl Mocks {
+        fn new() -> Self {
+            Self {
+                plan_repo: MockPlanRepository::new(),
+                subscription_repo: MockSubscriptionRepository::new(),
+                payment_repo: MockPaymentRepository::new(),
+                customer_repo: MockPaymentProviderCustomerRepository::new(),
+                invoice_repo: MockInvoiceRepository::new(),
+                stripe_event_repo: MockStripeEventRepository::new(),
+                job_repo: MockJobRepository::new(),
+                gift_repo: MockGiftCodeRepository::new(),
+                stripe: MockStripeGateway::new(),
+                live_following_repo: MockLiveFollowingRepository::new(),
+            }
+        }
+
+        fn into_usecase(self) -> TestUseCase {
+            SubscriptionUseCase::new(
+                SubscriptionRepositories {
+                    plan_repo: Arc::new(self.plan_repo),
+                    subscription_repo: Arc::new(self.subscription_repo),
+                    payment_repo: Arc::new(self.payment_repo),
+                    customer_repo: Arc::new(self.customer_repo),
+                    invoice_repo: Arc::new(self.invoice_repo),
+                    stripe_event_repo: Arc::new(self.stripe_event_repo),
+                    job_repo: Arc::new(self.job_repo),
+                    gift_repo: Arc::new(self.gift_repo),
+                    live_following_repo: Arc::new(self.live_following_repo),
+                },
+                Arc::new(self.stripe),
+                FREE_PLAN_ID,
+            )
+        }
+    }
+
+    fn sample_plan(id: Uuid) -> PlanEntity {
+        PlanEntity {
+            id,
+            name: Some("Premium".to_string()),
+            price_minor: 9900,
+            duration_days: 30,
+            features: Default::default(),
+            is_active: true,
+            prices: vec![
+                sample_price(
+                    id,
+                    "thb",
+                    BillingMode::Recurring,
+                    PaymentMethod::Card,
+                    "price_recurring",
+                ),
+                sample_price(
+                    id,
+                    "thb",
+                    BillingMode::OneTime,
+                    PaymentMethod::Card,
+                    "price_card",
+                ),
+                sample_price(
+                    id,
+                    "thb",
+                    BillingMode::OneTime,
+                    PaymentMethod::PromptPay,
+                    "price_promptpay",
+                ),
+                sample_price(
+                    id,
+                    "usd",
+                    BillingMode::OneTime,
+                    PaymentMethod::Card,
+                    "price_card_usd",
+                ),
+                PlanPriceEntity {
+                    term_months: 3,
+                    amount_minor: 26900,
+                    ..sample_price(
+                        id,
+                        "thb",
+                        BillingMode::OneTime,
+                        PaymentMethod::Card,
+                        "price_card_3m",
+                    )
+                },
+            ],
+        }
+    }
+
+    fn sample_price(
+        plan_id: Uuid,
+        currency: &str,
+        billing_mode: BillingMode,
+        payment_method: PaymentMethod,
+        stripe_price_id: &str,
+    ) -> PlanPriceEntity {
+        PlanPriceEntity {
+            id: Uuid::new_v4(),
+            plan_id,
+            currency: currency.to_string(),
+            billing_mode: billing_mode.to_string(),
+            payment_method: payment_method.to_string(),
+            amount_minor: if currency == "usd" { 299 } else { 9900 },
+            stripe_price_id: stripe_price_id.to_string(),
+            is_active: true,
+            created_at: Utc::now(),
+            term_months: 1,
+        }
+    }
+
+    fn selection(
+        billing_mode: BillingMode,
+        payment_method: PaymentMethod,
+        currency: &str,
+    ) -> CheckoutPriceSelection {
+        CheckoutPriceSelection {
+            billing_mode,
+            payment_method,
+            currency: currency.to_string(),
+            term_months: 1,
+            quantity: 1,
+        }
+    }
+
+    fn sample_promotion_code(active: bool, valid: bool) -> StripePromotionCode {
+        StripePromotionCode {
+            id: "promo_123".to_string(),
+            code: "LAUNCH20".to_string(),
+            active,
+            coupon: crates::payments::stripe_client::StripeCoupon {
+                id: "coupon_123".to_string(),
+                percent_off: Some(20.0),
+                amount_off: None,
+                currency: None,
+                valid: Some(valid),
+            },
+        }
+    }
+
+    fn sample_invoice(user_id: Uuid, created_at: DateTime<Utc>) -> InvoiceEntity {
+        InvoiceEntity {
+            id: Uuid::new_v4(),
+            user_id,
+            subscription_id: Some(Uuid::new_v4()),
+            plan_id: Uuid::new_v4(),
+            amount_minor: 9900,
+            currency: "thb".to_string(),
+            period_start: created_at,
+            period_end: created_at + Duration::days(30),
+            due_at: created_at,
+            status: "paid".to_string(),
+            created_at,
+            paid_at: Some(created_at),
+            provider_invoice_ref: Some("in_123".to_string()),
+            hosted_invoice_url: Some("https://invoice.stripe.com/i/in_123".to_string()),
+            invoice_pdf_url: None,
+            discount_minor: 0,
+            promotion_code: None,
+        }
+    }
+
+    fn sample_payment(
+        invoice: &InvoiceEntity,
+        method: PaymentMethod,
+        created_at: DateTime<Utc>,
+    ) -> PaymentEntity {
+        PaymentEntity {
+            id: Uuid::new_v4(),
+            invoice_id: invoice.id,
+            user_id: invoice.user_id,
+            provider: "stripe".to_string(),
+            method_type: method.to_string(),
+            payment_method_id: None,
+            amount_minor: invoice.amount_minor,
+            currency: invoice.currency.clone(),
+            status: PaymentStatus::Succeeded.to_string(),
+            provider_payment_id: Some(format!("pi_{}", Uuid::new_v4().simple())),
+            provider_session_ref: None,
+            error: None,
+            created_at,
+            updated_at: created_at,
+            receipt_url: None,
+            discount_minor: 0,
+            promotion_code: None,
+            refunded_minor: 0,
+            status_reason: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn list_invoices_pages_and_attaches_latest_payment_method() {
+        let user_id = Uuid::new_v4();
+        let now = Utc::now();
+        let newest = sample_invoice(user_id, now);
+        let older = sample_invoice(user_id, now - Duration::days(30));
+        let oldest = sample_invoice(user_id, now - Duration::days(60));
+
+        let latest_payment = sample_payment(&newest, PaymentMethod::PromptPay, now);
+        let earlier_payment =
+            sample_payment(&newest, PaymentMethod::Card, now - Duration::minutes(5));
+
+        let mut mocks = Mocks::new();
+        let invoices = vec![newest.clone(), older.clone(), oldest];
+        mocks
+            .invoice_repo
+            .expect_list_by_user()
+            .with(eq(user_id), eq(3), eq(None), eq(None))
+            .returning(move |_, _, _, _| {
+                let invoices = invoices.clone();
+                Box::pin(async move { Ok(invoices) })
+            });
+
+        let payments = vec![latest_payment, earlier_payment];
+        mocks
+            .payment_repo
+            .expect_list_by_invoice_ids()
+            .with(eq(vec![newest.id, older.id]))
+            .returning(move |_| {
+                let payments = payments.clone();
+                Box::pin(async move { Ok(payments) })
+            });
+
+        let usecase = mocks.into_usecase();
+        let page = usecase.list_invoices(user_id, 2, None).await.unwrap();
+
+        assert!(page.has_more);
+        assert_eq!(page.items.len(), 2);
+        assert_eq!(
+            page.items[0].payment_method_type.as_deref(),
+            Some("promptpay")
+        );
+        assert_eq!(page.items[1].payment_method_type, None);
+        let cursor = page.next_cursor.unwrap();
+        assert_eq!(cursor.id, older.id);
+        assert_eq!(cursor.created_at, older.created_at);
+    }
+
+    #[tokio::test]
+    async fn get_invoice_receipt_returns_not_found_for_foreign_invoice() {
+        let user_id = Uuid::new_v4();
+        let invoice_id = Uuid::new_v4();
+
+        let mut mocks = Mocks::new();
+        mocks
+            .invoice_repo
+            .expect_find_by_id_and_user()
+            .with(eq(invoice_id), eq(user_id))
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+
+        let usecase = mocks.into_usecase();
+        let err = usecase
+            .get_invoice_receipt(user_id, invoice_id)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SubscriptionError::InvoiceNotFound));
+    }
+
+    #[tokio::test]
+    async fn get_invoice_receipt_combines_invoice_links_and_payment_receipt() {
+        let user_id = Uuid::new_v4();
+        let now = Utc::now();
+        let invoice = sample_invoice(user_id, now);
+        let mut payment = sample_payment(&invoice, PaymentMethod::Card, now);
+        payment.receipt_url = Some("https://pay.stripe.com/receipts/abc".to_string());
+
+        let mut mocks = Mocks::new();
+        let found = invoice.clone();
+        mocks
+            .invoice_repo
+            .expect_find_by_id_and_user()
+            .with(eq(invoice.id), eq(user_id))
+            .returning(move |_, _| {
+                let invoice = found.clone();
+                Box::pin(async move { Ok(Some(invoice)) })
+            });
+
+        mocks
+            .payment_repo
+            .expect_list_by_invoice_ids()
+            .with(eq(vec![invoice.id]))
+            .returning(move |_| {
+                let payment = payment.clone();
+                Box::pin(async move { Ok(vec![payment]) })
+            });
+
+        let usecase = mocks.into_usecase();
+        let receipt = usecase
+            .get_invoice_receipt(user_id, invoice.id)
+            .await
+            .unwrap();
+
+        assert_eq!(receipt.invoice_id, invoice.id);
+        assert_eq!(receipt.hosted_invoice_url, invoice.hosted_invoice_url);
+        assert_eq!(
+            receipt.receipt_url.as_deref(),
+            Some("https://pay.stripe.com/receipts/abc")
+        );
+    }
+
+    fn checkout_mocks(user_id: Uuid, plan_id: Uuid) -> Mocks {
+        let mut mocks = Mocks::new();
+        mocks
+            .plan_repo
+            .expect_find_active_plan_by_id()
+            .with(eq(plan_id))
+            .returning(move |_| Box::pin(async move { Ok(sample_plan(plan_id)) }));
+        mocks
+            .subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .with(eq(user_id), eq(FREE_PLAN_ID))
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        mocks
+            .customer_repo
+            .expect_find_or_create_stripe_customer_id()
+            .returning(|_, _| Box::pin(async { Ok("cus_123".to_string()) }));
+        mocks
+            .subscription_repo
+            .expect_list_subscriptions_ending_after()
+            .returning(|_, _| Box::pin(async { Ok(Vec::new()) }));
+        mocks
+    }
+
+    #[tokio::test]
+    async fn checkout_applies_validated_promotion_code() {
+        let user_id = Uuid::new_v4();
+        let plan_id = Uuid::new_v4();
+        let mut mocks = checkout_mocks(user_id, plan_id);
+
+        mocks
+            .stripe
+            .expect_find_promotion_code()
+            .with(eq("LAUNCH20"))
+            .returning(|_| Ok(Some(sample_promotion_code(true, true))));
+        mocks
+            .stripe
+            .expect_create_checkout_session()
+            .withf(|price_id, _, mode, _, metadata, promotion| {
+                price_id == "price_card"
+                    && mode == "payment"
+                    && metadata.get("promotion_code").map(String::as_str) == Some("LAUNCH20")
+                    && *promotion == CheckoutPromotion::Code("promo_123".to_string())
+            })
+            .returning(|_, _, _, _, _, _| Ok("https://checkout.stripe.com/c/pay".to_string()));
+
+        let url = mocks
+            .into_usecase()
+            .create_checkout_session(
+                user_id,
+                Some("user@example.com".to_string()),
+                plan_id,
+                selection(BillingMode::OneTime, PaymentMethod::Card, "thb"),
+                CheckoutPromotion::Code("LAUNCH20".to_string()),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(url, "https://checkout.stripe.com/c/pay");
+    }
+
+    #[tokio::test]
+    async fn checkout_rejects_unknown_or_expired_promotion_code() {
+        for promotion in [None, Some(sample_promotion_code(true, false))] {
+            let user_id = Uuid::new_v4();
+            let plan_id = Uuid::new_v4();
+            let mut mocks = checkout_mocks(user_id, plan_id);
+
+            mocks
+                .stripe
+                .expect_find_promotion_code()
+                .returning(move |_| Ok(promotion.clone()));
+            mocks.stripe.expect_create_checkout_session().never();
+
+            let err = mocks
+                .into_usecase()
+                .create_checkout_session(
+                    user_id,
+                    Some("user@example.com".to_string()),
+                    plan_id,
+                    selection(BillingMode::Recurring, PaymentMethod::Card, "thb"),
+                    CheckoutPromotion::Code("LAUNCH20".to_string()),
+                )
+                .await
+                .unwrap_err();
+
+            assert!(matches!(err, SubscriptionError::InvalidPromotionCode(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn checkout_picks_price_in_requested_currency() {
+        let user_id = Uuid::new_v4();
+        let plan_id = Uuid::new_v4();
+        let mut mocks = checkout_mocks(user_id, plan_id);
+
+        mocks
+            .stripe
+            .expect_create_checkout_session()
+            .withf(|price_id, _, _, _, metadata, _| {
+                price_id == "price_card_usd"
+                    && metadata.get("currency").map(String::as_str) == Some("usd")
+            })
+            .returning(|_, _, _, _, _, _| Ok("https://checkout.stripe.com/c/pay".to_string()));
+
+        let usecase = mocks.into_usecase();
+        let url = usecase
+            .create_checkout_session(
+                user_id,
+                Some("user@example.com".to_string()),
+                plan_id,
+                selection(BillingMode::OneTime, PaymentMethod::Card, "usd"),
+                CheckoutPromotion::None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(url, "https://checkout.stripe.com/c/pay");
+
+        let err = usecase
+            .create_checkout_session(
+                user_id,
+                Some("user@example.com".to_string()),
+                plan_id,
+                selection(BillingMode::Recurring, PaymentMethod::Card, "usd"),
+                CheckoutPromotion::None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SubscriptionError::MissingPrice(_)));
+    }
+
+    fn stripe_event_payload(event_id: &str, event_type: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": event_id,
+            "type": event_type,
+            "data": { "object": { "object": "checkout.session" } }
+        })
+    }
+
+    fn stored_stripe_event(
+        payload: serde_json::Value,
+        processed_at: Option<DateTime<Utc>>,
+    ) -> StripeEventEntity {
+        StripeEventEntity {
+            id: payload["id"].as_str().unwrap_or_default().to_string(),
+            type_: payload["type"].as_str().unwrap_or_default().to_string(),
+            payload,
+            received_at: Utc::now(),
+            processed_at,
+            error: None,
+            attempts: 0,
+            claimed_at: None,
+        }
+    }
+
+    fn expect_claim(mocks: &mut Mocks, event_id: &'static str, reprocess: bool, claimed: bool) {
+        mocks
+            .stripe_event_repo
+            .expect_claim()
+            .withf(move |id, _, flag| id == event_id && *flag == reprocess)
+            .times(1)
+            .returning(move |_, _, _| Box::pin(async move { Ok(claimed) }));
+    }
+
+    #[tokio::test]
+    async fn webhook_skips_events_already_marked_processed() {
+        let mut mocks = Mocks::new();
+        let payload = stripe_event_payload("evt_processed", "checkout.session.completed");
+        let stored = stored_stripe_event(payload.clone(), Some(Utc::now()));
+
+        let verified = payload.clone();
+        mocks
+            .stripe
+            .expect_verify_webhook_signature()
+            .returning(move |_, _| Ok(serde_json::from_value(verified.clone()).unwrap()));
+        mocks
+            .stripe_event_repo
+            .expect_record_received()
+            .withf(|event| {
+                event.id == "evt_processed" && event.type_ == "checkout.session.completed"
+            })
+            .times(1)
+            .returning(move |_| {
+                let stored = stored.clone();
+                Box::pin(async move { Ok(stored) })
+            });
+        mocks.stripe_event_repo.expect_mark_processed().never();
+        mocks.stripe_event_repo.expect_mark_failed().never();
+
+        let body = serde_json::to_vec(&payload).unwrap();
+        mocks
+            .into_usecase()
+            .handle_stripe_webhook(&body, "sig")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn webhook_records_failure_on_ledger_when_processing_fails() {
+        let mut mocks = Mocks::new();
+        let payload = stripe_event_payload("evt_broken", "checkout.session.completed");
+        let stored = stored_stripe_event(payload.clone(), None);
+
+        let verified = payload.clone();
+        mocks
+            .stripe
+            .expect_verify_webhook_signature()
+            .returning(move |_, _| Ok(serde_json::from_value(verified.clone()).unwrap()));
+        mocks
+            .stripe_event_repo
+            .expect_record_received()
+            .returning(move |_| {
+                let stored = stored.clone();
+                Box::pin(async move { Ok(stored) })
+            });
+        expect_claim(&mut mocks, "evt_broken", false, true);
+        mocks
+            .stripe_event_repo
+            .expect_mark_failed()
+            .withf(|event_id, message| event_id == "evt_broken" && message.contains("metadata"))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        mocks.stripe_event_repo.expect_mark_processed().never();
+
+        let body = serde_json::to_vec(&payload).unwrap();
+        let err = mocks
+            .into_usecase()
+            .handle_stripe_webhook(&body, "sig")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SubscriptionError::InvalidWebhook(_)));
+    }
+
+    #[tokio::test]
+    async fn webhook_leaves_event_claimed_by_another_run_alone() {
+        let mut mocks = Mocks::new();
+        let payload = stripe_event_payload("evt_busy", "checkout.session.completed");
+        let stored = stored_stripe_event(payload.clone(), None);
+
+        let verified = payload.clone();
+        mocks
+            .stripe
+            .expect_verify_webhook_signature()
+            .returning(move |_, _| Ok(serde_json::from_value(verified.clone()).unwrap()));
+        mocks
+            .stripe_event_repo
+            .expect_record_received()
+            .returning(move |_| {
+                let stored = stored.clone();
+                Box::pin(async move { Ok(stored) })
+            });
+        expect_claim(&mut mocks, "evt_busy", false, false);
+        mocks.stripe_event_repo.expect_mark_processed().never();
+        mocks.stripe_event_repo.expect_mark_failed().never();
+
+        let body = serde_json::to_vec(&payload).unwrap();
+        let err = mocks
+            .into_usecase()
+            .handle_stripe_webhook(&body, "sig")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SubscriptionError::WebhookRetry(_)));
+    }
+
+    #[tokio::test]
+    async fn replay_reprocesses_stored_event_and_marks_it_processed() {
+        let mut mocks = Mocks::new();
+        let stored = stored_stripe_event(
+            stripe_event_payload("evt_replay", "customer.created"),
+            Some(Utc::now()),
+        );
+
+        mocks
+            .stripe_event_repo
+            .expect_find_by_id()
+            .with(eq("evt_replay"))
+            .returning(move |_| {
+                let stored = stored.clone();
+                Box::pin(async move { Ok(Some(stored)) })
+            });
+        expect_claim(&mut mocks, "evt_replay", true, true);
+        mocks
+            .stripe_event_repo
+            .expect_mark_processed()
+            .with(eq("evt_replay"))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+
+        mocks
+            .into_usecase()
+            .replay_stripe_event("evt_replay")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn replay_returns_not_found_for_unknown_event() {
+        let mut mocks = Mocks::new();
+        mocks
+            .stripe_event_repo
+            .expect_find_by_id()
+            .returning(|_| Box::pin(async { Ok(None) }));
+
+        let err = mocks
+            .into_usecase()
+            .replay_stripe_event("evt_missing")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, SubscriptionError::StripeEventNotFound));
+    }
+
+    #[tokio::test]
+    async fn checkout_sells_multi_month_terms_after_scheduled_periods() {
+        let user_id = Uuid::new_v4();
+        let plan_id = Uuid::new_v4();
+        let scheduled_end = Utc::now() + Duration::days(10);
+        let mut mocks = Mocks::new();
+        mocks
+            .plan_repo
+            .expect_find_active_plan_by_id()
+            .returning(move |_| Box::pin(async move { Ok(sample_plan(plan_id)) }));
+        mocks
+            .subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        mocks
+            .customer_repo
+            .expect_find_or_create_stripe_customer_id()
+            .returning(|_, _| Box::pin(async { Ok("cus_123".to_string()) }));
+        mocks
+            .subscription_repo
+            .expect_list_subscriptions_ending_after()
+            .with(eq(user_id), mockall::predicate::always())
+            .returning(move |_, _| {
+                let mut queued =
+                    sample_recurring_subscription("pi_queued", Utc::now(), scheduled_end);
+                queued.billing_mode = BillingMode::OneTime.to_string();
+                Box::pin(async move { Ok(vec![queued]) })
+            });
+
+        let expected_end = scheduled_end
+            .checked_add_months(chrono::Months::new(6))
+            .unwrap();
+        mocks
+            .stripe
+            .expect_create_checkout_session()
+            .withf(move |price_id, quantity, mode, _, metadata, _| {
+                price_id == "price_card_3m"
+                    && *quantity == 2
+                    && mode == "payment"
+                    && metadata.get("term_months").map(String::as_str) == Some("3")
+                    && metadata.get("term_quantity").map(String::as_str) == Some("2")
+                    && metadata.get("one_time_starts_at")
+                        == Some(&scheduled_end.timestamp().to_string())
+                    && metadata.get("one_time_ends_at")
+                        == Some(&expected_end.timestamp().to_string())
+            })
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok("https://checkout.stripe.com/c/pay".to_string()));
+
+        let usecase = mocks.into_usecase();
+        usecase
+            .create_checkout_session(
+                user_id,
+                Some("user@example.com".to_string()),
+                plan_id,
+                CheckoutPriceSelection {
+                    term_months: 3,
+                    quantity: 2,
+                    ..selection(BillingMode::OneTime, PaymentMethod::Card, "thb")
+                },
+                CheckoutPromotion::None,
+            )
+            .await
+            .unwrap();
+
+        let err = usecase
+            .create_checkout_session(
+                user_id,
+                Some("user@example.com".to_string()),
+                plan_id,
+                CheckoutPriceSelection {
+                    term_months: 3,
+                    ..selection(BillingMode::Recurring, PaymentMethod::Card, "thb")
+                },
+                CheckoutPromotion::None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SubscriptionError::InvalidCombination(_)));
+    }
+
+    #[tokio::test]
+    async fn paid_gift_checkout_issues_code_without_subscribing_buyer() {
+        let purchaser_id = Uuid::new_v4();
+        let plan_id = Uuid::new_v4();
+        let invoice_id = Uuid::new_v4();
+        let mut mocks = Mocks::new();
+        expect_replay(
+            &mut mocks,
+            reversal_payload(
+                "checkout.session.completed",
+                serde_json::json!({
+                    "id": "cs_gift",
+                    "mode": "payment",
+                    "payment_intent": "pi_gift",
+                    "payment_status": "paid",
+                    "currency": "thb",
+                    "amount_total": 53800,
+                    "metadata": {
+                        "user_id": purchaser_id.to_string(),
+                        "plan_id": plan_id.to_string(),
+                        "billing_mode": "one_time",
+                        "payment_method": "card",
+                        "term_months": "3",
+                        "term_quantity": "2",
+                        "gift": "true"
+                    }
+                }),
+            ),
+        );
+        mocks
+            .plan_repo
+            .expect_find_active_plan_by_id()
+            .returning(move |_| Box::pin(async move { Ok(sample_plan(plan_id)) }));
+        mocks
+            .subscription_repo
+            .expect_create_or_update_subscription_after_checkout()
+            .never();
+        mocks
+            .gift_repo
+            .expect_find_by_provider_payment_ref()
+            .with(eq("pi_gift"))
+            .returning(|_| Box::pin(async { Ok(None) }));
+        mocks
+            .gift_repo
+            .expect_create_gift_code()
+            .withf(move |invoice, gift| {
+                invoice.user_id == purchaser_id
+                    && invoice.subscription_id.is_none()
+                    && invoice.amount_minor == 53800
+                    && gift.purchaser_id == purchaser_id
+                    && gift.term_months == 6
+                    && gift.status == GiftCodeStatus::Pending.as_str()
+            })
+            .times(1)
+            .returning(move |_, insert| {
+                let gift = crates::domain::entities::gift_codes::GiftCodeEntity {
+                    id: Uuid::new_v4(),
+                    code: insert.code,
+                    plan_id: insert.plan_id,
+                    purchaser_id: insert.purchaser_id,
+                    term_months: insert.term_months,
+                    status: insert.status,
+                    provider_payment_ref: insert.provider_payment_ref,
+                    invoice_id,
+                    expires_at: insert.expires_at,
+                    redeemed_by: None,
+                    redeemed_at: None,
+                    subscription_id: None,
+                    void_reason: None,
+                    created_at: Utc::now(),
+                };
+                Box::pin(async move { Ok(gift) })
+            });
+        mocks
+            .payment_repo
+            .expect_exists_by_provider_payment_id()
+            .returning(|_| Box::pin(async { Ok(false) }));
+        mocks
+            .payment_repo
+            .expect_record_payment()
+            .withf(move |payment| payment.invoice_id == invoice_id)
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Uuid::new_v4()) }));
+        mocks
+            .gift_repo
+            .expect_activate_by_provider_payment_ref()
+            .with(eq("pi_gift"))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(None) }));
+        mocks
+            .invoice_repo
+            .expect_mark_invoice_paid()
+            .with(eq(invoice_id))
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        mocks
+            .payment_repo
+            .expect_update_status_by_provider_payment_id()
+            .with(eq("pi_gift"), eq(PaymentStatus::Succeeded))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        mocks
+            .into_usecase()
+            .replay_stripe_event("evt_reversal")
+            .await
+            .unwrap();
+    }
+
+    fn reversal_payload(event_type: &str, object: serde_json::Value) -> StripeEventEntity {
+        stored_stripe_event(
+            serde_json::json!({
+                "id": "evt_reversal",
+                "type": event_type,
+                "data": { "object": object }
+            }),
+            None,
+        )
+    }
+
+    /// Wires the ledger so `replay_stripe_event` processes `stored` and expects success.
+    fn expect_replay(mocks: &mut Mocks, stored: StripeEventEntity) {
+        mocks
+            .stripe_event_repo
+            .expect_find_by_id()
+            .returning(move |_| {
+                let stored = stored.clone();
+                Box::pin(async move { Ok(Some(stored)) })
+            });
+        mocks
+            .stripe_event_repo
+            .expect_claim()
+            .withf(|_, _, reprocess| *reprocess)
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(true) }));
+        mocks
+            .stripe_event_repo
+            .expect_mark_processed()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+    }
+
+    fn expect_reversed_payment(
+        mocks: &mut Mocks,
+        status: PaymentStatus,
+    ) -> (InvoiceEntity, PaymentEntity) {
+        let invoice = sample_invoice(Uuid::new_v4(), Utc::now() - Duration::days(3));
+        let mut payment = sample_payment(&invoice, PaymentMethod::Card, invoice.created_at);
+        payment.provider_payment_id = Some("pi_reversed".to_string());
+        payment.status = status.to_string();
+
+        let found = payment.clone();
+        mocks
+            .payment_repo
+            .expect_find_by_provider_payment_id()
+            .with(eq("pi_reversed"))
+            .returning(move |_| {
+                let found = found.clone();
+                Box::pin(async move { Ok(Some(found)) })
+            });
+        let linked = invoice.clone();
+        mocks
+            .invoice_repo
+            .expect_find_by_id_and_user()
+            .returning(move |_, _| {
+                let linked = linked.clone();
+                Box::pin(async move { Ok(Some(linked)) })
+            });
+        (invoice, payment)
+    }
+
+    #[tokio::test]
+    async fn full_refund_ends_subscription_and_cancels_it_in_stripe() {
+        let mut mocks = Mocks::new();
+        expect_replay(
+            &mut mocks,
+            reversal_payload(
+                "charge.refunded",
+                serde_json::json!({
+                    "id": "ch_1",
+                    "payment_intent": "pi_reversed",
+                    "amount": 9900,
+                    "amount_refunded": 9900,
+                    "refunded": true,
+                    "refunds": { "data": [{ "reason": "requested_by_customer" }] }
+                }),
+            ),
+        );
+        let (invoice, _) = expect_reversed_payment(&mut mocks, PaymentStatus::Succeeded);
+        let subscription_id = invoice.subscription_id.unwrap();
+
+        mocks
+            .payment_repo
+            .expect_record_refund()
+            .withf(|id, status, refunded_minor, reason| {
+                id == "pi_reversed"
+                    && *status == PaymentStatus::Refunded
+                    && *refunded_minor == 9900
+                    && reason.as_deref() == Some("refunded: requested_by_customer")
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async { Ok(()) }));
+        mocks
+            .invoice_repo
+            .expect_update_status_by_id()
+            .with(eq(invoice.id), eq("refunded"))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        mocks
+            .subscription_repo
+            .expect_end_subscription_early()
+            .withf(move |id, reason, _| {
+                *id == subscription_id && reason == "refunded: requested_by_customer"
+            })
+            .times(1)
+            .returning(|_, reason, now| {
+                let mut ended =
+                    sample_recurring_subscription("sub_refunded", now - Duration::days(3), now);
+                ended.status = SubscriptionStatus::Canceled.to_string();
+                ended.ended_reason = Some(reason);
+                Box::pin(async move { Ok(Some(ended)) })
+            });
+        mocks
+            .stripe
+            .expect_cancel_subscription_now()
+            .with(eq("sub_refunded"))
+            .times(1)
+            .returning(|_| Ok(()));
+        expect_follow_limit_enforced(&mut mocks, 3);
+
+        mocks
+            .into_usecase()
+            .replay_stripe_event("evt_reversal")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn partial_refund_keeps_subscription() {
+        let mut mocks = Mocks::new();
+        expect_replay(
+            &mut mocks,
+            reversal_payload(
+                "charge.refunded",
+                serde_json::json!({
+                    "id": "ch_1",
+                    "payment_intent": "pi_reversed",
+                    "amount": 9900,
+                    "amount_refunded": 2000,
+                    "refunded": false
+                }),
+            ),
+        );
+        expect_reversed_payment(&mut mocks, PaymentStatus::Succeeded);
+
+        mocks
+            .payment_repo
+            .expect_record_refund()
+            .withf(|_, status, refunded_minor, _| {
+                *status == PaymentStatus::PartiallyRefunded && *refunded_minor == 2000
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async { Ok(()) }));
+        mocks.invoice_repo.expect_update_status_by_id().never();
+        mocks
+            .subscription_repo
+            .expect_end_subscription_early()
+            .never();
+
+        mocks
+            .into_usecase()
+            .replay_stripe_event("evt_reversal")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn won_dispute_restores_payment_without_ending_subscription() {
+        let mut mocks = Mocks::new();
+        expect_replay(
+            &mut mocks,
+            reversal_payload(
+                "charge.dispute.closed",
+                serde_json::json!({
+                    "id": "dp_1",
+                    "charge": "ch_1",
+                    "payment_intent": "pi_reversed",
+                    "reason": "fraudulent",
+                    "status": "won"
+                }),
+            ),
+        );
+        let (invoice, _) = expect_reversed_payment(&mut mocks, PaymentStatus::Disputed);
+
+        mocks
+            .payment_repo
+            .expect_record_dispute()
+            .withf(|id, status, reason| {
+                id == "pi_reversed"
+                    && *status == PaymentStatus::Succeeded
+                    && reason.as_deref() == Some("dispute won: fraudulent")
+            })
+            .times(1)
+            .returning(|_, _, _| Box::pin(async { Ok(()) }));
+        mocks
+            .invoice_repo
+            .expect_update_status_by_id()
+            .with(eq(invoice.id), eq("paid"))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        mocks
+            .subscription_repo
+            .expect_end_subscription_early()
+            .never();
+
+        mocks
+            .into_usecase()
+            .replay_stripe_event("evt_reversal")
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn closed_dispute_keeps_refunded_payment_and_invoice() {
+        let mut mocks = Mocks::new();
+        expect_replay(
+            &mut mocks,
+            reversal_payload(
+                "charge.dispute.closed",
+                serde_json::json!({
+                    "id": "dp_1",
+                    "charge": "ch_1",
+                    "payment_intent": "pi_reversed",
+                    "reason": "fraudulent",
+                    "status": "won"
+                }),
+            ),
+        );
+        expect_reversed_payment(&mut mocks, PaymentStatus::Refunded);
+
+        mocks.payment_repo.expect_record_dispute().never();
+        mocks.invoice_repo.expect_update_status_by_id().never();
+
+        mocks
+            .into_usecase()
+            .replay_stripe_event("evt_reversal")
+            .await
+            .unwrap();
+    }
+
+    fn sample_recurring_subscription(
+        provider_subscription_id: &str,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> SubscriptionEntity {
+        SubscriptionEntity {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            plan_id: Uuid::new_v4(),
+            starts_at,
+            ends_at,
+            billing_mode: BillingMode::Recurring.to_string(),
+            default_payment_method_id: None,
+            cancel_at_period_end: false,
+            canceled_at: None,
+            provider_subscription_id: Some(provider_subscription_id.to_string()),
+            status: SubscriptionStatus::Active.to_string(),
+            created_at: starts_at,
+            ended_reason: None,
+        }
+    }
+
+    /// Expects one enforcement against a free plan allowing `max_follows` follows.
+    fn expect_follow_limit_enforced(mocks: &mut Mocks, max_follows: i64) {
+        mocks
+            .subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        mocks
+            .plan_repo
+            .expect_find_by_id()
+            .with(eq(FREE_PLAN_ID))
+            .returning(move |plan_id| {
+                let mut plan = sample_plan(plan_id);
+                plan.features.max_follows = Some(max_follows);
+                Box::pin(async move { Ok(plan) })
+            });
+        mocks
+            .live_following_repo
+            .expect_deactivate_follows_over_limit()
+            .withf(move |_, keep| *keep == max_follows)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(0) }));
+    }
+
+    #[tokio::test]
+    async fn stripe_subscription_deleted_enforces_follow_limit() {
+        let mut mocks = Mocks::new();
+        let now = Utc::now();
+        let subscription = sample_recurring_subscription("sub_deleted", now, now);
+        let user_id = subscription.user_id;
+        expect_replay(
+            &mut mocks,
+            stored_stripe_event(
+                serde_json::json!({
+                    "id": "evt_deleted",
+                    "type": "customer.subscription.deleted",
+                    "data": { "object": { "id": "sub_deleted" } }
+                }),
+                None,
+            ),
+        );
+        mocks
+            .subscription_repo
+            .expect_update_status_by_provider_subscription_id()
+            .withf(|id, status| id == "sub_deleted" && *status == SubscriptionStatus::Expired)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+        mocks
+            .subscription_repo
+            .expect_find_by_provider_subscription_id()
+            .returning(move |_| {
+                let subscription = subscription.clone();
+                Box::pin(async move { Ok(Some(subscription)) })
+            });
+        mocks
+            .subscription_repo
+            .expect_find_current_active_non_free_subscription()
+            .returning(|_, _| Box::pin(async { Ok(None) }));
+        mocks
+            .plan_repo
+            .expect_find_by_id()
+            .with(eq(FREE_PLAN_ID))
+            .returning(|plan_id| {
+                let mut plan = sample_plan(plan_id);
+                plan.features.max_follows = Some(3);
+                Box::pin(async move { Ok(plan) })
+            });
+        let mut live_following_repo = MockLiveFollowingRepository::new();
+        live_following_repo
+            .expect_deactivate_follows_over_limit()
+            .with(eq(user_id), eq(3))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(2) }));
+        mocks.live_following_repo = live_following_repo;
+
+        mocks
+            .into_usecase()
+            .replay_stripe_event("evt_deleted")
+            .await
+            .unwrap();
+    }
+
+    fn stripe_subscription(status: &str, start: i64, end: i64) -> StripeSubscription {
+        StripeSubscription {
+            status: Some(status.to_string()),
+            current_period_start: Some(start),
+            current_period_end: Some(end),
+            billing_cycle_anchor: None,
+            items: Default::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn reconciliation_corrects_drift_and_enforces_follow_limit_on_cancel() {
+        let mut mocks = Mocks::new();
+        let start = Utc.timestamp_opt(1_760_000_000, 0).unwrap();
+        let end = start + Duration::days(30);
+        let renewed_end = end + Duration::days(30);
+
+        let in_sync = sample_recurring_subscription("sub_in_sync", start, end);
+        let drifted = sample_recurring_subscription("sub_canceled", start, end);
+        let drifted_id = drifted.id;
+        mocks
+            .subscription_repo
+            .expect_list_active_subscriptions()
+            .returning(move || {
+                let subscriptions = vec![in_sync.clone(), drifted.clone()];
+                Box::pin(async move { Ok(subscriptions) })
+            });
+        mocks
+            .stripe
+            .expect_retrieve_subscription()
+            .returning(move |id| match id {
+                "sub_in_sync" => Ok(stripe_subscription(
+                    "active",
+                    start.timestamp(),
+                    end.timestamp(),
+                )),
+                _ => Ok(stripe_subscription(
+                    "canceled",
+                    end.timestamp(),
+                    renewed_end.timestamp(),
+                )),
+            });
+        mocks
+            .subscription_repo
+            .expect_update_status_and_period_by_provider_subscription_id()
+            .withf(move |id, status, starts_at, ends_at| {
+                id == "sub_canceled"
+                    && *status == SubscriptionStatus::Canceled
+                    && *starts_at == end
+                    && *ends_at == renewed_end
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async { Ok(()) }));
+        expect_follow_limit_enforced(&mut mocks, 3);
+
+        let report = mocks
+            .into_usecase()
+            .reconcile_recurring_subscriptions()
+            .await
+            .unwrap();
+
+        assert_eq!(report.scanned, 2);
+        assert_eq!(report.corrected, 1);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.corrected_ids, vec![drifted_id]);
+    }
+}
@@ -5,9 +5,16 @@ use crates::domain::{
     entities::recordings::RecordingEntity,
     repositories::{
         live_following::LiveFollowingRepository, plans::PlanRepository,
-        recording_upload::RecordingUploadRepository, subscriptions::SubscriptionRepository,
+        recording_upload::RecordingUploadRepository, recording_view::RecordingViewRepository,
+        subscriptions::SubscriptionRepository,
+    },
+    value_objects::{
+        enums::{
+            follow_statuses::FollowStatus, quota_enforcement_policies::QuotaEnforcementPolicy,
+        },
+        plans::PlanFeatures,
+        recordings::RecordingViewWindow,
     },
-    value_objects::{enums::follow_statuses::FollowStatus, plans::PlanFeatures},
 };
 use jsonwebtoken::{Algorithm, EncodingKey, Header, encode};
 use serde::{Deserialize, Serialize};
@@ -27,36 +34,46 @@ struct WatchUrlClaims {
 }
 
 /// Generates signed watch URLs for recordings a user is allowed to view.
-pub struct WatchUrlUseCase<R, F, P, S>
+/// Under `hide_oldest`, recordings past the plan's storage quota cannot be watched, matching
+/// what the recording lists show.
+pub struct WatchUrlUseCase<R, F, V, P, S>
 where
     R: RecordingUploadRepository + Send + Sync + 'static,
     F: LiveFollowingRepository + Send + Sync + 'static,
+    V: RecordingViewRepository + Send + Sync + 'static,
     P: PlanRepository + Send + Sync + 'static,
     S: SubscriptionRepository + Send + Sync + 'static,
 {
     recording_repository: Arc<R>,
     live_following_repository: Arc<F>,
+    recording_view_repository: Arc<V>,
     plan_resolver: Arc<PlanResolver<P, S>>,
+    quota_enforcement: QuotaEnforcementPolicy,
     config: WatchUrl,
 }
 
-impl<R, F, P, S> WatchUrlUseCase<R, F, P, S>
+impl<R, F, V, P, S> WatchUrlUseCase<R, F, V, P, S>
 where
     R: RecordingUploadRepository + Send + Sync + 'static,
     F: LiveFollowingRepository + Send + Sync + 'static,
+    V: RecordingViewRepository + Send + Sync + 'static,
     P: PlanRepository + Send + Sync + 'static,
     S: SubscriptionRepository + Send + Sync + 'static,
 {
     pub fn new(
         recording_repository: Arc<R>,
         live_following_repository: Arc<F>,
+        recording_view_repository: Arc<V>,
         plan_resolver: Arc<PlanResolver<P, S>>,
+        quota_enforcement: QuotaEnforcementPolicy,
         config: WatchUrl,
     ) -> Self {
         Self {
             recording_repository,
             live_following_repository,
+            recording_view_repository,
             plan_resolver,
+            quota_enforcement,
             config,
         }
     }
@@ -145,7 +162,7 @@ where
             bail!("Follow is not active");
         }
 
-        let retention_days = features.retention_days.unwrap_or(0);
+        let retention_days = features.retention_days_or_default();
         if retention_days <= 0 {
             warn!(
                 %user_id,
@@ -181,6 +198,52 @@ where
             bail!("Recording exceeds retention window");
         }
 
+        self.ensure_within_storage_quota(user_id, recording, features)
+            .await
+    }
+
+    async fn ensure_within_storage_quota(
+        &self,
+        user_id: Uuid,
+        recording: &RecordingEntity,
+        features: &PlanFeatures,
+    ) -> Result<()> {
+        let storage_limit_bytes = match self.quota_enforcement {
+            QuotaEnforcementPolicy::HideOldest => features.max_storage_bytes,
+            QuotaEnforcementPolicy::Warn | QuotaEnforcementPolicy::StopRecording => None,
+        };
+        if storage_limit_bytes.is_none() {
+            return Ok(());
+        }
+
+        let window = RecordingViewWindow {
+            retention_days: i64::from(features.retention_days_or_default().max(0)),
+            storage_limit_bytes,
+        };
+        let visible = self
+            .recording_view_repository
+            .is_recording_in_view_window(user_id, window, recording.id)
+            .await
+            .map_err(|err| {
+                error!(
+                    %user_id,
+                    recording_id = %recording.id,
+                    db_error = ?err,
+                    "watch_url: failed to check storage quota window"
+                );
+                err
+            })?;
+
+        if !visible {
+            warn!(
+                %user_id,
+                recording_id = %recording.id,
+                storage_limit_bytes,
+                "watch_url: recording hidden by storage quota"
+            );
+            bail!("Recording exceeds storage quota");
+        }
+
         Ok(())
     }
 
@@ -0,0 +1,55 @@
+//! Plan repository reads against a real Postgres.
+
+use std::sync::Arc;
+
+use crates::{
+    domain::repositories::plans::PlanRepository,
+    infra::db::{
+        postgres::postgres_connection::establish_connection, repositories::plans::PlanPostgres,
+    },
+};
+use diesel::{RunQueryDsl, sql_query, sql_types};
+use uuid::Uuid;
+
+/// Seeds one valid and one misconfigured plan into the database at `DATABASE_URL`; the
+/// misconfigured plan is deactivated again at the end so it does not linger in listings.
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs a local Postgres with migrations applied; set DATABASE_URL"]
+async fn invalid_plan_features_are_skipped_in_listings_but_fail_lookups() {
+    dotenvy::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let db_pool = Arc::new(establish_connection(&database_url).unwrap());
+
+    let valid_plan_id = Uuid::new_v4();
+    let invalid_plan_id = Uuid::new_v4();
+    {
+        let mut conn = db_pool.get().unwrap();
+        sql_query(
+            "INSERT INTO plans (id, name, price_minor, duration_days, features) \
+             VALUES ($1, 'Catalogue Pro', 29900, 30, '{\"max_follows\": 10}'), \
+                    ($2, 'Catalogue Broken', 29900, 30, '{\"max_follows\": \"ten\"}')",
+        )
+        .bind::<sql_types::Uuid, _>(valid_plan_id)
+        .bind::<sql_types::Uuid, _>(invalid_plan_id)
+        .execute(&mut conn)
+        .unwrap();
+    }
+
+    let plan_repo = PlanPostgres::new(Arc::clone(&db_pool));
+
+    let listed = plan_repo.list_active_plans().await;
+    let lookup = plan_repo.find_by_id(invalid_plan_id).await;
+
+    {
+        let mut conn = db_pool.get().unwrap();
+        sql_query("UPDATE plans SET is_active = false WHERE id = $1")
+            .bind::<sql_types::Uuid, _>(invalid_plan_id)
+            .execute(&mut conn)
+            .unwrap();
+    }
+
+    let listed = listed.unwrap();
+    assert!(listed.iter().any(|plan| plan.id == valid_plan_id));
+    assert!(!listed.iter().any(|plan| plan.id == invalid_plan_id));
+    assert!(lookup.is_err());
+}
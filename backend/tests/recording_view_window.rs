@@ -0,0 +1,97 @@
+//! Recording view window queries against a real Postgres.
+
+use std::sync::Arc;
+
+use crates::{
+    domain::{
+        repositories::recording_view::RecordingViewRepository,
+        value_objects::recordings::RecordingViewWindow,
+    },
+    infra::db::{
+        postgres::postgres_connection::establish_connection,
+        repositories::recording_view::RecordingViewPostgres,
+    },
+};
+use diesel::{RunQueryDsl, sql_query, sql_types};
+use uuid::Uuid;
+
+/// Seeds a user following one account with two ready recordings; rows use fresh ids and are
+/// left behind, so point `DATABASE_URL` at a disposable database.
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs a local Postgres with migrations applied; set DATABASE_URL"]
+async fn storage_limit_hides_oldest_recording_from_lists_and_lookups() {
+    dotenvy::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let db_pool = Arc::new(establish_connection(&database_url).unwrap());
+
+    let user_id = Uuid::new_v4();
+    let live_account_id = Uuid::new_v4();
+    let older_id = Uuid::new_v4();
+    let newer_id = Uuid::new_v4();
+    {
+        let mut conn = db_pool.get().unwrap();
+        sql_query("INSERT INTO auth.users (id, email) VALUES ($1, $2)")
+            .bind::<sql_types::Uuid, _>(user_id)
+            .bind::<sql_types::Text, _>(format!("{user_id}@example.com"))
+            .execute(&mut conn)
+            .unwrap();
+        sql_query("INSERT INTO app_users (id) VALUES ($1) ON CONFLICT (id) DO NOTHING")
+            .bind::<sql_types::Uuid, _>(user_id)
+            .execute(&mut conn)
+            .unwrap();
+        sql_query(
+            "INSERT INTO live_accounts (id, platform, account_id, canonical_url) \
+             VALUES ($1, 'tiktok', $2, $3)",
+        )
+        .bind::<sql_types::Uuid, _>(live_account_id)
+        .bind::<sql_types::Text, _>(live_account_id.to_string())
+        .bind::<sql_types::Text, _>(format!("https://example.com/{live_account_id}"))
+        .execute(&mut conn)
+        .unwrap();
+        sql_query("INSERT INTO follows (user_id, live_account_id) VALUES ($1, $2)")
+            .bind::<sql_types::Uuid, _>(user_id)
+            .bind::<sql_types::Uuid, _>(live_account_id)
+            .execute(&mut conn)
+            .unwrap();
+        sql_query(
+            "INSERT INTO recordings (id, live_account_id, started_at, status, size_bytes) \
+             VALUES ($1, $3, now() - INTERVAL '2 hours', 'ready', 600), \
+                    ($2, $3, now() - INTERVAL '1 hour', 'ready', 600)",
+        )
+        .bind::<sql_types::Uuid, _>(older_id)
+        .bind::<sql_types::Uuid, _>(newer_id)
+        .bind::<sql_types::Uuid, _>(live_account_id)
+        .execute(&mut conn)
+        .unwrap();
+    }
+
+    let repository = RecordingViewPostgres::new(Arc::clone(&db_pool));
+    let window = RecordingViewWindow {
+        retention_days: 7,
+        storage_limit_bytes: Some(1_000),
+    };
+
+    let listed = repository
+        .list_follows_entitled_recordings(user_id, window, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        listed
+            .iter()
+            .map(|recording| recording.id)
+            .collect::<Vec<_>>(),
+        vec![newer_id]
+    );
+    assert!(
+        repository
+            .is_recording_in_view_window(user_id, window, newer_id)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repository
+            .is_recording_in_view_window(user_id, window, older_id)
+            .await
+            .unwrap()
+    );
+}
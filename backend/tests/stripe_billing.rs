@@ -0,0 +1,246 @@
+//! Checkout and webhook flows against the in-process Stripe stand-in in `support::fake_stripe`.
+
+mod support;
+
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{Router, http::StatusCode, routing::post};
+use backend::{
+    axum_http::routers::subscriptions::{SubscriptionUseCaseState, stripe_webhook},
+    usecases::subscriptions::{SubscriptionRepositories, SubscriptionUseCase},
+};
+use chrono::{Duration, Utc};
+use crates::{
+    domain::value_objects::{
+        enums::{
+            billing_modes::BillingMode, payment_methods::PaymentMethod,
+            subscription_statuses::SubscriptionStatus,
+        },
+        subscriptions::{CheckoutPriceSelection, CheckoutPromotion},
+    },
+    infra::db::{
+        postgres::postgres_connection::establish_connection,
+        repositories::{
+            gift_codes::GiftCodePostgres, invoices::InvoicePostgres, job::JobPostgres,
+            live_following::LiveFollowingPostgres,
+            payment_provider_customers::PaymentProviderCustomerPostgres, payments::PaymentPostgres,
+            plans::PlanPostgres, stripe_events::StripeEventPostgres,
+            subscriptions::SubscriptionPostgres,
+        },
+    },
+    payments::stripe_client::StripeClient,
+};
+use diesel::{RunQueryDsl, sql_query, sql_types};
+use support::fake_stripe::{FakeStripe, checkout_session_id};
+use tokio::net::TcpListener;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn stripe_client_round_trips_through_fake_stripe() {
+    let fake = FakeStripe::start().await.unwrap();
+    fake.register_price("price_monthly", 29900, "thb", true);
+    let client = fake.client();
+    let user_id = Uuid::new_v4();
+
+    let customer = client
+        .create_customer("buyer@example.com", user_id)
+        .await
+        .unwrap();
+    let metadata = HashMap::from([("user_id".to_string(), user_id.to_string())]);
+    let checkout_url = client
+        .create_checkout_session(
+            "price_monthly",
+            1,
+            "subscription",
+            Some(customer.clone()),
+            metadata,
+            CheckoutPromotion::None,
+        )
+        .await
+        .unwrap();
+    let session_id = checkout_session_id(&checkout_url);
+    assert_eq!(
+        fake.checkout_session(session_id).unwrap().customer,
+        Some(customer)
+    );
+
+    let event = fake.complete_checkout_session(session_id).unwrap();
+    let (payload, signature) = fake.sign_event(&event).unwrap();
+    let verified = client
+        .verify_webhook_signature(&payload, &signature)
+        .unwrap();
+    assert_eq!(verified.type_, "checkout.session.completed");
+
+    let session = StripeClient::extract_checkout_session(&verified).unwrap();
+    assert_eq!(session.amount_total, Some(29900));
+    assert_eq!(
+        session.metadata.unwrap().get("user_id"),
+        Some(&user_id.to_string())
+    );
+
+    let subscription_id = session.subscription.unwrap();
+    let subscription = client
+        .retrieve_subscription(&subscription_id)
+        .await
+        .unwrap();
+    assert!(subscription.period_end() > subscription.period_start());
+
+    client.cancel_subscription(&subscription_id).await.unwrap();
+    assert!(
+        fake.subscription(&subscription_id)
+            .unwrap()
+            .cancel_at_period_end
+    );
+    client
+        .cancel_subscription_now(&subscription_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        fake.subscription(&subscription_id).unwrap().status,
+        "canceled"
+    );
+
+    let mut tampered = payload.clone();
+    tampered.extend_from_slice(b" ");
+    assert!(
+        client
+            .verify_webhook_signature(&tampered, &signature)
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn fake_stripe_rejects_unknown_prices() {
+    let fake = FakeStripe::start().await.unwrap();
+    let client = fake.client();
+
+    let result = client
+        .create_checkout_session(
+            "price_missing",
+            1,
+            "payment",
+            None,
+            HashMap::new(),
+            CheckoutPromotion::None,
+        )
+        .await;
+
+    assert!(result.is_err());
+    assert!(fake.base_url().starts_with("http://127.0.0.1:"));
+}
+
+/// Runs checkout → signed webhook → current subscription end to end against a local
+/// Supabase Postgres with all migrations applied. Seeded rows use fresh ids and are left
+/// behind, so point `DATABASE_URL` at a disposable database.
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "needs a local Postgres with migrations applied; set DATABASE_URL"]
+async fn recurring_checkout_webhook_activates_current_subscription() {
+    dotenvy::dotenv().ok();
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let db_pool = Arc::new(establish_connection(&database_url).unwrap());
+
+    let fake = FakeStripe::start().await.unwrap();
+    let stripe_price_id = format!("price_{}", Uuid::new_v4().simple());
+    fake.register_price(&stripe_price_id, 29900, "thb", true);
+
+    let user_id = Uuid::new_v4();
+    let plan_id = Uuid::new_v4();
+    {
+        let mut conn = db_pool.get().unwrap();
+        sql_query("INSERT INTO auth.users (id, email) VALUES ($1, $2)")
+            .bind::<sql_types::Uuid, _>(user_id)
+            .bind::<sql_types::Text, _>(format!("{user_id}@example.com"))
+            .execute(&mut conn)
+            .unwrap();
+        sql_query("INSERT INTO app_users (id) VALUES ($1) ON CONFLICT (id) DO NOTHING")
+            .bind::<sql_types::Uuid, _>(user_id)
+            .execute(&mut conn)
+            .unwrap();
+        sql_query(
+            "INSERT INTO plans (id, name, price_minor, duration_days) \
+             VALUES ($1, 'Fake Stripe Pro', 29900, 30)",
+        )
+        .bind::<sql_types::Uuid, _>(plan_id)
+        .execute(&mut conn)
+        .unwrap();
+        sql_query(
+            "INSERT INTO plan_prices \
+             (plan_id, currency, billing_mode, payment_method, amount_minor, stripe_price_id) \
+             VALUES ($1, 'thb', 'recurring', 'card', 29900, $2)",
+        )
+        .bind::<sql_types::Uuid, _>(plan_id)
+        .bind::<sql_types::Text, _>(&stripe_price_id)
+        .execute(&mut conn)
+        .unwrap();
+    }
+
+    let stripe_client = Arc::new(fake.client());
+    let repositories = SubscriptionRepositories {
+        plan_repo: Arc::new(PlanPostgres::new(Arc::clone(&db_pool))),
+        subscription_repo: Arc::new(SubscriptionPostgres::new(Arc::clone(&db_pool))),
+        payment_repo: Arc::new(PaymentPostgres::new(Arc::clone(&db_pool))),
+        customer_repo: Arc::new(PaymentProviderCustomerPostgres::new(
+            Arc::clone(&db_pool),
+            Arc::clone(&stripe_client),
+        )),
+        invoice_repo: Arc::new(InvoicePostgres::new(Arc::clone(&db_pool))),
+        stripe_event_repo: Arc::new(StripeEventPostgres::new(Arc::clone(&db_pool))),
+        job_repo: Arc::new(JobPostgres::new(Arc::clone(&db_pool))),
+        gift_repo: Arc::new(GiftCodePostgres::new(Arc::clone(&db_pool))),
+        live_following_repo: Arc::new(LiveFollowingPostgres::new(Arc::clone(&db_pool))),
+    };
+    let usecase: Arc<SubscriptionUseCaseState> = Arc::new(SubscriptionUseCase::new(
+        repositories,
+        stripe_client,
+        Uuid::new_v4(),
+    ));
+
+    let webhook_app = Router::new()
+        .route("/stripe/webhook", post(stripe_webhook))
+        .with_state(Arc::clone(&usecase));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let webhook_url = format!("http://{}/stripe/webhook", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, webhook_app).await.ok();
+    });
+
+    let checkout_url = usecase
+        .create_checkout_session(
+            user_id,
+            Some(format!("{user_id}@example.com")),
+            plan_id,
+            CheckoutPriceSelection {
+                billing_mode: BillingMode::Recurring,
+                payment_method: PaymentMethod::Card,
+                currency: "thb".to_string(),
+                term_months: 1,
+                quantity: 1,
+            },
+            CheckoutPromotion::None,
+        )
+        .await
+        .unwrap();
+
+    let event = fake
+        .complete_checkout_session(checkout_session_id(&checkout_url))
+        .unwrap();
+    assert_eq!(
+        fake.deliver_event(&webhook_url, &event).await.unwrap(),
+        StatusCode::OK
+    );
+    // Stripe retries deliveries; a duplicate must not create a second subscription.
+    assert_eq!(
+        fake.deliver_event(&webhook_url, &event).await.unwrap(),
+        StatusCode::OK
+    );
+
+    let current = usecase
+        .get_current_subscription(user_id)
+        .await
+        .unwrap()
+        .expect("subscription should be active after checkout");
+    assert_eq!(current.plan_id, plan_id);
+    assert_eq!(current.billing_mode, BillingMode::Recurring);
+    assert!(matches!(current.status, SubscriptionStatus::Active));
+    assert!(current.ends_at > Utc::now() + Duration::days(29));
+}
@@ -0,0 +1,459 @@
+//! In-process stand-in for the parts of the Stripe API the backend talks to.
+//!
+//! Serves customers, checkout sessions, promotion codes and subscriptions on a random
+//! local port, and can play the checkout page: completing a session produces the
+//! `checkout.session.completed` event Stripe would send, signed with the webhook secret.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use axum::{
+    Form, Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use chrono::{Duration, Utc};
+use crates::payments::stripe_client::{StripeClient, sign_webhook_payload};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+
+pub const FAKE_SECRET_KEY: &str = "sk_test_fake";
+pub const FAKE_WEBHOOK_SECRET: &str = "whsec_fake";
+
+#[derive(Debug, Clone)]
+pub struct FakePrice {
+    pub amount_minor: i64,
+    pub currency: String,
+    pub recurring: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeCheckoutSession {
+    pub id: String,
+    pub mode: String,
+    pub price_id: String,
+    pub quantity: i64,
+    pub customer: Option<String>,
+    pub metadata: HashMap<String, String>,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FakeSubscription {
+    pub id: String,
+    pub customer: Option<String>,
+    pub status: String,
+    pub cancel_at_period_end: bool,
+    pub current_period_start: i64,
+    pub current_period_end: i64,
+}
+
+impl FakeSubscription {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "object": "subscription",
+            "customer": self.customer,
+            "status": self.status,
+            "cancel_at_period_end": self.cancel_at_period_end,
+            "current_period_start": self.current_period_start,
+            "current_period_end": self.current_period_end,
+            "billing_cycle_anchor": self.current_period_start,
+            "items": {
+                "data": [{
+                    "current_period_start": self.current_period_start,
+                    "current_period_end": self.current_period_end,
+                }],
+            },
+        })
+    }
+}
+
+#[derive(Default)]
+struct FakeStripeState {
+    next_id: u64,
+    prices: HashMap<String, FakePrice>,
+    customers: HashMap<String, String>,
+    sessions: HashMap<String, FakeCheckoutSession>,
+    subscriptions: HashMap<String, FakeSubscription>,
+}
+
+impl FakeStripeState {
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}_fake_{}", prefix, self.next_id)
+    }
+}
+
+type SharedState = Arc<Mutex<FakeStripeState>>;
+
+pub struct FakeStripe {
+    base_url: String,
+    state: SharedState,
+    http: reqwest::Client,
+}
+
+impl FakeStripe {
+    /// Binds to an ephemeral local port and serves the fake API until the runtime shuts down.
+    pub async fn start() -> Result<Self> {
+        let state = SharedState::default();
+        let app = Router::new()
+            .route("/v1/customers", post(create_customer))
+            .route("/v1/checkout/sessions", post(create_checkout_session))
+            .route("/v1/promotion_codes", get(list_promotion_codes))
+            .route(
+                "/v1/subscriptions/:id",
+                get(retrieve_subscription)
+                    .post(update_subscription)
+                    .delete(cancel_subscription),
+            )
+            .with_state(Arc::clone(&state));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.ok();
+        });
+
+        Ok(Self {
+            base_url,
+            state,
+            http: reqwest::Client::new(),
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// A real client pointed at this server, sharing its webhook secret.
+    pub fn client(&self) -> StripeClient {
+        StripeClient::new(
+            FAKE_SECRET_KEY.to_string(),
+            FAKE_WEBHOOK_SECRET.to_string(),
+            "http://localhost/success".to_string(),
+            "http://localhost/cancel".to_string(),
+        )
+        .with_api_base(self.base_url.clone())
+    }
+
+    /// Makes a price id known to checkout; sessions for unknown prices are rejected like Stripe does.
+    pub fn register_price(
+        &self,
+        price_id: &str,
+        amount_minor: i64,
+        currency: &str,
+        recurring: bool,
+    ) {
+        self.state.lock().unwrap().prices.insert(
+            price_id.to_string(),
+            FakePrice {
+                amount_minor,
+                currency: currency.to_string(),
+                recurring,
+            },
+        );
+    }
+
+    pub fn checkout_session(&self, session_id: &str) -> Option<FakeCheckoutSession> {
+        self.state.lock().unwrap().sessions.get(session_id).cloned()
+    }
+
+    pub fn subscription(&self, subscription_id: &str) -> Option<FakeSubscription> {
+        self.state
+            .lock()
+            .unwrap()
+            .subscriptions
+            .get(subscription_id)
+            .cloned()
+    }
+
+    /// Pays for a session as a customer would on the hosted page and returns the
+    /// `checkout.session.completed` event. Subscription-mode sessions start a monthly subscription.
+    pub fn complete_checkout_session(&self, session_id: &str) -> Result<Value> {
+        let mut state = self.state.lock().unwrap();
+        let session = state
+            .sessions
+            .get(session_id)
+            .cloned()
+            .with_context(|| format!("unknown checkout session {session_id}"))?;
+        anyhow::ensure!(
+            !session.completed,
+            "checkout session {session_id} already completed"
+        );
+        let price = state
+            .prices
+            .get(&session.price_id)
+            .cloned()
+            .with_context(|| format!("unknown price {}", session.price_id))?;
+
+        let now = Utc::now();
+        let (subscription, payment_intent) = if session.mode == "subscription" {
+            let subscription = FakeSubscription {
+                id: state.next_id("sub"),
+                customer: session.customer.clone(),
+                status: "active".to_string(),
+                cancel_at_period_end: false,
+                current_period_start: now.timestamp(),
+                current_period_end: (now + Duration::days(30)).timestamp(),
+            };
+            let id = subscription.id.clone();
+            state.subscriptions.insert(id.clone(), subscription);
+            (Some(id), None)
+        } else {
+            (None, Some(state.next_id("pi")))
+        };
+
+        if let Some(stored) = state.sessions.get_mut(session_id) {
+            stored.completed = true;
+        }
+
+        Ok(json!({
+            "id": state.next_id("evt"),
+            "object": "event",
+            "type": "checkout.session.completed",
+            "created": now.timestamp(),
+            "livemode": false,
+            "api_version": "2024-06-20",
+            "request": { "id": null, "idempotency_key": null },
+            "data": {
+                "object": {
+                    "id": session.id,
+                    "object": "checkout.session",
+                    "mode": session.mode,
+                    "subscription": subscription,
+                    "customer": session.customer,
+                    "payment_intent": payment_intent,
+                    "payment_status": "paid",
+                    "amount_total": price.amount_minor * session.quantity,
+                    "currency": price.currency,
+                    "metadata": session.metadata,
+                    "total_details": { "amount_discount": 0 },
+                },
+            },
+        }))
+    }
+
+    /// Serializes `event` and returns the body with its `Stripe-Signature` header value.
+    pub fn sign_event(&self, event: &Value) -> Result<(Vec<u8>, String)> {
+        let payload = serde_json::to_vec(event)?;
+        let signature =
+            sign_webhook_payload(FAKE_WEBHOOK_SECRET, Utc::now().timestamp(), &payload)?;
+        Ok((payload, signature))
+    }
+
+    /// Delivers a signed event to a webhook endpoint and returns the endpoint's status.
+    pub async fn deliver_event(&self, webhook_url: &str, event: &Value) -> Result<StatusCode> {
+        let (payload, signature) = self.sign_event(event)?;
+        let resp = self
+            .http
+            .post(webhook_url)
+            .header("stripe-signature", signature)
+            .header("content-type", "application/json")
+            .body(payload)
+            .send()
+            .await?;
+
+        Ok(StatusCode::from_u16(resp.status().as_u16())?)
+    }
+}
+
+/// The session id at the end of a checkout URL handed out by the fake.
+pub fn checkout_session_id(checkout_url: &str) -> &str {
+    checkout_url.rsplit('/').next().unwrap_or_default()
+}
+
+fn stripe_error(status: StatusCode, message: &str, param: Option<&str>) -> Response {
+    let body = json!({
+        "error": {
+            "type": "invalid_request_error",
+            "code": "resource_missing",
+            "message": message,
+            "param": param,
+        }
+    });
+    (status, Json(body)).into_response()
+}
+
+fn authorized(headers: &HeaderMap) -> bool {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value == format!("Bearer {FAKE_SECRET_KEY}"))
+}
+
+fn form_value<'a>(form: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    form.iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+fn form_metadata(form: &[(String, String)]) -> HashMap<String, String> {
+    form.iter()
+        .filter_map(|(name, value)| {
+            let key = name.strip_prefix("metadata[")?.strip_suffix(']')?;
+            Some((key.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+async fn create_customer(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Form(form): Form<Vec<(String, String)>>,
+) -> Response {
+    if !authorized(&headers) {
+        return stripe_error(StatusCode::UNAUTHORIZED, "invalid api key", None);
+    }
+
+    let mut state = state.lock().unwrap();
+    let id = state.next_id("cus");
+    let email = form_value(&form, "email").unwrap_or_default().to_string();
+    state.customers.insert(id.clone(), email.clone());
+
+    Json(json!({ "id": id, "object": "customer", "email": email })).into_response()
+}
+
+async fn create_checkout_session(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Form(form): Form<Vec<(String, String)>>,
+) -> Response {
+    if !authorized(&headers) {
+        return stripe_error(StatusCode::UNAUTHORIZED, "invalid api key", None);
+    }
+
+    let mut state = state.lock().unwrap();
+    let mode = form_value(&form, "mode").unwrap_or_default().to_string();
+    let Some(price_id) = form_value(&form, "line_items[0][price]").map(str::to_string) else {
+        return stripe_error(
+            StatusCode::BAD_REQUEST,
+            "missing line item price",
+            Some("line_items"),
+        );
+    };
+    let Some(price) = state.prices.get(&price_id) else {
+        return stripe_error(
+            StatusCode::BAD_REQUEST,
+            &format!("No such price: '{price_id}'"),
+            Some("line_items[0][price]"),
+        );
+    };
+    if price.recurring != (mode == "subscription") {
+        return stripe_error(
+            StatusCode::BAD_REQUEST,
+            "price type does not match checkout mode",
+            Some("mode"),
+        );
+    }
+    let customer = form_value(&form, "customer").map(str::to_string);
+    if let Some(customer) = customer.as_deref()
+        && !state.customers.contains_key(customer)
+    {
+        return stripe_error(
+            StatusCode::BAD_REQUEST,
+            &format!("No such customer: '{customer}'"),
+            Some("customer"),
+        );
+    }
+
+    let id = state.next_id("cs");
+    let session = FakeCheckoutSession {
+        id: id.clone(),
+        mode,
+        price_id,
+        quantity: form_value(&form, "line_items[0][quantity]")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(1),
+        customer,
+        metadata: form_metadata(&form),
+        completed: false,
+    };
+    state.sessions.insert(id.clone(), session);
+
+    Json(json!({
+        "id": id,
+        "object": "checkout.session",
+        "url": format!("https://checkout.stripe.test/c/pay/{id}"),
+    }))
+    .into_response()
+}
+
+async fn list_promotion_codes(headers: HeaderMap) -> Response {
+    if !authorized(&headers) {
+        return stripe_error(StatusCode::UNAUTHORIZED, "invalid api key", None);
+    }
+
+    Json(json!({ "object": "list", "data": [] })).into_response()
+}
+
+async fn retrieve_subscription(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if !authorized(&headers) {
+        return stripe_error(StatusCode::UNAUTHORIZED, "invalid api key", None);
+    }
+
+    match state.lock().unwrap().subscriptions.get(&id) {
+        Some(subscription) => Json(subscription.to_json()).into_response(),
+        None => stripe_error(
+            StatusCode::NOT_FOUND,
+            &format!("No such subscription: '{id}'"),
+            Some("id"),
+        ),
+    }
+}
+
+async fn update_subscription(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+    Form(form): Form<Vec<(String, String)>>,
+) -> Response {
+    if !authorized(&headers) {
+        return stripe_error(StatusCode::UNAUTHORIZED, "invalid api key", None);
+    }
+
+    let mut state = state.lock().unwrap();
+    let Some(subscription) = state.subscriptions.get_mut(&id) else {
+        return stripe_error(
+            StatusCode::NOT_FOUND,
+            &format!("No such subscription: '{id}'"),
+            Some("id"),
+        );
+    };
+    if let Some(value) = form_value(&form, "cancel_at_period_end") {
+        subscription.cancel_at_period_end = value == "true";
+    }
+
+    Json(subscription.to_json()).into_response()
+}
+
+async fn cancel_subscription(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if !authorized(&headers) {
+        return stripe_error(StatusCode::UNAUTHORIZED, "invalid api key", None);
+    }
+
+    let mut state = state.lock().unwrap();
+    let Some(subscription) = state.subscriptions.get_mut(&id) else {
+        return stripe_error(
+            StatusCode::NOT_FOUND,
+            &format!("No such subscription: '{id}'"),
+            Some("id"),
+        );
+    };
+    subscription.status = "canceled".to_string();
+
+    Json(subscription.to_json()).into_response()
+}
@@ -8,21 +8,28 @@ use crate::infra::db::postgres::schema::deliveries;
 #[diesel(table_name = deliveries)]
 pub struct DeliveryEntity {
     pub id: Uuid,
-    pub recording_id: Uuid,
+    pub recording_id: Option<Uuid>,
     pub user_id: Uuid,
     pub via: String,
     pub delivered_at: Option<DateTime<Utc>>,
     pub status: String,
     pub error: Option<String>,
+    pub subscription_id: Option<Uuid>,
+    pub kind: Option<String>,
+    pub dedupe_key: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Insertable)]
 #[diesel(table_name = deliveries)]
 pub struct InsertDeliveryEntity {
-    pub recording_id: Uuid,
+    pub recording_id: Option<Uuid>,
     pub user_id: Uuid,
     pub via: String,
     pub delivered_at: Option<DateTime<Utc>>,
     pub status: String,
     pub error: Option<String>,
+    pub subscription_id: Option<Uuid>,
+    pub kind: Option<String>,
+    pub dedupe_key: Option<String>,
 }
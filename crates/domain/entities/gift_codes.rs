@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infra::db::postgres::schema::gift_codes;
+
+#[derive(Debug, Clone, Identifiable, Selectable, Queryable)]
+#[diesel(table_name = gift_codes)]
+pub struct GiftCodeEntity {
+    pub id: Uuid,
+    pub code: String,
+    pub plan_id: Uuid,
+    pub purchaser_id: Uuid,
+    pub term_months: i32,
+    pub status: String,
+    pub provider_payment_ref: String,
+    pub invoice_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub redeemed_by: Option<Uuid>,
+    pub redeemed_at: Option<DateTime<Utc>>,
+    pub subscription_id: Option<Uuid>,
+    pub void_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = gift_codes)]
+pub struct InsertGiftCodeEntity {
+    pub code: String,
+    pub plan_id: Uuid,
+    pub purchaser_id: Uuid,
+    pub term_months: i32,
+    pub status: String,
+    pub provider_payment_ref: String,
+    pub invoice_id: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
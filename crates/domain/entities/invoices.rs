@@ -19,6 +19,11 @@ pub struct InvoiceEntity {
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub paid_at: Option<DateTime<Utc>>,
+    pub provider_invoice_ref: Option<String>,
+    pub hosted_invoice_url: Option<String>,
+    pub invoice_pdf_url: Option<String>,
+    pub discount_minor: i32,
+    pub promotion_code: Option<String>,
 }
 
 #[derive(Debug, Clone, Insertable)]
@@ -35,3 +40,20 @@ pub struct InsertInvoiceEntity {
     pub status: String,
     pub paid_at: Option<DateTime<Utc>>,
 }
+
+/// Provider-side links for an invoice; `None` fields are left untouched on update.
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = invoices)]
+pub struct InvoiceProviderLinksChangeset {
+    pub provider_invoice_ref: Option<String>,
+    pub hosted_invoice_url: Option<String>,
+    pub invoice_pdf_url: Option<String>,
+}
+
+impl InvoiceProviderLinksChangeset {
+    pub fn is_empty(&self) -> bool {
+        self.provider_invoice_ref.is_none()
+            && self.hosted_invoice_url.is_none()
+            && self.invoice_pdf_url.is_none()
+    }
+}
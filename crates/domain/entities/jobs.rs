@@ -18,6 +18,7 @@ pub struct JobEntity {
     pub status: String,
     pub error: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub dedupe_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Insertable, Queryable)]
@@ -32,6 +33,7 @@ pub struct InsertJobEntity {
     pub status: String,
     pub error: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub dedupe_key: Option<String>,
 }
 
 #[derive(Debug, Clone, AsChangeset, Queryable)]
@@ -1,12 +1,18 @@
 pub mod app_users;
 pub mod deliveries;
 pub mod follows;
+pub mod gift_codes;
 pub mod invoices;
 pub mod jobs;
 pub mod live_accounts;
 pub mod payment_methods;
 pub mod payment_provider_customers;
 pub mod payments;
+pub mod plan_prices;
 pub mod plans;
+pub mod recording_engine_errors;
+pub mod recording_engine_webhook_events;
 pub mod recordings;
+pub mod stripe_events;
+pub mod subscription_grants;
 pub mod subscriptions;
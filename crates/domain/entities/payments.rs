@@ -21,6 +21,11 @@ pub struct PaymentEntity {
     pub error: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub receipt_url: Option<String>,
+    pub discount_minor: i32,
+    pub promotion_code: Option<String>,
+    pub refunded_minor: i32,
+    pub status_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Insertable)]
@@ -37,6 +42,8 @@ pub struct InsertPaymentEntity {
     pub provider_payment_id: Option<String>,
     pub provider_session_ref: Option<String>,
     pub error: Option<String>,
+    pub discount_minor: i32,
+    pub promotion_code: Option<String>,
 }
 
 // NewPaymentEntity is the application-facing alias for inserting rows into `payments`.
@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infra::db::postgres::schema::plan_prices;
+
+#[derive(Debug, Clone, Identifiable, Selectable, Queryable)]
+#[diesel(table_name = plan_prices)]
+pub struct PlanPriceEntity {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub currency: String,
+    pub billing_mode: String,
+    pub payment_method: String,
+    pub amount_minor: i32,
+    pub stripe_price_id: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    /// Months covered by one unit of a one-time price; always 1 for recurring prices.
+    pub term_months: i32,
+}
@@ -1,7 +1,17 @@
+use anyhow::Context;
 use diesel::prelude::*;
 use uuid::Uuid;
 
-use crate::{domain::value_objects::plans::PlanFeatures, infra::db::postgres::schema::plans};
+use crate::{
+    domain::{
+        entities::plan_prices::PlanPriceEntity,
+        value_objects::{
+            enums::{billing_modes::BillingMode, payment_methods::PaymentMethod},
+            plans::PlanFeatures,
+        },
+    },
+    infra::db::postgres::schema::plans,
+};
 
 #[derive(Debug, Clone)]
 pub struct PlanEntity {
@@ -11,9 +21,35 @@ pub struct PlanEntity {
     pub duration_days: i32,
     pub features: PlanFeatures,
     pub is_active: bool,
-    pub stripe_price_recurring: Option<String>,
-    pub stripe_price_one_time_card: Option<String>,
-    pub stripe_price_one_time_promptpay: Option<String>,
+    /// Active Stripe prices, one per currency / billing mode / payment method.
+    pub prices: Vec<PlanPriceEntity>,
+}
+
+impl PlanEntity {
+    pub fn find_price(
+        &self,
+        currency: &str,
+        billing_mode: BillingMode,
+        payment_method: PaymentMethod,
+        term_months: i32,
+    ) -> Option<&PlanPriceEntity> {
+        self.prices.iter().find(|price| {
+            price.currency == currency
+                && price.billing_mode == billing_mode.as_str()
+                && price.payment_method == payment_method.as_str()
+                && price.term_months == term_months
+        })
+    }
+
+    /// Single-month list amount in the given currency, falling back to the plan's base
+    /// `price_minor` when the plan has no price in that currency.
+    pub fn price_minor_in(&self, currency: &str) -> i32 {
+        self.prices
+            .iter()
+            .find(|price| price.currency == currency && price.term_months == 1)
+            .map(|price| price.amount_minor)
+            .unwrap_or(self.price_minor)
+    }
 }
 
 /// Raw row used for Diesel queries. Features stay as JSON and are parsed into PlanFeatures.
@@ -26,25 +62,23 @@ pub struct PlanRow {
     pub duration_days: i32,
     pub features: serde_json::Value,
     pub is_active: bool,
-    pub stripe_price_recurring: Option<String>,
-    pub stripe_price_one_time_card: Option<String>,
-    pub stripe_price_one_time_promptpay: Option<String>,
 }
 
-impl From<PlanRow> for PlanEntity {
-    fn from(value: PlanRow) -> Self {
-        let features = serde_json::from_value(value.features).unwrap_or_default();
+impl TryFrom<PlanRow> for PlanEntity {
+    type Error = anyhow::Error;
+
+    fn try_from(value: PlanRow) -> Result<Self, Self::Error> {
+        let features = PlanFeatures::from_json(value.features)
+            .with_context(|| format!("plan {} has invalid features", value.id))?;
 
-        Self {
+        Ok(Self {
             id: value.id,
             name: value.name,
             price_minor: value.price_minor,
             duration_days: value.duration_days,
             features,
             is_active: value.is_active,
-            stripe_price_recurring: value.stripe_price_recurring,
-            stripe_price_one_time_card: value.stripe_price_one_time_card,
-            stripe_price_one_time_promptpay: value.stripe_price_one_time_promptpay,
-        }
+            prices: Vec::new(),
+        })
     }
 }
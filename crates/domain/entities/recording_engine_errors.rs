@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infra::db::postgres::schema::recording_engine_errors;
+
+#[derive(Debug, Clone, PartialEq, Selectable, Queryable)]
+#[diesel(table_name = recording_engine_errors)]
+pub struct RecordingEngineErrorEntity {
+    pub platform: String,
+    pub channel: String,
+    pub live_account_id: Option<Uuid>,
+    pub recording_id: Option<Uuid>,
+    pub last_error: String,
+    pub error_count: i32,
+    pub consecutive_errors: i32,
+    pub first_error_at: DateTime<Utc>,
+    pub last_error_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Insertable)]
+#[diesel(table_name = recording_engine_errors)]
+pub struct InsertRecordingEngineErrorEntity {
+    pub platform: String,
+    pub channel: String,
+    pub live_account_id: Option<Uuid>,
+    pub recording_id: Option<Uuid>,
+    pub last_error: String,
+    pub first_error_at: DateTime<Utc>,
+    pub last_error_at: DateTime<Utc>,
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infra::db::postgres::schema::recording_engine_webhook_events;
+
+#[derive(Debug, Clone, Identifiable, Selectable, Queryable)]
+#[diesel(table_name = recording_engine_webhook_events)]
+pub struct RecordingEngineWebhookEventEntity {
+    pub id: Uuid,
+    pub type_: String,
+    pub ts: DateTime<Utc>,
+    pub recording_id: Option<Uuid>,
+    pub received_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Insertable)]
+#[diesel(table_name = recording_engine_webhook_events)]
+pub struct InsertRecordingEngineWebhookEventEntity {
+    pub id: Uuid,
+    pub type_: String,
+    pub ts: DateTime<Utc>,
+}
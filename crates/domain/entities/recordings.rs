@@ -21,6 +21,12 @@ pub struct RecordingEntity {
     pub poster_storage_path: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bitrate_bps: Option<i64>,
+    pub fps: Option<f64>,
 }
 
 #[derive(Debug, Clone, Insertable, Queryable)]
@@ -43,4 +49,39 @@ pub struct RecordingTransmuxUpdateEntity {
     pub status: String,
     pub updated_at: chrono::DateTime<Utc>,
     pub poster_storage_path: Option<Option<String>>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bitrate_bps: Option<i64>,
+    pub fps: Option<f64>,
+}
+
+/// Written when the live ends or the engine finishes the raw file; `None` fields are kept.
+#[derive(Debug, Clone, PartialEq, AsChangeset)]
+#[diesel(table_name = recordings)]
+pub struct RecordingLiveEndUpdateEntity {
+    pub status: String,
+    pub ended_at: Option<DateTime<Utc>>,
+    pub duration_sec: Option<i32>,
+    pub size_bytes: Option<i64>,
+    /// Host path of the raw (not yet transmuxed) file.
+    pub storage_temp_path: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Points the recording at its remuxed MP4; `None` fields are kept.
+#[derive(Debug, Clone, PartialEq, AsChangeset)]
+#[diesel(table_name = recordings)]
+pub struct RecordingRemuxUpdateEntity {
+    pub storage_path: String,
+    pub size_bytes: Option<i64>,
+    pub duration_sec: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bitrate_bps: Option<i64>,
+    pub fps: Option<f64>,
+    pub updated_at: DateTime<Utc>,
 }
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde_json::Value;
+
+use crate::infra::db::postgres::schema::stripe_events;
+
+#[derive(Debug, Clone, Identifiable, Selectable, Queryable)]
+#[diesel(table_name = stripe_events)]
+pub struct StripeEventEntity {
+    pub id: String,
+    pub type_: String,
+    pub payload: Value,
+    pub received_at: DateTime<Utc>,
+    pub processed_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub attempts: i32,
+    pub claimed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = stripe_events)]
+pub struct InsertStripeEventEntity {
+    pub id: String,
+    pub type_: String,
+    pub payload: Value,
+}
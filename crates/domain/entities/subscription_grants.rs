@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infra::db::postgres::schema::subscription_grants;
+
+#[derive(Debug, Clone, Identifiable, Selectable, Queryable)]
+#[diesel(table_name = subscription_grants, primary_key(subscription_id))]
+pub struct SubscriptionGrantEntity {
+    pub subscription_id: Uuid,
+    pub reason: String,
+    pub granted_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_by: Option<Uuid>,
+    pub revoke_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = subscription_grants)]
+pub struct InsertSubscriptionGrantEntity {
+    pub subscription_id: Uuid,
+    pub reason: String,
+    pub granted_by: Uuid,
+}
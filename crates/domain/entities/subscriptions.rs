@@ -19,6 +19,7 @@ pub struct SubscriptionEntity {
     pub provider_subscription_id: Option<String>,
     pub status: String,
     pub created_at: DateTime<Utc>,
+    pub ended_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Insertable)]
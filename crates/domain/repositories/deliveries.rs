@@ -0,0 +1,15 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domain::entities::deliveries::{DeliveryEntity, InsertDeliveryEntity};
+
+#[async_trait]
+#[automock]
+pub trait DeliveryRepository {
+    /// Returns the existing row for the same `dedupe_key`/`via`, inserting it when missing.
+    async fn find_or_create(&self, delivery: InsertDeliveryEntity) -> Result<DeliveryEntity>;
+    async fn mark_sent(&self, delivery_id: Uuid) -> Result<()>;
+    async fn mark_failed(&self, delivery_id: Uuid, error: &str) -> Result<()>;
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domain::entities::{
+    gift_codes::{GiftCodeEntity, InsertGiftCodeEntity},
+    invoices::InsertInvoiceEntity,
+};
+
+#[async_trait]
+#[automock]
+pub trait GiftCodeRepository {
+    /// Issues a code for a gift purchase together with its invoice in one transaction; the
+    /// code's `invoice_id` is set to the new invoice. A concurrent insert for the same
+    /// `provider_payment_ref` returns the code that was issued first and keeps no extra invoice.
+    async fn create_gift_code(
+        &self,
+        invoice: InsertInvoiceEntity,
+        gift_code: InsertGiftCodeEntity,
+    ) -> Result<GiftCodeEntity>;
+
+    async fn find_by_code(&self, code: &str) -> Result<Option<GiftCodeEntity>>;
+
+    async fn find_by_provider_payment_ref(
+        &self,
+        provider_payment_ref: &str,
+    ) -> Result<Option<GiftCodeEntity>>;
+
+    /// Moves a pending code to active once its payment has cleared.
+    async fn activate_by_provider_payment_ref(
+        &self,
+        provider_payment_ref: &str,
+    ) -> Result<Option<GiftCodeEntity>>;
+
+    /// Marks an active, unexpired code as redeemed by `user_id`. A code the same user already
+    /// claimed but whose subscription was never attached can be claimed again so a failed
+    /// redemption can be retried. Returns `None` when the claim is not allowed.
+    async fn claim_gift_code(
+        &self,
+        gift_code_id: Uuid,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<GiftCodeEntity>>;
+
+    async fn attach_subscription(&self, gift_code_id: Uuid, subscription_id: Uuid) -> Result<()>;
+
+    /// Voids the unredeemed code paid for by `invoice_id`, e.g. after a refund.
+    async fn void_unredeemed_by_invoice_id(
+        &self,
+        invoice_id: Uuid,
+        reason: String,
+    ) -> Result<Option<GiftCodeEntity>>;
+
+    /// Gifts bought by a user, newest first.
+    async fn list_by_purchaser(&self, purchaser_id: Uuid) -> Result<Vec<GiftCodeEntity>>;
+}
@@ -17,4 +17,33 @@ pub trait InvoiceRepository {
         subscription_id: Uuid,
         period_start: DateTime<Utc>,
     ) -> Result<Option<InvoiceEntity>>;
+    async fn list_by_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        cursor_created_at: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+    ) -> Result<Vec<InvoiceEntity>>;
+    async fn find_by_id_and_user(
+        &self,
+        invoice_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<InvoiceEntity>>;
+    async fn update_provider_links(
+        &self,
+        invoice_id: Uuid,
+        provider_invoice_ref: Option<String>,
+        hosted_invoice_url: Option<String>,
+        invoice_pdf_url: Option<String>,
+    ) -> Result<()>;
+    async fn update_discount(
+        &self,
+        invoice_id: Uuid,
+        discount_minor: i32,
+        promotion_code: Option<String>,
+    ) -> Result<()>;
+    async fn find_latest_by_subscription_id(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<Option<InvoiceEntity>>;
 }
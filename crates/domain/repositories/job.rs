@@ -1,9 +1,13 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use mockall::automock;
 use uuid::Uuid;
 
-use crate::domain::entities::jobs::JobEntity;
+use crate::domain::{
+    entities::jobs::JobEntity,
+    value_objects::subscription_notifications::SubscriptionNotificationPayload,
+};
 
 #[async_trait]
 #[automock]
@@ -16,7 +20,31 @@ pub trait JobRepository {
 
     async fn lock_next_recording_upload_job(&self) -> Result<Option<JobEntity>>;
 
+    async fn enqueue_recording_remux_job(
+        &self,
+        recording_id: Uuid,
+        local_path: String,
+    ) -> Result<Uuid>;
+
+    async fn lock_next_recording_remux_job(&self) -> Result<Option<JobEntity>>;
+
+    /// Whether a remux or upload job for the recording is queued, or running with a lock taken
+    /// after `locked_after` (older locks belong to a worker that died mid-job).
+    async fn has_pending_recording_job(
+        &self,
+        recording_id: Uuid,
+        locked_after: DateTime<Utc>,
+    ) -> Result<bool>;
+
     async fn mark_job_done(&self, job_id: Uuid) -> Result<()>;
 
     async fn mark_job_failed(&self, job_id: Uuid, err: &str, max_attempts: i32) -> Result<()>;
+
+    /// Returns `None` when a job with the same dedupe key was already enqueued.
+    async fn enqueue_subscription_notification_job(
+        &self,
+        payload: SubscriptionNotificationPayload,
+    ) -> Result<Option<Uuid>>;
+
+    async fn lock_next_subscription_notification_job(&self) -> Result<Option<JobEntity>>;
 }
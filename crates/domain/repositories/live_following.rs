@@ -28,4 +28,17 @@ pub trait LiveFollowingRepository {
         find_live_account_model: &FindLiveAccountModel,
     ) -> Result<LiveAccountEntity>;
     async fn count_active_follows(&self, user_id: Uuid) -> Result<i64>;
+    /// Active follows whose live account is being recorded right now.
+    async fn count_recording_follows(&self, user_id: Uuid) -> Result<i64>;
+    /// Keeps the `keep` oldest active follows and marks the rest `TemporaryInactive`.
+    async fn deactivate_follows_over_limit(&self, user_id: Uuid, keep: i64) -> Result<usize>;
+    /// Reactivates the oldest `TemporaryInactive` follows until `max_active` are active.
+    async fn restore_temporary_inactive_follows(
+        &self,
+        user_id: Uuid,
+        max_active: i64,
+    ) -> Result<usize>;
+    async fn list_users_with_temporary_inactive_follows(&self) -> Result<Vec<Uuid>>;
+    /// Users with more than `max_active` active follows.
+    async fn list_users_with_active_follows_over(&self, max_active: i64) -> Result<Vec<Uuid>>;
 }
@@ -1,14 +1,22 @@
+pub mod deliveries;
+pub mod gift_codes;
 pub mod invoices;
 pub mod job;
 pub mod live_account_recording_engine;
 pub mod live_following;
+pub mod notification_channel;
 pub mod payment_provider_customers;
 pub mod payments;
 pub mod plans;
 pub mod recording_cleanup;
 pub mod recording_dashboard;
+pub mod recording_engine_control;
 pub mod recording_engine_webhook;
 pub mod recording_upload;
 pub mod recording_view;
+pub mod recording_watchdog;
 pub mod storage;
+pub mod stripe_events;
+pub mod subscription_grants;
 pub mod subscriptions;
+pub mod user_contacts;
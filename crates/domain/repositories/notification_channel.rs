@@ -0,0 +1,20 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use mockall::automock;
+
+use crate::domain::value_objects::{
+    enums::delivery_channels::DeliveryChannel,
+    subscription_notifications::{NotificationRecipient, SubscriptionNotificationPayload},
+};
+
+#[async_trait]
+#[automock]
+pub trait NotificationChannel {
+    fn channel(&self) -> DeliveryChannel;
+
+    async fn send(
+        &self,
+        recipient: &NotificationRecipient,
+        notification: &SubscriptionNotificationPayload,
+    ) -> Result<()>;
+}
@@ -1,9 +1,13 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use mockall::automock;
 use uuid::Uuid;
 
-use crate::domain::entities::payments::NewPaymentEntity;
+use crate::domain::entities::{
+    invoices::InvoiceEntity,
+    payments::{NewPaymentEntity, PaymentEntity},
+};
 use crate::domain::value_objects::enums::payment_statuses::PaymentStatus;
 
 #[async_trait]
@@ -16,4 +20,35 @@ pub trait PaymentRepository {
         status: PaymentStatus,
     ) -> Result<()>;
     async fn exists_by_provider_payment_id(&self, provider_payment_id: &str) -> Result<bool>;
+    async fn find_by_provider_payment_id(
+        &self,
+        provider_payment_id: &str,
+    ) -> Result<Option<PaymentEntity>>;
+    /// Stores the cumulative refunded amount reported by the provider.
+    async fn record_refund(
+        &self,
+        provider_payment_id: &str,
+        status: PaymentStatus,
+        refunded_minor: i32,
+        reason: Option<String>,
+    ) -> Result<()>;
+    async fn record_dispute(
+        &self,
+        provider_payment_id: &str,
+        status: PaymentStatus,
+        reason: Option<String>,
+    ) -> Result<()>;
+    async fn update_receipt_url_by_provider_payment_id(
+        &self,
+        provider_payment_id: &str,
+        receipt_url: &str,
+    ) -> Result<()>;
+    async fn list_by_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        cursor_created_at: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+    ) -> Result<Vec<(PaymentEntity, InvoiceEntity)>>;
+    async fn list_by_invoice_ids(&self, invoice_ids: Vec<Uuid>) -> Result<Vec<PaymentEntity>>;
 }
@@ -0,0 +1,14 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use mockall::automock;
+
+#[async_trait]
+#[automock]
+pub trait RecordingEngineControl {
+    /// Asks the recording engine to stop recording a channel that is currently live.
+    async fn stop_recording(&self, platform: &str, channel: &str) -> Result<()>;
+
+    /// Whether the engine is still recording the channel; `None` when the engine cannot be
+    /// asked.
+    async fn is_recording(&self, platform: &str, channel: &str) -> Result<Option<bool>>;
+}
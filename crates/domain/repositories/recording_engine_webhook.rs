@@ -1,14 +1,24 @@
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use mockall::automock;
 use uuid::Uuid;
 
 use crate::{
     domain::entities::{
         live_accounts::LiveAccountEntity,
-        recordings::{InsertRecordingEntity, RecordingEntity, RecordingTransmuxUpdateEntity},
+        recording_engine_errors::{InsertRecordingEngineErrorEntity, RecordingEngineErrorEntity},
+        recording_engine_webhook_events::InsertRecordingEngineWebhookEventEntity,
+        recordings::{
+            InsertRecordingEntity, RecordingEntity, RecordingLiveEndUpdateEntity,
+            RecordingTransmuxUpdateEntity,
+        },
+    },
+    domain::value_objects::{
+        enums::recording_statuses::RecordingStatus,
+        recording_engine_webhook::WebhookEventClaim,
+        recordings::{FollowerRecordingCapacity, RecordingUsage},
     },
-    domain::value_objects::enums::recording_statuses::RecordingStatus,
 };
 
 #[async_trait]
@@ -32,4 +42,75 @@ pub trait RecordingEngineWebhookRepository {
         changeset: RecordingTransmuxUpdateEntity,
     ) -> Result<Uuid>;
     async fn update_file_uploading(&self, recording_id: Uuid) -> Result<Uuid>;
+    async fn update_live_end(
+        &self,
+        recording_id: Uuid,
+        changeset: RecordingLiveEndUpdateEntity,
+    ) -> Result<Uuid>;
+    /// Bumps `updated_at` of a recording that is still `live_recording`, so the stale
+    /// watchdog sees the engine is still reporting on it.
+    async fn touch_live_recording(&self, recording_id: Uuid) -> Result<()>;
+    /// `live_end` recordings with a raw file that have not changed since `finished_before`,
+    /// i.e. the engine finished the file but never sent `video_transmux_finish`.
+    async fn list_untransmuxed_recordings(
+        &self,
+        finished_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RecordingEntity>>;
+    /// Moves an untransmuxed recording to `waiting_upload` with its raw file as the storage
+    /// path. Returns `false` if a transmux (or another sweep) got to it first.
+    async fn claim_untransmuxed_recording(
+        &self,
+        recording_id: Uuid,
+        finished_before: DateTime<Utc>,
+    ) -> Result<bool>;
+    /// Most recently started recording of a live account, whatever its status.
+    async fn find_latest_recording_by_live_account(
+        &self,
+        platform: String,
+        account_id: String,
+    ) -> Result<Option<RecordingEntity>>;
+    /// Active followers of a live account, each with the recording limits of their effective
+    /// plan (active paid subscription, else `free_plan_id`) and how many other followed
+    /// accounts are currently recording.
+    async fn list_follower_recording_capacity(
+        &self,
+        live_account_id: Uuid,
+        free_plan_id: Uuid,
+    ) -> Result<Vec<FollowerRecordingCapacity>>;
+    /// Recording usage attributed to a follower, as reported by the backend usage API.
+    async fn find_recording_usage(
+        &self,
+        user_id: Uuid,
+        retention_days: i64,
+        month_start: DateTime<Utc>,
+    ) -> Result<RecordingUsage>;
+    /// Records that `event` is being processed. A retry can take over a claim made before
+    /// `stale_before` that never completed, e.g. because the worker died mid-way.
+    async fn claim_webhook_event(
+        &self,
+        event: InsertRecordingEngineWebhookEventEntity,
+        stale_before: DateTime<Utc>,
+    ) -> Result<WebhookEventClaim>;
+    async fn complete_webhook_event(
+        &self,
+        event_id: Uuid,
+        recording_id: Option<Uuid>,
+    ) -> Result<()>;
+    /// Drops an uncompleted claim so the engine's retry is processed afresh.
+    async fn release_webhook_event(&self, event_id: Uuid) -> Result<()>;
+    /// Stores an engine error against its channel. The consecutive count restarts when the
+    /// previous error is older than `streak_since`.
+    async fn record_engine_error(
+        &self,
+        error: InsertRecordingEngineErrorEntity,
+        streak_since: DateTime<Utc>,
+    ) -> Result<RecordingEngineErrorEntity>;
+    /// Flips the live account to `error`; an `unsynced` account keeps its status.
+    async fn mark_live_account_error(&self, live_account_id: Uuid) -> Result<()>;
+    /// Ends the error streak of the recording's live account now that it has produced a
+    /// recording, flipping an `error` account back to `synced`; other statuses are kept.
+    async fn clear_engine_errors_for_recording(&self, recording_id: Uuid) -> Result<()>;
+    /// Channels currently in an error streak, longest streak first.
+    async fn list_failing_channels(&self, limit: i64) -> Result<Vec<RecordingEngineErrorEntity>>;
 }
@@ -3,7 +3,7 @@ use async_trait::async_trait;
 use mockall::automock;
 use uuid::Uuid;
 
-use crate::domain::entities::recordings::RecordingEntity;
+use crate::domain::entities::recordings::{RecordingEntity, RecordingRemuxUpdateEntity};
 
 #[async_trait]
 #[automock]
@@ -17,4 +17,10 @@ pub trait RecordingUploadRepository {
         size_bytes: i64,
         duration_sec: i32,
     ) -> Result<Uuid>;
+
+    async fn update_remuxed_recording(
+        &self,
+        recording_id: Uuid,
+        changeset: RecordingRemuxUpdateEntity,
+    ) -> Result<Uuid>;
 }
@@ -4,7 +4,10 @@ use chrono::{DateTime, Utc};
 use mockall::automock;
 use uuid::Uuid;
 
-use crate::domain::entities::{live_accounts::LiveAccountEntity, recordings::RecordingEntity};
+use crate::domain::{
+    entities::{live_accounts::LiveAccountEntity, recordings::RecordingEntity},
+    value_objects::recordings::{RecordingUsage, RecordingViewWindow},
+};
 
 #[async_trait]
 #[automock]
@@ -12,7 +15,7 @@ pub trait RecordingViewRepository {
     async fn list_home_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
         limit: i64,
         cursor_started_at: Option<DateTime<Utc>>,
         cursor_id: Option<Uuid>,
@@ -21,23 +24,40 @@ pub trait RecordingViewRepository {
     async fn list_follows_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
         live_account_id: Option<Uuid>,
     ) -> Result<Vec<RecordingEntity>>;
 
     async fn count_follows_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
     ) -> Result<Vec<(Uuid, i64)>>;
 
+    /// Whether `recording_id` is one of the user's ready recordings inside `window`.
+    async fn is_recording_in_view_window(
+        &self,
+        user_id: Uuid,
+        window: RecordingViewWindow,
+        recording_id: Uuid,
+    ) -> Result<bool>;
+
     async fn list_currently_recording_live_account_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>>;
 
     async fn count_home_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
     ) -> Result<i64>;
 
     async fn count_currently_recording(&self, user_id: Uuid) -> Result<i64>;
+
+    /// Storage of the user's watchable recordings within `retention_days` and the seconds
+    /// recorded for their follows since `month_start`.
+    async fn find_recording_usage(
+        &self,
+        user_id: Uuid,
+        retention_days: i64,
+        month_start: DateTime<Utc>,
+    ) -> Result<RecordingUsage>;
 }
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::{
+        live_accounts::LiveAccountEntity,
+        recordings::{RecordingEntity, RecordingLiveEndUpdateEntity},
+    },
+    value_objects::enums::recording_statuses::RecordingStatus,
+};
+
+#[async_trait]
+#[automock]
+pub trait RecordingWatchdogRepository {
+    /// Recordings in `status` that have not changed since `stale_before`, oldest first.
+    async fn list_stale_recordings(
+        &self,
+        status: RecordingStatus,
+        stale_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RecordingEntity>>;
+
+    /// Applies `changeset` only if the recording is still in `status` and unchanged since
+    /// `stale_before`; returns `false` when a webhook or upload moved it on first.
+    async fn update_stale_recording(
+        &self,
+        recording_id: Uuid,
+        status: RecordingStatus,
+        stale_before: DateTime<Utc>,
+        changeset: RecordingLiveEndUpdateEntity,
+    ) -> Result<bool>;
+
+    async fn find_live_account(&self, live_account_id: Uuid) -> Result<Option<LiveAccountEntity>>;
+
+    /// Local files still in use: the paths of recordings that are live, awaiting transmux
+    /// or awaiting upload, and of queued or running remux and upload jobs.
+    async fn list_local_recording_paths(&self) -> Result<Vec<String>>;
+}
@@ -2,6 +2,7 @@ use crate::domain::entities::recordings::RecordingEntity;
 use crate::domain::value_objects::storage::UploadResult;
 use anyhow::Result;
 use async_trait::async_trait;
+use mockall::automock;
 use uuid::Uuid;
 
 #[async_trait]
@@ -16,6 +17,7 @@ pub trait StorageClient {
 }
 
 #[async_trait]
+#[automock]
 pub trait CoverStorageClient {
     async fn upload_cover(
         &self,
@@ -0,0 +1,34 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::automock;
+
+use crate::domain::entities::stripe_events::{InsertStripeEventEntity, StripeEventEntity};
+
+#[async_trait]
+#[automock]
+pub trait StripeEventRepository {
+    /// Stores the event if it has not been seen yet and returns the ledger row either way.
+    async fn record_received(&self, event: InsertStripeEventEntity) -> Result<StripeEventEntity>;
+    async fn find_by_id(&self, event_id: &str) -> Result<Option<StripeEventEntity>>;
+    /// Claims the event for processing unless another claim newer than `stale_before` holds
+    /// it; processed events are only claimable when `reprocess` is set. Returns whether the
+    /// claim was taken.
+    async fn claim(
+        &self,
+        event_id: &str,
+        stale_before: DateTime<Utc>,
+        reprocess: bool,
+    ) -> Result<bool>;
+    /// Marks the event processed and drops its claim.
+    async fn mark_processed(&self, event_id: &str) -> Result<()>;
+    /// Records the failure and drops the claim so a retry can pick the event up.
+    async fn mark_failed(&self, event_id: &str, error: &str) -> Result<()>;
+    /// Unprocessed events received before `received_before` with fewer than `max_attempts` attempts.
+    async fn list_retryable(
+        &self,
+        max_attempts: i32,
+        received_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<StripeEventEntity>>;
+}
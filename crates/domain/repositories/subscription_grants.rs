@@ -0,0 +1,39 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use mockall::automock;
+use uuid::Uuid;
+
+use crate::domain::entities::{
+    subscription_grants::SubscriptionGrantEntity,
+    subscriptions::{InsertSubscriptionEntity, SubscriptionEntity},
+};
+
+#[async_trait]
+#[automock]
+pub trait SubscriptionGrantRepository {
+    /// Inserts the complimentary subscription and its grant record in one transaction.
+    async fn create_grant(
+        &self,
+        subscription: InsertSubscriptionEntity,
+        reason: String,
+        granted_by: Uuid,
+    ) -> Result<(SubscriptionEntity, SubscriptionGrantEntity)>;
+
+    /// Cancels an unrevoked grant and ends its subscription at `now`.
+    /// Returns `None` when the grant does not exist or was already revoked.
+    async fn revoke_grant(
+        &self,
+        subscription_id: Uuid,
+        revoked_by: Uuid,
+        revoke_reason: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(SubscriptionEntity, SubscriptionGrantEntity)>>;
+
+    /// Lists grants newest first, optionally for a single user.
+    async fn list_grants(
+        &self,
+        user_id: Option<Uuid>,
+        include_revoked: bool,
+    ) -> Result<Vec<(SubscriptionEntity, SubscriptionGrantEntity)>>;
+}
@@ -56,4 +56,61 @@ pub trait SubscriptionRepository {
     async fn cancel_recurring_subscription(&self, user_id: Uuid) -> Result<()>;
 
     async fn list_active_subscriptions(&self) -> Result<Vec<SubscriptionEntity>>;
+
+    /// Moves active one-time and complimentary subscriptions whose `ends_at` has passed to
+    /// `Expired`.
+    async fn expire_ended_non_renewing_subscriptions(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>>;
+
+    /// Lists expired one-time and complimentary subscriptions whose expiry notice has not
+    /// been enqueued yet, oldest first.
+    async fn list_expired_pending_notice(&self) -> Result<Vec<SubscriptionEntity>>;
+
+    async fn mark_expiry_notice_enqueued(
+        &self,
+        subscription_id: Uuid,
+        enqueued_at: DateTime<Utc>,
+    ) -> Result<()>;
+
+    /// Moves `PastDue` subscriptions whose `ends_at` is before `cutoff` to `Canceled`.
+    async fn cancel_past_due_subscriptions(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>>;
+
+    /// Finds any subscription of the user whose period intersects `[starts_at, ends_at)`.
+    async fn find_overlapping_subscription(
+        &self,
+        user_id: Uuid,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> Result<Option<SubscriptionEntity>>;
+
+    /// Lists every subscription of the user, whatever its status, whose period runs past
+    /// `after`, ordered by `ends_at`. Mirrors what `subscriptions_user_timerange_excl` would
+    /// reject a new period for.
+    async fn list_subscriptions_ending_after(
+        &self,
+        user_id: Uuid,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>>;
+
+    /// Lists active subscriptions that will not renew on their own (one-time terms, grants and
+    /// recurring ones set to cancel at period end) with `ends_at` in `(from, to]`.
+    async fn list_non_renewing_ending_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>>;
+
+    /// Cancels a subscription that is still running as of `now`, cutting its period short and
+    /// recording why. Returns `None` when the subscription does not exist or already ended.
+    async fn end_subscription_early(
+        &self,
+        subscription_id: Uuid,
+        reason: String,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SubscriptionEntity>>;
 }
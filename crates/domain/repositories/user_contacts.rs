@@ -0,0 +1,10 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use mockall::automock;
+use uuid::Uuid;
+
+#[async_trait]
+#[automock]
+pub trait UserContactRepository {
+    async fn find_email(&self, user_id: Uuid) -> Result<Option<String>>;
+}
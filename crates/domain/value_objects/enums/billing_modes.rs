@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub enum BillingMode {
     Recurring,
     OneTime,
+    /// Granted by an admin; never billed through Stripe.
+    Complimentary,
 }
 
 impl BillingMode {
@@ -13,6 +15,7 @@ impl BillingMode {
         match self {
             BillingMode::Recurring => "recurring",
             BillingMode::OneTime => "one_time",
+            BillingMode::Complimentary => "complimentary",
         }
     }
 
@@ -20,6 +23,7 @@ impl BillingMode {
         match value {
             "recurring" => Some(BillingMode::Recurring),
             "one_time" => Some(BillingMode::OneTime),
+            "complimentary" => Some(BillingMode::Complimentary),
             _ => None,
         }
     }
@@ -0,0 +1,29 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryChannel {
+    WebNotify,
+    Email,
+    Telegram,
+    Webhook,
+}
+
+impl DeliveryChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryChannel::WebNotify => "web_notify",
+            DeliveryChannel::Email => "email",
+            DeliveryChannel::Telegram => "telegram",
+            DeliveryChannel::Webhook => "webhook",
+        }
+    }
+}
+
+impl Display for DeliveryChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GiftCodeStatus {
+    /// Issued at checkout; the payment has not cleared yet.
+    Pending,
+    /// Paid and redeemable until it expires.
+    Active,
+    Redeemed,
+    /// Refunded or otherwise withdrawn before redemption.
+    Voided,
+}
+
+impl GiftCodeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GiftCodeStatus::Pending => "pending",
+            GiftCodeStatus::Active => "active",
+            GiftCodeStatus::Redeemed => "redeemed",
+            GiftCodeStatus::Voided => "voided",
+        }
+    }
+}
+
+impl Display for GiftCodeStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -4,14 +4,18 @@ use std::fmt::Display;
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum JobType {
     RecordingUpload,
+    RecordingRemux,
     NotifyReady,
+    SubscriptionNotify,
 }
 
 impl Display for JobType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let job_type = match self {
             JobType::RecordingUpload => "RecordingUpload",
+            JobType::RecordingRemux => "RecordingRemux",
             JobType::NotifyReady => "NotifyReady",
+            JobType::SubscriptionNotify => "SubscriptionNotify",
         };
         write!(f, "{}", job_type)
     }
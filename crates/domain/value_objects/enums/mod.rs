@@ -1,11 +1,16 @@
 pub mod billing_modes;
+pub mod delivery_channels;
+pub mod delivery_statuses;
 pub mod follow_statuses;
+pub mod gift_code_statuses;
 pub mod job_statuses;
 pub mod job_types;
 pub mod live_account_statuses;
+pub mod notification_kinds;
 pub mod payment_methods;
 pub mod payment_statuses;
 pub mod platforms;
+pub mod quota_enforcement_policies;
 pub mod recording_statuses;
 pub mod sort_order;
 pub mod subscription_statuses;
@@ -0,0 +1,27 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    RenewalReminder,
+    SubscriptionExpired,
+    PaymentFailed,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::RenewalReminder => "renewal_reminder",
+            NotificationKind::SubscriptionExpired => "subscription_expired",
+            NotificationKind::PaymentFailed => "payment_failed",
+        }
+    }
+}
+
+impl Display for NotificationKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -9,6 +9,9 @@ pub enum PaymentStatus {
     Succeeded,
     Failed,
     Canceled,
+    Refunded,
+    PartiallyRefunded,
+    Disputed,
 }
 
 impl PaymentStatus {
@@ -19,6 +22,9 @@ impl PaymentStatus {
             PaymentStatus::Succeeded => "succeeded",
             PaymentStatus::Failed => "failed",
             PaymentStatus::Canceled => "canceled",
+            PaymentStatus::Refunded => "refunded",
+            PaymentStatus::PartiallyRefunded => "partially_refunded",
+            PaymentStatus::Disputed => "disputed",
         }
     }
 }
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+
+/// What happens when a user goes over their plan's storage or recording-hour quota.
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaEnforcementPolicy {
+    /// Only report the overage in the usage API.
+    #[default]
+    Warn,
+    /// Hide the user's oldest recordings until the rest fit in `max_storage_bytes`.
+    HideOldest,
+    /// Skip new recordings unless some follower of the account is within quota.
+    StopRecording,
+}
+
+impl Display for QuotaEnforcementPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = match self {
+            QuotaEnforcementPolicy::Warn => "warn",
+            QuotaEnforcementPolicy::HideOldest => "hide_oldest",
+            QuotaEnforcementPolicy::StopRecording => "stop_recording",
+        };
+        write!(f, "{}", policy)
+    }
+}
+
+impl FromStr for QuotaEnforcementPolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "warn" => Ok(QuotaEnforcementPolicy::Warn),
+            "hide_oldest" => Ok(QuotaEnforcementPolicy::HideOldest),
+            "stop_recording" => Ok(QuotaEnforcementPolicy::StopRecording),
+            other => Err(format!("Unsupported quota enforcement policy: {}", other)),
+        }
+    }
+}
@@ -11,6 +11,10 @@ pub enum RecordingStatus {
     Ready,
     Failed,
     ExpiredDeleted,
+    /// Not recorded because no follower's plan had concurrent recording capacity left.
+    SkippedConcurrencyLimit,
+    /// Not recorded because every follower with capacity was over a usage quota.
+    SkippedQuotaExceeded,
 }
 
 impl Display for RecordingStatus {
@@ -23,6 +27,8 @@ impl Display for RecordingStatus {
             RecordingStatus::Ready => "ready",
             RecordingStatus::Failed => "failed",
             RecordingStatus::ExpiredDeleted => "expired_deleted",
+            RecordingStatus::SkippedConcurrencyLimit => "skipped_concurrency_limit",
+            RecordingStatus::SkippedQuotaExceeded => "skipped_quota_exceeded",
         };
         write!(f, "{}", follow_status)
     }
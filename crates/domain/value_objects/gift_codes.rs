@@ -0,0 +1,118 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::{gift_codes::GiftCodeEntity, subscriptions::SubscriptionEntity},
+    value_objects::subscriptions::{checkout_currency, checkout_term},
+};
+
+/// How long a paid gift code can be redeemed.
+pub const GIFT_CODE_VALIDITY_DAYS: i64 = 365;
+
+/// Characters used for generated codes; easily confused ones (0/O, 1/I/L) are left out.
+pub const GIFT_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+pub const GIFT_CODE_GROUPS: usize = 4;
+pub const GIFT_CODE_GROUP_LEN: usize = 4;
+
+/// Canonical `XXXX-XXXX-XXXX-XXXX` form of a user-entered code, ignoring case, spaces and
+/// dashes. Returns `None` when the input cannot be a gift code.
+pub fn normalize_gift_code(input: &str) -> Option<String> {
+    let chars: Vec<char> = input
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    if chars.len() != GIFT_CODE_GROUPS * GIFT_CODE_GROUP_LEN
+        || !chars
+            .iter()
+            .all(|c| c.is_ascii() && GIFT_CODE_ALPHABET.contains(&(*c as u8)))
+    {
+        return None;
+    }
+
+    let groups: Vec<String> = chars
+        .chunks(GIFT_CODE_GROUP_LEN)
+        .map(|group| group.iter().collect())
+        .collect();
+    Some(groups.join("-"))
+}
+
+/// Buys a one-time term for someone else. Gifts are always one-time payments.
+#[derive(Debug, Deserialize)]
+pub struct CreateGiftCheckoutRequest {
+    pub plan_id: Uuid,
+    pub payment_method: String,
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub term_months: Option<i32>,
+    #[serde(default)]
+    pub quantity: Option<i32>,
+}
+
+impl CreateGiftCheckoutRequest {
+    pub fn currency(&self) -> Result<String, &'static str> {
+        checkout_currency(self.currency.as_deref())
+    }
+
+    pub fn term(&self) -> Result<(i32, i32), &'static str> {
+        checkout_term(self.term_months, self.quantity)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RedeemGiftCodeRequest {
+    pub code: String,
+}
+
+/// A gift as seen by the buyer, including the code to hand over.
+#[derive(Debug, Serialize)]
+pub struct GiftCodeDto {
+    pub id: Uuid,
+    pub code: String,
+    pub plan_id: Uuid,
+    pub term_months: i32,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub redeemed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<GiftCodeEntity> for GiftCodeDto {
+    fn from(gift: GiftCodeEntity) -> Self {
+        Self {
+            id: gift.id,
+            code: gift.code,
+            plan_id: gift.plan_id,
+            term_months: gift.term_months,
+            status: gift.status,
+            expires_at: gift.expires_at,
+            redeemed_at: gift.redeemed_at,
+            created_at: gift.created_at,
+        }
+    }
+}
+
+/// The subscription created for the redeeming user.
+#[derive(Debug, Serialize)]
+pub struct GiftRedemptionDto {
+    pub gift_code_id: Uuid,
+    pub subscription_id: Uuid,
+    pub plan_id: Uuid,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+}
+
+impl From<(&GiftCodeEntity, &SubscriptionEntity)> for GiftRedemptionDto {
+    fn from((gift, subscription): (&GiftCodeEntity, &SubscriptionEntity)) -> Self {
+        Self {
+            gift_code_id: gift.id,
+            subscription_id: subscription.id,
+            plan_id: subscription.plan_id,
+            starts_at: subscription.starts_at,
+            ends_at: subscription.ends_at,
+        }
+    }
+}
@@ -41,6 +41,7 @@ impl InsertJobModel {
             status: JobStatus::Queued.to_string(),
             error: None,
             created_at: Utc::now(),
+            dedupe_key: None,
         }
     }
 }
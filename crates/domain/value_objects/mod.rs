@@ -1,4 +1,5 @@
 pub mod enums;
+pub mod gift_codes;
 pub mod iam;
 pub mod jobs;
 pub mod live_account_url;
@@ -8,4 +9,6 @@ pub mod recording_engine_webhook;
 pub mod recording_upload;
 pub mod recordings;
 pub mod storage;
+pub mod subscription_grants;
+pub mod subscription_notifications;
 pub mod subscriptions;
@@ -1,29 +1,229 @@
+use anyhow::{Context, Result, bail};
 use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt::Display};
 use uuid::Uuid;
 
+use crate::domain::value_objects::{
+    enums::quota_enforcement_policies::QuotaEnforcementPolicy, recordings::RecordingUsage,
+};
+
 /// Fixed UUID representing the free plan.
 pub const FREE_PLAN_ID: Uuid = Uuid::nil();
 
-/// Limits and feature flags attached to a plan. Stored as JSONB in the database.
+/// Currency used when a checkout does not ask for one and for payloads without a currency.
+pub const DEFAULT_CURRENCY: &str = "thb";
+
+/// Lengths, in months, a one-time term can be bought in.
+pub const ONE_TIME_TERM_MONTHS: [i32; 3] = [1, 3, 12];
+
+/// Upper bound on how many terms a single checkout can buy.
+pub const MAX_TERMS_PER_CHECKOUT: i32 = 12;
+
+/// Limits and feature flags attached to a plan. Stored as JSONB in the database and validated
+/// with [`PlanFeatures::from_json`] when a plan is loaded; unknown keys are rejected.
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
 pub struct PlanFeatures {
+    /// Live accounts a user can follow at once. Default 0 (cannot follow).
     #[serde(default)]
     pub max_follows: Option<i64>,
 
+    /// Days a recording stays watchable after it starts. Default 0 (nothing watchable).
     #[serde(default)]
     pub retention_days: Option<i32>,
 
+    /// Followed accounts that can be recording at the same time. Default 1.
     #[serde(default)]
     pub max_concurrent_recordings: Option<i32>,
 
+    /// Bytes of watchable recordings attributed to the user. Default unlimited.
+    #[serde(default)]
+    pub max_storage_bytes: Option<i64>,
+
+    /// Hours recorded from the user's follows per calendar month (UTC). Default unlimited.
+    #[serde(default)]
+    pub max_recording_hours_per_month: Option<i32>,
+
+    /// Shown on plan cards; nothing enforces it yet. Default false.
     #[serde(default)]
     pub priority_support: Option<bool>,
 
+    /// Shown on plan cards; nothing enforces it yet. Default false.
     #[serde(default)]
     pub custom_branding: Option<bool>,
 }
 
+/// The feature keys a plan can set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanFeatureKey {
+    MaxFollows,
+    RetentionDays,
+    MaxConcurrentRecordings,
+    MaxStorageBytes,
+    MaxRecordingHoursPerMonth,
+    PrioritySupport,
+    CustomBranding,
+}
+
+impl PlanFeatureKey {
+    pub const ALL: [PlanFeatureKey; 7] = [
+        PlanFeatureKey::MaxFollows,
+        PlanFeatureKey::RetentionDays,
+        PlanFeatureKey::MaxConcurrentRecordings,
+        PlanFeatureKey::MaxStorageBytes,
+        PlanFeatureKey::MaxRecordingHoursPerMonth,
+        PlanFeatureKey::PrioritySupport,
+        PlanFeatureKey::CustomBranding,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlanFeatureKey::MaxFollows => "max_follows",
+            PlanFeatureKey::RetentionDays => "retention_days",
+            PlanFeatureKey::MaxConcurrentRecordings => "max_concurrent_recordings",
+            PlanFeatureKey::MaxStorageBytes => "max_storage_bytes",
+            PlanFeatureKey::MaxRecordingHoursPerMonth => "max_recording_hours_per_month",
+            PlanFeatureKey::PrioritySupport => "priority_support",
+            PlanFeatureKey::CustomBranding => "custom_branding",
+        }
+    }
+
+    /// Inclusive range a numeric limit must fall in; `None` for flags.
+    pub fn bounds(&self) -> Option<(i64, i64)> {
+        match self {
+            PlanFeatureKey::MaxFollows => Some((0, 10_000)),
+            PlanFeatureKey::RetentionDays => Some((0, 3_650)),
+            PlanFeatureKey::MaxConcurrentRecordings => Some((0, 100)),
+            PlanFeatureKey::MaxStorageBytes => Some((0, 1 << 50)),
+            PlanFeatureKey::MaxRecordingHoursPerMonth => Some((0, 100_000)),
+            PlanFeatureKey::PrioritySupport | PlanFeatureKey::CustomBranding => None,
+        }
+    }
+}
+
+impl Display for PlanFeatureKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Effective value of a feature: a numeric limit, no limit at all, or an on/off flag.
+/// `Unlimited` serializes as `null`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum PlanFeatureValue {
+    Limit(i64),
+    Unlimited,
+    Flag(bool),
+}
+
+impl PlanFeatureValue {
+    fn rank(&self) -> i64 {
+        match self {
+            PlanFeatureValue::Limit(value) => *value,
+            PlanFeatureValue::Unlimited => i64::MAX,
+            PlanFeatureValue::Flag(value) => i64::from(*value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanFeatureChangeDirection {
+    Upgrade,
+    Downgrade,
+}
+
+/// One feature that differs between two plans.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanFeatureChange {
+    pub key: PlanFeatureKey,
+    pub from: PlanFeatureValue,
+    pub to: PlanFeatureValue,
+    pub direction: PlanFeatureChangeDirection,
+}
+
 impl PlanFeatures {
+    /// Parses a `plans.features` document, rejecting unknown keys, wrongly typed values and
+    /// limits outside [`PlanFeatureKey::bounds`].
+    pub fn from_json(value: serde_json::Value) -> Result<Self> {
+        let features: PlanFeatures =
+            serde_json::from_value(value).context("invalid plan features")?;
+
+        for key in PlanFeatureKey::ALL {
+            if let (Some((min, max)), Some(PlanFeatureValue::Limit(value))) =
+                (key.bounds(), features.configured(key))
+                && !(min..=max).contains(&value)
+            {
+                bail!("plan feature {key} must be between {min} and {max}, got {value}");
+            }
+        }
+
+        Ok(features)
+    }
+
+    /// Value set on the plan, or `None` when the key falls back to its default.
+    fn configured(&self, key: PlanFeatureKey) -> Option<PlanFeatureValue> {
+        match key {
+            PlanFeatureKey::MaxFollows => self.max_follows.map(PlanFeatureValue::Limit),
+            PlanFeatureKey::RetentionDays => self
+                .retention_days
+                .map(|value| PlanFeatureValue::Limit(i64::from(value))),
+            PlanFeatureKey::MaxConcurrentRecordings => self
+                .max_concurrent_recordings
+                .map(|value| PlanFeatureValue::Limit(i64::from(value))),
+            PlanFeatureKey::MaxStorageBytes => self.max_storage_bytes.map(PlanFeatureValue::Limit),
+            PlanFeatureKey::MaxRecordingHoursPerMonth => self
+                .max_recording_hours_per_month
+                .map(|value| PlanFeatureValue::Limit(i64::from(value))),
+            PlanFeatureKey::PrioritySupport => self.priority_support.map(PlanFeatureValue::Flag),
+            PlanFeatureKey::CustomBranding => self.custom_branding.map(PlanFeatureValue::Flag),
+        }
+    }
+
+    /// Effective value of `key`, with the documented default applied.
+    pub fn value(&self, key: PlanFeatureKey) -> PlanFeatureValue {
+        match key {
+            PlanFeatureKey::MaxFollows => PlanFeatureValue::Limit(self.max_follows_or_default()),
+            PlanFeatureKey::RetentionDays => {
+                PlanFeatureValue::Limit(i64::from(self.retention_days_or_default()))
+            }
+            PlanFeatureKey::MaxConcurrentRecordings => {
+                PlanFeatureValue::Limit(i64::from(self.max_concurrent_recordings_or_default()))
+            }
+            PlanFeatureKey::MaxStorageBytes | PlanFeatureKey::MaxRecordingHoursPerMonth => {
+                self.configured(key).unwrap_or(PlanFeatureValue::Unlimited)
+            }
+            PlanFeatureKey::PrioritySupport => PlanFeatureValue::Flag(self.has_priority_support()),
+            PlanFeatureKey::CustomBranding => PlanFeatureValue::Flag(self.has_custom_branding()),
+        }
+    }
+
+    /// Features whose effective value changes when moving from `self` to `target`, in catalog
+    /// order. Higher limits, removed limits and enabled flags count as upgrades.
+    pub fn compare(&self, target: &PlanFeatures) -> Vec<PlanFeatureChange> {
+        PlanFeatureKey::ALL
+            .into_iter()
+            .filter_map(|key| {
+                let from = self.value(key);
+                let to = target.value(key);
+                let direction = match to.rank().cmp(&from.rank()) {
+                    Ordering::Greater => PlanFeatureChangeDirection::Upgrade,
+                    Ordering::Less => PlanFeatureChangeDirection::Downgrade,
+                    Ordering::Equal => return None,
+                };
+
+                Some(PlanFeatureChange {
+                    key,
+                    from,
+                    to,
+                    direction,
+                })
+            })
+            .collect()
+    }
+
     pub fn max_follows_or_default(&self) -> i64 {
         self.max_follows.unwrap_or(0)
     }
@@ -44,3 +244,95 @@ impl PlanFeatures {
         self.custom_branding.unwrap_or(false)
     }
 }
+
+/// One feature of the user's effective plan, with current usage for metered limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlanFeatureUsageDto {
+    pub key: PlanFeatureKey,
+    pub value: PlanFeatureValue,
+    /// How much of the limit is in use; `None` for features that are not metered.
+    pub used: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectivePlanFeaturesDto {
+    pub plan_id: Uuid,
+    pub plan_name: Option<String>,
+    pub features: Vec<PlanFeatureUsageDto>,
+    /// Exact storage and monthly recording usage behind the rounded `used` figures.
+    pub recording_usage: RecordingUsage,
+    /// Set once storage or monthly recording hours reach the plan's quota.
+    pub quota_exceeded: bool,
+    pub quota_enforcement: QuotaEnforcementPolicy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanComparisonDto {
+    pub from_plan_id: Uuid,
+    pub to_plan_id: Uuid,
+    pub changes: Vec<PlanFeatureChange>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn from_json_rejects_unknown_keys_and_out_of_range_limits() {
+        assert!(PlanFeatures::from_json(json!({ "max_follow": 5 })).is_err());
+        assert!(PlanFeatures::from_json(json!({ "max_follows": "5" })).is_err());
+        assert!(PlanFeatures::from_json(json!({ "retention_days": -1 })).is_err());
+        assert!(PlanFeatures::from_json(json!({ "max_concurrent_recordings": 101 })).is_err());
+
+        let features = PlanFeatures::from_json(json!({ "max_follows": 5 })).unwrap();
+        assert_eq!(
+            features.value(PlanFeatureKey::MaxFollows),
+            PlanFeatureValue::Limit(5)
+        );
+        assert_eq!(
+            features.value(PlanFeatureKey::MaxConcurrentRecordings),
+            PlanFeatureValue::Limit(1)
+        );
+    }
+
+    #[test]
+    fn compare_reports_only_changed_features_with_direction() {
+        let free = PlanFeatures {
+            max_follows: Some(1),
+            retention_days: Some(7),
+            ..PlanFeatures::default()
+        };
+        let pro = PlanFeatures {
+            max_follows: Some(20),
+            retention_days: Some(7),
+            max_concurrent_recordings: Some(0),
+            priority_support: Some(true),
+            ..PlanFeatures::default()
+        };
+
+        let changes = free.compare(&pro);
+
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| (change.key, change.direction))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    PlanFeatureKey::MaxFollows,
+                    PlanFeatureChangeDirection::Upgrade
+                ),
+                (
+                    PlanFeatureKey::MaxConcurrentRecordings,
+                    PlanFeatureChangeDirection::Downgrade
+                ),
+                (
+                    PlanFeatureKey::PrioritySupport,
+                    PlanFeatureChangeDirection::Upgrade
+                ),
+            ]
+        );
+        assert!(pro.compare(&pro).is_empty());
+    }
+}
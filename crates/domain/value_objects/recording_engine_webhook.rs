@@ -20,23 +20,23 @@ pub struct StartData {
     pub live_info: Option<LiveInfo>,
 }
 
-// // Recording end webhook // no use for now
-// #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-// pub struct RecordingEngineLiveEndWebhook {
-//     pub id: Uuid,
-//     pub ts: DateTime<Utc>,
-//     #[serde(rename = "type")]
-//     pub type_: String,
-//     pub data: EndData,
-// }
+// Recording end webhook
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecordingEngineLiveEndWebhook {
+    pub id: Uuid,
+    pub ts: DateTime<Utc>,
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub data: EndData,
+}
 
-// #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-// struct EndData {
-//     platform: Option<String>,
-//     channel: Option<String>,
-//     url: Option<String>,
-//     live_info: Option<LiveInfo>,
-// }
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EndData {
+    pub platform: Option<String>,
+    pub channel: Option<String>,
+    pub url: Option<String>,
+    pub live_info: Option<LiveInfo>,
+}
 
 // video_file_finish webhook
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -104,3 +104,14 @@ pub struct LiveInfo {
     pub status: Option<String>,
     pub live_id: Option<String>,
 }
+
+/// Outcome of claiming a webhook id in the processed-webhook ledger.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebhookEventClaim {
+    /// First delivery, or a retry taking over a stale claim: process it.
+    Claimed,
+    /// Another delivery with the same id is still being processed.
+    InProgress,
+    /// Already processed; `recording_id` is what the first delivery answered with.
+    Processed { recording_id: Option<Uuid> },
+}
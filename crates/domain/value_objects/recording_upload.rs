@@ -6,3 +6,10 @@ pub struct RecordingUploadPayload {
     pub recording_id: Uuid,
     pub local_path: String,
 }
+
+/// A finished recording file to turn into a faststart MP4 before it is uploaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingRemuxPayload {
+    pub recording_id: Uuid,
+    pub local_path: String,
+}
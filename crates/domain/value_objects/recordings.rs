@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -25,6 +25,12 @@ pub struct RecordingModel {
     pub poster_storage_path: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bitrate_bps: Option<i64>,
+    pub fps: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -49,6 +55,74 @@ impl InsertRecordingModel {
     }
 }
 
+/// An active follower of a live account and the recording budget of their effective plan.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowerRecordingCapacity {
+    pub user_id: Uuid,
+    pub max_concurrent_recordings: i32,
+    /// Other accounts the user follows that are being recorded right now.
+    pub active_recordings: i64,
+    pub retention_days: i32,
+    pub max_storage_bytes: Option<i64>,
+    pub max_recording_hours_per_month: Option<i32>,
+}
+
+impl FollowerRecordingCapacity {
+    pub fn has_capacity(&self) -> bool {
+        self.active_recordings < i64::from(self.max_concurrent_recordings)
+    }
+
+    pub fn has_quota(&self) -> bool {
+        self.max_storage_bytes.is_some() || self.max_recording_hours_per_month.is_some()
+    }
+}
+
+/// Recording usage attributed to a user: watchable recordings of the accounts they actively
+/// follow.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RecordingUsage {
+    /// Bytes of `ready` recordings still inside the user's retention window.
+    pub storage_bytes: i64,
+    /// Seconds recorded since the start of the current calendar month (UTC).
+    pub recorded_seconds: i64,
+}
+
+impl RecordingUsage {
+    pub fn reached_storage_limit(&self, max_storage_bytes: Option<i64>) -> bool {
+        max_storage_bytes.is_some_and(|max| self.storage_bytes >= max)
+    }
+
+    pub fn reached_recording_hours_limit(
+        &self,
+        max_recording_hours_per_month: Option<i32>,
+    ) -> bool {
+        max_recording_hours_per_month
+            .is_some_and(|max| self.recorded_seconds >= i64::from(max) * 3600)
+    }
+
+    /// Whole hours recorded this month, rounded down.
+    pub fn recorded_hours(&self) -> i64 {
+        self.recorded_seconds / 3600
+    }
+}
+
+/// Start of the calendar month (UTC) `now` falls in; monthly recording hours count from here.
+pub fn usage_month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    now.date_naive()
+        .with_day(1)
+        .and_then(|day| day.and_hms_opt(0, 0, 0))
+        .map(|start| start.and_utc())
+        .unwrap_or(now)
+}
+
+/// Which recordings a user may see: those started within `retention_days` and, when
+/// `storage_limit_bytes` is set, only the newest ones whose sizes add up to that limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordingViewWindow {
+    pub retention_days: i64,
+    pub storage_limit_bytes: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct ListRecordingsFilter {
     pub live_account_id: Option<Uuid>,
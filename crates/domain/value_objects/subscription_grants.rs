@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::entities::{
+    subscription_grants::SubscriptionGrantEntity, subscriptions::SubscriptionEntity,
+};
+
+/// Grants start immediately; the end is either `ends_at` or `duration_days` from now,
+/// falling back to the plan's own duration when neither is given.
+#[derive(Debug, Deserialize)]
+pub struct CreateSubscriptionGrantRequest {
+    pub user_id: Uuid,
+    pub plan_id: Uuid,
+    pub reason: String,
+    #[serde(default)]
+    pub duration_days: Option<i64>,
+    #[serde(default)]
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RevokeSubscriptionGrantRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionGrantDto {
+    pub subscription_id: Uuid,
+    pub user_id: Uuid,
+    pub plan_id: Uuid,
+    pub status: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    pub reason: String,
+    pub granted_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoked_by: Option<Uuid>,
+    pub revoke_reason: Option<String>,
+}
+
+impl From<(SubscriptionEntity, SubscriptionGrantEntity)> for SubscriptionGrantDto {
+    fn from((subscription, grant): (SubscriptionEntity, SubscriptionGrantEntity)) -> Self {
+        Self {
+            subscription_id: subscription.id,
+            user_id: subscription.user_id,
+            plan_id: subscription.plan_id,
+            status: subscription.status,
+            starts_at: subscription.starts_at,
+            ends_at: subscription.ends_at,
+            reason: grant.reason,
+            granted_by: grant.granted_by,
+            created_at: grant.created_at,
+            revoked_at: grant.revoked_at,
+            revoked_by: grant.revoked_by,
+            revoke_reason: grant.revoke_reason,
+        }
+    }
+}
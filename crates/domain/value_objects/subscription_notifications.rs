@@ -0,0 +1,110 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{
+    entities::subscriptions::SubscriptionEntity,
+    value_objects::enums::notification_kinds::NotificationKind,
+};
+
+/// Job payload for a subscription notification; `dedupe_key` makes enqueueing idempotent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubscriptionNotificationPayload {
+    pub kind: NotificationKind,
+    pub user_id: Uuid,
+    pub subscription_id: Uuid,
+    pub plan_id: Uuid,
+    pub ends_at: DateTime<Utc>,
+    pub dedupe_key: String,
+    /// Paid plan that is effective once an expired subscription has ended, e.g. a queued
+    /// term; `None` means the user falls back to the free plan.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_plan_name: Option<String>,
+}
+
+impl SubscriptionNotificationPayload {
+    pub fn renewal_reminder(subscription: &SubscriptionEntity) -> Self {
+        Self::for_subscription(
+            NotificationKind::RenewalReminder,
+            subscription,
+            subscription.ends_at.timestamp().to_string(),
+        )
+    }
+
+    pub fn expired(subscription: &SubscriptionEntity, next_plan_name: Option<String>) -> Self {
+        Self {
+            next_plan_name,
+            ..Self::for_subscription(
+                NotificationKind::SubscriptionExpired,
+                subscription,
+                subscription.ends_at.timestamp().to_string(),
+            )
+        }
+    }
+
+    /// `attempt_ref` identifies the failed charge (e.g. the provider invoice id).
+    pub fn payment_failed(subscription: &SubscriptionEntity, attempt_ref: &str) -> Self {
+        Self::for_subscription(
+            NotificationKind::PaymentFailed,
+            subscription,
+            attempt_ref.to_string(),
+        )
+    }
+
+    pub fn subject(&self) -> String {
+        match self.kind {
+            NotificationKind::RenewalReminder => "Your StreamCatch plan ends soon".to_string(),
+            NotificationKind::SubscriptionExpired => "Your StreamCatch plan has ended".to_string(),
+            NotificationKind::PaymentFailed => {
+                "Payment failed for your StreamCatch subscription".to_string()
+            }
+        }
+    }
+
+    pub fn body(&self) -> String {
+        let ends_on = self.ends_at.format("%Y-%m-%d");
+        match self.kind {
+            NotificationKind::RenewalReminder => format!(
+                "Your StreamCatch plan ends on {ends_on}. One-time plans do not renew \
+                 automatically, so buy another term before then to keep your follows and \
+                 recordings running."
+            ),
+            NotificationKind::SubscriptionExpired => match &self.next_plan_name {
+                Some(next_plan) => format!(
+                    "Your StreamCatch plan ended on {ends_on} and your {next_plan} plan is now \
+                     active, so your follows and recordings keep running."
+                ),
+                None => format!(
+                    "Your StreamCatch plan ended on {ends_on} and your account is now on the \
+                     free plan. Follows above the free limit are paused until you renew."
+                ),
+            },
+            NotificationKind::PaymentFailed => format!(
+                "We could not charge your payment method for the period ending {ends_on}. \
+                 Please update your payment details to keep your subscription active."
+            ),
+        }
+    }
+
+    fn for_subscription(
+        kind: NotificationKind,
+        subscription: &SubscriptionEntity,
+        discriminator: String,
+    ) -> Self {
+        Self {
+            kind,
+            user_id: subscription.user_id,
+            subscription_id: subscription.id,
+            plan_id: subscription.plan_id,
+            ends_at: subscription.ends_at,
+            dedupe_key: format!("{}:{}:{}", kind, subscription.id, discriminator),
+            next_plan_name: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationRecipient {
+    pub user_id: Uuid,
+    pub email: Option<String>,
+}
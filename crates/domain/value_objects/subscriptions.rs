@@ -2,11 +2,17 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::domain::entities::plans::PlanEntity;
+use crate::domain::entities::{
+    invoices::InvoiceEntity, payments::PaymentEntity, plan_prices::PlanPriceEntity,
+    plans::PlanEntity,
+};
 use crate::domain::value_objects::enums::{
-    billing_modes::BillingMode, subscription_statuses::SubscriptionStatus,
+    billing_modes::BillingMode, payment_methods::PaymentMethod,
+    subscription_statuses::SubscriptionStatus,
+};
+use crate::domain::value_objects::plans::{
+    DEFAULT_CURRENCY, MAX_TERMS_PER_CHECKOUT, ONE_TIME_TERM_MONTHS, PlanFeatures,
 };
-use crate::domain::value_objects::plans::PlanFeatures;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SubscriptionModel {
@@ -37,9 +43,6 @@ pub struct PlanModel {
     pub duration_days: i32,
     pub features: PlanFeatures,
     pub is_active: bool,
-    pub stripe_price_recurring: Option<String>,
-    pub stripe_price_one_time_card: Option<String>,
-    pub stripe_price_one_time_promptpay: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -49,6 +52,7 @@ pub struct PlanDto {
     pub price_minor: i32,
     pub duration_days: i32,
     pub features: PlanFeatures,
+    pub prices: Vec<PlanPriceDto>,
 }
 
 impl From<PlanEntity> for PlanDto {
@@ -59,6 +63,28 @@ impl From<PlanEntity> for PlanDto {
             price_minor: value.price_minor,
             duration_days: value.duration_days,
             features: value.features,
+            prices: value.prices.into_iter().map(PlanPriceDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlanPriceDto {
+    pub currency: String,
+    pub billing_mode: String,
+    pub payment_method: String,
+    pub amount_minor: i32,
+    pub term_months: i32,
+}
+
+impl From<PlanPriceEntity> for PlanPriceDto {
+    fn from(value: PlanPriceEntity) -> Self {
+        Self {
+            currency: value.currency,
+            billing_mode: value.billing_mode,
+            payment_method: value.payment_method,
+            amount_minor: value.amount_minor,
+            term_months: value.term_months,
         }
     }
 }
@@ -72,6 +98,14 @@ pub struct CurrentSubscriptionDto {
     pub starts_at: DateTime<Utc>,
     pub ends_at: DateTime<Utc>,
     pub features: PlanFeatures,
+    pub discount: Option<SubscriptionDiscountDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionDiscountDto {
+    pub amount_minor: i32,
+    pub currency: String,
+    pub promotion_code: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -79,9 +113,191 @@ pub struct CreateCheckoutRequest {
     pub plan_id: Uuid,
     pub billing_mode: String,
     pub payment_method: String,
+    #[serde(default)]
+    pub promotion_code: Option<String>,
+    #[serde(default)]
+    pub allow_promotion_codes: bool,
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Length of one one-time term in months; defaults to 1.
+    #[serde(default)]
+    pub term_months: Option<i32>,
+    /// Number of consecutive terms to buy; defaults to 1.
+    #[serde(default)]
+    pub quantity: Option<i32>,
+}
+
+impl CreateCheckoutRequest {
+    /// Lower-cased ISO 4217 currency for the checkout, defaulting to THB.
+    pub fn currency(&self) -> Result<String, &'static str> {
+        checkout_currency(self.currency.as_deref())
+    }
+
+    /// Requested `(term_months, quantity)` for the checkout.
+    pub fn term(&self) -> Result<(i32, i32), &'static str> {
+        checkout_term(self.term_months, self.quantity)
+    }
+
+    /// Resolves the requested promotion handling; a fixed code and the Stripe-hosted
+    /// promotion code field cannot be combined.
+    pub fn promotion(&self) -> Result<CheckoutPromotion, &'static str> {
+        let code = self
+            .promotion_code
+            .as_deref()
+            .map(str::trim)
+            .filter(|code| !code.is_empty());
+
+        match (code, self.allow_promotion_codes) {
+            (Some(_), true) => Err("promotion_code cannot be combined with allow_promotion_codes"),
+            (Some(code), false) => Ok(CheckoutPromotion::Code(code.to_string())),
+            (None, true) => Ok(CheckoutPromotion::AllowPromotionCodes),
+            (None, false) => Ok(CheckoutPromotion::None),
+        }
+    }
+}
+
+/// Lower-cased ISO 4217 currency, defaulting to THB when none is given.
+pub fn checkout_currency(currency: Option<&str>) -> Result<String, &'static str> {
+    let currency = match currency.map(str::trim) {
+        Some(value) if !value.is_empty() => value.to_ascii_lowercase(),
+        _ => return Ok(DEFAULT_CURRENCY.to_string()),
+    };
+
+    if currency.len() != 3 || !currency.bytes().all(|b| b.is_ascii_lowercase()) {
+        return Err("currency must be a three-letter ISO 4217 code");
+    }
+
+    Ok(currency)
+}
+
+/// Validated `(term_months, quantity)`; both default to 1.
+pub fn checkout_term(
+    term_months: Option<i32>,
+    quantity: Option<i32>,
+) -> Result<(i32, i32), &'static str> {
+    let term_months = term_months.unwrap_or(1);
+    if !ONE_TIME_TERM_MONTHS.contains(&term_months) {
+        return Err("term_months must be one of 1, 3 or 12");
+    }
+
+    let quantity = quantity.unwrap_or(1);
+    if !(1..=MAX_TERMS_PER_CHECKOUT).contains(&quantity) {
+        return Err("quantity must be between 1 and 12");
+    }
+
+    Ok((term_months, quantity))
+}
+
+/// Promotion handling requested by the user for a checkout.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CheckoutPromotion {
+    #[default]
+    None,
+    /// Let the user type a code on the Stripe-hosted checkout page.
+    AllowPromotionCodes,
+    /// Apply a promotion code. Requests carry the customer-facing code; once it is validated
+    /// the checkout is created with the provider's promotion code id instead.
+    Code(String),
+}
+
+/// Which plan price a checkout should charge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckoutPriceSelection {
+    pub billing_mode: BillingMode,
+    pub payment_method: PaymentMethod,
+    pub currency: String,
+    pub term_months: i32,
+    pub quantity: i32,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CreateCheckoutResponse {
     pub checkout_url: String,
 }
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BillingHistoryCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BillingHistoryPageDto<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<BillingHistoryCursor>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvoiceHistoryDto {
+    pub id: Uuid,
+    pub plan_id: Uuid,
+    pub amount_minor: i32,
+    pub currency: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub status: String,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub payment_method_type: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl InvoiceHistoryDto {
+    pub fn from_entities(invoice: InvoiceEntity, payment: Option<&PaymentEntity>) -> Self {
+        Self {
+            id: invoice.id,
+            plan_id: invoice.plan_id,
+            amount_minor: invoice.amount_minor,
+            currency: invoice.currency,
+            period_start: invoice.period_start,
+            period_end: invoice.period_end,
+            status: invoice.status,
+            paid_at: invoice.paid_at,
+            payment_method_type: payment.map(|payment| payment.method_type.clone()),
+            created_at: invoice.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaymentHistoryDto {
+    pub id: Uuid,
+    pub invoice_id: Uuid,
+    pub amount_minor: i32,
+    pub currency: String,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub status: String,
+    pub paid_at: Option<DateTime<Utc>>,
+    pub payment_method_type: String,
+    pub created_at: DateTime<Utc>,
+    pub refunded_minor: i32,
+    pub status_reason: Option<String>,
+}
+
+impl PaymentHistoryDto {
+    pub fn from_entities(payment: PaymentEntity, invoice: InvoiceEntity) -> Self {
+        Self {
+            id: payment.id,
+            invoice_id: payment.invoice_id,
+            amount_minor: payment.amount_minor,
+            currency: payment.currency,
+            period_start: invoice.period_start,
+            period_end: invoice.period_end,
+            status: payment.status,
+            paid_at: invoice.paid_at,
+            payment_method_type: payment.method_type,
+            created_at: payment.created_at,
+            refunded_minor: payment.refunded_minor,
+            status_reason: payment.status_reason,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct InvoiceReceiptDto {
+    pub invoice_id: Uuid,
+    pub hosted_invoice_url: Option<String>,
+    pub invoice_pdf_url: Option<String>,
+    pub receipt_url: Option<String>,
+}
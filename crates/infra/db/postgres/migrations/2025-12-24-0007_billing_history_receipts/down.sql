@@ -0,0 +1,11 @@
+DROP INDEX IF EXISTS "payments_invoice_id_idx";
+DROP INDEX IF EXISTS "payments_user_created_at_id_idx";
+DROP INDEX IF EXISTS "invoices_user_created_at_id_idx";
+
+ALTER TABLE public.payments
+  DROP COLUMN IF EXISTS receipt_url;
+
+ALTER TABLE public.invoices
+  DROP COLUMN IF EXISTS invoice_pdf_url,
+  DROP COLUMN IF EXISTS hosted_invoice_url,
+  DROP COLUMN IF EXISTS provider_invoice_ref;
@@ -0,0 +1,16 @@
+ALTER TABLE public.invoices
+  ADD COLUMN provider_invoice_ref TEXT,
+  ADD COLUMN hosted_invoice_url TEXT,
+  ADD COLUMN invoice_pdf_url TEXT;
+
+ALTER TABLE public.payments
+  ADD COLUMN receipt_url TEXT;
+
+CREATE INDEX "invoices_user_created_at_id_idx"
+  ON "invoices" ("user_id", "created_at" DESC, "id" DESC);
+
+CREATE INDEX "payments_user_created_at_id_idx"
+  ON "payments" ("user_id", "created_at" DESC, "id" DESC);
+
+CREATE INDEX "payments_invoice_id_idx"
+  ON "payments" ("invoice_id");
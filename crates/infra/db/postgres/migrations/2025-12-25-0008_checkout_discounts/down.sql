@@ -0,0 +1,7 @@
+ALTER TABLE public.payments
+  DROP COLUMN IF EXISTS promotion_code,
+  DROP COLUMN IF EXISTS discount_minor;
+
+ALTER TABLE public.invoices
+  DROP COLUMN IF EXISTS promotion_code,
+  DROP COLUMN IF EXISTS discount_minor;
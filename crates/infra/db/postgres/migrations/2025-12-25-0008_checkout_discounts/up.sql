@@ -0,0 +1,7 @@
+ALTER TABLE public.invoices
+  ADD COLUMN discount_minor INT NOT NULL DEFAULT 0 CHECK (discount_minor >= 0),
+  ADD COLUMN promotion_code TEXT;
+
+ALTER TABLE public.payments
+  ADD COLUMN discount_minor INT NOT NULL DEFAULT 0 CHECK (discount_minor >= 0),
+  ADD COLUMN promotion_code TEXT;
@@ -0,0 +1,13 @@
+CREATE TABLE public.stripe_events (
+  id TEXT PRIMARY KEY,
+  type TEXT NOT NULL,
+  payload JSONB NOT NULL,
+  received_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  processed_at TIMESTAMPTZ,
+  error TEXT,
+  attempts INT NOT NULL DEFAULT 0 CHECK (attempts >= 0)
+);
+
+CREATE INDEX stripe_events_unprocessed_idx
+  ON public.stripe_events (received_at)
+  WHERE processed_at IS NULL;
@@ -0,0 +1,24 @@
+DROP INDEX IF EXISTS public.deliveries_dedupe_key_via_uidx;
+
+DELETE FROM public.deliveries WHERE recording_id IS NULL;
+
+ALTER TABLE public.deliveries
+  DROP CONSTRAINT IF EXISTS deliveries_target_check,
+  DROP CONSTRAINT IF EXISTS deliveries_via_check,
+  ADD CONSTRAINT deliveries_via_check
+    CHECK (via IN ('web_notify', 'email', 'telegram')),
+  DROP COLUMN IF EXISTS created_at,
+  DROP COLUMN IF EXISTS dedupe_key,
+  DROP COLUMN IF EXISTS kind,
+  DROP COLUMN IF EXISTS subscription_id,
+  ALTER COLUMN recording_id SET NOT NULL;
+
+DROP INDEX IF EXISTS public.jobs_dedupe_key_uidx;
+
+DELETE FROM public.jobs WHERE "type" = 'SubscriptionNotify';
+
+ALTER TABLE public.jobs
+  DROP COLUMN IF EXISTS dedupe_key,
+  DROP CONSTRAINT IF EXISTS jobs_type_check,
+  ADD CONSTRAINT jobs_type_check
+    CHECK ("type" IN ('RecordingUpload', 'NotifyReady'));
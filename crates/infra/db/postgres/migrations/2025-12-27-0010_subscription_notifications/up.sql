@@ -0,0 +1,26 @@
+-- Subscription notifications reuse the jobs queue and the deliveries table.
+ALTER TABLE public.jobs
+  DROP CONSTRAINT IF EXISTS jobs_type_check,
+  ADD CONSTRAINT jobs_type_check
+    CHECK ("type" IN ('RecordingUpload', 'NotifyReady', 'SubscriptionNotify')),
+  ADD COLUMN dedupe_key TEXT;
+
+CREATE UNIQUE INDEX jobs_dedupe_key_uidx
+  ON public.jobs (dedupe_key)
+  WHERE dedupe_key IS NOT NULL;
+
+ALTER TABLE public.deliveries
+  ALTER COLUMN recording_id DROP NOT NULL,
+  ADD COLUMN subscription_id UUID REFERENCES public.subscriptions(id) ON DELETE CASCADE,
+  ADD COLUMN kind TEXT,
+  ADD COLUMN dedupe_key TEXT,
+  ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  DROP CONSTRAINT IF EXISTS deliveries_via_check,
+  ADD CONSTRAINT deliveries_via_check
+    CHECK (via IN ('web_notify', 'email', 'telegram', 'webhook')),
+  ADD CONSTRAINT deliveries_target_check
+    CHECK (recording_id IS NOT NULL OR subscription_id IS NOT NULL);
+
+CREATE UNIQUE INDEX deliveries_dedupe_key_via_uidx
+  ON public.deliveries (dedupe_key, via)
+  WHERE dedupe_key IS NOT NULL;
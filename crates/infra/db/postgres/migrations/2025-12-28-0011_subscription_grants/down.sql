@@ -0,0 +1,8 @@
+DROP TABLE IF EXISTS public.subscription_grants;
+
+DELETE FROM public.subscriptions WHERE billing_mode = 'complimentary';
+
+ALTER TABLE public.subscriptions
+  DROP CONSTRAINT IF EXISTS subscriptions_billing_mode_check,
+  ADD CONSTRAINT subscriptions_billing_mode_check
+    CHECK (billing_mode IN ('recurring', 'one_time'));
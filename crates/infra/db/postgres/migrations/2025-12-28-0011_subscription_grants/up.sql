@@ -0,0 +1,20 @@
+-- Admin-granted complimentary subscriptions.
+ALTER TABLE public.subscriptions
+  DROP CONSTRAINT IF EXISTS subscriptions_billing_mode_check,
+  ADD CONSTRAINT subscriptions_billing_mode_check
+    CHECK (billing_mode IN ('recurring', 'one_time', 'complimentary'));
+
+CREATE TABLE public.subscription_grants (
+  subscription_id UUID PRIMARY KEY REFERENCES public.subscriptions(id) ON DELETE CASCADE,
+  reason TEXT NOT NULL CHECK (length(trim(reason)) > 0),
+  granted_by UUID NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  revoked_at TIMESTAMPTZ,
+  revoked_by UUID,
+  revoke_reason TEXT
+);
+
+CREATE INDEX subscription_grants_created_at_idx
+  ON public.subscription_grants (created_at DESC);
+
+ALTER TABLE public.subscription_grants ENABLE ROW LEVEL SECURITY;
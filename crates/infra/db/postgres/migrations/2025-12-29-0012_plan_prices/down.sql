@@ -0,0 +1,24 @@
+ALTER TABLE public.plans
+  ADD COLUMN IF NOT EXISTS stripe_price_recurring TEXT,
+  ADD COLUMN IF NOT EXISTS stripe_price_one_time_card TEXT,
+  ADD COLUMN IF NOT EXISTS stripe_price_one_time_promptpay TEXT;
+
+UPDATE public.plans p
+SET
+  stripe_price_recurring = (
+    SELECT stripe_price_id FROM public.plan_prices
+    WHERE plan_id = p.id AND currency = 'thb'
+      AND billing_mode = 'recurring' AND payment_method = 'card'
+  ),
+  stripe_price_one_time_card = (
+    SELECT stripe_price_id FROM public.plan_prices
+    WHERE plan_id = p.id AND currency = 'thb'
+      AND billing_mode = 'one_time' AND payment_method = 'card'
+  ),
+  stripe_price_one_time_promptpay = (
+    SELECT stripe_price_id FROM public.plan_prices
+    WHERE plan_id = p.id AND currency = 'thb'
+      AND billing_mode = 'one_time' AND payment_method = 'promptpay'
+  );
+
+DROP TABLE IF EXISTS public.plan_prices;
@@ -0,0 +1,47 @@
+-- Per-currency Stripe prices per plan.
+-- Replaces the fixed THB price columns on plans; checkout picks a row by
+-- (plan, currency, billing mode, payment method).
+CREATE TABLE public.plan_prices (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  plan_id UUID NOT NULL REFERENCES public.plans(id) ON DELETE CASCADE,
+  currency TEXT NOT NULL CHECK (currency ~ '^[a-z]{3}$'),
+  billing_mode TEXT NOT NULL CHECK (billing_mode IN ('recurring', 'one_time')),
+  payment_method TEXT NOT NULL CHECK (payment_method IN ('card', 'promptpay')),
+  amount_minor INT NOT NULL CHECK (amount_minor >= 0),
+  stripe_price_id TEXT NOT NULL,
+  is_active BOOLEAN NOT NULL DEFAULT true,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  CONSTRAINT plan_prices_plan_currency_mode_method_key
+    UNIQUE (plan_id, currency, billing_mode, payment_method),
+  -- Recurring billing is card-only; PromptPay only settles in THB.
+  CONSTRAINT plan_prices_recurring_card_check
+    CHECK (billing_mode <> 'recurring' OR payment_method = 'card'),
+  CONSTRAINT plan_prices_promptpay_thb_check
+    CHECK (payment_method <> 'promptpay' OR currency = 'thb')
+);
+
+INSERT INTO public.plan_prices (plan_id, currency, billing_mode, payment_method, amount_minor, stripe_price_id)
+SELECT id, 'thb', 'recurring', 'card', price_minor, stripe_price_recurring
+FROM public.plans
+WHERE stripe_price_recurring IS NOT NULL
+UNION ALL
+SELECT id, 'thb', 'one_time', 'card', price_minor, stripe_price_one_time_card
+FROM public.plans
+WHERE stripe_price_one_time_card IS NOT NULL
+UNION ALL
+SELECT id, 'thb', 'one_time', 'promptpay', price_minor, stripe_price_one_time_promptpay
+FROM public.plans
+WHERE stripe_price_one_time_promptpay IS NOT NULL;
+
+ALTER TABLE public.plans
+  DROP COLUMN IF EXISTS stripe_price_recurring,
+  DROP COLUMN IF EXISTS stripe_price_one_time_card,
+  DROP COLUMN IF EXISTS stripe_price_one_time_promptpay;
+
+ALTER TABLE public.plan_prices ENABLE ROW LEVEL SECURITY;
+
+DROP POLICY IF EXISTS "plan prices readable by everyone" ON public.plan_prices;
+CREATE POLICY "plan prices readable by everyone"
+  ON public.plan_prices
+  FOR SELECT
+  USING (true);
@@ -0,0 +1,20 @@
+ALTER TABLE public.subscriptions
+  DROP COLUMN IF EXISTS ended_reason;
+
+UPDATE public.invoices SET status = 'paid' WHERE status IN ('refunded', 'disputed');
+
+ALTER TABLE public.invoices
+  DROP CONSTRAINT IF EXISTS invoices_status_check,
+  ADD CONSTRAINT invoices_status_check
+    CHECK (status IN ('pending','paid','failed','void','past_due'));
+
+UPDATE public.payments
+SET status = 'succeeded'
+WHERE status IN ('refunded', 'partially_refunded', 'disputed');
+
+ALTER TABLE public.payments
+  DROP CONSTRAINT IF EXISTS payments_status_check,
+  ADD CONSTRAINT payments_status_check
+    CHECK (status IN ('requires_action','processing','succeeded','failed','canceled')),
+  DROP COLUMN IF EXISTS status_reason,
+  DROP COLUMN IF EXISTS refunded_minor;
@@ -0,0 +1,19 @@
+-- Refunds and disputes reported by Stripe.
+--   - payments.refunded_minor: total refunded so far (partial refunds accumulate in Stripe)
+--   - payments.status_reason: why a payment left `succeeded` (refund or dispute reason)
+--   - subscriptions.ended_reason: why a subscription was ended before its period end
+ALTER TABLE public.payments
+  DROP CONSTRAINT IF EXISTS payments_status_check,
+  ADD CONSTRAINT payments_status_check
+    CHECK (status IN ('requires_action','processing','succeeded','failed','canceled',
+                      'refunded','partially_refunded','disputed')),
+  ADD COLUMN IF NOT EXISTS refunded_minor INT NOT NULL DEFAULT 0 CHECK (refunded_minor >= 0),
+  ADD COLUMN IF NOT EXISTS status_reason TEXT;
+
+ALTER TABLE public.invoices
+  DROP CONSTRAINT IF EXISTS invoices_status_check,
+  ADD CONSTRAINT invoices_status_check
+    CHECK (status IN ('pending','paid','failed','void','past_due','refunded','disputed'));
+
+ALTER TABLE public.subscriptions
+  ADD COLUMN IF NOT EXISTS ended_reason TEXT;
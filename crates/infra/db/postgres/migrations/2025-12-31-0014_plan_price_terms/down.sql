@@ -0,0 +1,8 @@
+DELETE FROM public.plan_prices WHERE term_months <> 1;
+
+ALTER TABLE public.plan_prices
+  DROP CONSTRAINT IF EXISTS plan_prices_plan_currency_mode_method_term_key,
+  DROP CONSTRAINT IF EXISTS plan_prices_recurring_term_check,
+  DROP COLUMN IF EXISTS term_months,
+  ADD CONSTRAINT plan_prices_plan_currency_mode_method_key
+    UNIQUE (plan_id, currency, billing_mode, payment_method);
@@ -0,0 +1,11 @@
+-- Multi-month one-time terms.
+-- A plan can sell 1, 3 or 12 month one-time terms, each with its own (discounted) price.
+-- Recurring prices always describe a single billing period.
+ALTER TABLE public.plan_prices
+  ADD COLUMN IF NOT EXISTS term_months INT NOT NULL DEFAULT 1
+    CHECK (term_months IN (1, 3, 12)),
+  ADD CONSTRAINT plan_prices_recurring_term_check
+    CHECK (billing_mode = 'one_time' OR term_months = 1),
+  DROP CONSTRAINT IF EXISTS plan_prices_plan_currency_mode_method_key,
+  ADD CONSTRAINT plan_prices_plan_currency_mode_method_term_key
+    UNIQUE (plan_id, currency, billing_mode, payment_method, term_months);
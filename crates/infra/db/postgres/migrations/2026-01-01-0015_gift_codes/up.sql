@@ -0,0 +1,28 @@
+-- Gift subscriptions.
+-- A buyer pays for a one-time term without it being attached to their own account; the
+-- payment issues a single-use code that any user can redeem for that term.
+CREATE TABLE public.gift_codes (
+  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+  code TEXT NOT NULL UNIQUE,
+  plan_id UUID NOT NULL REFERENCES public.plans(id),
+  purchaser_id UUID NOT NULL REFERENCES public.app_users(id) ON DELETE CASCADE,
+  term_months INT NOT NULL CHECK (term_months > 0),
+  status TEXT NOT NULL DEFAULT 'pending'
+    CHECK (status IN ('pending', 'active', 'redeemed', 'voided')),
+  provider_payment_ref TEXT NOT NULL UNIQUE,
+  invoice_id UUID NOT NULL REFERENCES public.invoices(id) ON DELETE CASCADE,
+  expires_at TIMESTAMPTZ NOT NULL,
+  redeemed_by UUID REFERENCES public.app_users(id) ON DELETE SET NULL,
+  redeemed_at TIMESTAMPTZ,
+  subscription_id UUID REFERENCES public.subscriptions(id) ON DELETE SET NULL,
+  void_reason TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX gift_codes_purchaser_created_at_idx
+  ON public.gift_codes (purchaser_id, created_at DESC);
+
+CREATE INDEX gift_codes_invoice_id_idx
+  ON public.gift_codes (invoice_id);
+
+ALTER TABLE public.gift_codes ENABLE ROW LEVEL SECURITY;
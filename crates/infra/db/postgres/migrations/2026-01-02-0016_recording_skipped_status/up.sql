@@ -0,0 +1,8 @@
+-- Recordings the worker declined because no follower's plan had concurrent recording
+-- capacity left when the live started.
+ALTER TABLE public.recordings
+  DROP CONSTRAINT IF EXISTS recordings_status_check;
+
+ALTER TABLE public.recordings
+  ADD CONSTRAINT recordings_status_check
+  CHECK (status IN ('live_recording','live_end','waiting_upload','uploading','ready','failed','expired_deleted','skipped_concurrency_limit'));
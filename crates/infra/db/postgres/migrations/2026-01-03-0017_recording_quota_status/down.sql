@@ -0,0 +1,10 @@
+UPDATE public.recordings
+  SET status = 'failed'
+  WHERE status = 'skipped_quota_exceeded';
+
+ALTER TABLE public.recordings
+  DROP CONSTRAINT IF EXISTS recordings_status_check;
+
+ALTER TABLE public.recordings
+  ADD CONSTRAINT recordings_status_check
+  CHECK (status IN ('live_recording','live_end','waiting_upload','uploading','ready','failed','expired_deleted','skipped_concurrency_limit'));
@@ -0,0 +1,8 @@
+-- Recordings the worker declined because every follower with capacity was over a storage or
+-- recording-hour quota.
+ALTER TABLE public.recordings
+  DROP CONSTRAINT IF EXISTS recordings_status_check;
+
+ALTER TABLE public.recordings
+  ADD CONSTRAINT recordings_status_check
+  CHECK (status IN ('live_recording','live_end','waiting_upload','uploading','ready','failed','expired_deleted','skipped_concurrency_limit','skipped_quota_exceeded'));
@@ -0,0 +1,10 @@
+-- Ledger of recording engine webhooks, keyed by the payload id, so a retried webhook is
+-- answered with the original result instead of being processed twice.
+CREATE TABLE public.recording_engine_webhook_events (
+  id UUID PRIMARY KEY,
+  type TEXT NOT NULL,
+  ts TIMESTAMPTZ NOT NULL,
+  recording_id UUID REFERENCES public.recordings(id) ON DELETE SET NULL,
+  received_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  processed_at TIMESTAMPTZ
+);
@@ -0,0 +1 @@
+DROP TABLE IF EXISTS public.recording_engine_errors;
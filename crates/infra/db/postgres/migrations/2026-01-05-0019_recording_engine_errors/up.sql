@@ -0,0 +1,20 @@
+-- Latest recording engine error per channel. consecutive_errors counts errors since the
+-- channel last produced a recording; a channel whose count reaches the threshold flips its
+-- live account to 'error'.
+CREATE TABLE public.recording_engine_errors (
+  platform TEXT NOT NULL,
+  channel TEXT NOT NULL,
+  live_account_id UUID REFERENCES public.live_accounts(id) ON DELETE SET NULL,
+  recording_id UUID REFERENCES public.recordings(id) ON DELETE SET NULL,
+  last_error TEXT NOT NULL,
+  error_count INTEGER NOT NULL DEFAULT 1,
+  consecutive_errors INTEGER NOT NULL DEFAULT 1,
+  first_error_at TIMESTAMPTZ NOT NULL,
+  last_error_at TIMESTAMPTZ NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+  PRIMARY KEY (platform, channel)
+);
+
+CREATE INDEX recording_engine_errors_live_account_id_idx
+  ON public.recording_engine_errors (live_account_id);
@@ -0,0 +1,7 @@
+ALTER TABLE public.recordings
+  DROP COLUMN IF EXISTS fps,
+  DROP COLUMN IF EXISTS bitrate_bps,
+  DROP COLUMN IF EXISTS audio_codec,
+  DROP COLUMN IF EXISTS video_codec,
+  DROP COLUMN IF EXISTS height,
+  DROP COLUMN IF EXISTS width;
@@ -0,0 +1,8 @@
+-- Quality info probed from the final recording file, shown alongside the recording.
+ALTER TABLE public.recordings
+  ADD COLUMN width INTEGER,
+  ADD COLUMN height INTEGER,
+  ADD COLUMN video_codec TEXT,
+  ADD COLUMN audio_codec TEXT,
+  ADD COLUMN bitrate_bps BIGINT,
+  ADD COLUMN fps DOUBLE PRECISION;
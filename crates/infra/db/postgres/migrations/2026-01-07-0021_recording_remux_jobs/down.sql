@@ -0,0 +1,6 @@
+DELETE FROM public.jobs WHERE "type" = 'RecordingRemux';
+
+ALTER TABLE public.jobs
+  DROP CONSTRAINT IF EXISTS jobs_type_check,
+  ADD CONSTRAINT jobs_type_check
+    CHECK ("type" IN ('RecordingUpload', 'NotifyReady', 'SubscriptionNotify'));
@@ -0,0 +1,5 @@
+-- Recordings are remuxed to faststart MP4 before the upload job is queued.
+ALTER TABLE public.jobs
+  DROP CONSTRAINT IF EXISTS jobs_type_check,
+  ADD CONSTRAINT jobs_type_check
+    CHECK ("type" IN ('RecordingUpload', 'RecordingRemux', 'NotifyReady', 'SubscriptionNotify'));
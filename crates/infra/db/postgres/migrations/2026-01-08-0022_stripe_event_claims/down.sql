@@ -0,0 +1,2 @@
+ALTER TABLE public.stripe_events
+  DROP COLUMN IF EXISTS claimed_at;
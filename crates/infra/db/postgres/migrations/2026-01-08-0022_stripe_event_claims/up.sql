@@ -0,0 +1,3 @@
+-- Set while a delivery, retry or replay is processing the event, so only one runs at a time.
+ALTER TABLE public.stripe_events
+  ADD COLUMN claimed_at TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS public.subscriptions_expiry_notice_pending_idx;
+
+ALTER TABLE public.subscriptions
+  DROP COLUMN IF EXISTS expiry_notice_enqueued_at;
@@ -0,0 +1,13 @@
+-- Set once the expiry notice of an expired one-time or complimentary subscription is
+-- enqueued; the lifecycle job retries expired rows where it is still NULL.
+ALTER TABLE public.subscriptions
+  ADD COLUMN expiry_notice_enqueued_at TIMESTAMPTZ;
+
+-- Rows that expired before this column existed were already handled by earlier runs.
+UPDATE public.subscriptions
+  SET expiry_notice_enqueued_at = ends_at
+  WHERE status = 'expired';
+
+CREATE INDEX subscriptions_expiry_notice_pending_idx
+  ON public.subscriptions (ends_at)
+  WHERE status = 'expired' AND expiry_notice_enqueued_at IS NULL;
@@ -12,12 +12,16 @@ diesel::table! {
 diesel::table! {
     deliveries (id) {
         id -> Uuid,
-        recording_id -> Uuid,
+        recording_id -> Nullable<Uuid>,
         user_id -> Uuid,
         via -> Text,
         delivered_at -> Nullable<Timestamptz>,
         status -> Text,
         error -> Nullable<Text>,
+        subscription_id -> Nullable<Uuid>,
+        kind -> Nullable<Text>,
+        dedupe_key -> Nullable<Text>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -31,6 +35,25 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    gift_codes (id) {
+        id -> Uuid,
+        code -> Text,
+        plan_id -> Uuid,
+        purchaser_id -> Uuid,
+        term_months -> Int4,
+        status -> Text,
+        provider_payment_ref -> Text,
+        invoice_id -> Uuid,
+        expires_at -> Timestamptz,
+        redeemed_by -> Nullable<Uuid>,
+        redeemed_at -> Nullable<Timestamptz>,
+        subscription_id -> Nullable<Uuid>,
+        void_reason -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     invoices (id) {
         id -> Uuid,
@@ -45,6 +68,11 @@ diesel::table! {
         status -> Text,
         created_at -> Timestamptz,
         paid_at -> Nullable<Timestamptz>,
+        provider_invoice_ref -> Nullable<Text>,
+        hosted_invoice_url -> Nullable<Text>,
+        invoice_pdf_url -> Nullable<Text>,
+        discount_minor -> Int4,
+        promotion_code -> Nullable<Text>,
     }
 }
 
@@ -61,6 +89,7 @@ diesel::table! {
         error -> Nullable<Text>,
         status -> Text,
         created_at -> Timestamptz,
+        dedupe_key -> Nullable<Text>,
     }
 }
 
@@ -120,6 +149,26 @@ diesel::table! {
         error -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        receipt_url -> Nullable<Text>,
+        discount_minor -> Int4,
+        promotion_code -> Nullable<Text>,
+        refunded_minor -> Int4,
+        status_reason -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    plan_prices (id) {
+        id -> Uuid,
+        plan_id -> Uuid,
+        currency -> Text,
+        billing_mode -> Text,
+        payment_method -> Text,
+        amount_minor -> Int4,
+        stripe_price_id -> Text,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        term_months -> Int4,
     }
 }
 
@@ -131,9 +180,34 @@ diesel::table! {
         duration_days -> Int4,
         features -> Jsonb,
         is_active -> Bool,
-        stripe_price_recurring -> Nullable<Text>,
-        stripe_price_one_time_card -> Nullable<Text>,
-        stripe_price_one_time_promptpay -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    recording_engine_errors (platform, channel) {
+        platform -> Text,
+        channel -> Text,
+        live_account_id -> Nullable<Uuid>,
+        recording_id -> Nullable<Uuid>,
+        last_error -> Text,
+        error_count -> Int4,
+        consecutive_errors -> Int4,
+        first_error_at -> Timestamptz,
+        last_error_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    recording_engine_webhook_events (id) {
+        id -> Uuid,
+        #[sql_name = "type"]
+        type_ -> Text,
+        ts -> Timestamptz,
+        recording_id -> Nullable<Uuid>,
+        received_at -> Timestamptz,
+        processed_at -> Nullable<Timestamptz>,
     }
 }
 
@@ -153,6 +227,38 @@ diesel::table! {
         poster_storage_path -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        width -> Nullable<Int4>,
+        height -> Nullable<Int4>,
+        video_codec -> Nullable<Text>,
+        audio_codec -> Nullable<Text>,
+        bitrate_bps -> Nullable<Int8>,
+        fps -> Nullable<Float8>,
+    }
+}
+
+diesel::table! {
+    stripe_events (id) {
+        id -> Text,
+        #[sql_name = "type"]
+        type_ -> Text,
+        payload -> Jsonb,
+        received_at -> Timestamptz,
+        processed_at -> Nullable<Timestamptz>,
+        error -> Nullable<Text>,
+        attempts -> Int4,
+        claimed_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    subscription_grants (subscription_id) {
+        subscription_id -> Uuid,
+        reason -> Text,
+        granted_by -> Uuid,
+        created_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+        revoked_by -> Nullable<Uuid>,
+        revoke_reason -> Nullable<Text>,
     }
 }
 
@@ -170,13 +276,19 @@ diesel::table! {
         provider_subscription_id -> Nullable<Text>,
         status -> Text,
         created_at -> Timestamptz,
+        ended_reason -> Nullable<Text>,
+        expiry_notice_enqueued_at -> Nullable<Timestamptz>,
     }
 }
 
 diesel::joinable!(deliveries -> app_users (user_id));
 diesel::joinable!(deliveries -> recordings (recording_id));
+diesel::joinable!(deliveries -> subscriptions (subscription_id));
 diesel::joinable!(follows -> app_users (user_id));
 diesel::joinable!(follows -> live_accounts (live_account_id));
+diesel::joinable!(gift_codes -> invoices (invoice_id));
+diesel::joinable!(gift_codes -> plans (plan_id));
+diesel::joinable!(gift_codes -> subscriptions (subscription_id));
 diesel::joinable!(invoices -> app_users (user_id));
 diesel::joinable!(invoices -> plans (plan_id));
 diesel::joinable!(invoices -> subscriptions (subscription_id));
@@ -185,7 +297,12 @@ diesel::joinable!(payment_provider_customers -> app_users (user_id));
 diesel::joinable!(payments -> app_users (user_id));
 diesel::joinable!(payments -> invoices (invoice_id));
 diesel::joinable!(payments -> payment_methods (payment_method_id));
+diesel::joinable!(plan_prices -> plans (plan_id));
+diesel::joinable!(recording_engine_errors -> live_accounts (live_account_id));
+diesel::joinable!(recording_engine_errors -> recordings (recording_id));
+diesel::joinable!(recording_engine_webhook_events -> recordings (recording_id));
 diesel::joinable!(recordings -> live_accounts (live_account_id));
+diesel::joinable!(subscription_grants -> subscriptions (subscription_id));
 diesel::joinable!(subscriptions -> app_users (user_id));
 diesel::joinable!(subscriptions -> payment_methods (default_payment_method_id));
 diesel::joinable!(subscriptions -> plans (plan_id));
@@ -194,13 +311,19 @@ diesel::allow_tables_to_appear_in_same_query!(
     app_users,
     deliveries,
     follows,
+    gift_codes,
     invoices,
     jobs,
     live_accounts,
     payment_methods,
     payment_provider_customers,
     payments,
+    plan_prices,
     plans,
+    recording_engine_errors,
+    recording_engine_webhook_events,
     recordings,
+    stripe_events,
+    subscription_grants,
     subscriptions,
 );
@@ -0,0 +1,78 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use diesel::{RunQueryDsl, insert_into, prelude::*, update};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain,
+    infra::db::postgres::{postgres_connection::PgPoolSquad, schema::deliveries},
+};
+use domain::{
+    entities::deliveries::{DeliveryEntity, InsertDeliveryEntity},
+    repositories::deliveries::DeliveryRepository,
+    value_objects::enums::delivery_statuses::DeliveryStatus,
+};
+
+pub struct DeliveryPostgres {
+    db_pool: Arc<PgPoolSquad>,
+}
+
+impl DeliveryPostgres {
+    pub fn new(db_pool: Arc<PgPoolSquad>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl DeliveryRepository for DeliveryPostgres {
+    async fn find_or_create(&self, delivery: InsertDeliveryEntity) -> Result<DeliveryEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let dedupe_key = delivery
+            .dedupe_key
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("delivery dedupe_key is required"))?;
+
+        insert_into(deliveries::table)
+            .values(&delivery)
+            .on_conflict_do_nothing()
+            .execute(&mut conn)?;
+
+        let stored = deliveries::table
+            .filter(deliveries::dedupe_key.eq(dedupe_key))
+            .filter(deliveries::via.eq(&delivery.via))
+            .select(DeliveryEntity::as_select())
+            .first::<DeliveryEntity>(&mut conn)?;
+
+        Ok(stored)
+    }
+
+    async fn mark_sent(&self, delivery_id: Uuid) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(deliveries::table.filter(deliveries::id.eq(delivery_id)))
+            .set((
+                deliveries::status.eq(DeliveryStatus::Sent.to_string()),
+                deliveries::delivered_at.eq(Some(Utc::now())),
+                deliveries::error.eq::<Option<String>>(None),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, delivery_id: Uuid, error: &str) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(deliveries::table.filter(deliveries::id.eq(delivery_id)))
+            .set((
+                deliveries::status.eq(DeliveryStatus::Failed.to_string()),
+                deliveries::error.eq(Some(error)),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{OptionalExtension, RunQueryDsl, delete, insert_into, prelude::*, update};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain,
+    infra::db::postgres::{
+        postgres_connection::PgPoolSquad,
+        schema::{gift_codes, invoices},
+    },
+};
+use domain::{
+    entities::{
+        gift_codes::{GiftCodeEntity, InsertGiftCodeEntity},
+        invoices::InsertInvoiceEntity,
+    },
+    repositories::gift_codes::GiftCodeRepository,
+    value_objects::enums::gift_code_statuses::GiftCodeStatus,
+};
+
+pub struct GiftCodePostgres {
+    db_pool: Arc<PgPoolSquad>,
+}
+
+impl GiftCodePostgres {
+    pub fn new(db_pool: Arc<PgPoolSquad>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl GiftCodeRepository for GiftCodePostgres {
+    async fn create_gift_code(
+        &self,
+        invoice: InsertInvoiceEntity,
+        mut gift_code: InsertGiftCodeEntity,
+    ) -> Result<GiftCodeEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        conn.transaction::<GiftCodeEntity, anyhow::Error, _>(|tx| {
+            let invoice_id = insert_into(invoices::table)
+                .values(&invoice)
+                .returning(invoices::id)
+                .get_result::<Uuid>(tx)?;
+            gift_code.invoice_id = invoice_id;
+
+            let inserted = insert_into(gift_codes::table)
+                .values(&gift_code)
+                .on_conflict(gift_codes::provider_payment_ref)
+                .do_nothing()
+                .execute(tx)?;
+            if inserted == 0 {
+                // Lost the race to another delivery: its code and invoice stand.
+                delete(invoices::table.find(invoice_id)).execute(tx)?;
+            }
+
+            let stored = gift_codes::table
+                .filter(gift_codes::provider_payment_ref.eq(&gift_code.provider_payment_ref))
+                .select(GiftCodeEntity::as_select())
+                .first::<GiftCodeEntity>(tx)?;
+            Ok(stored)
+        })
+    }
+
+    async fn find_by_code(&self, code: &str) -> Result<Option<GiftCodeEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let gift_code = gift_codes::table
+            .filter(gift_codes::code.eq(code))
+            .select(GiftCodeEntity::as_select())
+            .first::<GiftCodeEntity>(&mut conn)
+            .optional()?;
+
+        Ok(gift_code)
+    }
+
+    async fn find_by_provider_payment_ref(
+        &self,
+        provider_payment_ref: &str,
+    ) -> Result<Option<GiftCodeEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let gift_code = gift_codes::table
+            .filter(gift_codes::provider_payment_ref.eq(provider_payment_ref))
+            .select(GiftCodeEntity::as_select())
+            .first::<GiftCodeEntity>(&mut conn)
+            .optional()?;
+
+        Ok(gift_code)
+    }
+
+    async fn activate_by_provider_payment_ref(
+        &self,
+        provider_payment_ref: &str,
+    ) -> Result<Option<GiftCodeEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let activated = update(
+            gift_codes::table
+                .filter(gift_codes::provider_payment_ref.eq(provider_payment_ref))
+                .filter(gift_codes::status.eq(GiftCodeStatus::Pending.to_string())),
+        )
+        .set(gift_codes::status.eq(GiftCodeStatus::Active.to_string()))
+        .returning(GiftCodeEntity::as_returning())
+        .get_result::<GiftCodeEntity>(&mut conn)
+        .optional()?;
+
+        Ok(activated)
+    }
+
+    async fn claim_gift_code(
+        &self,
+        gift_code_id: Uuid,
+        user_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Option<GiftCodeEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let claimable = gift_codes::status
+            .eq(GiftCodeStatus::Active.to_string())
+            .and(gift_codes::expires_at.gt(now))
+            .or(gift_codes::status
+                .eq(GiftCodeStatus::Redeemed.to_string())
+                .and(gift_codes::redeemed_by.is_not_distinct_from(user_id))
+                .and(gift_codes::subscription_id.is_null()));
+
+        let claimed = update(
+            gift_codes::table
+                .filter(gift_codes::id.eq(gift_code_id))
+                .filter(claimable),
+        )
+        .set((
+            gift_codes::status.eq(GiftCodeStatus::Redeemed.to_string()),
+            gift_codes::redeemed_by.eq(Some(user_id)),
+            gift_codes::redeemed_at.eq(Some(now)),
+        ))
+        .returning(GiftCodeEntity::as_returning())
+        .get_result::<GiftCodeEntity>(&mut conn)
+        .optional()?;
+
+        Ok(claimed)
+    }
+
+    async fn attach_subscription(&self, gift_code_id: Uuid, subscription_id: Uuid) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(gift_codes::table.filter(gift_codes::id.eq(gift_code_id)))
+            .set(gift_codes::subscription_id.eq(Some(subscription_id)))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn void_unredeemed_by_invoice_id(
+        &self,
+        invoice_id: Uuid,
+        reason: String,
+    ) -> Result<Option<GiftCodeEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let voided = update(
+            gift_codes::table
+                .filter(gift_codes::invoice_id.eq(invoice_id))
+                .filter(gift_codes::status.eq_any([
+                    GiftCodeStatus::Pending.to_string(),
+                    GiftCodeStatus::Active.to_string(),
+                ])),
+        )
+        .set((
+            gift_codes::status.eq(GiftCodeStatus::Voided.to_string()),
+            gift_codes::void_reason.eq(Some(reason)),
+        ))
+        .returning(GiftCodeEntity::as_returning())
+        .get_result::<GiftCodeEntity>(&mut conn)
+        .optional()?;
+
+        Ok(voided)
+    }
+
+    async fn list_by_purchaser(&self, purchaser_id: Uuid) -> Result<Vec<GiftCodeEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let gift_codes = gift_codes::table
+            .filter(gift_codes::purchaser_id.eq(purchaser_id))
+            .order(gift_codes::created_at.desc())
+            .select(GiftCodeEntity::as_select())
+            .load::<GiftCodeEntity>(&mut conn)?;
+
+        Ok(gift_codes)
+    }
+}
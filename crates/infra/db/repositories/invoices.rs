@@ -10,7 +10,7 @@ use crate::{
     infra::db::postgres::{postgres_connection::PgPoolSquad, schema::invoices},
 };
 use domain::{
-    entities::invoices::{InsertInvoiceEntity, InvoiceEntity},
+    entities::invoices::{InsertInvoiceEntity, InvoiceEntity, InvoiceProviderLinksChangeset},
     repositories::invoices::InvoiceRepository,
 };
 
@@ -78,4 +78,116 @@ impl InvoiceRepository for InvoicePostgres {
 
         Ok(invoice)
     }
+
+    async fn list_by_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        cursor_created_at: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+    ) -> Result<Vec<InvoiceEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let mut query = invoices::table
+            .filter(invoices::user_id.eq(user_id))
+            .into_boxed();
+
+        if let (Some(cursor_created_at), Some(cursor_id)) = (cursor_created_at, cursor_id) {
+            query = query.filter(
+                invoices::created_at
+                    .lt(cursor_created_at)
+                    .or(invoices::created_at
+                        .eq(cursor_created_at)
+                        .and(invoices::id.lt(cursor_id))),
+            );
+        }
+
+        let results = query
+            .order((invoices::created_at.desc(), invoices::id.desc()))
+            .limit(limit)
+            .load::<InvoiceEntity>(&mut conn)?;
+
+        Ok(results)
+    }
+
+    async fn find_by_id_and_user(
+        &self,
+        invoice_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<InvoiceEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let invoice = invoices::table
+            .filter(invoices::id.eq(invoice_id))
+            .filter(invoices::user_id.eq(user_id))
+            .first::<InvoiceEntity>(&mut conn)
+            .optional()?;
+
+        Ok(invoice)
+    }
+
+    async fn update_provider_links(
+        &self,
+        invoice_id: Uuid,
+        provider_invoice_ref: Option<String>,
+        hosted_invoice_url: Option<String>,
+        invoice_pdf_url: Option<String>,
+    ) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let changes = InvoiceProviderLinksChangeset {
+            provider_invoice_ref,
+            hosted_invoice_url,
+            invoice_pdf_url,
+        };
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        update(invoices::table.filter(invoices::id.eq(invoice_id)))
+            .set(&changes)
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn update_discount(
+        &self,
+        invoice_id: Uuid,
+        discount_minor: i32,
+        promotion_code: Option<String>,
+    ) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let target = invoices::table.filter(invoices::id.eq(invoice_id));
+        // Renewal invoices carry the amount but not the code; keep the code from checkout.
+        match promotion_code {
+            Some(promotion_code) => update(target)
+                .set((
+                    invoices::discount_minor.eq(discount_minor),
+                    invoices::promotion_code.eq(Some(promotion_code)),
+                ))
+                .execute(&mut conn)?,
+            None => update(target)
+                .set(invoices::discount_minor.eq(discount_minor))
+                .execute(&mut conn)?,
+        };
+
+        Ok(())
+    }
+
+    async fn find_latest_by_subscription_id(
+        &self,
+        subscription_id: Uuid,
+    ) -> Result<Option<InvoiceEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let invoice = invoices::table
+            .filter(invoices::subscription_id.eq(subscription_id))
+            .order((invoices::period_start.desc(), invoices::created_at.desc()))
+            .first::<InvoiceEntity>(&mut conn)
+            .optional()?;
+
+        Ok(invoice)
+    }
 }
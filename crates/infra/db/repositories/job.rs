@@ -1,7 +1,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::Utc;
-use diesel::prelude::*;
+use chrono::{DateTime, Utc};
+use diesel::{dsl::sql, prelude::*, sql_types::Bool};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -12,7 +12,11 @@ use crate::{
 use domain::{
     entities::jobs::{InsertJobEntity, JobEntity},
     repositories::job::JobRepository,
-    value_objects::recording_upload::RecordingUploadPayload,
+    value_objects::{
+        enums::job_types::JobType,
+        recording_upload::{RecordingRemuxPayload, RecordingUploadPayload},
+        subscription_notifications::SubscriptionNotificationPayload,
+    },
 };
 
 pub struct JobPostgres {
@@ -32,73 +36,61 @@ impl JobRepository for JobPostgres {
         recording_id: Uuid,
         local_path: String,
     ) -> Result<Uuid> {
-        let mut conn = Arc::clone(&self.db_pool).get()?;
-
         let payload = RecordingUploadPayload {
             recording_id,
             local_path,
         };
-        let payload_json = serde_json::to_value(payload)?;
 
-        let insert_entity = InsertJobEntity {
-            type_: "RecordingUpload".to_string(),
-            payload: payload_json,
-            run_at: Utc::now(),
-            attempts: 0,
-            locked_at: None,
-            locked_by: None,
-            status: "queued".to_string(),
-            error: None,
-            created_at: Utc::now(),
-        };
-
-        let result = diesel::insert_into(jobs::table)
-            .values(&insert_entity)
-            .returning(jobs::id)
-            .get_result::<Uuid>(&mut conn)?;
-
-        Ok(result)
+        self.enqueue_job(JobType::RecordingUpload, serde_json::to_value(payload)?)
     }
 
     async fn lock_next_recording_upload_job(&self) -> Result<Option<JobEntity>> {
-        let mut conn = Arc::clone(&self.db_pool).get()?;
-        let worker_id = Uuid::new_v4().to_string();
-        let current_time = Utc::now();
+        self.lock_next_job(JobType::RecordingUpload)
+    }
 
-        // Using a transaction to lock the job
-        let job = conn.transaction::<Option<JobEntity>, diesel::result::Error, _>(|conn| {
-            // Find a candidate job
-            // We use raw SQL for FOR UPDATE SKIP LOCKED because Diesel support might vary or be verbose
-            // But let's try to use Diesel DSL if possible.
-            // Assuming Postgres, we can use .for_update().skip_locked()
+    async fn enqueue_recording_remux_job(
+        &self,
+        recording_id: Uuid,
+        local_path: String,
+    ) -> Result<Uuid> {
+        let payload = RecordingRemuxPayload {
+            recording_id,
+            local_path,
+        };
 
-            let candidate: Option<JobEntity> = jobs::table
-                .select(JobEntity::as_select())
-                .filter(jobs::type_.eq("RecordingUpload"))
-                .filter(jobs::status.eq("queued"))
-                .filter(jobs::run_at.le(current_time))
-                .order(jobs::run_at.asc())
-                .for_update()
-                .skip_locked()
-                .first::<JobEntity>(conn)
-                .optional()?;
+        self.enqueue_job(JobType::RecordingRemux, serde_json::to_value(payload)?)
+    }
 
-            if let Some(job) = candidate {
-                let updated_job = diesel::update(jobs::table.find(job.id))
-                    .set((
-                        jobs::status.eq("running"),
-                        jobs::locked_at.eq(Some(current_time)),
-                        jobs::locked_by.eq(Some(worker_id)),
-                    ))
-                    .returning(JobEntity::as_select())
-                    .get_result::<JobEntity>(conn)?;
-                Ok(Some(updated_job))
-            } else {
-                Ok(None)
-            }
-        })?;
+    async fn lock_next_recording_remux_job(&self) -> Result<Option<JobEntity>> {
+        self.lock_next_job(JobType::RecordingRemux)
+    }
 
-        Ok(job)
+    async fn has_pending_recording_job(
+        &self,
+        recording_id: Uuid,
+        locked_after: DateTime<Utc>,
+    ) -> Result<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let pending = diesel::select(diesel::dsl::exists(
+            jobs::table
+                .filter(jobs::type_.eq_any([
+                    JobType::RecordingRemux.to_string(),
+                    JobType::RecordingUpload.to_string(),
+                ]))
+                .filter(
+                    sql::<Bool>("payload ->> 'recording_id' = ")
+                        .bind::<diesel::sql_types::Text, _>(recording_id.to_string()),
+                )
+                .filter(
+                    jobs::status.eq("queued").or(jobs::status
+                        .eq("running")
+                        .and(jobs::locked_at.gt(locked_after))),
+                ),
+        ))
+        .get_result::<bool>(&mut conn)?;
+
+        Ok(pending)
     }
 
     async fn mark_job_done(&self, job_id: Uuid) -> Result<()> {
@@ -153,4 +145,104 @@ impl JobRepository for JobPostgres {
 
         Ok(())
     }
+
+    async fn enqueue_subscription_notification_job(
+        &self,
+        payload: SubscriptionNotificationPayload,
+    ) -> Result<Option<Uuid>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let insert_entity = InsertJobEntity {
+            type_: JobType::SubscriptionNotify.to_string(),
+            payload: serde_json::to_value(&payload)?,
+            run_at: Utc::now(),
+            attempts: 0,
+            locked_at: None,
+            locked_by: None,
+            status: "queued".to_string(),
+            error: None,
+            created_at: Utc::now(),
+            dedupe_key: Some(payload.dedupe_key),
+        };
+
+        let result = diesel::insert_into(jobs::table)
+            .values(&insert_entity)
+            .on_conflict_do_nothing()
+            .returning(jobs::id)
+            .get_result::<Uuid>(&mut conn)
+            .optional()?;
+
+        Ok(result)
+    }
+
+    async fn lock_next_subscription_notification_job(&self) -> Result<Option<JobEntity>> {
+        self.lock_next_job(JobType::SubscriptionNotify)
+    }
+}
+
+impl JobPostgres {
+    fn enqueue_job(&self, job_type: JobType, payload: serde_json::Value) -> Result<Uuid> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let insert_entity = InsertJobEntity {
+            type_: job_type.to_string(),
+            payload,
+            run_at: Utc::now(),
+            attempts: 0,
+            locked_at: None,
+            locked_by: None,
+            status: "queued".to_string(),
+            error: None,
+            created_at: Utc::now(),
+            dedupe_key: None,
+        };
+
+        let result = diesel::insert_into(jobs::table)
+            .values(&insert_entity)
+            .returning(jobs::id)
+            .get_result::<Uuid>(&mut conn)?;
+
+        Ok(result)
+    }
+
+    fn lock_next_job(&self, job_type: JobType) -> Result<Option<JobEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let worker_id = Uuid::new_v4().to_string();
+        let current_time = Utc::now();
+
+        // Using a transaction to lock the job
+        let job = conn.transaction::<Option<JobEntity>, diesel::result::Error, _>(|conn| {
+            // Find a candidate job
+            // We use raw SQL for FOR UPDATE SKIP LOCKED because Diesel support might vary or be verbose
+            // But let's try to use Diesel DSL if possible.
+            // Assuming Postgres, we can use .for_update().skip_locked()
+
+            let candidate: Option<JobEntity> = jobs::table
+                .select(JobEntity::as_select())
+                .filter(jobs::type_.eq(job_type.to_string()))
+                .filter(jobs::status.eq("queued"))
+                .filter(jobs::run_at.le(current_time))
+                .order(jobs::run_at.asc())
+                .for_update()
+                .skip_locked()
+                .first::<JobEntity>(conn)
+                .optional()?;
+
+            if let Some(job) = candidate {
+                let updated_job = diesel::update(jobs::table.find(job.id))
+                    .set((
+                        jobs::status.eq("running"),
+                        jobs::locked_at.eq(Some(current_time)),
+                        jobs::locked_by.eq(Some(worker_id)),
+                    ))
+                    .returning(JobEntity::as_select())
+                    .get_result::<JobEntity>(conn)?;
+                Ok(Some(updated_job))
+            } else {
+                Ok(None)
+            }
+        })?;
+
+        Ok(job)
+    }
 }
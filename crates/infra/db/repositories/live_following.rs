@@ -2,7 +2,11 @@ use anyhow::Result;
 use async_trait::async_trait;
 use chrono::Utc;
 use diesel::upsert::excluded;
-use diesel::{Connection, RunQueryDsl, dsl::count_star, insert_into};
+use diesel::{
+    Connection, RunQueryDsl,
+    dsl::{count, count_star},
+    insert_into,
+};
 use diesel::{prelude::*, update};
 use std::sync::Arc;
 use uuid::Uuid;
@@ -10,7 +14,7 @@ use uuid::Uuid;
 use crate::domain;
 use crate::infra::db::postgres::{
     postgres_connection::PgPoolSquad,
-    schema::{follows, live_accounts},
+    schema::{follows, live_accounts, recordings},
 };
 use domain::{
     entities::{
@@ -19,7 +23,10 @@ use domain::{
     },
     repositories::live_following::LiveFollowingRepository,
     value_objects::{
-        enums::{follow_statuses::FollowStatus, sort_order::SortOrder},
+        enums::{
+            follow_statuses::FollowStatus, recording_statuses::RecordingStatus,
+            sort_order::SortOrder,
+        },
         live_following::{FindLiveAccountModel, ListFollowsFilter},
     },
 };
@@ -169,4 +176,122 @@ impl LiveFollowingRepository for LiveFollowingPostgres {
 
         Ok(total)
     }
+
+    async fn count_recording_follows(&self, user_id: Uuid) -> Result<i64> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let total = follows::table
+            .inner_join(
+                recordings::table.on(recordings::live_account_id.eq(follows::live_account_id)),
+            )
+            .filter(follows::user_id.eq(user_id))
+            .filter(follows::status.eq(FollowStatus::Active.to_string()))
+            .filter(recordings::status.eq(RecordingStatus::LiveRecording.to_string()))
+            .select(count(follows::live_account_id).aggregate_distinct())
+            .first::<i64>(&mut conn)?;
+
+        Ok(total)
+    }
+
+    async fn deactivate_follows_over_limit(&self, user_id: Uuid, keep: i64) -> Result<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let now = Utc::now();
+
+        conn.transaction::<usize, anyhow::Error, _>(|tx| {
+            let active_ids = follows::table
+                .filter(follows::user_id.eq(user_id))
+                .filter(follows::status.eq(FollowStatus::Active.to_string()))
+                .order((follows::created_at.asc(), follows::live_account_id.asc()))
+                .select(follows::live_account_id)
+                .for_update()
+                .load::<Uuid>(tx)?;
+
+            let keep = usize::try_from(keep.max(0)).unwrap_or(usize::MAX);
+            let over_limit = active_ids.into_iter().skip(keep).collect::<Vec<_>>();
+            if over_limit.is_empty() {
+                return Ok(0);
+            }
+
+            let updated = update(follows::table)
+                .filter(follows::user_id.eq(user_id))
+                .filter(follows::live_account_id.eq_any(&over_limit))
+                .set((
+                    follows::status.eq(FollowStatus::TemporaryInactive.to_string()),
+                    follows::updated_at.eq(now),
+                ))
+                .execute(tx)?;
+
+            Ok(updated)
+        })
+    }
+
+    async fn restore_temporary_inactive_follows(
+        &self,
+        user_id: Uuid,
+        max_active: i64,
+    ) -> Result<usize> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let now = Utc::now();
+
+        conn.transaction::<usize, anyhow::Error, _>(|tx| {
+            let active = follows::table
+                .filter(follows::user_id.eq(user_id))
+                .filter(follows::status.eq(FollowStatus::Active.to_string()))
+                .select(count_star())
+                .first::<i64>(tx)?;
+
+            let available = max_active - active;
+            if available <= 0 {
+                return Ok(0);
+            }
+
+            let restorable_ids = follows::table
+                .filter(follows::user_id.eq(user_id))
+                .filter(follows::status.eq(FollowStatus::TemporaryInactive.to_string()))
+                .order((follows::created_at.asc(), follows::live_account_id.asc()))
+                .limit(available)
+                .select(follows::live_account_id)
+                .for_update()
+                .load::<Uuid>(tx)?;
+            if restorable_ids.is_empty() {
+                return Ok(0);
+            }
+
+            let updated = update(follows::table)
+                .filter(follows::user_id.eq(user_id))
+                .filter(follows::live_account_id.eq_any(&restorable_ids))
+                .set((
+                    follows::status.eq(FollowStatus::Active.to_string()),
+                    follows::updated_at.eq(now),
+                ))
+                .execute(tx)?;
+
+            Ok(updated)
+        })
+    }
+
+    async fn list_users_with_temporary_inactive_follows(&self) -> Result<Vec<Uuid>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let user_ids = follows::table
+            .filter(follows::status.eq(FollowStatus::TemporaryInactive.to_string()))
+            .select(follows::user_id)
+            .distinct()
+            .load::<Uuid>(&mut conn)?;
+
+        Ok(user_ids)
+    }
+
+    async fn list_users_with_active_follows_over(&self, max_active: i64) -> Result<Vec<Uuid>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let user_ids = follows::table
+            .filter(follows::status.eq(FollowStatus::Active.to_string()))
+            .group_by(follows::user_id)
+            .having(count_star().gt(max_active))
+            .select(follows::user_id)
+            .load::<Uuid>(&mut conn)?;
+
+        Ok(user_ids)
+    }
 }
@@ -1,3 +1,5 @@
+pub mod deliveries;
+pub mod gift_codes;
 pub mod invoices;
 pub mod job;
 pub mod live_account_recording_engine;
@@ -10,4 +12,8 @@ pub mod recording_dashboard;
 pub mod recording_engine_webhook;
 pub mod recording_upload;
 pub mod recording_view;
+pub mod recording_watchdog;
+pub mod stripe_events;
+pub mod subscription_grants;
 pub mod subscriptions;
+pub mod user_contacts;
@@ -1,16 +1,22 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::{OptionalExtension, RunQueryDsl, insert_into, prelude::*, update};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{
     domain,
-    infra::db::postgres::{postgres_connection::PgPoolSquad, schema::payments},
+    infra::db::postgres::{
+        postgres_connection::PgPoolSquad,
+        schema::{invoices, payments},
+    },
 };
 use domain::{
-    entities::payments::NewPaymentEntity,
+    entities::{
+        invoices::InvoiceEntity,
+        payments::{NewPaymentEntity, PaymentEntity},
+    },
     repositories::payments::PaymentRepository,
     value_objects::enums::payment_statuses::PaymentStatus,
 };
@@ -67,4 +73,125 @@ impl PaymentRepository for PaymentPostgres {
 
         Ok(exists)
     }
+
+    async fn find_by_provider_payment_id(
+        &self,
+        provider_payment_id: &str,
+    ) -> Result<Option<PaymentEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let payment = payments::table
+            .filter(payments::provider_payment_id.eq(provider_payment_id))
+            .order(payments::created_at.desc())
+            .select(PaymentEntity::as_select())
+            .first::<PaymentEntity>(&mut conn)
+            .optional()?;
+
+        Ok(payment)
+    }
+
+    async fn record_refund(
+        &self,
+        provider_payment_id: &str,
+        status: PaymentStatus,
+        refunded_minor: i32,
+        reason: Option<String>,
+    ) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(payments::table.filter(payments::provider_payment_id.eq(provider_payment_id)))
+            .set((
+                payments::status.eq(status.to_string()),
+                payments::refunded_minor.eq(refunded_minor),
+                payments::status_reason.eq(reason),
+                payments::updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn record_dispute(
+        &self,
+        provider_payment_id: &str,
+        status: PaymentStatus,
+        reason: Option<String>,
+    ) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(payments::table.filter(payments::provider_payment_id.eq(provider_payment_id)))
+            .set((
+                payments::status.eq(status.to_string()),
+                payments::status_reason.eq(reason),
+                payments::updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn update_receipt_url_by_provider_payment_id(
+        &self,
+        provider_payment_id: &str,
+        receipt_url: &str,
+    ) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(payments::table.filter(payments::provider_payment_id.eq(provider_payment_id)))
+            .set((
+                payments::receipt_url.eq(Some(receipt_url)),
+                payments::updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn list_by_user(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        cursor_created_at: Option<DateTime<Utc>>,
+        cursor_id: Option<Uuid>,
+    ) -> Result<Vec<(PaymentEntity, InvoiceEntity)>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let mut query = payments::table
+            .inner_join(invoices::table.on(payments::invoice_id.eq(invoices::id)))
+            .select((PaymentEntity::as_select(), InvoiceEntity::as_select()))
+            .filter(payments::user_id.eq(user_id))
+            .into_boxed();
+
+        if let (Some(cursor_created_at), Some(cursor_id)) = (cursor_created_at, cursor_id) {
+            query = query.filter(
+                payments::created_at
+                    .lt(cursor_created_at)
+                    .or(payments::created_at
+                        .eq(cursor_created_at)
+                        .and(payments::id.lt(cursor_id))),
+            );
+        }
+
+        let results = query
+            .order((payments::created_at.desc(), payments::id.desc()))
+            .limit(limit)
+            .load::<(PaymentEntity, InvoiceEntity)>(&mut conn)?;
+
+        Ok(results)
+    }
+
+    async fn list_by_invoice_ids(&self, invoice_ids: Vec<Uuid>) -> Result<Vec<PaymentEntity>> {
+        if invoice_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let results = payments::table
+            .filter(payments::invoice_id.eq_any(invoice_ids))
+            .order((payments::created_at.desc(), payments::id.desc()))
+            .load::<PaymentEntity>(&mut conn)?;
+
+        Ok(results)
+    }
 }
@@ -1,13 +1,20 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use diesel::{RunQueryDsl, prelude::*};
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
+use tracing::error;
 use uuid::Uuid;
 
 use crate::domain;
-use crate::infra::db::postgres::{postgres_connection::PgPoolSquad, schema::plans};
+use crate::infra::db::postgres::{
+    postgres_connection::PgPoolSquad,
+    schema::{plan_prices, plans},
+};
 use domain::{
-    entities::plans::{PlanEntity, PlanRow},
+    entities::{
+        plan_prices::PlanPriceEntity,
+        plans::{PlanEntity, PlanRow},
+    },
     repositories::plans::PlanRepository,
 };
 
@@ -19,6 +26,29 @@ impl PlanPostgres {
     pub fn new(db_pool: Arc<PgPoolSquad>) -> Self {
         Self { db_pool }
     }
+
+    fn attach_prices(
+        conn: &mut PgConnection,
+        mut plans: Vec<PlanEntity>,
+    ) -> Result<Vec<PlanEntity>> {
+        let plan_ids: Vec<Uuid> = plans.iter().map(|plan| plan.id).collect();
+        let prices = plan_prices::table
+            .filter(plan_prices::plan_id.eq_any(&plan_ids))
+            .filter(plan_prices::is_active.eq(true))
+            .order((plan_prices::currency.asc(), plan_prices::created_at.asc()))
+            .select(PlanPriceEntity::as_select())
+            .load::<PlanPriceEntity>(conn)?;
+
+        let mut prices_by_plan: HashMap<Uuid, Vec<PlanPriceEntity>> = HashMap::new();
+        for price in prices {
+            prices_by_plan.entry(price.plan_id).or_default().push(price);
+        }
+
+        for plan in &mut plans {
+            plan.prices = prices_by_plan.remove(&plan.id).unwrap_or_default();
+        }
+        Ok(plans)
+    }
 }
 
 #[async_trait]
@@ -29,9 +59,12 @@ impl PlanRepository for PlanPostgres {
         let row = plans::table
             .filter(plans::id.eq(plan_id))
             .filter(plans::is_active.eq(true))
+            .select(PlanRow::as_select())
             .first::<PlanRow>(&mut conn)?;
 
-        Ok(row.into())
+        let plan = PlanEntity::try_from(row)?;
+        let mut plans = Self::attach_prices(&mut conn, vec![plan])?;
+        Ok(plans.remove(0))
     }
 
     async fn find_active_plan_by_id(&self, plan_id: Uuid) -> Result<PlanEntity> {
@@ -46,6 +79,23 @@ impl PlanRepository for PlanPostgres {
             .select(PlanRow::as_select())
             .load::<PlanRow>(&mut conn)?;
 
-        Ok(rows.into_iter().map(PlanEntity::from).collect())
+        // One misconfigured plan should not take the whole catalogue down with it.
+        let plans = rows
+            .into_iter()
+            .filter_map(|row| {
+                let plan_id = row.id;
+                PlanEntity::try_from(row)
+                    .map_err(|err| {
+                        error!(
+                            %plan_id,
+                            error = ?err,
+                            "plan_repository: skipping plan with invalid features"
+                        );
+                    })
+                    .ok()
+            })
+            .collect();
+
+        Self::attach_prices(&mut conn, plans)
     }
 }
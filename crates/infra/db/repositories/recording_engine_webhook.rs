@@ -1,24 +1,53 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use chrono::Utc;
-use diesel::{OptionalExtension, RunQueryDsl, insert_into, prelude::*, update};
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use diesel::{
+    OptionalExtension, RunQueryDsl, delete,
+    dsl::{case_when, count},
+    insert_into,
+    prelude::*,
+    sql_types::Integer,
+    update,
+    upsert::excluded,
+};
+use std::{collections::HashMap, sync::Arc};
 use uuid::Uuid;
 
 use crate::{
     domain,
-    infra::db::postgres::{
-        postgres_connection::PgPoolSquad,
-        schema::{live_accounts, recordings},
+    infra::db::{
+        postgres::{
+            postgres_connection::PgPoolSquad,
+            schema::{
+                follows, live_accounts, plans, recording_engine_errors,
+                recording_engine_webhook_events, recordings, subscriptions,
+            },
+        },
+        repositories::recording_view::RecordingViewPostgres,
     },
 };
 use domain::{
     entities::{
         live_accounts::LiveAccountEntity,
-        recordings::{InsertRecordingEntity, RecordingEntity, RecordingTransmuxUpdateEntity},
+        recording_engine_errors::{InsertRecordingEngineErrorEntity, RecordingEngineErrorEntity},
+        recording_engine_webhook_events::{
+            InsertRecordingEngineWebhookEventEntity, RecordingEngineWebhookEventEntity,
+        },
+        recordings::{
+            InsertRecordingEntity, RecordingEntity, RecordingLiveEndUpdateEntity,
+            RecordingTransmuxUpdateEntity,
+        },
     },
     repositories::recording_engine_webhook::RecordingEngineWebhookRepository,
-    value_objects::enums::recording_statuses::RecordingStatus,
+    value_objects::{
+        enums::{
+            follow_statuses::FollowStatus, live_account_statuses::LiveAccountStatus,
+            recording_statuses::RecordingStatus, subscription_statuses::SubscriptionStatus,
+        },
+        plans::PlanFeatures,
+        recording_engine_webhook::WebhookEventClaim,
+        recordings::{FollowerRecordingCapacity, RecordingUsage},
+    },
 };
 
 pub struct RecordingEngineWebhookPostgres {
@@ -108,4 +137,364 @@ impl RecordingEngineWebhookRepository for RecordingEngineWebhookPostgres {
 
         Ok(result)
     }
+
+    async fn update_live_end(
+        &self,
+        recording_id: Uuid,
+        changeset: RecordingLiveEndUpdateEntity,
+    ) -> Result<Uuid> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let result = update(recordings::table.filter(recordings::id.eq(recording_id)))
+            .set(changeset)
+            .returning(recordings::id)
+            .get_result::<Uuid>(&mut conn)?;
+
+        Ok(result)
+    }
+
+    async fn touch_live_recording(&self, recording_id: Uuid) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        update(
+            recordings::table
+                .filter(recordings::id.eq(recording_id))
+                .filter(recordings::status.eq(RecordingStatus::LiveRecording.to_string())),
+        )
+        .set(recordings::updated_at.eq(Utc::now()))
+        .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn list_untransmuxed_recordings(
+        &self,
+        finished_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RecordingEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let results = recordings::table
+            .filter(recordings::status.eq(RecordingStatus::LiveEnd.to_string()))
+            .filter(recordings::storage_temp_path.is_not_null())
+            .filter(recordings::updated_at.lt(finished_before))
+            .order(recordings::updated_at.asc())
+            .limit(limit)
+            .select(RecordingEntity::as_select())
+            .load::<RecordingEntity>(&mut conn)?;
+
+        Ok(results)
+    }
+
+    async fn claim_untransmuxed_recording(
+        &self,
+        recording_id: Uuid,
+        finished_before: DateTime<Utc>,
+    ) -> Result<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let claimed = update(
+            recordings::table
+                .filter(recordings::id.eq(recording_id))
+                .filter(recordings::status.eq(RecordingStatus::LiveEnd.to_string()))
+                .filter(recordings::storage_temp_path.is_not_null())
+                .filter(recordings::updated_at.lt(finished_before)),
+        )
+        .set((
+            recordings::status.eq(RecordingStatus::WaitingUpload.to_string()),
+            recordings::storage_path.eq(recordings::storage_temp_path),
+            recordings::updated_at.eq(Utc::now()),
+        ))
+        .execute(&mut conn)?;
+
+        Ok(claimed == 1)
+    }
+
+    async fn find_latest_recording_by_live_account(
+        &self,
+        platform: String,
+        account_id: String,
+    ) -> Result<Option<RecordingEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let result = recordings::table
+            .inner_join(live_accounts::table.on(recordings::live_account_id.eq(live_accounts::id)))
+            .select(RecordingEntity::as_select())
+            .filter(live_accounts::platform.eq(platform))
+            .filter(live_accounts::account_id.eq(account_id))
+            .order(recordings::started_at.desc())
+            .first::<RecordingEntity>(&mut conn)
+            .optional()?;
+
+        Ok(result)
+    }
+
+    async fn list_follower_recording_capacity(
+        &self,
+        live_account_id: Uuid,
+        free_plan_id: Uuid,
+    ) -> Result<Vec<FollowerRecordingCapacity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+        let now = Utc::now();
+
+        let follower_ids = follows::table
+            .filter(follows::live_account_id.eq(live_account_id))
+            .filter(follows::status.eq(FollowStatus::Active.to_string()))
+            .select(follows::user_id)
+            .load::<Uuid>(&mut conn)?;
+
+        if follower_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let free_plan_features = plans::table
+            .filter(plans::id.eq(free_plan_id))
+            .select(plans::features)
+            .first::<serde_json::Value>(&mut conn)
+            .optional()?;
+
+        // Newest subscription first, matching how the effective plan is resolved elsewhere.
+        let subscribed_features = subscriptions::table
+            .inner_join(plans::table)
+            .filter(subscriptions::user_id.eq_any(&follower_ids))
+            .filter(subscriptions::plan_id.ne(free_plan_id))
+            .filter(subscriptions::status.eq(SubscriptionStatus::Active.to_string()))
+            .filter(subscriptions::starts_at.le(now))
+            .filter(subscriptions::ends_at.gt(now))
+            .order(subscriptions::starts_at.desc())
+            .select((subscriptions::user_id, plans::features))
+            .load::<(Uuid, serde_json::Value)>(&mut conn)?;
+
+        let active_recordings = follows::table
+            .inner_join(
+                recordings::table.on(recordings::live_account_id.eq(follows::live_account_id)),
+            )
+            .filter(follows::user_id.eq_any(&follower_ids))
+            .filter(follows::status.eq(FollowStatus::Active.to_string()))
+            .filter(follows::live_account_id.ne(live_account_id))
+            .filter(recordings::status.eq(RecordingStatus::LiveRecording.to_string()))
+            .group_by(follows::user_id)
+            .select((
+                follows::user_id,
+                count(recordings::live_account_id).aggregate_distinct(),
+            ))
+            .load::<(Uuid, i64)>(&mut conn)?
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        let mut plan_features = HashMap::new();
+        for (user_id, features) in subscribed_features {
+            plan_features.entry(user_id).or_insert(features);
+        }
+
+        follower_ids
+            .into_iter()
+            .map(|user_id| {
+                let features = match plan_features.get(&user_id).or(free_plan_features.as_ref()) {
+                    Some(value) => PlanFeatures::from_json(value.clone())?,
+                    None => PlanFeatures::default(),
+                };
+
+                Ok(FollowerRecordingCapacity {
+                    user_id,
+                    max_concurrent_recordings: features.max_concurrent_recordings_or_default(),
+                    active_recordings: active_recordings.get(&user_id).copied().unwrap_or(0),
+                    retention_days: features.retention_days_or_default(),
+                    max_storage_bytes: features.max_storage_bytes,
+                    max_recording_hours_per_month: features.max_recording_hours_per_month,
+                })
+            })
+            .collect()
+    }
+
+    async fn find_recording_usage(
+        &self,
+        user_id: Uuid,
+        retention_days: i64,
+        month_start: DateTime<Utc>,
+    ) -> Result<RecordingUsage> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        RecordingViewPostgres::load_recording_usage(&mut conn, user_id, retention_days, month_start)
+    }
+
+    async fn claim_webhook_event(
+        &self,
+        event: InsertRecordingEngineWebhookEventEntity,
+        stale_before: DateTime<Utc>,
+    ) -> Result<WebhookEventClaim> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        conn.transaction::<WebhookEventClaim, anyhow::Error, _>(|tx| {
+            let inserted = insert_into(recording_engine_webhook_events::table)
+                .values(&event)
+                .on_conflict(recording_engine_webhook_events::id)
+                .do_nothing()
+                .execute(tx)?;
+            if inserted == 1 {
+                return Ok(WebhookEventClaim::Claimed);
+            }
+
+            let existing = recording_engine_webhook_events::table
+                .filter(recording_engine_webhook_events::id.eq(event.id))
+                .select(RecordingEngineWebhookEventEntity::as_select())
+                .for_update()
+                .first::<RecordingEngineWebhookEventEntity>(tx)?;
+
+            if existing.processed_at.is_some() {
+                return Ok(WebhookEventClaim::Processed {
+                    recording_id: existing.recording_id,
+                });
+            }
+            if existing.received_at >= stale_before {
+                return Ok(WebhookEventClaim::InProgress);
+            }
+
+            update(recording_engine_webhook_events::table.find(event.id))
+                .set(recording_engine_webhook_events::received_at.eq(Utc::now()))
+                .execute(tx)?;
+            Ok(WebhookEventClaim::Claimed)
+        })
+    }
+
+    async fn complete_webhook_event(
+        &self,
+        event_id: Uuid,
+        recording_id: Option<Uuid>,
+    ) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(recording_engine_webhook_events::table.find(event_id))
+            .set((
+                recording_engine_webhook_events::recording_id.eq(recording_id),
+                recording_engine_webhook_events::processed_at.eq(Some(Utc::now())),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn release_webhook_event(&self, event_id: Uuid) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        delete(
+            recording_engine_webhook_events::table
+                .filter(recording_engine_webhook_events::id.eq(event_id))
+                .filter(recording_engine_webhook_events::processed_at.is_null()),
+        )
+        .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn record_engine_error(
+        &self,
+        error: InsertRecordingEngineErrorEntity,
+        streak_since: DateTime<Utc>,
+    ) -> Result<RecordingEngineErrorEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        // One upsert, so concurrent errors for a new channel cannot both insert and every
+        // error is counted exactly once.
+        let stored = insert_into(recording_engine_errors::table)
+            .values(&error)
+            .on_conflict((
+                recording_engine_errors::platform,
+                recording_engine_errors::channel,
+            ))
+            .do_update()
+            .set((
+                recording_engine_errors::live_account_id.eq(case_when(
+                    excluded(recording_engine_errors::live_account_id).is_not_null(),
+                    excluded(recording_engine_errors::live_account_id),
+                )
+                .otherwise(recording_engine_errors::live_account_id)),
+                recording_engine_errors::recording_id.eq(case_when(
+                    excluded(recording_engine_errors::recording_id).is_not_null(),
+                    excluded(recording_engine_errors::recording_id),
+                )
+                .otherwise(recording_engine_errors::recording_id)),
+                recording_engine_errors::last_error
+                    .eq(excluded(recording_engine_errors::last_error)),
+                recording_engine_errors::error_count.eq(recording_engine_errors::error_count + 1),
+                recording_engine_errors::consecutive_errors.eq(case_when(
+                    recording_engine_errors::last_error_at.lt(streak_since),
+                    1.into_sql::<Integer>(),
+                )
+                .otherwise(recording_engine_errors::consecutive_errors + 1)),
+                recording_engine_errors::last_error_at
+                    .eq(excluded(recording_engine_errors::last_error_at)),
+                recording_engine_errors::updated_at.eq(Utc::now()),
+            ))
+            .returning(RecordingEngineErrorEntity::as_returning())
+            .get_result::<RecordingEngineErrorEntity>(&mut conn)?;
+
+        Ok(stored)
+    }
+
+    async fn mark_live_account_error(&self, live_account_id: Uuid) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(
+            live_accounts::table
+                .filter(live_accounts::id.eq(live_account_id))
+                .filter(live_accounts::status.ne(LiveAccountStatus::Unsynced.to_string())),
+        )
+        .set((
+            live_accounts::status.eq(LiveAccountStatus::Error.to_string()),
+            live_accounts::updated_at.eq(Utc::now()),
+        ))
+        .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn clear_engine_errors_for_recording(&self, recording_id: Uuid) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        conn.transaction::<(), anyhow::Error, _>(|tx| {
+            let live_account_id = recordings::table
+                .find(recording_id)
+                .select(recordings::live_account_id)
+                .first::<Uuid>(tx)?;
+
+            update(
+                recording_engine_errors::table
+                    .filter(recording_engine_errors::live_account_id.eq(live_account_id))
+                    .filter(recording_engine_errors::consecutive_errors.gt(0)),
+            )
+            .set((
+                recording_engine_errors::consecutive_errors.eq(0),
+                recording_engine_errors::updated_at.eq(Utc::now()),
+            ))
+            .execute(tx)?;
+
+            update(
+                live_accounts::table
+                    .filter(live_accounts::id.eq(live_account_id))
+                    .filter(live_accounts::status.eq(LiveAccountStatus::Error.to_string())),
+            )
+            .set((
+                live_accounts::status.eq(LiveAccountStatus::Synced.to_string()),
+                live_accounts::updated_at.eq(Utc::now()),
+            ))
+            .execute(tx)?;
+
+            Ok(())
+        })
+    }
+
+    async fn list_failing_channels(&self, limit: i64) -> Result<Vec<RecordingEngineErrorEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let results = recording_engine_errors::table
+            .filter(recording_engine_errors::consecutive_errors.gt(0))
+            .order((
+                recording_engine_errors::consecutive_errors.desc(),
+                recording_engine_errors::last_error_at.desc(),
+            ))
+            .limit(limit)
+            .select(RecordingEngineErrorEntity::as_select())
+            .load::<RecordingEngineErrorEntity>(&mut conn)?;
+
+        Ok(results)
+    }
 }
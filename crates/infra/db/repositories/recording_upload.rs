@@ -10,7 +10,7 @@ use crate::{
     infra::db::postgres::{postgres_connection::PgPoolSquad, schema::recordings},
 };
 use domain::{
-    entities::recordings::RecordingEntity,
+    entities::recordings::{RecordingEntity, RecordingRemuxUpdateEntity},
     repositories::recording_upload::RecordingUploadRepository,
     value_objects::enums::recording_statuses::RecordingStatus,
 };
@@ -62,4 +62,19 @@ impl RecordingUploadRepository for RecordingUploadPostgres {
 
         Ok(result)
     }
+
+    async fn update_remuxed_recording(
+        &self,
+        recording_id: Uuid,
+        changeset: RecordingRemuxUpdateEntity,
+    ) -> Result<Uuid> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let result = update(recordings::table.filter(recordings::id.eq(recording_id)))
+            .set(changeset)
+            .returning(recordings::id)
+            .get_result::<Uuid>(&mut conn)?;
+
+        Ok(result)
+    }
 }
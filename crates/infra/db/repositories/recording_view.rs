@@ -1,7 +1,13 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use diesel::{RunQueryDsl, dsl::count_star, prelude::*};
+use diesel::{
+    PgConnection, RunQueryDsl,
+    dsl::{count_star, sql},
+    pg::Pg,
+    prelude::*,
+    sql_types::{BigInt, Bool, Text, Uuid as SqlUuid},
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -15,7 +21,10 @@ use crate::{
 use domain::{
     entities::{live_accounts::LiveAccountEntity, recordings::RecordingEntity},
     repositories::recording_view::RecordingViewRepository,
-    value_objects::enums::{follow_statuses::FollowStatus, recording_statuses::RecordingStatus},
+    value_objects::{
+        enums::{follow_statuses::FollowStatus, recording_statuses::RecordingStatus},
+        recordings::{RecordingUsage, RecordingViewWindow},
+    },
 };
 
 pub struct RecordingViewPostgres {
@@ -27,14 +36,97 @@ impl RecordingViewPostgres {
         Self { db_pool }
     }
 
-    fn view_window_filter_sql(retention_days: i64) -> String {
+    fn view_window_filter<QS>(
+        user_id: Uuid,
+        window: RecordingViewWindow,
+    ) -> Box<dyn BoxableExpression<QS, Pg, SqlType = Bool>> {
+        let retention_filter = sql::<Bool>(&Self::retention_filter_sql(
+            "recordings",
+            window.retention_days,
+        ));
+
+        let Some(storage_limit_bytes) = window.storage_limit_bytes else {
+            return Box::new(retention_filter);
+        };
+
+        // Keep the newest recordings whose running total of sizes still fits the limit.
+        Box::new(
+            retention_filter
+                .sql(
+                    " AND recordings.id IN (\
+                     SELECT ranked.id FROM (\
+                     SELECT r.id, SUM(COALESCE(r.size_bytes, 0)) \
+                     OVER (ORDER BY r.started_at DESC, r.id DESC) AS kept_bytes \
+                     FROM recordings r \
+                     JOIN follows f ON f.live_account_id = r.live_account_id \
+                     WHERE f.user_id = ",
+                )
+                .bind::<SqlUuid, _>(user_id)
+                .sql(" AND f.status = ")
+                .bind::<Text, _>(FollowStatus::Active.to_string())
+                .sql(" AND r.status = ")
+                .bind::<Text, _>(RecordingStatus::Ready.to_string())
+                .sql(" AND ")
+                .sql(&Self::retention_filter_sql("r", window.retention_days))
+                .sql(") ranked WHERE ranked.kept_bytes <= ")
+                .bind::<BigInt, _>(storage_limit_bytes.max(0))
+                .sql(")"),
+        )
+    }
+
+    fn retention_filter_sql(table: &str, retention_days: i64) -> String {
         let retention_days = retention_days.max(0);
         format!(
-            "now() >= recordings.started_at \
-             AND now() < (recordings.started_at + (INTERVAL '1 day' * {}))",
+            "now() >= {table}.started_at \
+             AND now() < ({table}.started_at + (INTERVAL '1 day' * {}))",
             retention_days
         )
     }
+
+    /// Shared with the worker's webhook repository so both sides attribute usage the same way.
+    pub(crate) fn load_recording_usage(
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        retention_days: i64,
+        month_start: DateTime<Utc>,
+    ) -> Result<RecordingUsage> {
+        let retention_filter_sql = Self::retention_filter_sql("recordings", retention_days);
+
+        let storage_bytes = recordings::table
+            .inner_join(follows::table.on(follows::live_account_id.eq(recordings::live_account_id)))
+            .filter(follows::user_id.eq(user_id))
+            .filter(follows::status.eq(FollowStatus::Active.to_string()))
+            .filter(recordings::status.eq(RecordingStatus::Ready.to_string()))
+            .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(
+                &retention_filter_sql,
+            ))
+            .select(sql::<BigInt>(
+                "COALESCE(SUM(recordings.size_bytes), 0)::bigint",
+            ))
+            .first::<i64>(conn)?;
+
+        let not_recorded = vec![
+            RecordingStatus::Failed.to_string(),
+            RecordingStatus::SkippedConcurrencyLimit.to_string(),
+            RecordingStatus::SkippedQuotaExceeded.to_string(),
+        ];
+
+        let recorded_seconds = recordings::table
+            .inner_join(follows::table.on(follows::live_account_id.eq(recordings::live_account_id)))
+            .filter(follows::user_id.eq(user_id))
+            .filter(follows::status.eq(FollowStatus::Active.to_string()))
+            .filter(recordings::status.ne_all(not_recorded))
+            .filter(recordings::started_at.ge(month_start))
+            .select(sql::<BigInt>(
+                "COALESCE(SUM(recordings.duration_sec), 0)::bigint",
+            ))
+            .first::<i64>(conn)?;
+
+        Ok(RecordingUsage {
+            storage_bytes,
+            recorded_seconds,
+        })
+    }
 }
 
 #[async_trait]
@@ -42,15 +134,13 @@ impl RecordingViewRepository for RecordingViewPostgres {
     async fn list_home_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
         limit: i64,
         cursor_started_at: Option<DateTime<Utc>>,
         cursor_id: Option<Uuid>,
     ) -> Result<Vec<(RecordingEntity, LiveAccountEntity)>> {
         let mut conn = Arc::clone(&self.db_pool).get()?;
 
-        let view_filter_sql = Self::view_window_filter_sql(retention_days);
-
         let mut query = recordings::table
             .inner_join(live_accounts::table.on(recordings::live_account_id.eq(live_accounts::id)))
             .inner_join(follows::table.on(follows::live_account_id.eq(recordings::live_account_id)))
@@ -58,9 +148,7 @@ impl RecordingViewRepository for RecordingViewPostgres {
             .filter(follows::user_id.eq(user_id))
             .filter(follows::status.eq(FollowStatus::Active.to_string()))
             .filter(recordings::status.eq(RecordingStatus::Ready.to_string()))
-            .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(
-                &view_filter_sql,
-            ))
+            .filter(Self::view_window_filter(user_id, window))
             .into_boxed();
 
         if let (Some(cursor_started_at), Some(cursor_id)) = (cursor_started_at, cursor_id) {
@@ -84,22 +172,18 @@ impl RecordingViewRepository for RecordingViewPostgres {
     async fn list_follows_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
         live_account_id: Option<Uuid>,
     ) -> Result<Vec<RecordingEntity>> {
         let mut conn = Arc::clone(&self.db_pool).get()?;
 
-        let view_filter_sql = Self::view_window_filter_sql(retention_days);
-
         let mut query = recordings::table
             .inner_join(follows::table.on(follows::live_account_id.eq(recordings::live_account_id)))
             .select(RecordingEntity::as_select())
             .filter(follows::user_id.eq(user_id))
             .filter(follows::status.eq(FollowStatus::Active.to_string()))
             .filter(recordings::status.eq(RecordingStatus::Ready.to_string()))
-            .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(
-                &view_filter_sql,
-            ))
+            .filter(Self::view_window_filter(user_id, window))
             .into_boxed();
 
         if let Some(live_account_id) = live_account_id {
@@ -116,20 +200,16 @@ impl RecordingViewRepository for RecordingViewPostgres {
     async fn count_follows_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
     ) -> Result<Vec<(Uuid, i64)>> {
         let mut conn = Arc::clone(&self.db_pool).get()?;
 
-        let view_filter_sql = Self::view_window_filter_sql(retention_days);
-
         let results = recordings::table
             .inner_join(follows::table.on(follows::live_account_id.eq(recordings::live_account_id)))
             .filter(follows::user_id.eq(user_id))
             .filter(follows::status.eq(FollowStatus::Active.to_string()))
             .filter(recordings::status.eq(RecordingStatus::Ready.to_string()))
-            .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(
-                &view_filter_sql,
-            ))
+            .filter(Self::view_window_filter(user_id, window))
             .group_by(recordings::live_account_id)
             .select((recordings::live_account_id, count_star()))
             .load::<(Uuid, i64)>(&mut conn)?;
@@ -137,6 +217,27 @@ impl RecordingViewRepository for RecordingViewPostgres {
         Ok(results)
     }
 
+    async fn is_recording_in_view_window(
+        &self,
+        user_id: Uuid,
+        window: RecordingViewWindow,
+        recording_id: Uuid,
+    ) -> Result<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let matching = recordings::table
+            .inner_join(follows::table.on(follows::live_account_id.eq(recordings::live_account_id)))
+            .filter(recordings::id.eq(recording_id))
+            .filter(follows::user_id.eq(user_id))
+            .filter(follows::status.eq(FollowStatus::Active.to_string()))
+            .filter(recordings::status.eq(RecordingStatus::Ready.to_string()))
+            .filter(Self::view_window_filter(user_id, window))
+            .select(count_star())
+            .first::<i64>(&mut conn)?;
+
+        Ok(matching > 0)
+    }
+
     async fn list_currently_recording_live_account_ids(&self, user_id: Uuid) -> Result<Vec<Uuid>> {
         let mut conn = Arc::clone(&self.db_pool).get()?;
 
@@ -160,20 +261,16 @@ impl RecordingViewRepository for RecordingViewPostgres {
     async fn count_home_entitled_recordings(
         &self,
         user_id: Uuid,
-        retention_days: i64,
+        window: RecordingViewWindow,
     ) -> Result<i64> {
         let mut conn = Arc::clone(&self.db_pool).get()?;
 
-        let view_filter_sql = Self::view_window_filter_sql(retention_days);
-
         let total = recordings::table
             .inner_join(follows::table.on(follows::live_account_id.eq(recordings::live_account_id)))
             .filter(follows::user_id.eq(user_id))
             .filter(follows::status.eq(FollowStatus::Active.to_string()))
             .filter(recordings::status.eq(RecordingStatus::Ready.to_string()))
-            .filter(diesel::dsl::sql::<diesel::sql_types::Bool>(
-                &view_filter_sql,
-            ))
+            .filter(Self::view_window_filter(user_id, window))
             .select(count_star())
             .first::<i64>(&mut conn)?;
 
@@ -198,4 +295,15 @@ impl RecordingViewRepository for RecordingViewPostgres {
 
         Ok(total)
     }
+
+    async fn find_recording_usage(
+        &self,
+        user_id: Uuid,
+        retention_days: i64,
+        month_start: DateTime<Utc>,
+    ) -> Result<RecordingUsage> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        Self::load_recording_usage(&mut conn, user_id, retention_days, month_start)
+    }
 }
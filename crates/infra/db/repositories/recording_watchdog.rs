@@ -0,0 +1,126 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{
+    RunQueryDsl,
+    dsl::sql,
+    prelude::*,
+    sql_types::{Nullable, Text},
+    update,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain,
+    infra::db::postgres::{
+        postgres_connection::PgPoolSquad,
+        schema::{jobs, live_accounts, recordings},
+    },
+};
+use domain::{
+    entities::{
+        live_accounts::LiveAccountEntity,
+        recordings::{RecordingEntity, RecordingLiveEndUpdateEntity},
+    },
+    repositories::recording_watchdog::RecordingWatchdogRepository,
+    value_objects::enums::{job_types::JobType, recording_statuses::RecordingStatus},
+};
+
+pub struct RecordingWatchdogPostgres {
+    db_pool: Arc<PgPoolSquad>,
+}
+
+impl RecordingWatchdogPostgres {
+    pub fn new(db_pool: Arc<PgPoolSquad>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl RecordingWatchdogRepository for RecordingWatchdogPostgres {
+    async fn list_stale_recordings(
+        &self,
+        status: RecordingStatus,
+        stale_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RecordingEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let results = recordings::table
+            .filter(recordings::status.eq(status.to_string()))
+            .filter(recordings::updated_at.lt(stale_before))
+            .order(recordings::updated_at.asc())
+            .limit(limit)
+            .select(RecordingEntity::as_select())
+            .load::<RecordingEntity>(&mut conn)?;
+
+        Ok(results)
+    }
+
+    async fn update_stale_recording(
+        &self,
+        recording_id: Uuid,
+        status: RecordingStatus,
+        stale_before: DateTime<Utc>,
+        changeset: RecordingLiveEndUpdateEntity,
+    ) -> Result<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let updated = update(
+            recordings::table
+                .filter(recordings::id.eq(recording_id))
+                .filter(recordings::status.eq(status.to_string()))
+                .filter(recordings::updated_at.lt(stale_before)),
+        )
+        .set(changeset)
+        .execute(&mut conn)?;
+
+        Ok(updated == 1)
+    }
+
+    async fn find_live_account(&self, live_account_id: Uuid) -> Result<Option<LiveAccountEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let result = live_accounts::table
+            .find(live_account_id)
+            .select(LiveAccountEntity::as_select())
+            .first::<LiveAccountEntity>(&mut conn)
+            .optional()?;
+
+        Ok(result)
+    }
+
+    async fn list_local_recording_paths(&self) -> Result<Vec<String>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let in_use_statuses = [
+            RecordingStatus::LiveRecording,
+            RecordingStatus::LiveEnd,
+            RecordingStatus::WaitingUpload,
+            RecordingStatus::Uploading,
+        ]
+        .map(|status| status.to_string());
+
+        let recording_paths = recordings::table
+            .filter(recordings::status.eq_any(&in_use_statuses))
+            .select((recordings::storage_path, recordings::storage_temp_path))
+            .load::<(Option<String>, Option<String>)>(&mut conn)?;
+
+        let job_paths = jobs::table
+            .filter(jobs::type_.eq_any([
+                JobType::RecordingRemux.to_string(),
+                JobType::RecordingUpload.to_string(),
+            ]))
+            .filter(jobs::status.eq_any(["queued", "running"]))
+            .select(sql::<Nullable<Text>>("payload ->> 'local_path'"))
+            .load::<Option<String>>(&mut conn)?;
+
+        Ok(recording_paths
+            .into_iter()
+            .flat_map(|(storage_path, storage_temp_path)| [storage_path, storage_temp_path])
+            .chain(job_paths)
+            .flatten()
+            .collect())
+    }
+}
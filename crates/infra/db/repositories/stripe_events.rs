@@ -0,0 +1,133 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{OptionalExtension, RunQueryDsl, insert_into, prelude::*, sql_types::Bool, update};
+use std::sync::Arc;
+
+use crate::{
+    domain,
+    infra::db::postgres::{postgres_connection::PgPoolSquad, schema::stripe_events},
+};
+use domain::{
+    entities::stripe_events::{InsertStripeEventEntity, StripeEventEntity},
+    repositories::stripe_events::StripeEventRepository,
+};
+
+pub struct StripeEventPostgres {
+    db_pool: Arc<PgPoolSquad>,
+}
+
+impl StripeEventPostgres {
+    pub fn new(db_pool: Arc<PgPoolSquad>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl StripeEventRepository for StripeEventPostgres {
+    async fn record_received(&self, event: InsertStripeEventEntity) -> Result<StripeEventEntity> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        insert_into(stripe_events::table)
+            .values(&event)
+            .on_conflict(stripe_events::id)
+            .do_nothing()
+            .execute(&mut conn)?;
+
+        let stored = stripe_events::table
+            .filter(stripe_events::id.eq(&event.id))
+            .select(StripeEventEntity::as_select())
+            .first::<StripeEventEntity>(&mut conn)?;
+
+        Ok(stored)
+    }
+
+    async fn find_by_id(&self, event_id: &str) -> Result<Option<StripeEventEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let event = stripe_events::table
+            .filter(stripe_events::id.eq(event_id))
+            .select(StripeEventEntity::as_select())
+            .first::<StripeEventEntity>(&mut conn)
+            .optional()?;
+
+        Ok(event)
+    }
+
+    async fn claim(
+        &self,
+        event_id: &str,
+        stale_before: DateTime<Utc>,
+        reprocess: bool,
+    ) -> Result<bool> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let claimed = update(
+            stripe_events::table
+                .filter(stripe_events::id.eq(event_id))
+                .filter(
+                    stripe_events::claimed_at
+                        .is_null()
+                        .or(stripe_events::claimed_at.lt(stale_before)),
+                )
+                .filter(
+                    stripe_events::processed_at
+                        .is_null()
+                        .or(reprocess.into_sql::<Bool>()),
+                ),
+        )
+        .set(stripe_events::claimed_at.eq(Some(Utc::now())))
+        .execute(&mut conn)?;
+
+        Ok(claimed == 1)
+    }
+
+    async fn mark_processed(&self, event_id: &str) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(stripe_events::table.filter(stripe_events::id.eq(event_id)))
+            .set((
+                stripe_events::processed_at.eq(Some(Utc::now())),
+                stripe_events::error.eq::<Option<String>>(None),
+                stripe_events::attempts.eq(stripe_events::attempts + 1),
+                stripe_events::claimed_at.eq::<Option<DateTime<Utc>>>(None),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(&self, event_id: &str, error: &str) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(stripe_events::table.filter(stripe_events::id.eq(event_id)))
+            .set((
+                stripe_events::error.eq(Some(error)),
+                stripe_events::attempts.eq(stripe_events::attempts + 1),
+                stripe_events::claimed_at.eq::<Option<DateTime<Utc>>>(None),
+            ))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn list_retryable(
+        &self,
+        max_attempts: i32,
+        received_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<StripeEventEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let events = stripe_events::table
+            .filter(stripe_events::processed_at.is_null())
+            .filter(stripe_events::attempts.lt(max_attempts))
+            .filter(stripe_events::received_at.lt(received_before))
+            .order(stripe_events::received_at.asc())
+            .limit(limit)
+            .select(StripeEventEntity::as_select())
+            .load::<StripeEventEntity>(&mut conn)?;
+
+        Ok(events)
+    }
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::{OptionalExtension, RunQueryDsl, insert_into, prelude::*, update};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain,
+    infra::db::postgres::{
+        postgres_connection::PgPoolSquad,
+        schema::{subscription_grants, subscriptions},
+    },
+};
+use domain::{
+    entities::{
+        subscription_grants::{InsertSubscriptionGrantEntity, SubscriptionGrantEntity},
+        subscriptions::{InsertSubscriptionEntity, SubscriptionEntity},
+    },
+    repositories::subscription_grants::SubscriptionGrantRepository,
+    value_objects::enums::subscription_statuses::SubscriptionStatus,
+};
+
+pub struct SubscriptionGrantPostgres {
+    db_pool: Arc<PgPoolSquad>,
+}
+
+impl SubscriptionGrantPostgres {
+    pub fn new(db_pool: Arc<PgPoolSquad>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[async_trait]
+impl SubscriptionGrantRepository for SubscriptionGrantPostgres {
+    async fn create_grant(
+        &self,
+        subscription: InsertSubscriptionEntity,
+        reason: String,
+        granted_by: Uuid,
+    ) -> Result<(SubscriptionEntity, SubscriptionGrantEntity)> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let created = conn.transaction::<_, anyhow::Error, _>(|tx| {
+            let subscription = insert_into(subscriptions::table)
+                .values(&subscription)
+                .returning(SubscriptionEntity::as_returning())
+                .get_result::<SubscriptionEntity>(tx)?;
+
+            let grant = insert_into(subscription_grants::table)
+                .values(&InsertSubscriptionGrantEntity {
+                    subscription_id: subscription.id,
+                    reason,
+                    granted_by,
+                })
+                .returning(SubscriptionGrantEntity::as_returning())
+                .get_result::<SubscriptionGrantEntity>(tx)?;
+
+            Ok((subscription, grant))
+        })?;
+
+        Ok(created)
+    }
+
+    async fn revoke_grant(
+        &self,
+        subscription_id: Uuid,
+        revoked_by: Uuid,
+        revoke_reason: Option<String>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<(SubscriptionEntity, SubscriptionGrantEntity)>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let revoked = conn.transaction::<_, anyhow::Error, _>(|tx| {
+            let Some(grant) = update(
+                subscription_grants::table
+                    .filter(subscription_grants::subscription_id.eq(subscription_id))
+                    .filter(subscription_grants::revoked_at.is_null()),
+            )
+            .set((
+                subscription_grants::revoked_at.eq(Some(now)),
+                subscription_grants::revoked_by.eq(Some(revoked_by)),
+                subscription_grants::revoke_reason.eq(revoke_reason),
+            ))
+            .returning(SubscriptionGrantEntity::as_returning())
+            .get_result::<SubscriptionGrantEntity>(tx)
+            .optional()?
+            else {
+                return Ok(None);
+            };
+
+            let current = subscriptions::table
+                .filter(subscriptions::id.eq(subscription_id))
+                .select(SubscriptionEntity::as_select())
+                .first::<SubscriptionEntity>(tx)?;
+
+            // Only shorten the period while it is still running so the freed range can be
+            // used by a new subscription; grants that already ended keep their history.
+            let subscription = if current.starts_at < now && current.ends_at > now {
+                update(subscriptions::table.filter(subscriptions::id.eq(subscription_id)))
+                    .set((
+                        subscriptions::status.eq(SubscriptionStatus::Canceled.to_string()),
+                        subscriptions::canceled_at.eq(Some(now)),
+                        subscriptions::ends_at.eq(now),
+                    ))
+                    .returning(SubscriptionEntity::as_returning())
+                    .get_result::<SubscriptionEntity>(tx)?
+            } else {
+                update(subscriptions::table.filter(subscriptions::id.eq(subscription_id)))
+                    .set((
+                        subscriptions::status.eq(SubscriptionStatus::Canceled.to_string()),
+                        subscriptions::canceled_at.eq(Some(now)),
+                    ))
+                    .returning(SubscriptionEntity::as_returning())
+                    .get_result::<SubscriptionEntity>(tx)?
+            };
+
+            Ok(Some((subscription, grant)))
+        })?;
+
+        Ok(revoked)
+    }
+
+    async fn list_grants(
+        &self,
+        user_id: Option<Uuid>,
+        include_revoked: bool,
+    ) -> Result<Vec<(SubscriptionEntity, SubscriptionGrantEntity)>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let mut query = subscriptions::table
+            .inner_join(subscription_grants::table)
+            .select((
+                SubscriptionEntity::as_select(),
+                SubscriptionGrantEntity::as_select(),
+            ))
+            .into_boxed();
+
+        if let Some(user_id) = user_id {
+            query = query.filter(subscriptions::user_id.eq(user_id));
+        }
+        if !include_revoked {
+            query = query.filter(subscription_grants::revoked_at.is_null());
+        }
+
+        let grants = query
+            .order(subscription_grants::created_at.desc())
+            .load::<(SubscriptionEntity, SubscriptionGrantEntity)>(&mut conn)?;
+
+        Ok(grants)
+    }
+}
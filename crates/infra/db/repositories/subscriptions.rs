@@ -80,6 +80,7 @@ impl SubscriptionRepository for SubscriptionPostgres {
         let subscription = subscriptions::table
             .filter(subscriptions::provider_subscription_id.eq(provider_subscription_id))
             .order(subscriptions::created_at.desc())
+            .select(SubscriptionEntity::as_select())
             .first::<SubscriptionEntity>(&mut conn)
             .optional()?;
 
@@ -163,6 +164,7 @@ impl SubscriptionRepository for SubscriptionPostgres {
             .filter(subscriptions::starts_at.le(now))
             .filter(subscriptions::ends_at.gt(now))
             .order(subscriptions::starts_at.desc())
+            .select(SubscriptionEntity::as_select())
             .first::<SubscriptionEntity>(&mut conn)
             .optional()?
         {
@@ -185,10 +187,193 @@ impl SubscriptionRepository for SubscriptionPostgres {
             .filter(subscriptions::status.eq(SubscriptionStatus::Active.to_string()))
             .filter(subscriptions::starts_at.le(now))
             .filter(subscriptions::ends_at.gt(now))
+            .select(SubscriptionEntity::as_select())
             .load::<SubscriptionEntity>(&mut conn)?;
 
         Ok(subscriptions)
     }
+
+    async fn expire_ended_non_renewing_subscriptions(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let expired = update(
+            subscriptions::table
+                .filter(subscriptions::status.eq(SubscriptionStatus::Active.to_string()))
+                .filter(subscriptions::billing_mode.eq_any([
+                    BillingMode::OneTime.to_string(),
+                    BillingMode::Complimentary.to_string(),
+                ]))
+                .filter(subscriptions::ends_at.le(now)),
+        )
+        .set(subscriptions::status.eq(SubscriptionStatus::Expired.to_string()))
+        .returning(SubscriptionEntity::as_returning())
+        .get_results::<SubscriptionEntity>(&mut conn)?;
+
+        Ok(expired)
+    }
+
+    async fn list_expired_pending_notice(&self) -> Result<Vec<SubscriptionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let pending = subscriptions::table
+            .filter(subscriptions::status.eq(SubscriptionStatus::Expired.to_string()))
+            .filter(subscriptions::billing_mode.eq_any([
+                BillingMode::OneTime.to_string(),
+                BillingMode::Complimentary.to_string(),
+            ]))
+            .filter(subscriptions::expiry_notice_enqueued_at.is_null())
+            .order(subscriptions::ends_at.asc())
+            .select(SubscriptionEntity::as_select())
+            .load::<SubscriptionEntity>(&mut conn)?;
+
+        Ok(pending)
+    }
+
+    async fn mark_expiry_notice_enqueued(
+        &self,
+        subscription_id: Uuid,
+        enqueued_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        update(subscriptions::table.filter(subscriptions::id.eq(subscription_id)))
+            .set(subscriptions::expiry_notice_enqueued_at.eq(Some(enqueued_at)))
+            .execute(&mut conn)?;
+
+        Ok(())
+    }
+
+    async fn cancel_past_due_subscriptions(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let canceled = update(
+            subscriptions::table
+                .filter(subscriptions::status.eq(SubscriptionStatus::PastDue.to_string()))
+                .filter(subscriptions::ends_at.le(cutoff)),
+        )
+        .set((
+            subscriptions::status.eq(SubscriptionStatus::Canceled.to_string()),
+            subscriptions::canceled_at.eq(Some(Utc::now())),
+        ))
+        .returning(SubscriptionEntity::as_returning())
+        .get_results::<SubscriptionEntity>(&mut conn)?;
+
+        Ok(canceled)
+    }
+
+    async fn find_overlapping_subscription(
+        &self,
+        user_id: Uuid,
+        starts_at: DateTime<Utc>,
+        ends_at: DateTime<Utc>,
+    ) -> Result<Option<SubscriptionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        // Mirrors `subscriptions_user_timerange_excl`, which applies regardless of status.
+        let overlapping = subscriptions::table
+            .filter(subscriptions::user_id.eq(user_id))
+            .filter(subscriptions::starts_at.lt(ends_at))
+            .filter(subscriptions::ends_at.gt(starts_at))
+            .order(subscriptions::starts_at.asc())
+            .select(SubscriptionEntity::as_select())
+            .first::<SubscriptionEntity>(&mut conn)
+            .optional()?;
+
+        Ok(overlapping)
+    }
+
+    async fn list_subscriptions_ending_after(
+        &self,
+        user_id: Uuid,
+        after: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let subscriptions = subscriptions::table
+            .filter(subscriptions::user_id.eq(user_id))
+            .filter(subscriptions::ends_at.gt(after))
+            .order(subscriptions::ends_at.asc())
+            .select(SubscriptionEntity::as_select())
+            .load::<SubscriptionEntity>(&mut conn)?;
+
+        Ok(subscriptions)
+    }
+
+    async fn list_non_renewing_ending_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<SubscriptionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let ending = subscriptions::table
+            .filter(subscriptions::status.eq(SubscriptionStatus::Active.to_string()))
+            .filter(
+                subscriptions::billing_mode
+                    .eq_any([
+                        BillingMode::OneTime.to_string(),
+                        BillingMode::Complimentary.to_string(),
+                    ])
+                    .or(subscriptions::cancel_at_period_end.eq(true)),
+            )
+            .filter(subscriptions::ends_at.gt(from))
+            .filter(subscriptions::ends_at.le(to))
+            .order(subscriptions::ends_at.asc())
+            .select(SubscriptionEntity::as_select())
+            .load::<SubscriptionEntity>(&mut conn)?;
+
+        Ok(ending)
+    }
+
+    async fn end_subscription_early(
+        &self,
+        subscription_id: Uuid,
+        reason: String,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SubscriptionEntity>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let ended = conn.transaction::<_, anyhow::Error, _>(|tx| {
+            let Some(current) = subscriptions::table
+                .filter(subscriptions::id.eq(subscription_id))
+                .filter(subscriptions::ends_at.gt(now))
+                .select(SubscriptionEntity::as_select())
+                .for_update()
+                .first::<SubscriptionEntity>(tx)
+                .optional()?
+            else {
+                return Ok(None);
+            };
+
+            // A term that has not started yet keeps its period; it is only marked canceled.
+            let ends_at = if current.starts_at < now {
+                now
+            } else {
+                current.ends_at
+            };
+
+            let subscription =
+                update(subscriptions::table.filter(subscriptions::id.eq(subscription_id)))
+                    .set((
+                        subscriptions::status.eq(SubscriptionStatus::Canceled.to_string()),
+                        subscriptions::canceled_at.eq(Some(now)),
+                        subscriptions::ends_at.eq(ends_at),
+                        subscriptions::ended_reason.eq(Some(reason)),
+                    ))
+                    .returning(SubscriptionEntity::as_returning())
+                    .get_result::<SubscriptionEntity>(tx)?;
+
+            Ok(Some(subscription))
+        })?;
+
+        Ok(ended)
+    }
 }
 
 impl SubscriptionPostgres {
@@ -213,6 +398,7 @@ impl SubscriptionPostgres {
 
         let current = query
             .order(subscriptions::starts_at.desc())
+            .select(SubscriptionEntity::as_select())
             .first::<SubscriptionEntity>(&mut conn)
             .optional()?;
 
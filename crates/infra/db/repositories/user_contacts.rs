@@ -0,0 +1,46 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use diesel::{
+    OptionalExtension, QueryableByName, RunQueryDsl,
+    sql_types::{Nullable, Text, Uuid as SqlUuid},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    domain::repositories::user_contacts::UserContactRepository,
+    infra::db::postgres::postgres_connection::PgPoolSquad,
+};
+
+/// Reads contact details from Supabase's `auth.users`, which is not part of the diesel schema.
+pub struct UserContactPostgres {
+    db_pool: Arc<PgPoolSquad>,
+}
+
+impl UserContactPostgres {
+    pub fn new(db_pool: Arc<PgPoolSquad>) -> Self {
+        Self { db_pool }
+    }
+}
+
+#[derive(QueryableByName)]
+struct EmailRow {
+    #[diesel(sql_type = Nullable<Text>)]
+    email: Option<String>,
+}
+
+#[async_trait]
+impl UserContactRepository for UserContactPostgres {
+    async fn find_email(&self, user_id: Uuid) -> Result<Option<String>> {
+        let mut conn = Arc::clone(&self.db_pool).get()?;
+
+        let row = diesel::sql_query("SELECT email::text AS email FROM auth.users WHERE id = $1")
+            .bind::<SqlUuid, _>(user_id)
+            .get_result::<EmailRow>(&mut conn)
+            .optional()?;
+
+        Ok(row
+            .and_then(|row| row.email)
+            .filter(|email| !email.trim().is_empty()))
+    }
+}
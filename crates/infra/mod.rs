@@ -1,3 +1,5 @@
 pub mod db;
+pub mod notifications;
+pub mod recording_engine;
 pub mod storages;
 pub mod web_driver;
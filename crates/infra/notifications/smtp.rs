@@ -0,0 +1,203 @@
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor, message::header::ContentType,
+    transport::smtp::authentication::Credentials,
+};
+
+use crate::domain::{
+    repositories::notification_channel::NotificationChannel,
+    value_objects::{
+        enums::delivery_channels::DeliveryChannel,
+        subscription_notifications::{NotificationRecipient, SubscriptionNotificationPayload},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    /// Plain connection; only meant for local stand-ins such as Mailpit.
+    None,
+    StartTls,
+    Tls,
+}
+
+impl SmtpTls {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "none" => Some(SmtpTls::None),
+            "starttls" => Some(SmtpTls::StartTls),
+            "tls" => Some(SmtpTls::Tls),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    pub tls: SmtpTls,
+}
+
+pub struct SmtpEmailChannel {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: lettre::message::Mailbox,
+}
+
+impl SmtpEmailChannel {
+    pub fn new(config: SmtpConfig) -> Result<Self> {
+        let builder = match config.tls {
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+            SmtpTls::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)
+                .with_context(|| format!("invalid smtp host: {}", config.host))?,
+            SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)
+                .with_context(|| format!("invalid smtp host: {}", config.host))?,
+        };
+        let builder = builder.port(config.port);
+        let builder = match (config.username, config.password) {
+            (Some(username), Some(password)) => {
+                builder.credentials(Credentials::new(username, password))
+            }
+            _ => builder,
+        };
+
+        let from = config
+            .from
+            .parse()
+            .with_context(|| format!("invalid smtp from address: {}", config.from))?;
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for SmtpEmailChannel {
+    fn channel(&self) -> DeliveryChannel {
+        DeliveryChannel::Email
+    }
+
+    async fn send(
+        &self,
+        recipient: &NotificationRecipient,
+        notification: &SubscriptionNotificationPayload,
+    ) -> Result<()> {
+        let email = recipient
+            .email
+            .as_deref()
+            .ok_or_else(|| anyhow!("no email address for user {}", recipient.user_id))?;
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(email
+                .parse()
+                .with_context(|| format!("invalid recipient email: {email}"))?)
+            .subject(notification.subject())
+            .header(ContentType::TEXT_PLAIN)
+            .body(notification.body())
+            .context("failed to build notification email")?;
+
+        self.transport
+            .send(message)
+            .await
+            .context("failed to send notification email")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::enums::notification_kinds::NotificationKind;
+    use chrono::Utc;
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+    };
+    use uuid::Uuid;
+
+    /// Minimal SMTP stand-in: accepts a single message and returns the raw DATA section.
+    async fn spawn_smtp_stand_in() -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            writer.write_all(b"220 stand-in ESMTP\r\n").await.unwrap();
+
+            let mut data = String::new();
+            let mut in_data = false;
+            while let Some(line) = lines.next_line().await.unwrap() {
+                if in_data {
+                    if line == "." {
+                        in_data = false;
+                        writer.write_all(b"250 queued\r\n").await.unwrap();
+                    } else {
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    continue;
+                }
+
+                let command = line.to_ascii_uppercase();
+                if command.starts_with("EHLO") || command.starts_with("HELO") {
+                    writer.write_all(b"250 stand-in\r\n").await.unwrap();
+                } else if command.starts_with("DATA") {
+                    in_data = true;
+                    writer.write_all(b"354 end with .\r\n").await.unwrap();
+                } else if command.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    writer.write_all(b"250 ok\r\n").await.unwrap();
+                }
+            }
+            data
+        });
+
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn sends_notification_email_to_smtp_stand_in() {
+        let (port, server) = spawn_smtp_stand_in().await;
+        let channel = SmtpEmailChannel::new(SmtpConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            username: None,
+            password: None,
+            from: "StreamCatch <noreply@example.com>".to_string(),
+            tls: SmtpTls::None,
+        })
+        .unwrap();
+
+        let notification = SubscriptionNotificationPayload {
+            kind: NotificationKind::RenewalReminder,
+            user_id: Uuid::new_v4(),
+            subscription_id: Uuid::new_v4(),
+            plan_id: Uuid::new_v4(),
+            ends_at: Utc::now(),
+            dedupe_key: "renewal_reminder:test".to_string(),
+            next_plan_name: None,
+        };
+        let recipient = NotificationRecipient {
+            user_id: notification.user_id,
+            email: Some("buyer@example.com".to_string()),
+        };
+
+        channel.send(&recipient, &notification).await.unwrap();
+        drop(channel);
+
+        let data = server.await.unwrap();
+        assert!(data.contains("To: buyer@example.com"));
+        assert!(data.contains("Subject: Your StreamCatch plan ends soon"));
+    }
+}
@@ -0,0 +1,90 @@
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::header::AUTHORIZATION;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::{
+    repositories::notification_channel::NotificationChannel,
+    value_objects::{
+        enums::{delivery_channels::DeliveryChannel, notification_kinds::NotificationKind},
+        subscription_notifications::{NotificationRecipient, SubscriptionNotificationPayload},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct WebhookChannelConfig {
+    pub url: String,
+    pub bearer_token: Option<String>,
+}
+
+/// Posts notifications as JSON to an external endpoint (e.g. a LINE/Discord relay).
+pub struct WebhookNotificationChannel {
+    http: reqwest::Client,
+    config: WebhookChannelConfig,
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookNotificationBody<'a> {
+    kind: NotificationKind,
+    user_id: Uuid,
+    email: Option<&'a str>,
+    subscription_id: Uuid,
+    plan_id: Uuid,
+    ends_at: DateTime<Utc>,
+    subject: String,
+    body: String,
+    dedupe_key: &'a str,
+}
+
+impl WebhookNotificationChannel {
+    pub fn new(config: WebhookChannelConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookNotificationChannel {
+    fn channel(&self) -> DeliveryChannel {
+        DeliveryChannel::Webhook
+    }
+
+    async fn send(
+        &self,
+        recipient: &NotificationRecipient,
+        notification: &SubscriptionNotificationPayload,
+    ) -> Result<()> {
+        let body = WebhookNotificationBody {
+            kind: notification.kind,
+            user_id: recipient.user_id,
+            email: recipient.email.as_deref(),
+            subscription_id: notification.subscription_id,
+            plan_id: notification.plan_id,
+            ends_at: notification.ends_at,
+            subject: notification.subject(),
+            body: notification.body(),
+            dedupe_key: &notification.dedupe_key,
+        };
+
+        let mut request = self.http.post(&self.config.url).json(&body);
+        if let Some(token) = self.config.bearer_token.as_deref() {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+
+        let response = request
+            .send()
+            .await
+            .context("failed to call notification webhook")?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!("notification webhook returned {status}: {text}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use reqwest::{StatusCode, header::AUTHORIZATION};
+use serde::Deserialize;
+use tracing::info;
+use url::form_urlencoded::byte_serialize;
+
+use crate::domain::repositories::recording_engine_control::RecordingEngineControl;
+
+#[derive(Debug, Clone)]
+pub struct RecordingEngineControlConfig {
+    /// Endpoint that stops a channel; `{platform}` and `{channel}` are substituted. Required,
+    /// since plan limits are only enforced if the engine can be told to stop.
+    pub stop_url: String,
+    /// Endpoint answering `{"recording": bool}` for a channel; `{platform}` and `{channel}`
+    /// are substituted. A 404 means the channel is not being recorded.
+    pub status_url: Option<String>,
+    /// Sent verbatim as the `Authorization` header, e.g. `Basic ...`.
+    pub authorization: Option<String>,
+}
+
+/// Controls the recording engine over its HTTP API.
+pub struct HttpRecordingEngineControl {
+    http: reqwest::Client,
+    config: RecordingEngineControlConfig,
+}
+
+impl HttpRecordingEngineControl {
+    pub fn new(config: RecordingEngineControlConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config,
+        }
+    }
+}
+
+#[async_trait]
+impl RecordingEngineControl for HttpRecordingEngineControl {
+    async fn stop_recording(&self, platform: &str, channel: &str) -> Result<()> {
+        let url = channel_url(&self.config.stop_url, platform, channel);
+        let response = self
+            .authorized(self.http.post(&url))
+            .send()
+            .await
+            .context("failed to call recording engine stop endpoint")?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!("recording engine stop endpoint returned {status}: {text}");
+        }
+
+        info!(platform, channel, "recording engine stop requested");
+        Ok(())
+    }
+
+    async fn is_recording(&self, platform: &str, channel: &str) -> Result<Option<bool>> {
+        let Some(template) = self.config.status_url.as_deref() else {
+            return Ok(None);
+        };
+
+        let url = channel_url(template, platform, channel);
+        let response = self
+            .authorized(self.http.get(&url))
+            .send()
+            .await
+            .context("failed to call recording engine status endpoint")?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Ok(Some(false));
+        }
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!("recording engine status endpoint returned {status}: {text}");
+        }
+
+        let body: ChannelStatus = response
+            .json()
+            .await
+            .context("failed to parse recording engine status response")?;
+        Ok(Some(body.recording))
+    }
+}
+
+impl HttpRecordingEngineControl {
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.config.authorization.as_deref() {
+            Some(authorization) => request.header(AUTHORIZATION, authorization),
+            None => request,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ChannelStatus {
+    recording: bool,
+}
+
+fn channel_url(template: &str, platform: &str, channel: &str) -> String {
+    let encoded_platform: String = byte_serialize(platform.as_bytes()).collect();
+    let encoded_channel: String = byte_serialize(channel.as_bytes()).collect();
+    template
+        .replace("{platform}", &encoded_platform)
+        .replace("{channel}", &encoded_channel)
+}
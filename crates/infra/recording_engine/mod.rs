@@ -0,0 +1,2 @@
+pub mod http_control;
+pub mod webhook_signature;
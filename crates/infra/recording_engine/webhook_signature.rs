@@ -0,0 +1,113 @@
+use anyhow::{Result, anyhow, bail};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Header carrying `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`.
+pub const SIGNATURE_HEADER: &str = "x-recording-engine-signature";
+
+/// Builds a [`SIGNATURE_HEADER`] value for `payload`, signed at `timestamp`.
+pub fn sign_webhook_payload(secret: &str, timestamp: i64, payload: &[u8]) -> Result<String> {
+    let mac = signed_payload_mac(secret, timestamp, payload)?;
+    Ok(format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
+
+/// Checks a [`SIGNATURE_HEADER`] value against `payload`, rejecting signatures whose
+/// timestamp is more than `tolerance_secs` away from `now` so captured requests cannot be
+/// replayed later.
+pub fn verify_webhook_signature(
+    secret: &str,
+    signature_header: &str,
+    payload: &[u8],
+    now: i64,
+    tolerance_secs: u64,
+) -> Result<()> {
+    let mut timestamp: Option<i64> = None;
+    let mut signatures = Vec::new();
+
+    for part in signature_header.split(',') {
+        let part = part.trim();
+        if let Some(rest) = part.strip_prefix("t=") {
+            timestamp = Some(
+                rest.parse()
+                    .map_err(|_| anyhow!("invalid signature timestamp"))?,
+            );
+        } else if let Some(rest) = part.strip_prefix("v1=") {
+            signatures.push(rest);
+        }
+    }
+
+    let timestamp = timestamp.ok_or_else(|| anyhow!("missing timestamp in signature"))?;
+    if signatures.is_empty() {
+        bail!("missing v1 in signature");
+    }
+    if now.abs_diff(timestamp) > tolerance_secs {
+        bail!("signature timestamp outside tolerance window");
+    }
+
+    for signature in signatures {
+        let Ok(provided) = hex::decode(signature) else {
+            continue;
+        };
+        if signed_payload_mac(secret, timestamp, payload)?
+            .verify_slice(&provided)
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+
+    bail!("invalid webhook signature")
+}
+
+fn signed_payload_mac(secret: &str, timestamp: i64, payload: &[u8]) -> Result<HmacSha256> {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload);
+    Ok(mac)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &[u8] = br#"{"id":"1","type":"live_start"}"#;
+
+    #[test]
+    fn signed_payload_verifies_within_tolerance() {
+        let header = sign_webhook_payload(SECRET, 1_000, BODY).unwrap();
+
+        assert!(verify_webhook_signature(SECRET, &header, BODY, 1_200, 300).is_ok());
+    }
+
+    #[test]
+    fn tampered_or_foreign_signatures_are_rejected() {
+        let header = sign_webhook_payload(SECRET, 1_000, BODY).unwrap();
+
+        assert!(verify_webhook_signature(SECRET, &header, b"{}", 1_000, 300).is_err());
+        assert!(verify_webhook_signature("other", &header, BODY, 1_000, 300).is_err());
+        assert!(verify_webhook_signature(SECRET, "v1=abcd", BODY, 1_000, 300).is_err());
+    }
+
+    #[test]
+    fn replayed_signature_outside_window_is_rejected() {
+        let header = sign_webhook_payload(SECRET, 1_000, BODY).unwrap();
+
+        assert!(verify_webhook_signature(SECRET, &header, BODY, 1_301, 300).is_err());
+    }
+
+    #[test]
+    fn extreme_timestamps_are_rejected_without_overflow() {
+        let header = sign_webhook_payload(SECRET, i64::MIN, BODY).unwrap();
+
+        assert!(verify_webhook_signature(SECRET, &header, BODY, i64::MAX, 300).is_err());
+        assert!(verify_webhook_signature(SECRET, &header, BODY, 1_000, u64::MAX).is_ok());
+    }
+}
@@ -1,4 +1,6 @@
 pub mod b2;
 pub mod s3;
 pub mod supabase_storage;
+#[cfg(test)]
+mod test_support;
 pub mod wasabi;
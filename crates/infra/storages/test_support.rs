@@ -0,0 +1,41 @@
+//! Fixtures shared by the manual storage upload checks.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::domain::entities::recordings::RecordingEntity;
+
+pub(super) fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("workspace root should exist")
+        .to_path_buf()
+}
+
+pub(super) fn dummy_recording() -> RecordingEntity {
+    let now = Utc::now();
+    RecordingEntity {
+        id: Uuid::new_v4(),
+        live_account_id: Uuid::new_v4(),
+        recording_key: None,
+        title: Some("manual-upload-check".to_string()),
+        started_at: now,
+        ended_at: None,
+        duration_sec: Some(5),
+        size_bytes: None,
+        storage_path: None,
+        storage_temp_path: None,
+        status: "uploading".to_string(),
+        poster_storage_path: None,
+        created_at: now,
+        updated_at: now,
+        width: None,
+        height: None,
+        video_codec: None,
+        audio_codec: None,
+        bitrate_bps: None,
+        fps: None,
+    }
+}
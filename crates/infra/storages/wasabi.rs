@@ -1106,20 +1106,11 @@ fn map_delete_object_error(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::{
-        entities::recordings::RecordingEntity, repositories::storage::StorageClient,
+    use crate::{
+        domain::repositories::storage::StorageClient,
+        infra::storages::test_support::{dummy_recording, workspace_root},
     };
     use anyhow::{Context, Result};
-    use chrono::Utc;
-    use std::path::{Path, PathBuf};
-    use uuid::Uuid;
-
-    fn workspace_root() -> PathBuf {
-        Path::new(env!("CARGO_MANIFEST_DIR"))
-            .parent()
-            .expect("workspace root should exist")
-            .to_path_buf()
-    }
 
     fn load_wasabi_config_from_env() -> WasabiStorageConfig {
         dotenvy::dotenv().ok();
@@ -1176,26 +1167,6 @@ mod tests {
         }
     }
 
-    fn dummy_recording() -> RecordingEntity {
-        let now = Utc::now();
-        RecordingEntity {
-            id: Uuid::new_v4(),
-            live_account_id: Uuid::new_v4(),
-            recording_key: None,
-            title: Some("manual-upload-check".to_string()),
-            started_at: now,
-            ended_at: None,
-            duration_sec: Some(5),
-            size_bytes: None,
-            storage_path: None,
-            storage_temp_path: None,
-            status: "uploading".to_string(),
-            poster_storage_path: None,
-            created_at: now,
-            updated_at: now,
-        }
-    }
-
     // Manual check: place an mp4 named `test-recording.mp4` in the repo root,
     // export the VIDEO_STORAGE_S3_* credentials, then run:
     // cargo test -p crates wasabi::tests::upload_mp4_to_wasabi -- --ignored --nocapture
@@ -8,11 +8,16 @@ use sha2::Sha256;
 use tracing::error;
 use uuid::Uuid;
 
+use crate::domain::value_objects::subscriptions::CheckoutPromotion;
+
 type HmacSha256 = Hmac<Sha256>;
 
+pub const DEFAULT_STRIPE_API_BASE: &str = "https://api.stripe.com";
+
 /// Minimal Stripe client built on reqwest.
 pub struct StripeClient {
     http: reqwest::Client,
+    api_base: String,
     secret_key: String,
     webhook_secret: String,
     success_url: String,
@@ -57,6 +62,29 @@ pub struct StripeCheckoutSession {
     pub amount_total: Option<i64>,
     pub currency: Option<String>,
     pub metadata: Option<HashMap<String, String>>,
+    pub total_details: Option<StripeCheckoutTotalDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StripeCheckoutTotalDetails {
+    pub amount_discount: Option<i64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StripePromotionCode {
+    pub id: String,
+    pub code: String,
+    pub active: bool,
+    pub coupon: StripeCoupon,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StripeCoupon {
+    pub id: String,
+    pub percent_off: Option<f64>,
+    pub amount_off: Option<i64>,
+    pub currency: Option<String>,
+    pub valid: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -76,6 +104,7 @@ struct StripeErrorDetails {
 
 #[derive(Debug, Deserialize)]
 pub struct StripeSubscription {
+    pub status: Option<String>,
     pub current_period_start: Option<i64>,
     pub current_period_end: Option<i64>,
     pub billing_cycle_anchor: Option<i64>,
@@ -128,6 +157,7 @@ impl StripeClient {
     ) -> Self {
         Self {
             http: reqwest::Client::new(),
+            api_base: DEFAULT_STRIPE_API_BASE.to_string(),
             secret_key,
             webhook_secret,
             success_url,
@@ -135,6 +165,16 @@ impl StripeClient {
         }
     }
 
+    /// Points the client at another Stripe-compatible API, e.g. a local stand-in for tests.
+    pub fn with_api_base(mut self, api_base: impl Into<String>) -> Self {
+        self.api_base = api_base.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.api_base, path)
+    }
+
     async fn ensure_success(
         resp: reqwest::Response,
         context: &str,
@@ -203,7 +243,7 @@ impl StripeClient {
 
         let resp = self
             .http
-            .post("https://api.stripe.com/v1/customers")
+            .post(self.url("/v1/customers"))
             .header(AUTHORIZATION, format!("Bearer {}", self.secret_key))
             .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
             .form(&body)
@@ -224,16 +264,18 @@ impl StripeClient {
     pub async fn create_checkout_session(
         &self,
         price_id: &str,
+        quantity: i32,
         mode: &str,
         customer_id: Option<String>,
         metadata: HashMap<String, String>,
+        promotion: CheckoutPromotion,
     ) -> Result<String> {
         // Stripe Checkout docs:
         // https://stripe.com/docs/payments/checkout
         let mut body: Vec<(String, String)> = vec![
             ("mode".to_string(), mode.to_string()),
             ("line_items[0][price]".to_string(), price_id.to_string()),
-            ("line_items[0][quantity]".to_string(), "1".to_string()),
+            ("line_items[0][quantity]".to_string(), quantity.to_string()),
             ("success_url".to_string(), self.success_url.clone()),
             ("cancel_url".to_string(), self.cancel_url.clone()),
         ];
@@ -246,13 +288,27 @@ impl StripeClient {
             body.push(("customer".to_string(), customer));
         }
 
+        // Stripe rejects sessions that set both `allow_promotion_codes` and `discounts`.
+        match promotion {
+            CheckoutPromotion::None => {}
+            CheckoutPromotion::AllowPromotionCodes => {
+                body.push(("allow_promotion_codes".to_string(), "true".to_string()));
+            }
+            CheckoutPromotion::Code(promotion_code_id) => {
+                body.push((
+                    "discounts[0][promotion_code]".to_string(),
+                    promotion_code_id,
+                ));
+            }
+        }
+
         for (key, value) in metadata {
             body.push((format!("metadata[{}]", key), value));
         }
 
         let resp = self
             .http
-            .post("https://api.stripe.com/v1/checkout/sessions")
+            .post(self.url("/v1/checkout/sessions"))
             .header(AUTHORIZATION, format!("Bearer {}", self.secret_key))
             .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
             .form(&body)
@@ -271,16 +327,34 @@ impl StripeClient {
             .ok_or_else(|| anyhow::anyhow!("Stripe Checkout session URL is missing"))
     }
 
+    /// Looks up an active promotion code by its customer-facing code.
+    pub async fn find_promotion_code(&self, code: &str) -> Result<Option<StripePromotionCode>> {
+        // https://stripe.com/docs/api/promotion_codes/list
+        let resp = self
+            .http
+            .get(self.url("/v1/promotion_codes"))
+            .header(AUTHORIZATION, format!("Bearer {}", self.secret_key))
+            .query(&[("code", code), ("active", "true"), ("limit", "1")])
+            .send()
+            .await?;
+        let resp = Self::ensure_success(resp, "list promotion codes").await?;
+
+        #[derive(Deserialize)]
+        struct PromotionCodeList {
+            data: Vec<StripePromotionCode>,
+        }
+
+        let parsed: PromotionCodeList = resp.json().await?;
+        Ok(parsed.data.into_iter().next())
+    }
+
     /// Marks a Stripe subscription to cancel at period end.
     pub async fn cancel_subscription(&self, provider_subscription_id: &str) -> Result<()> {
         // https://stripe.com/docs/api/subscriptions/cancel#cancel_subscription-at_period_end
         let body = [("cancel_at_period_end", "true".to_string())];
         let resp = self
             .http
-            .post(format!(
-                "https://api.stripe.com/v1/subscriptions/{}",
-                provider_subscription_id
-            ))
+            .post(self.url(&format!("/v1/subscriptions/{provider_subscription_id}")))
             .header(AUTHORIZATION, format!("Bearer {}", self.secret_key))
             .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
             .form(&body)
@@ -291,6 +365,20 @@ impl StripeClient {
         Ok(())
     }
 
+    /// Cancels the subscription right away instead of at period end.
+    pub async fn cancel_subscription_now(&self, provider_subscription_id: &str) -> Result<()> {
+        // https://stripe.com/docs/api/subscriptions/cancel
+        let resp = self
+            .http
+            .delete(self.url(&format!("/v1/subscriptions/{provider_subscription_id}")))
+            .header(AUTHORIZATION, format!("Bearer {}", self.secret_key))
+            .send()
+            .await?;
+        Self::ensure_success(resp, "cancel subscription now").await?;
+
+        Ok(())
+    }
+
     /// Verifies the webhook signature. https://stripe.com/docs/webhooks/signatures
     pub fn verify_webhook_signature(
         &self,
@@ -313,10 +401,7 @@ impl StripeClient {
         let signature =
             signature.ok_or_else(|| anyhow::anyhow!("missing v1 in stripe-signature"))?;
 
-        let signed_payload = format!("{}.{}", timestamp, String::from_utf8_lossy(payload));
-        let mut mac = HmacSha256::new_from_slice(self.webhook_secret.as_bytes())?;
-        mac.update(signed_payload.as_bytes());
-        let expected = mac.finalize().into_bytes();
+        let expected = webhook_signature_v1(&self.webhook_secret, &timestamp, payload)?;
         let provided = hex::decode(signature)?;
 
         if expected[..] != provided[..] {
@@ -335,10 +420,7 @@ impl StripeClient {
         // https://stripe.com/docs/api/subscriptions/retrieve
         let resp = self
             .http
-            .get(format!(
-                "https://api.stripe.com/v1/subscriptions/{}",
-                subscription_id
-            ))
+            .get(self.url(&format!("/v1/subscriptions/{subscription_id}")))
             .header(AUTHORIZATION, format!("Bearer {}", self.secret_key))
             .send()
             .await?;
@@ -348,3 +430,21 @@ impl StripeClient {
         Ok(subscription)
     }
 }
+
+/// Builds a `Stripe-Signature` header value for `payload`, using the scheme checked by
+/// `StripeClient::verify_webhook_signature`.
+pub fn sign_webhook_payload(
+    webhook_secret: &str,
+    timestamp: i64,
+    payload: &[u8],
+) -> Result<String> {
+    let signature = webhook_signature_v1(webhook_secret, &timestamp.to_string(), payload)?;
+    Ok(format!("t={},v1={}", timestamp, hex::encode(signature)))
+}
+
+fn webhook_signature_v1(webhook_secret: &str, timestamp: &str, payload: &[u8]) -> Result<Vec<u8>> {
+    let signed_payload = format!("{}.{}", timestamp, String::from_utf8_lossy(payload));
+    let mut mac = HmacSha256::new_from_slice(webhook_secret.as_bytes())?;
+    mac.update(signed_payload.as_bytes());
+    Ok(mac.finalize().into_bytes().to_vec())
+}
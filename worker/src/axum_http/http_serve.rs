@@ -39,7 +39,11 @@ pub async fn start(
         .fallback(default_routers::not_found)
         .nest(
             "/internal/recording-engine",
-            routers::recording_engine_webhook::routes(usecase),
+            routers::recording_engine_webhook::routes(Arc::clone(&config), Arc::clone(&usecase)),
+        )
+        .nest(
+            "/internal/v1/recording-engine",
+            routers::recording_engine_errors::routes(Arc::clone(&config), usecase),
         )
         .nest(
             "/internal/v1/cleanup",
@@ -64,9 +68,13 @@ pub async fn start(
     let listener = TcpListener::bind(addr).await?;
     info!("Worker HTTP server running on {}", addr);
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    // Peer addresses let rejected webhook callers be logged.
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
     Ok(())
 }
 
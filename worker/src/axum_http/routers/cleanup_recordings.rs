@@ -8,6 +8,7 @@ use axum::{
     routing::post,
 };
 use serde::{Deserialize, Serialize};
+use subtle::ConstantTimeEq;
 use tracing::error;
 use uuid::Uuid;
 
@@ -107,7 +108,10 @@ pub async fn cleanup_recordings(
     }
 }
 
-fn authorize_bearer(headers: &HeaderMap, expected_token: &str) -> Result<(), StatusCode> {
+pub(crate) fn authorize_bearer(
+    headers: &HeaderMap,
+    expected_token: &str,
+) -> Result<(), StatusCode> {
     let auth = headers
         .get(AUTHORIZATION)
         .and_then(|v| v.to_str().ok())
@@ -117,7 +121,7 @@ fn authorize_bearer(headers: &HeaderMap, expected_token: &str) -> Result<(), Sta
         .strip_prefix("Bearer ")
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
-    if token == expected_token {
+    if bool::from(token.as_bytes().ct_eq(expected_token.as_bytes())) {
         Ok(())
     } else {
         Err(StatusCode::UNAUTHORIZED)
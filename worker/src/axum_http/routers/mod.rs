@@ -1,2 +1,3 @@
 pub mod cleanup_recordings;
+pub mod recording_engine_errors;
 pub mod recording_engine_webhook;
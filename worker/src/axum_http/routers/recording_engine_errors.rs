@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use crates::domain::entities::recording_engine_errors::RecordingEngineErrorEntity;
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    axum_http::routers::cleanup_recordings::authorize_bearer, config::config_model::DotEnvyConfig,
+    usecases::recording_engine_webhook::RecordingEngineWebhookUseCase,
+};
+
+// Run example
+//   curl "http://localhost:$SERVER_PORT_WORKER/internal/v1/recording-engine/errors?limit=20" \
+//     -H "Authorization: Bearer $INTERNAL_API_TOKEN"
+
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Clone)]
+pub struct RecordingEngineErrorsRouteState {
+    config: Arc<DotEnvyConfig>,
+    usecase: Arc<RecordingEngineWebhookUseCase>,
+}
+
+pub fn routes(config: Arc<DotEnvyConfig>, usecase: Arc<RecordingEngineWebhookUseCase>) -> Router {
+    Router::new()
+        .route("/errors", get(list_failing_channels))
+        .with_state(RecordingEngineErrorsRouteState { config, usecase })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FailingChannelsQuery {
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailingChannelResponse {
+    pub platform: String,
+    pub channel: String,
+    pub live_account_id: Option<Uuid>,
+    pub recording_id: Option<Uuid>,
+    pub last_error: String,
+    pub error_count: i32,
+    pub consecutive_errors: i32,
+    pub first_error_at: DateTime<Utc>,
+    pub last_error_at: DateTime<Utc>,
+}
+
+impl From<RecordingEngineErrorEntity> for FailingChannelResponse {
+    fn from(entity: RecordingEngineErrorEntity) -> Self {
+        Self {
+            platform: entity.platform,
+            channel: entity.channel,
+            live_account_id: entity.live_account_id,
+            recording_id: entity.recording_id,
+            last_error: entity.last_error,
+            error_count: entity.error_count,
+            consecutive_errors: entity.consecutive_errors,
+            first_error_at: entity.first_error_at,
+            last_error_at: entity.last_error_at,
+        }
+    }
+}
+
+/// Noisiest failing channels: those in the longest error streak since their last recording.
+pub async fn list_failing_channels(
+    State(state): State<RecordingEngineErrorsRouteState>,
+    headers: HeaderMap,
+    Query(query): Query<FailingChannelsQuery>,
+) -> Response {
+    let expected_token = match state.config.internal.token.as_deref() {
+        Some(token) => token,
+        None => {
+            return (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "internal token is not configured",
+            )
+                .into_response();
+        }
+    };
+
+    if let Err(status) = authorize_bearer(&headers, expected_token) {
+        return (status, "unauthorized").into_response();
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    match state.usecase.list_failing_channels(limit).await {
+        Ok(channels) => Json(
+            channels
+                .into_iter()
+                .map(FailingChannelResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(err) => {
+            error!(error = ?err, "recording_engine_errors: failed to list failing channels");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to list failing channels",
+            )
+                .into_response()
+        }
+    }
+}
@@ -1,33 +1,157 @@
-use std::sync::Arc;
+use std::{net::SocketAddr, sync::Arc};
 
 use axum::{
     Json, Router,
-    extract::State,
-    http::StatusCode,
+    body::{Body, to_bytes},
+    extract::{ConnectInfo, DefaultBodyLimit, Request, State},
+    http::{StatusCode, header::CONTENT_LENGTH, request::Parts},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
     routing::post,
 };
-use crates::domain;
+use chrono::Utc;
+use crates::{domain, infra::recording_engine::webhook_signature};
 use domain::value_objects::recording_engine_webhook::{
-    RecordingEngineErrorWebhook, RecordingEngineFileFinishWebhook,
-    RecordingEngineLiveStartWebhook,
-    RecordingEngineTransmuxFinishWebhook,
+    RecordingEngineErrorWebhook, RecordingEngineFileFinishWebhook, RecordingEngineLiveEndWebhook,
+    RecordingEngineLiveStartWebhook, RecordingEngineTransmuxFinishWebhook,
 };
+use http_body_util::LengthLimitError;
 use tracing::{error, info, warn};
 use url::Url;
 use uuid::Uuid;
 
-use crate::usecases::recording_engine_webhook::RecordingEngineWebhookUseCase;
+use crate::{
+    axum_http::routers::cleanup_recordings::authorize_bearer,
+    config::config_model::{DotEnvyConfig, RecordingEngineWebhookAuth},
+    usecases::recording_engine_webhook::RecordingEngineWebhookUseCase,
+};
+
+// Run example (signed)
+//   BODY='{"id":"...","ts":"...","type":"live_start","data":{...}}'
+//   TS=$(date +%s)
+//   SIG=$(printf '%s.%s' "$TS" "$BODY" | openssl dgst -sha256 -hmac "$RECORDING_ENGINE_WEBHOOK_SECRET" -hex | sed 's/^.* //')
+//   curl -X POST "http://localhost:$SERVER_PORT_WORKER/internal/recording-engine/live-start" \
+//     -H "X-Recording-Engine-Signature: t=$TS,v1=$SIG" \
+//     -H "Content-Type: application/json" \
+//     -d "$BODY"
+
+/// Largest webhook body accepted; engine payloads are a few hundred bytes.
+const MAX_WEBHOOK_BYTES: usize = 64 * 1024;
 
-pub fn routes(usecase: Arc<RecordingEngineWebhookUseCase>) -> Router {
+pub fn routes(config: Arc<DotEnvyConfig>, usecase: Arc<RecordingEngineWebhookUseCase>) -> Router {
+    let auth = Arc::new(config.recording_engine_webhook_auth.clone());
     Router::new()
         .route("/live-start", post(live_start))
+        .route("/live-end", post(live_end))
+        .route("/video-file-finish", post(video_file_finish))
         .route("/video-transmux-finish", post(video_transmux_finish))
         .route("/video-uploading", post(video_uploading))
         .route("/error", post(error_webhook))
+        .route_layer(middleware::from_fn_with_state(auth, authenticate))
+        .layer(DefaultBodyLimit::max(MAX_WEBHOOK_BYTES))
         .with_state(usecase)
 }
 
+/// Lets a webhook through only with a valid signature or bearer token. Bearer requests are
+/// checked before the body is touched; signed ones have their body buffered, up to
+/// [`MAX_WEBHOOK_BYTES`], so the signature can cover it, then handed on unchanged.
+async fn authenticate(
+    State(auth): State<Arc<RecordingEngineWebhookAuth>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if auth.signing_secret.is_none() && auth.bearer_token.is_none() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            "recording engine webhook authentication is not configured",
+        )
+            .into_response();
+    }
+
+    let (parts, body) = request.into_parts();
+    let signature = auth.signing_secret.as_deref().and_then(|secret| {
+        parts
+            .headers
+            .get(webhook_signature::SIGNATURE_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(|signature| (secret, signature))
+    });
+    let Some((secret, signature)) = signature else {
+        let verified = match auth.bearer_token.as_deref() {
+            Some(token) => authorize_bearer(&parts.headers, token)
+                .map_err(|_| "missing or invalid bearer token".to_string()),
+            None => Err("missing signature".to_string()),
+        };
+        if let Err(reason) = verified {
+            return reject(&parts, reason);
+        }
+        return next.run(Request::from_parts(parts, body)).await;
+    };
+
+    let declared_len = parts
+        .headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok());
+    if declared_len.is_some_and(|len| len > MAX_WEBHOOK_BYTES) {
+        return payload_too_large();
+    }
+    let payload = match to_bytes(body, MAX_WEBHOOK_BYTES).await {
+        Ok(payload) => payload,
+        Err(err) => {
+            let err = err.into_inner();
+            if std::iter::successors(Some(&*err as &(dyn std::error::Error + 'static)), |err| {
+                err.source()
+            })
+            .any(|err| err.is::<LengthLimitError>())
+            {
+                return payload_too_large();
+            }
+            warn!(
+                error = %err,
+                "recording_engine_webhook: failed to read request body"
+            );
+            return (StatusCode::BAD_REQUEST, "invalid request body").into_response();
+        }
+    };
+
+    if let Err(err) = webhook_signature::verify_webhook_signature(
+        secret,
+        signature,
+        &payload,
+        Utc::now().timestamp(),
+        auth.signature_tolerance_secs,
+    ) {
+        return reject(&parts, err.to_string());
+    }
+
+    next.run(Request::from_parts(parts, Body::from(payload)))
+        .await
+}
+
+fn reject(parts: &Parts, reason: String) -> Response {
+    let peer = parts
+        .extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.to_string());
+    let forwarded_for = parts
+        .headers
+        .get("x-forwarded-for")
+        .and_then(|v| v.to_str().ok());
+    warn!(
+        path = %parts.uri.path(),
+        peer = peer.as_deref().unwrap_or("unknown"),
+        forwarded_for = forwarded_for.unwrap_or("-"),
+        reason,
+        "recording_engine_webhook: rejected unauthenticated request"
+    );
+    (StatusCode::UNAUTHORIZED, "unauthorized").into_response()
+}
+
+fn payload_too_large() -> Response {
+    (StatusCode::PAYLOAD_TOO_LARGE, "request body too large").into_response()
+}
+
 pub async fn live_start(
     State(usecase): State<Arc<RecordingEngineWebhookUseCase>>,
     Json(payload): Json<RecordingEngineLiveStartWebhook>,
@@ -42,6 +166,34 @@ pub async fn live_start(
     }
 }
 
+pub async fn live_end(
+    State(usecase): State<Arc<RecordingEngineWebhookUseCase>>,
+    Json(payload): Json<RecordingEngineLiveEndWebhook>,
+) -> Response {
+    info!(
+        payload = ?payload,
+        "recording_engine_webhook: live_end received"
+    );
+    match usecase.handle_live_end(payload).await {
+        Ok(recording_id) => success_response(recording_id),
+        Err(err) => map_error("live_end", err),
+    }
+}
+
+pub async fn video_file_finish(
+    State(usecase): State<Arc<RecordingEngineWebhookUseCase>>,
+    Json(payload): Json<RecordingEngineFileFinishWebhook>,
+) -> Response {
+    info!(
+        payload = ?payload,
+        "recording_engine_webhook: video_file_finish received"
+    );
+    match usecase.handle_file_finish(payload).await {
+        Ok(recording_id) => success_response(recording_id),
+        Err(err) => map_error("video_file_finish", err),
+    }
+}
+
 pub async fn video_transmux_finish(
     State(usecase): State<Arc<RecordingEngineWebhookUseCase>>,
     Json(payload): Json<RecordingEngineTransmuxFinishWebhook>,
@@ -64,10 +216,7 @@ pub async fn video_uploading(
         payload = ?payload,
         "recording_engine_webhook: video_uploading received"
     );
-    match usecase
-        .handle_uploading_status(payload.data.platform.clone(), payload.data.channel.clone())
-        .await
-    {
+    match usecase.handle_uploading_status(payload).await {
         Ok(recording_id) => success_response(recording_id),
         Err(err) => map_error("video_uploading", err),
     }
@@ -121,6 +270,8 @@ fn map_error(label: &str, err: anyhow::Error) -> Response {
     let message = err.to_string();
     let status = if message.contains("required") || message.contains("Unsupported platform") {
         StatusCode::BAD_REQUEST
+    } else if message.contains("already being processed") {
+        StatusCode::CONFLICT
     } else if message.contains("not found") {
         StatusCode::INTERNAL_SERVER_ERROR
     } else {
@@ -152,3 +303,105 @@ fn recording_engine_alert_content(payload: &RecordingEngineErrorWebhook) -> Stri
         error_message
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::AUTHORIZATION;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "whsec_test";
+    const BODY: &str = r#"{"id":"evt_1","type":"live_start"}"#;
+
+    fn app(signing_secret: Option<&str>, bearer_token: Option<&str>) -> Router {
+        let auth = Arc::new(RecordingEngineWebhookAuth {
+            signing_secret: signing_secret.map(str::to_string),
+            bearer_token: bearer_token.map(str::to_string),
+            signature_tolerance_secs: 300,
+        });
+        Router::new()
+            .route("/live-start", post(|body: String| async move { body }))
+            .route_layer(middleware::from_fn_with_state(auth, authenticate))
+    }
+
+    fn request(header: Option<(&str, String)>, body: impl Into<Body>) -> Request {
+        let mut builder = Request::post("/live-start");
+        if let Some((name, value)) = header {
+            builder = builder.header(name, value);
+        }
+        builder.body(body.into()).unwrap()
+    }
+
+    fn signature(payload: &[u8]) -> Option<(&'static str, String)> {
+        let signature =
+            webhook_signature::sign_webhook_payload(SECRET, Utc::now().timestamp(), payload)
+                .unwrap();
+        Some((webhook_signature::SIGNATURE_HEADER, signature))
+    }
+
+    async fn status(app: Router, request: Request) -> StatusCode {
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn valid_signature_passes_the_body_through() {
+        let response = app(Some(SECRET), None)
+            .oneshot(request(signature(BODY.as_bytes()), BODY))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, BODY.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn missing_or_bad_signature_is_unauthorized() {
+        let tampered = signature(b"{}");
+        let bad_hex = Some((
+            webhook_signature::SIGNATURE_HEADER,
+            format!("t={},v1=00", Utc::now().timestamp()),
+        ));
+
+        for header in [None, tampered, bad_hex] {
+            assert_eq!(
+                status(app(Some(SECRET), None), request(header, BODY)).await,
+                StatusCode::UNAUTHORIZED
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn bearer_token_is_checked() {
+        let valid = Some((AUTHORIZATION.as_str(), "Bearer engine-token".to_string()));
+        let invalid = Some((AUTHORIZATION.as_str(), "Bearer engine-tokem".to_string()));
+
+        assert_eq!(
+            status(app(None, Some("engine-token")), request(valid, BODY)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(app(None, Some("engine-token")), request(invalid, BODY)).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn unconfigured_auth_is_unavailable() {
+        assert_eq!(
+            status(app(None, None), request(signature(BODY.as_bytes()), BODY)).await,
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_signed_body_is_rejected() {
+        let body = vec![b'a'; MAX_WEBHOOK_BYTES + 1];
+        let header = signature(&body);
+
+        assert_eq!(
+            status(app(Some(SECRET), None), request(header, body)).await,
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+    }
+}
@@ -1,11 +1,26 @@
 use crate::config::stage::Stage;
 
 use super::config_model::{
-    Cleanup, Database, DotEnvyConfig, RecordingEnginePaths, RecordingUploadConfig, Supabase,
-    WorkerServer,
+    Cleanup, Database, DotEnvyConfig, Internal, Notifications, RecordingDiskConfig,
+    RecordingEngineErrorPolicy, RecordingEnginePaths, RecordingEngineWebhookAuth,
+    RecordingPathMapping, RecordingUploadConfig, StaleRecordingWatchdogConfig, Supabase,
+    TransmuxFallbackConfig, WorkerServer,
 };
 use anyhow::{Context, Result};
-use crates::infra::storages::wasabi::{WasabiMultipartConfig, WasabiStorageConfig};
+use crates::{
+    domain::value_objects::enums::quota_enforcement_policies::QuotaEnforcementPolicy,
+    infra::{
+        notifications::{
+            smtp::{SmtpConfig, SmtpTls},
+            webhook::WebhookChannelConfig,
+        },
+        recording_engine::http_control::RecordingEngineControlConfig,
+        storages::wasabi::{WasabiMultipartConfig, WasabiStorageConfig},
+    },
+};
+use std::path::PathBuf;
+use url::Url;
+use uuid::Uuid;
 
 pub fn load() -> Result<DotEnvyConfig> {
     dotenvy::dotenv().ok();
@@ -121,16 +136,145 @@ pub fn load() -> Result<DotEnvyConfig> {
             .unwrap_or(60),
     };
 
-    let container_prefix =
-        std::env::var("RECORDING_ENGINE_CONTAINER_PREFIX").unwrap_or_else(|_| "/app/".to_string());
-    let container_prefix = container_prefix.trim().to_string();
-    let container_prefix = if container_prefix.ends_with('/') {
-        container_prefix
-    } else {
-        format!("{}/", container_prefix)
+    let internal = Internal {
+        token: std::env::var("INTERNAL_API_TOKEN").ok().and_then(|v| {
+            let trimmed = v.trim().to_string();
+            (!trimmed.is_empty()).then_some(trimmed)
+        }),
+    };
+
+    let recording_engine_paths = RecordingEnginePaths {
+        mappings: match non_empty_env("RECORDING_ENGINE_PATH_MAPPINGS") {
+            Some(mappings) => parse_path_mappings(&mappings).context(
+                "RECORDING_ENGINE_PATH_MAPPINGS must look like /app/=/rec,/app2/=/mnt/rec2",
+            )?,
+            None => vec![path_mapping(
+                &std::env::var("RECORDING_ENGINE_CONTAINER_PREFIX")
+                    .unwrap_or_else(|_| "/app/".to_string()),
+                &std::env::var("RECORDING_ENGINE_HOST_ROOT").unwrap_or_else(|_| "/rec".to_string()),
+            )],
+        },
+    };
+
+    let recording_engine_webhook_auth = RecordingEngineWebhookAuth {
+        signing_secret: non_empty_env("RECORDING_ENGINE_WEBHOOK_SECRET"),
+        bearer_token: non_empty_env("RECORDING_ENGINE_WEBHOOK_TOKEN"),
+        signature_tolerance_secs: std::env::var("RECORDING_ENGINE_WEBHOOK_TOLERANCE_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(300),
     };
 
-    let recording_engine_paths = RecordingEnginePaths { container_prefix };
+    let transmux_fallback = TransmuxFallbackConfig {
+        timeout_secs: std::env::var("TRANSMUX_FALLBACK_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse()
+            .context("TRANSMUX_FALLBACK_TIMEOUT_SECONDS is invalid")?,
+        poll_interval_secs: std::env::var("TRANSMUX_FALLBACK_POLL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(60),
+    };
+
+    let stale_recording_watchdog = StaleRecordingWatchdogConfig {
+        live_recording_timeout_secs: std::env::var("STALE_LIVE_RECORDING_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse()
+            .context("STALE_LIVE_RECORDING_TIMEOUT_SECONDS is invalid")?,
+        uploading_timeout_secs: std::env::var("STALE_UPLOADING_TIMEOUT_SECONDS")
+            .unwrap_or_else(|_| "21600".to_string())
+            .parse()
+            .context("STALE_UPLOADING_TIMEOUT_SECONDS is invalid")?,
+        poll_interval_secs: std::env::var("STALE_RECORDING_POLL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(300),
+    };
+
+    let recording_disk = RecordingDiskConfig {
+        orphan_min_age_secs: std::env::var("ORPHAN_SWEEP_MIN_AGE_SECONDS")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse()
+            .context("ORPHAN_SWEEP_MIN_AGE_SECONDS is invalid")?,
+        delete_orphans: std::env::var("ORPHAN_SWEEP_DELETE")
+            .ok()
+            .and_then(|v| v.parse::<bool>().ok())
+            .unwrap_or(false),
+        min_free_bytes: std::env::var("DISK_MIN_FREE_BYTES")
+            .unwrap_or_else(|_| "10737418240".to_string())
+            .parse()
+            .context("DISK_MIN_FREE_BYTES is invalid")?,
+        alert_webhook_url: non_empty_env("DISCORD_DISK_ALERT_WEBHOOK_URL")
+            .map(|url| Url::parse(&url))
+            .transpose()
+            .context("DISCORD_DISK_ALERT_WEBHOOK_URL is invalid")?,
+        poll_interval_secs: std::env::var("RECORDING_DISK_POLL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(900),
+    };
+
+    let default_error_policy = RecordingEngineErrorPolicy::default();
+    let recording_engine_errors = RecordingEngineErrorPolicy {
+        failing_threshold: std::env::var("RECORDING_ENGINE_ERROR_THRESHOLD")
+            .ok()
+            .and_then(|v| v.parse::<i32>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default_error_policy.failing_threshold),
+        streak_window_secs: std::env::var("RECORDING_ENGINE_ERROR_WINDOW_SECONDS")
+            .ok()
+            .and_then(|v| v.parse::<i64>().ok())
+            .filter(|v| *v > 0)
+            .unwrap_or(default_error_policy.streak_window_secs),
+    };
+
+    let recording_engine_control = RecordingEngineControlConfig {
+        stop_url: non_empty_env("RECORDING_ENGINE_STOP_URL")
+            .context("RECORDING_ENGINE_STOP_URL is required to enforce plan recording limits")?,
+        status_url: non_empty_env("RECORDING_ENGINE_STATUS_URL"),
+        authorization: non_empty_env("RECORDING_ENGINE_AUTHORIZATION"),
+    };
+
+    let free_plan_id = std::env::var("STREAMCATCH_FREE_PLAN_ID")
+        .unwrap_or_else(|_| Uuid::nil().to_string())
+        .parse::<Uuid>()
+        .context("STREAMCATCH_FREE_PLAN_ID is invalid")?;
+
+    let quota_enforcement = match non_empty_env("QUOTA_ENFORCEMENT_POLICY") {
+        Some(policy) => policy
+            .parse::<QuotaEnforcementPolicy>()
+            .map_err(anyhow::Error::msg)
+            .context("QUOTA_ENFORCEMENT_POLICY must be one of warn, hide_oldest, stop_recording")?,
+        None => QuotaEnforcementPolicy::default(),
+    };
+
+    let notifications = Notifications {
+        smtp: match non_empty_env("NOTIFY_SMTP_HOST") {
+            Some(host) => Some(SmtpConfig {
+                host,
+                port: std::env::var("NOTIFY_SMTP_PORT")
+                    .unwrap_or_else(|_| "587".to_string())
+                    .parse()
+                    .context("NOTIFY_SMTP_PORT is invalid")?,
+                username: non_empty_env("NOTIFY_SMTP_USERNAME"),
+                password: non_empty_env("NOTIFY_SMTP_PASSWORD"),
+                from: std::env::var("NOTIFY_SMTP_FROM").context("NOTIFY_SMTP_FROM is invalid")?,
+                tls: SmtpTls::parse(
+                    &std::env::var("NOTIFY_SMTP_TLS").unwrap_or_else(|_| "starttls".to_string()),
+                )
+                .context("NOTIFY_SMTP_TLS must be one of none, starttls, tls")?,
+            }),
+            None => None,
+        },
+        webhook: non_empty_env("NOTIFY_WEBHOOK_URL").map(|url| WebhookChannelConfig {
+            url,
+            bearer_token: non_empty_env("NOTIFY_WEBHOOK_TOKEN"),
+        }),
+    };
 
     Ok(DotEnvyConfig {
         worker_server,
@@ -139,7 +283,53 @@ pub fn load() -> Result<DotEnvyConfig> {
         video_storage,
         recording_upload,
         cleanup,
+        internal,
         recording_engine_paths,
+        recording_engine_webhook_auth,
+        transmux_fallback,
+        recording_engine_errors,
+        stale_recording_watchdog,
+        recording_disk,
+        recording_engine_control,
+        notifications,
+        free_plan_id,
+        quota_enforcement,
+    })
+}
+
+/// Parses comma-separated `container_prefix=host_root` pairs.
+fn parse_path_mappings(value: &str) -> Result<Vec<RecordingPathMapping>> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (container_prefix, host_root) = pair
+                .split_once('=')
+                .with_context(|| format!("invalid path mapping: {pair}"))?;
+            Ok(path_mapping(container_prefix, host_root))
+        })
+        .collect()
+}
+
+fn path_mapping(container_prefix: &str, host_root: &str) -> RecordingPathMapping {
+    let container_prefix = container_prefix.trim();
+    let container_prefix = if container_prefix.ends_with('/') {
+        container_prefix.to_string()
+    } else {
+        format!("{}/", container_prefix)
+    };
+
+    RecordingPathMapping {
+        container_prefix,
+        host_root: PathBuf::from(host_root.trim()),
+    }
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    std::env::var(key).ok().and_then(|v| {
+        let trimmed = v.trim().to_string();
+        (!trimmed.is_empty()).then_some(trimmed)
     })
 }
 
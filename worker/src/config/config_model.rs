@@ -1,4 +1,14 @@
-use crates::infra::storages::wasabi::WasabiStorageConfig;
+use crates::{
+    domain::value_objects::enums::quota_enforcement_policies::QuotaEnforcementPolicy,
+    infra::{
+        notifications::{smtp::SmtpConfig, webhook::WebhookChannelConfig},
+        recording_engine::http_control::RecordingEngineControlConfig,
+        storages::wasabi::WasabiStorageConfig,
+    },
+};
+use std::path::PathBuf;
+use url::Url;
+use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct DotEnvyConfig {
@@ -8,7 +18,18 @@ pub struct DotEnvyConfig {
     pub video_storage: WasabiStorageConfig,
     pub recording_upload: RecordingUploadConfig,
     pub cleanup: Cleanup,
+    pub internal: Internal,
     pub recording_engine_paths: RecordingEnginePaths,
+    pub recording_engine_webhook_auth: RecordingEngineWebhookAuth,
+    pub transmux_fallback: TransmuxFallbackConfig,
+    pub recording_engine_errors: RecordingEngineErrorPolicy,
+    pub stale_recording_watchdog: StaleRecordingWatchdogConfig,
+    pub recording_disk: RecordingDiskConfig,
+    pub recording_engine_control: RecordingEngineControlConfig,
+    pub notifications: Notifications,
+    pub free_plan_id: Uuid,
+    /// Under `stop_recording`, lives whose followers are all over quota are not recorded.
+    pub quota_enforcement: QuotaEnforcementPolicy,
 }
 
 #[derive(Debug, Clone)]
@@ -18,9 +39,86 @@ pub struct WorkerServer {
     pub body_limit: u64,
 }
 
+/// Where the recording engine's output directories are mounted on this host.
 #[derive(Debug, Clone)]
 pub struct RecordingEnginePaths {
+    pub mappings: Vec<RecordingPathMapping>,
+}
+
+impl RecordingEnginePaths {
+    pub fn host_roots(&self) -> Vec<PathBuf> {
+        self.mappings
+            .iter()
+            .map(|mapping| mapping.host_root.clone())
+            .collect()
+    }
+}
+
+/// Engine paths starting with `container_prefix` live under `host_root` on this host.
+#[derive(Debug, Clone)]
+pub struct RecordingPathMapping {
     pub container_prefix: String,
+    pub host_root: PathBuf,
+}
+
+/// How `/internal/recording-engine/*` callers prove they are the recording engine. A request
+/// passes with either a valid signature or the bearer token.
+#[derive(Debug, Clone)]
+pub struct RecordingEngineWebhookAuth {
+    /// HMAC-SHA256 secret for the `X-Recording-Engine-Signature` header.
+    pub signing_secret: Option<String>,
+    /// Static `Authorization: Bearer` token, for engines that cannot sign requests.
+    pub bearer_token: Option<String>,
+    /// How far a signature timestamp may drift from now before it counts as a replay.
+    pub signature_tolerance_secs: u64,
+}
+
+/// When a finished raw file with no `video_transmux_finish` gets uploaded as-is.
+#[derive(Debug, Clone)]
+pub struct TransmuxFallbackConfig {
+    /// Seconds after `video_file_finish` before the raw file is uploaded; 0 disables it.
+    pub timeout_secs: u64,
+    pub poll_interval_secs: u64,
+}
+
+/// When recordings stuck in one status are considered abandoned; 0 disables a check.
+#[derive(Debug, Clone)]
+pub struct StaleRecordingWatchdogConfig {
+    pub live_recording_timeout_secs: u64,
+    pub uploading_timeout_secs: u64,
+    pub poll_interval_secs: u64,
+}
+
+/// Housekeeping of the recording volume on this host.
+#[derive(Debug, Clone)]
+pub struct RecordingDiskConfig {
+    /// Unreferenced files younger than this are kept; 0 disables the orphan sweep.
+    pub orphan_min_age_secs: u64,
+    /// Delete orphans instead of only reporting them.
+    pub delete_orphans: bool,
+    /// Alert when free space drops below this; 0 disables the guard.
+    pub min_free_bytes: u64,
+    pub alert_webhook_url: Option<Url>,
+    pub poll_interval_secs: u64,
+}
+
+/// When repeated engine errors mark a channel as failing.
+#[derive(Debug, Clone)]
+pub struct RecordingEngineErrorPolicy {
+    /// Consecutive errors, without a finished recording in between, that flip the live
+    /// account to `error`.
+    pub failing_threshold: i32,
+    /// An error streak restarts when the previous error is older than this.
+    pub streak_window_secs: i64,
+}
+
+impl Default for RecordingEngineErrorPolicy {
+    fn default() -> Self {
+        Self {
+            failing_threshold: 5,
+            streak_window_secs: 86_400,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -45,7 +143,20 @@ pub struct Cleanup {
     pub default_retention_days: i64,
 }
 
+/// Bearer token for internal endpoints other than cleanup; shared with the backend's
+/// internal API.
+#[derive(Debug, Clone)]
+pub struct Internal {
+    pub token: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct RecordingUploadConfig {
     pub max_files_in_flight: usize,
 }
+
+#[derive(Debug, Clone)]
+pub struct Notifications {
+    pub smtp: Option<SmtpConfig>,
+    pub webhook: Option<WebhookChannelConfig>,
+}
@@ -1,5 +1,11 @@
 pub mod axum_http;
 pub mod config;
+pub mod media_probe;
+pub mod recording_disk_sweeping;
 pub mod recording_engine_web_driver;
+pub mod recording_remuxing;
 pub mod recording_uploading;
+pub mod stale_recording_watchdog;
+pub mod subscription_notifying;
+pub mod transmux_fallback;
 pub mod usecases;
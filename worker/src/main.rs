@@ -1,22 +1,31 @@
 use anyhow::Result;
 use crates::domain::repositories::{
+    deliveries::DeliveryRepository,
     job::JobRepository,
     live_account_recording_engine::LiveAccountRecordingEngineRepository,
+    notification_channel::NotificationChannel,
     recording_cleanup::RecordingCleanupRepository,
+    recording_engine_control::RecordingEngineControl,
     recording_engine_webhook::RecordingEngineWebhookRepository,
     recording_upload::RecordingUploadRepository,
+    recording_watchdog::RecordingWatchdogRepository,
     storage::{CoverStorageClient, StorageClient},
+    user_contacts::UserContactRepository,
 };
 use crates::infra::{
     db::{
         postgres::postgres_connection,
         repositories::{
-            job::JobPostgres, live_account_recording_engine::LiveAccountRecordingEnginePostgres,
+            deliveries::DeliveryPostgres, job::JobPostgres,
+            live_account_recording_engine::LiveAccountRecordingEnginePostgres,
             recording_cleanup::RecordingCleanupPostgres,
             recording_engine_webhook::RecordingEngineWebhookPostgres,
             recording_upload::RecordingUploadPostgres,
+            recording_watchdog::RecordingWatchdogPostgres, user_contacts::UserContactPostgres,
         },
     },
+    notifications::{smtp::SmtpEmailChannel, webhook::WebhookNotificationChannel},
+    recording_engine::http_control::HttpRecordingEngineControl,
     storages::{
         supabase_storage::{SupabaseStorageClient, SupabaseStorageConfig},
         wasabi::WasabiStorageClient,
@@ -26,11 +35,16 @@ use std::sync::Arc;
 use tracing::error;
 use tracing::info;
 use worker::{
-    axum_http, config, recording_engine_web_driver, recording_uploading,
+    axum_http, config, recording_disk_sweeping, recording_engine_web_driver, recording_remuxing,
+    recording_uploading, stale_recording_watchdog, subscription_notifying, transmux_fallback,
     usecases::{
         cleanup_expired_recordings::CleanupExpiredRecordingsUseCase,
         insert_live_account_recording_engine::InsertLiveAccountUseCase,
+        recording_disk::RecordingDiskUseCase,
         recording_engine_webhook::RecordingEngineWebhookUseCase,
+        recording_remux::RecordingRemuxUseCase,
+        stale_recording_watchdog::StaleRecordingWatchdogUseCase,
+        subscription_notification::SubscriptionNotificationUseCase,
     },
 };
 
@@ -94,15 +108,31 @@ async fn run() -> Result<()> {
     let job_repository: Arc<dyn JobRepository + Send + Sync> =
         Arc::new(JobPostgres::new(Arc::clone(&db_pool_arc)));
 
-    let recording_engine_webhook_usecase = Arc::new(RecordingEngineWebhookUseCase::new(
-        recording_engine_webhook_repository,
-        Arc::clone(&job_repository),
-        Arc::clone(&cover_storage_client),
-        dotenvy_env.recording_engine_paths.clone(),
-    ));
+    let recording_engine_control: Arc<dyn RecordingEngineControl + Send + Sync> = Arc::new(
+        HttpRecordingEngineControl::new(dotenvy_env.recording_engine_control.clone()),
+    );
+
+    let recording_engine_webhook_usecase = Arc::new(
+        RecordingEngineWebhookUseCase::new(
+            recording_engine_webhook_repository,
+            Arc::clone(&job_repository),
+            Arc::clone(&cover_storage_client),
+            Arc::clone(&recording_engine_control),
+            dotenvy_env.recording_engine_paths.clone(),
+            dotenvy_env.free_plan_id,
+            dotenvy_env.quota_enforcement,
+        )
+        .with_error_policy(dotenvy_env.recording_engine_errors.clone()),
+    );
 
     let server_config = Arc::clone(&dotenvy_env);
-    let server_usecase = recording_engine_webhook_usecase;
+    let server_usecase = Arc::clone(&recording_engine_webhook_usecase);
+
+    // Spawn background loop
+    let transmux_fallback_loop = tokio::spawn(transmux_fallback::worker::run(
+        recording_engine_webhook_usecase,
+        dotenvy_env.transmux_fallback.clone(),
+    ));
 
     let cleanup_repo: Arc<dyn RecordingCleanupRepository + Send + Sync> =
         Arc::new(RecordingCleanupPostgres::new(Arc::clone(&db_pool_arc)));
@@ -124,6 +154,66 @@ async fn run() -> Result<()> {
         axum_http::http_serve::start(server_config, server_usecase, cleanup_usecase).await
     });
 
+    let mut notification_channels: Vec<Arc<dyn NotificationChannel + Send + Sync>> = Vec::new();
+    if let Some(smtp) = dotenvy_env.notifications.smtp.clone() {
+        notification_channels.push(Arc::new(SmtpEmailChannel::new(smtp)?));
+    }
+    if let Some(webhook) = dotenvy_env.notifications.webhook.clone() {
+        notification_channels.push(Arc::new(WebhookNotificationChannel::new(webhook)));
+    }
+
+    let delivery_repository: Arc<dyn DeliveryRepository + Send + Sync> =
+        Arc::new(DeliveryPostgres::new(Arc::clone(&db_pool_arc)));
+    let user_contact_repository: Arc<dyn UserContactRepository + Send + Sync> =
+        Arc::new(UserContactPostgres::new(Arc::clone(&db_pool_arc)));
+    let subscription_notification_usecase = Arc::new(SubscriptionNotificationUseCase::new(
+        delivery_repository,
+        user_contact_repository,
+        notification_channels,
+    ));
+
+    // Spawn background loop
+    let subscription_notifying_loop = tokio::spawn(subscription_notifying::worker::run(
+        Arc::clone(&job_repository),
+        subscription_notification_usecase,
+    ));
+
+    let recording_watchdog_repository: Arc<dyn RecordingWatchdogRepository + Send + Sync> =
+        Arc::new(RecordingWatchdogPostgres::new(Arc::clone(&db_pool_arc)));
+    let recording_disk_usecase = Arc::new(RecordingDiskUseCase::new(
+        Arc::clone(&recording_watchdog_repository),
+        dotenvy_env.recording_engine_paths.host_roots(),
+    ));
+    let stale_recording_watchdog_usecase = Arc::new(StaleRecordingWatchdogUseCase::new(
+        recording_watchdog_repository,
+        Arc::clone(&job_repository),
+        recording_engine_control,
+        dotenvy_env.recording_engine_paths.host_roots(),
+    ));
+
+    // Spawn background loop
+    let stale_recording_watchdog_loop = tokio::spawn(stale_recording_watchdog::worker::run(
+        stale_recording_watchdog_usecase,
+        dotenvy_env.stale_recording_watchdog.clone(),
+    ));
+
+    // Spawn background loop
+    let recording_disk_loop = tokio::spawn(recording_disk_sweeping::worker::run(
+        recording_disk_usecase,
+        dotenvy_env.recording_disk.clone(),
+    ));
+
+    let recording_remux_usecase = Arc::new(RecordingRemuxUseCase::new(
+        Arc::clone(&job_repository),
+        Arc::clone(&recording_upload_repository),
+    ));
+
+    // Spawn background loop
+    let recording_remuxing_loop = tokio::spawn(recording_remuxing::worker::run(
+        Arc::clone(&job_repository),
+        recording_remux_usecase,
+    ));
+
     // Spawn background loop
     let recording_uploading_loop = tokio::spawn(recording_uploading::worker::run(
         job_repository,
@@ -134,8 +224,13 @@ async fn run() -> Result<()> {
 
     tokio::select! {
         result = recording_uploading_loop => result??,
+        result = recording_remuxing_loop => result??,
+        result = subscription_notifying_loop => result??,
         result = recording_engine_web_driver_loop => result??,
         result = recording_engine_webhook => result??,
+        result = transmux_fallback_loop => result??,
+        result = stale_recording_watchdog_loop => result??,
+        result = recording_disk_loop => result??,
     };
     Ok(())
 }
@@ -0,0 +1,317 @@
+use anyhow::{Context, Result, bail};
+use mp4::{MediaType, Mp4Reader, TrackType};
+use serde::Deserialize;
+use std::{
+    fs::File,
+    io::{BufReader, ErrorKind, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+use tokio::process::Command;
+use tracing::warn;
+
+/// What the UI needs to describe a recording's quality; fields the container does not
+/// expose are `None`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    pub duration_sec: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bitrate_bps: Option<i64>,
+    pub fps: Option<f64>,
+}
+
+/// Probes a local media file: MP4 headers are read in-process, anything else (or an MP4 the
+/// `mp4` crate cannot make sense of) goes through `ffprobe`.
+pub async fn probe(path: &Path) -> Result<MediaInfo> {
+    if is_mp4_path(path) {
+        let mp4_path = path.to_path_buf();
+        match tokio::task::spawn_blocking(move || probe_mp4(mp4_path))
+            .await
+            .context("failed to join mp4 probe task")?
+        {
+            Ok(info) => return Ok(info),
+            Err(err) => {
+                warn!(
+                    path = %path.display(),
+                    error = ?err,
+                    "media_probe: mp4 header probe failed; falling back to ffprobe"
+                );
+            }
+        }
+    }
+
+    probe_ffprobe(path).await
+}
+
+/// Whether `path` is an MP4 whose `moov` box comes before `mdat`, so players can start
+/// before the whole file is downloaded.
+pub async fn is_faststart_mp4(path: &Path) -> Result<bool> {
+    if !is_mp4_path(path) {
+        return Ok(false);
+    }
+
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || moov_before_mdat(BufReader::new(File::open(&path)?)))
+        .await
+        .context("failed to join mp4 box scan task")?
+}
+
+fn is_mp4_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("mp4"))
+        .unwrap_or(false)
+}
+
+fn probe_mp4(path: PathBuf) -> Result<MediaInfo> {
+    let file = File::open(&path)?;
+    let size = file.metadata()?.len();
+    let reader = BufReader::new(file);
+    let mp4 = Mp4Reader::read_header(reader, size)?;
+    let duration = mp4.duration().as_secs_f64();
+
+    let mut info = MediaInfo {
+        duration_sec: Some(seconds(duration)?),
+        bitrate_bps: (duration > 0.0).then(|| (size as f64 * 8.0 / duration).round() as i64),
+        ..Default::default()
+    };
+    for track in mp4.tracks().values() {
+        match track.track_type()? {
+            TrackType::Video if info.video_codec.is_none() => {
+                info.video_codec = Some(codec_name(track.media_type()?));
+                info.width = Some(i32::from(track.width()));
+                info.height = Some(i32::from(track.height()));
+                info.fps = Some(track.frame_rate()).filter(|fps| *fps > 0.0);
+            }
+            TrackType::Audio if info.audio_codec.is_none() => {
+                info.audio_codec = Some(codec_name(track.media_type()?));
+            }
+            _ => {}
+        }
+    }
+
+    if info.video_codec.is_none() {
+        bail!("mp4 has no supported video track");
+    }
+    Ok(info)
+}
+
+/// Walks the top-level boxes until `moov` or `mdat` shows up.
+fn moov_before_mdat(mut reader: impl Read + Seek) -> Result<bool> {
+    loop {
+        let mut header = [0u8; 8];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(false),
+            Err(err) => return Err(err.into()),
+        }
+
+        match &header[4..] {
+            b"moov" => return Ok(true),
+            b"mdat" => return Ok(false),
+            _ => {}
+        }
+
+        let body_size = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            // The box runs to the end of the file.
+            0 => return Ok(false),
+            1 => {
+                let mut large_size = [0u8; 8];
+                reader.read_exact(&mut large_size)?;
+                u64::from_be_bytes(large_size).checked_sub(16)
+            }
+            size => u64::from(size).checked_sub(8),
+        }
+        .context("invalid mp4 box size")?;
+        reader.seek(SeekFrom::Current(i64::try_from(body_size)?))?;
+    }
+}
+
+/// `ffprobe` names for the codecs the `mp4` crate recognises.
+fn codec_name(media_type: MediaType) -> String {
+    match media_type {
+        MediaType::H265 => "hevc".to_string(),
+        other => other.to_string(),
+    }
+}
+
+async fn probe_ffprobe(path: &Path) -> Result<MediaInfo> {
+    let output = Command::new("ffprobe")
+        .arg("-v")
+        .arg("error")
+        .arg("-print_format")
+        .arg("json")
+        .arg("-show_format")
+        .arg("-show_streams")
+        .arg(path)
+        .output()
+        .await
+        .context("failed to run ffprobe")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("ffprobe failed with {}: {}", output.status, stderr.trim());
+    }
+
+    parse_ffprobe_output(&output.stdout)
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeOutput {
+    #[serde(default)]
+    streams: Vec<FfprobeStream>,
+    format: Option<FfprobeFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FfprobeStream {
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<i32>,
+    height: Option<i32>,
+    avg_frame_rate: Option<String>,
+    r_frame_rate: Option<String>,
+    duration: Option<String>,
+}
+
+/// ffprobe prints numbers in `format` as strings.
+#[derive(Debug, Deserialize)]
+struct FfprobeFormat {
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+fn parse_ffprobe_output(stdout: &[u8]) -> Result<MediaInfo> {
+    let output: FfprobeOutput =
+        serde_json::from_slice(stdout).context("failed to parse ffprobe output")?;
+    let video = output
+        .streams
+        .iter()
+        .find(|stream| stream.codec_type.as_deref() == Some("video"));
+    let audio = output
+        .streams
+        .iter()
+        .find(|stream| stream.codec_type.as_deref() == Some("audio"));
+
+    // Live FLV/TS files often lack a container duration; the video stream may still have one.
+    let duration = output
+        .format
+        .as_ref()
+        .and_then(|format| format.duration.as_deref())
+        .or_else(|| video.and_then(|stream| stream.duration.as_deref()))
+        .and_then(|duration| duration.parse::<f64>().ok());
+
+    Ok(MediaInfo {
+        duration_sec: duration.map(seconds).transpose()?,
+        width: video.and_then(|stream| stream.width),
+        height: video.and_then(|stream| stream.height),
+        video_codec: video.and_then(|stream| stream.codec_name.clone()),
+        audio_codec: audio.and_then(|stream| stream.codec_name.clone()),
+        bitrate_bps: output
+            .format
+            .as_ref()
+            .and_then(|format| format.bit_rate.as_deref())
+            .and_then(|bit_rate| bit_rate.parse::<i64>().ok()),
+        fps: video.and_then(|stream| {
+            parse_frame_rate(stream.avg_frame_rate.as_deref())
+                .or_else(|| parse_frame_rate(stream.r_frame_rate.as_deref()))
+        }),
+    })
+}
+
+/// Parses ffprobe's `num/den` rates; `0/0` means unknown.
+fn parse_frame_rate(rate: Option<&str>) -> Option<f64> {
+    let (num, den) = rate?.split_once('/')?;
+    let num = num.parse::<f64>().ok()?;
+    let den = den.parse::<f64>().ok()?;
+    (num > 0.0 && den > 0.0).then(|| num / den)
+}
+
+fn seconds(duration: f64) -> Result<i32> {
+    i32::try_from(duration.round() as i64).context("media duration seconds exceed i32")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn mp4_box(box_type: &[u8; 4], body_size: usize) -> Vec<u8> {
+        let mut bytes = u32::try_from(body_size + 8).unwrap().to_be_bytes().to_vec();
+        bytes.extend_from_slice(box_type);
+        bytes.resize(body_size + 8, 0);
+        bytes
+    }
+
+    #[test]
+    fn faststart_is_detected_from_box_order() {
+        let faststart = [
+            mp4_box(b"ftyp", 16),
+            mp4_box(b"moov", 32),
+            mp4_box(b"mdat", 64),
+        ]
+        .concat();
+        let trailing_moov = [
+            mp4_box(b"ftyp", 16),
+            mp4_box(b"mdat", 64),
+            mp4_box(b"moov", 32),
+        ]
+        .concat();
+
+        assert!(moov_before_mdat(Cursor::new(faststart)).unwrap());
+        assert!(!moov_before_mdat(Cursor::new(trailing_moov)).unwrap());
+    }
+
+    #[test]
+    fn ffprobe_output_is_read_into_media_info() {
+        let stdout = br#"{
+            "streams": [
+                {"codec_type": "audio", "codec_name": "aac", "avg_frame_rate": "0/0"},
+                {
+                    "codec_type": "video",
+                    "codec_name": "h264",
+                    "width": 720,
+                    "height": 1280,
+                    "avg_frame_rate": "30000/1001",
+                    "r_frame_rate": "30/1"
+                }
+            ],
+            "format": {"duration": "61.600000", "bit_rate": "2500000"}
+        }"#;
+
+        let info = parse_ffprobe_output(stdout).unwrap();
+
+        assert_eq!(info.duration_sec, Some(62));
+        assert_eq!(info.width, Some(720));
+        assert_eq!(info.height, Some(1280));
+        assert_eq!(info.video_codec.as_deref(), Some("h264"));
+        assert_eq!(info.audio_codec.as_deref(), Some("aac"));
+        assert_eq!(info.bitrate_bps, Some(2_500_000));
+        assert!((info.fps.unwrap() - 29.97).abs() < 0.01);
+    }
+
+    #[test]
+    fn ffprobe_stream_duration_and_rate_are_used_when_format_lacks_them() {
+        let stdout = br#"{
+            "streams": [
+                {
+                    "codec_type": "video",
+                    "codec_name": "hevc",
+                    "avg_frame_rate": "0/0",
+                    "r_frame_rate": "25/1",
+                    "duration": "10.2"
+                }
+            ],
+            "format": {}
+        }"#;
+
+        let info = parse_ffprobe_output(stdout).unwrap();
+
+        assert_eq!(info.duration_sec, Some(10));
+        assert_eq!(info.fps, Some(25.0));
+        assert_eq!(info.audio_codec, None);
+        assert_eq!(info.bitrate_bps, None);
+    }
+}
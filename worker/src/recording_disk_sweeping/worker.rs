@@ -0,0 +1,102 @@
+use anyhow::Result;
+use std::{collections::HashSet, path::Path, sync::Arc, time::Duration};
+use tracing::{error, info, warn};
+
+use crate::{
+    config::config_model::RecordingDiskConfig,
+    usecases::recording_disk::{OrphanFileSweepParams, RecordingDiskUseCase},
+};
+
+pub async fn run(usecase: Arc<RecordingDiskUseCase>, config: RecordingDiskConfig) -> Result<()> {
+    if config.orphan_min_age_secs == 0 && config.min_free_bytes == 0 {
+        info!("recording_disk: disabled");
+        std::future::pending::<()>().await;
+    }
+
+    info!(
+        orphan_min_age_secs = config.orphan_min_age_secs,
+        delete_orphans = config.delete_orphans,
+        min_free_bytes = config.min_free_bytes,
+        "recording_disk: starting worker loop"
+    );
+    // Alert once per root when free space drops below the limit, not on every poll.
+    let mut low_space_roots = HashSet::new();
+    loop {
+        if config.min_free_bytes > 0 {
+            for root in usecase.recording_roots() {
+                let alerted = low_space_roots.contains(root);
+                if check_free_space(&usecase, &config, root, alerted).await {
+                    low_space_roots.insert(root.clone());
+                } else {
+                    low_space_roots.remove(root);
+                }
+            }
+        }
+
+        if config.orphan_min_age_secs > 0 {
+            let params = OrphanFileSweepParams {
+                min_age: Duration::from_secs(config.orphan_min_age_secs),
+                delete: config.delete_orphans,
+            };
+            if let Err(err) = usecase.sweep_orphan_files(params).await {
+                error!(error = %err, "recording_disk: orphan file sweep failed");
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(config.poll_interval_secs)).await;
+    }
+}
+
+/// Returns whether the low-space alert is (still) raised.
+async fn check_free_space(
+    usecase: &RecordingDiskUseCase,
+    config: &RecordingDiskConfig,
+    root: &Path,
+    alerted: bool,
+) -> bool {
+    let available_bytes = match usecase.available_space_bytes(root).await {
+        Ok(available_bytes) => available_bytes,
+        Err(err) => {
+            error!(
+                root = %root.display(),
+                error = %err,
+                "recording_disk: failed to read free space"
+            );
+            return alerted;
+        }
+    };
+
+    if available_bytes >= config.min_free_bytes {
+        if alerted {
+            info!(
+                root = %root.display(),
+                available_bytes,
+                "recording_disk: free space recovered"
+            );
+        }
+        return false;
+    }
+
+    error!(
+        root = %root.display(),
+        available_bytes,
+        min_free_bytes = config.min_free_bytes,
+        "recording_disk: free space on the recording volume is below the limit"
+    );
+    if !alerted && let Some(webhook_url) = config.alert_webhook_url.clone() {
+        let content = format!(
+            "**Recording volume low on space**\nroot: `{}`\navailable: `{:.1} GiB`\nlimit: `{:.1} GiB`",
+            root.display(),
+            gib(available_bytes),
+            gib(config.min_free_bytes)
+        );
+        if let Err(err) = crates::observability::send_discord_webhook(webhook_url, content).await {
+            warn!(error = %err, "recording_disk: failed to send low space alert");
+        }
+    }
+    true
+}
+
+fn gib(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use crates::domain::{
+    entities::jobs::JobEntity, repositories::job::JobRepository,
+    value_objects::recording_upload::RecordingRemuxPayload,
+};
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info};
+
+use crate::usecases::recording_remux::{RecordingRemuxOutcome, RecordingRemuxUseCase};
+
+const MAX_ATTEMPTS: i32 = 3;
+
+pub async fn run(
+    job_repo: Arc<dyn JobRepository + Send + Sync>,
+    usecase: Arc<RecordingRemuxUseCase>,
+) -> Result<()> {
+    info!("recording_remux: starting worker loop");
+    loop {
+        match job_repo.lock_next_recording_remux_job().await {
+            Ok(Some(job)) => {
+                info!(job_id = %job.id, "recording_remux: processing job");
+                match process_job(&usecase, &job).await {
+                    Ok(()) => {
+                        if let Err(err) = job_repo.mark_job_done(job.id).await {
+                            error!(
+                                job_id = %job.id,
+                                error = %err,
+                                "recording_remux: failed to mark job as done"
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        error!(
+                            job_id = %job.id,
+                            error = %err,
+                            "recording_remux: failed to process job"
+                        );
+                        if let Err(mark_err) = job_repo
+                            .mark_job_failed(job.id, &err.to_string(), MAX_ATTEMPTS)
+                            .await
+                        {
+                            error!(
+                                job_id = %job.id,
+                                error = %mark_err,
+                                "recording_remux: failed to mark job as failed"
+                            );
+                        }
+                    }
+                }
+            }
+            Ok(None) => {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            Err(err) => {
+                error!(
+                    error = %err,
+                    "recording_remux: error locking next job"
+                );
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+async fn process_job(usecase: &RecordingRemuxUseCase, job: &JobEntity) -> Result<()> {
+    let payload: RecordingRemuxPayload = serde_json::from_value(job.payload.clone())?;
+    match usecase.remux(&payload).await? {
+        RecordingRemuxOutcome::AlreadyFaststart(path) => info!(
+            job_id = %job.id,
+            recording_id = %payload.recording_id,
+            path = %path.display(),
+            "recording_remux: already faststart mp4; queued upload"
+        ),
+        RecordingRemuxOutcome::Remuxed(path) => info!(
+            job_id = %job.id,
+            recording_id = %payload.recording_id,
+            path = %path.display(),
+            "recording_remux: job processed"
+        ),
+    }
+    Ok(())
+}
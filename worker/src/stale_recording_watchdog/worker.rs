@@ -0,0 +1,63 @@
+use anyhow::Result;
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info};
+
+use crate::{
+    config::config_model::StaleRecordingWatchdogConfig,
+    usecases::stale_recording_watchdog::{
+        StaleRecordingWatchdogParams, StaleRecordingWatchdogUseCase,
+    },
+};
+
+const BATCH_SIZE: i64 = 50;
+
+pub async fn run(
+    usecase: Arc<StaleRecordingWatchdogUseCase>,
+    config: StaleRecordingWatchdogConfig,
+) -> Result<()> {
+    let params = StaleRecordingWatchdogParams {
+        live_recording_timeout: timeout(config.live_recording_timeout_secs)?,
+        uploading_timeout: timeout(config.uploading_timeout_secs)?,
+        limit: BATCH_SIZE,
+    };
+    if params.live_recording_timeout.is_none() && params.uploading_timeout.is_none() {
+        info!("stale_recording_watchdog: disabled");
+        std::future::pending::<()>().await;
+    }
+
+    info!(
+        live_recording_timeout_secs = config.live_recording_timeout_secs,
+        uploading_timeout_secs = config.uploading_timeout_secs,
+        "stale_recording_watchdog: starting worker loop"
+    );
+    loop {
+        match usecase.run(params.clone()).await {
+            Ok(result) => {
+                if result.closed_live_end + result.requeued + result.failed > 0 {
+                    info!(
+                        closed_live_end = result.closed_live_end,
+                        requeued = result.requeued,
+                        failed = result.failed,
+                        pending_upload = result.pending_upload,
+                        still_live = result.still_live,
+                        "stale_recording_watchdog: sweep finished"
+                    );
+                }
+            }
+            Err(err) => {
+                error!(
+                    error = %err,
+                    "stale_recording_watchdog: sweep failed"
+                );
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(config.poll_interval_secs)).await;
+    }
+}
+
+fn timeout(secs: u64) -> Result<Option<chrono::Duration>> {
+    if secs == 0 {
+        return Ok(None);
+    }
+    Ok(Some(chrono::Duration::seconds(i64::try_from(secs)?)))
+}
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use crates::domain::{
+    entities::jobs::JobEntity, repositories::job::JobRepository,
+    value_objects::subscription_notifications::SubscriptionNotificationPayload,
+};
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info, warn};
+
+use crate::usecases::subscription_notification::SubscriptionNotificationUseCase;
+
+const MAX_ATTEMPTS: i32 = 5;
+
+pub async fn run(
+    job_repo: Arc<dyn JobRepository + Send + Sync>,
+    usecase: Arc<SubscriptionNotificationUseCase>,
+) -> Result<()> {
+    if !usecase.has_channels() {
+        warn!("subscription_notification: no channels configured; jobs stay queued");
+        std::future::pending::<()>().await;
+    }
+
+    info!("subscription_notification: starting worker loop");
+    loop {
+        match job_repo.lock_next_subscription_notification_job().await {
+            Ok(Some(job)) => {
+                info!(job_id = %job.id, "subscription_notification: processing job");
+                match process_job(&usecase, &job).await {
+                    Ok(()) => {
+                        if let Err(err) = job_repo.mark_job_done(job.id).await {
+                            error!(
+                                job_id = %job.id,
+                                error = %err,
+                                "subscription_notification: failed to mark job as done"
+                            );
+                        }
+                    }
+                    Err(err) => {
+                        error!(
+                            job_id = %job.id,
+                            error = %err,
+                            "subscription_notification: failed to process job"
+                        );
+                        if let Err(mark_err) = job_repo
+                            .mark_job_failed(job.id, &err.to_string(), MAX_ATTEMPTS)
+                            .await
+                        {
+                            error!(
+                                job_id = %job.id,
+                                error = %mark_err,
+                                "subscription_notification: failed to mark job as failed"
+                            );
+                        }
+                    }
+                }
+            }
+            Ok(None) => {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+            Err(err) => {
+                error!(
+                    error = %err,
+                    "subscription_notification: error locking next job"
+                );
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        }
+    }
+}
+
+async fn process_job(usecase: &SubscriptionNotificationUseCase, job: &JobEntity) -> Result<()> {
+    let payload: SubscriptionNotificationPayload = serde_json::from_value(job.payload.clone())?;
+    let result = usecase.deliver(&payload).await?;
+    info!(
+        job_id = %job.id,
+        kind = %payload.kind,
+        sent = result.sent,
+        already_sent = result.already_sent,
+        "subscription_notification: job processed"
+    );
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+use std::{sync::Arc, time::Duration};
+use tracing::{error, info};
+
+use crate::{
+    config::config_model::TransmuxFallbackConfig,
+    usecases::recording_engine_webhook::RecordingEngineWebhookUseCase,
+};
+
+const BATCH_SIZE: i64 = 20;
+
+pub async fn run(
+    usecase: Arc<RecordingEngineWebhookUseCase>,
+    config: TransmuxFallbackConfig,
+) -> Result<()> {
+    if config.timeout_secs == 0 {
+        info!("transmux_fallback: disabled");
+        std::future::pending::<()>().await;
+    }
+
+    info!(
+        timeout_secs = config.timeout_secs,
+        "transmux_fallback: starting worker loop"
+    );
+    let timeout = chrono::Duration::seconds(i64::try_from(config.timeout_secs)?);
+    loop {
+        match usecase
+            .upload_untransmuxed_recordings(timeout, BATCH_SIZE)
+            .await
+        {
+            Ok(0) => {}
+            Ok(uploaded) => {
+                info!(uploaded, "transmux_fallback: queued raw files for upload");
+                continue;
+            }
+            Err(err) => {
+                error!(
+                    error = %err,
+                    "transmux_fallback: failed to sweep untransmuxed recordings"
+                );
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(config.poll_interval_secs)).await;
+    }
+}
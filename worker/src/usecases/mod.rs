@@ -1,3 +1,9 @@
 pub mod cleanup_expired_recordings;
 pub mod insert_live_account_recording_engine;
+pub mod recording_disk;
 pub mod recording_engine_webhook;
+pub mod recording_remux;
+pub mod stale_recording_watchdog;
+pub mod subscription_notification;
+#[cfg(test)]
+pub(crate) mod test_support;
@@ -0,0 +1,275 @@
+use anyhow::{Context, Result, bail};
+use crates::domain::repositories::recording_watchdog::RecordingWatchdogRepository;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::process::Command;
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone)]
+pub struct OrphanFileSweepParams {
+    /// Unreferenced files modified more recently than this are left alone; the engine may
+    /// still be writing them or about to send their webhook.
+    pub min_age: Duration,
+    /// Only report orphans unless set.
+    pub delete: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrphanFileSweepResult {
+    pub scanned: usize,
+    pub orphaned: usize,
+    pub orphaned_bytes: u64,
+    pub deleted: usize,
+    pub delete_failed: usize,
+}
+
+#[derive(Debug, Clone)]
+struct LocalFile {
+    path: PathBuf,
+    size_bytes: u64,
+    modified: SystemTime,
+}
+
+pub struct RecordingDiskUseCase {
+    repository: Arc<dyn RecordingWatchdogRepository + Send + Sync>,
+    recording_roots: Vec<PathBuf>,
+}
+
+impl RecordingDiskUseCase {
+    pub fn new(
+        repository: Arc<dyn RecordingWatchdogRepository + Send + Sync>,
+        recording_roots: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            repository,
+            recording_roots,
+        }
+    }
+
+    pub fn recording_roots(&self) -> &[PathBuf] {
+        &self.recording_roots
+    }
+
+    /// Finds files under the recording roots that no in-flight recording or upload job refers
+    /// to, and removes them when `params.delete` is set.
+    pub async fn sweep_orphan_files(
+        &self,
+        params: OrphanFileSweepParams,
+    ) -> Result<OrphanFileSweepResult> {
+        // Scan before loading references so a file referenced mid-sweep is still protected.
+        let roots = self.recording_roots.clone();
+        let files = tokio::task::spawn_blocking(move || {
+            roots.iter().try_fold(Vec::new(), |mut files, root| {
+                files.extend(list_files(root)?);
+                Ok::<_, anyhow::Error>(files)
+            })
+        })
+        .await
+        .context("failed to join recording directory scan")??;
+        let referenced: HashSet<PathBuf> = self
+            .repository
+            .list_local_recording_paths()
+            .await?
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+
+        let now = SystemTime::now();
+        let mut result = OrphanFileSweepResult {
+            scanned: files.len(),
+            ..Default::default()
+        };
+
+        for file in files {
+            let age = now.duration_since(file.modified).unwrap_or_default();
+            if referenced.contains(&file.path) || age < params.min_age {
+                continue;
+            }
+
+            result.orphaned += 1;
+            result.orphaned_bytes += file.size_bytes;
+
+            if !params.delete {
+                warn!(
+                    path = %file.path.display(),
+                    size_bytes = file.size_bytes,
+                    age_secs = age.as_secs(),
+                    "orphan_file_sweep: found orphaned recording file"
+                );
+                continue;
+            }
+
+            match tokio::fs::remove_file(&file.path).await {
+                Ok(()) => {
+                    result.deleted += 1;
+                    warn!(
+                        path = %file.path.display(),
+                        size_bytes = file.size_bytes,
+                        age_secs = age.as_secs(),
+                        "orphan_file_sweep: deleted orphaned recording file"
+                    );
+                }
+                Err(err) => {
+                    result.delete_failed += 1;
+                    error!(
+                        path = %file.path.display(),
+                        error = %err,
+                        "orphan_file_sweep: failed to delete orphaned recording file"
+                    );
+                }
+            }
+        }
+
+        info!(
+            scanned = result.scanned,
+            orphaned = result.orphaned,
+            orphaned_bytes = result.orphaned_bytes,
+            deleted = result.deleted,
+            delete_failed = result.delete_failed,
+            "orphan_file_sweep: sweep finished"
+        );
+        Ok(result)
+    }
+
+    /// Bytes available to unprivileged writers on the filesystem holding `root`.
+    pub async fn available_space_bytes(&self, root: &Path) -> Result<u64> {
+        let output = Command::new("df")
+            .arg("-P")
+            .arg("-k")
+            .arg(root)
+            .output()
+            .await
+            .context("failed to run df")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!("df failed with {}: {}", output.status, stderr.trim());
+        }
+
+        parse_df_available_bytes(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Regular files under `root`, recursively; symlinks are not followed.
+fn list_files(root: &Path) -> Result<Vec<LocalFile>> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .with_context(|| format!("failed to read directory {}", dir.display()))?;
+        for entry in entries {
+            let entry = entry?;
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                let metadata = entry.metadata()?;
+                files.push(LocalFile {
+                    path: entry.path(),
+                    size_bytes: metadata.len(),
+                    modified: metadata.modified()?,
+                });
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Reads the "Available" column (1024-byte blocks) of POSIX `df -P -k` output.
+fn parse_df_available_bytes(output: &str) -> Result<u64> {
+    let line = output
+        .lines()
+        .nth(1)
+        .context("df output has no filesystem line")?;
+    let available_kib = line
+        .split_whitespace()
+        .nth(3)
+        .context("df output has no available column")?
+        .parse::<u64>()
+        .context("df available column is not a number")?;
+
+    Ok(available_kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usecases::test_support::TempDir;
+    use crates::domain::repositories::recording_watchdog::MockRecordingWatchdogRepository;
+
+    fn usecase(root: &TempDir, referenced: Vec<PathBuf>) -> RecordingDiskUseCase {
+        let mut repository = MockRecordingWatchdogRepository::new();
+        repository
+            .expect_list_local_recording_paths()
+            .returning(move || {
+                let paths = referenced
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect();
+                Box::pin(async move { Ok(paths) })
+            });
+        RecordingDiskUseCase::new(Arc::new(repository), vec![root.path().to_path_buf()])
+    }
+
+    #[tokio::test]
+    async fn unreferenced_files_are_deleted_and_referenced_ones_kept() {
+        let root = TempDir::new();
+        let in_use = root.write("tiktok/chan/in_use.flv", b"video");
+        let orphan = root.write("tiktok/chan/orphan.mp4", b"video");
+
+        let result = usecase(&root, vec![in_use.clone()])
+            .sweep_orphan_files(OrphanFileSweepParams {
+                min_age: Duration::ZERO,
+                delete: true,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(result.scanned, 2);
+        assert_eq!(result.deleted, 1);
+        assert!(in_use.exists());
+        assert!(!orphan.exists());
+    }
+
+    #[tokio::test]
+    async fn report_only_and_recent_files_are_left_on_disk() {
+        let root = TempDir::new();
+        let orphan = root.write("tiktok/chan/orphan.mp4", b"video");
+        let disk = usecase(&root, Vec::new());
+
+        let reported = disk
+            .sweep_orphan_files(OrphanFileSweepParams {
+                min_age: Duration::ZERO,
+                delete: false,
+            })
+            .await
+            .unwrap();
+        let recent = disk
+            .sweep_orphan_files(OrphanFileSweepParams {
+                min_age: Duration::from_secs(3600),
+                delete: true,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(reported.orphaned, 1);
+        assert_eq!(reported.deleted, 0);
+        assert_eq!(recent.orphaned, 0);
+        assert!(orphan.exists());
+    }
+
+    #[test]
+    fn df_available_column_is_read_in_bytes() {
+        let output = "Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+                      /dev/sda1        102400000  92160000  10240000      90% /rec\n";
+
+        assert_eq!(parse_df_available_bytes(output).unwrap(), 10_485_760_000);
+    }
+}
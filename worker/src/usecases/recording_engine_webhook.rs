@@ -1,22 +1,31 @@
 use anyhow::{Context, Result, bail};
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use crates::domain;
 use domain::{
-    entities::recordings::RecordingTransmuxUpdateEntity,
+    entities::{
+        recording_engine_errors::{InsertRecordingEngineErrorEntity, RecordingEngineErrorEntity},
+        recording_engine_webhook_events::InsertRecordingEngineWebhookEventEntity,
+        recordings::{
+            RecordingEntity, RecordingLiveEndUpdateEntity, RecordingTransmuxUpdateEntity,
+        },
+    },
     repositories::recording_engine_webhook::RecordingEngineWebhookRepository,
     value_objects::{
-        enums::{platforms::Platform, recording_statuses::RecordingStatus},
+        enums::{
+            platforms::Platform, quota_enforcement_policies::QuotaEnforcementPolicy,
+            recording_statuses::RecordingStatus,
+        },
         recording_engine_webhook::{
-            RecordingEngineErrorWebhook, RecordingEngineLiveStartWebhook,
-            RecordingEngineTransmuxFinishWebhook,
+            RecordingEngineErrorWebhook, RecordingEngineFileFinishWebhook,
+            RecordingEngineLiveEndWebhook, RecordingEngineLiveStartWebhook,
+            RecordingEngineTransmuxFinishWebhook, WebhookEventClaim,
         },
-        recordings::InsertRecordingModel,
+        recordings::{FollowerRecordingCapacity, InsertRecordingModel, usage_month_start},
     },
 };
-use mp4::Mp4Reader;
 use std::{
-    fs::{self, File},
-    io::BufReader,
+    fs,
+    future::Future,
     path::{Component, Path, PathBuf},
     str::FromStr,
     sync::Arc,
@@ -25,15 +34,26 @@ use tokio::process::Command;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
-use crate::config::config_model::RecordingEnginePaths;
+use crate::{
+    config::config_model::{RecordingEngineErrorPolicy, RecordingEnginePaths},
+    media_probe::{self, MediaInfo},
+};
 use domain::repositories::job::JobRepository;
+use domain::repositories::recording_engine_control::RecordingEngineControl;
 use domain::repositories::storage::CoverStorageClient;
 
+/// How long a delivery may hold a webhook id before a retry is allowed to take it over.
+const WEBHOOK_CLAIM_TIMEOUT_MINUTES: i64 = 15;
+
 pub struct RecordingEngineWebhookUseCase {
     repository: Arc<dyn RecordingEngineWebhookRepository + Send + Sync>,
     job_repository: Arc<dyn JobRepository + Send + Sync>,
     cover_storage: Arc<dyn CoverStorageClient + Send + Sync>,
+    recording_engine_control: Arc<dyn RecordingEngineControl + Send + Sync>,
     recording_engine_paths: RecordingEnginePaths,
+    free_plan_id: Uuid,
+    quota_enforcement: QuotaEnforcementPolicy,
+    error_policy: RecordingEngineErrorPolicy,
 }
 
 impl RecordingEngineWebhookUseCase {
@@ -41,20 +61,40 @@ impl RecordingEngineWebhookUseCase {
         repository: Arc<dyn RecordingEngineWebhookRepository + Send + Sync>,
         job_repository: Arc<dyn JobRepository + Send + Sync>,
         cover_storage: Arc<dyn CoverStorageClient + Send + Sync>,
+        recording_engine_control: Arc<dyn RecordingEngineControl + Send + Sync>,
         recording_engine_paths: RecordingEnginePaths,
+        free_plan_id: Uuid,
+        quota_enforcement: QuotaEnforcementPolicy,
     ) -> Self {
         Self {
             repository,
             job_repository,
             cover_storage,
+            recording_engine_control,
             recording_engine_paths,
+            free_plan_id,
+            quota_enforcement,
+            error_policy: RecordingEngineErrorPolicy::default(),
         }
     }
 
+    pub fn with_error_policy(mut self, error_policy: RecordingEngineErrorPolicy) -> Self {
+        self.error_policy = error_policy;
+        self
+    }
+
     pub async fn handle_live_start(
         &self,
         payload: RecordingEngineLiveStartWebhook,
     ) -> Result<Uuid> {
+        let event = Self::webhook_event(payload.id, &payload.type_, payload.ts);
+        self.process_once(event, async {
+            self.process_live_start(payload).await.map(Some)
+        })
+        .await
+    }
+
+    async fn process_live_start(&self, payload: RecordingEngineLiveStartWebhook) -> Result<Uuid> {
         info!(
             payload_id = %payload.id,
             payload = ?payload,
@@ -99,13 +139,30 @@ impl RecordingEngineWebhookUseCase {
             .ok_or_else(|| anyhow::anyhow!("live_info is required"))?;
         let title = live_info.title.clone();
 
+        let followers = self
+            .repository
+            .list_follower_recording_capacity(live_account.id, self.free_plan_id)
+            .await
+            .map_err(|err| {
+                error!(
+                    live_account_id = %live_account.id,
+                    db_error = ?err,
+                    "live_start: failed to load follower recording capacity"
+                );
+                err
+            })?;
+        let skip_status = self.recording_skip_status(&followers).await;
+
         let insert_model = InsertRecordingModel {
             live_account_id: live_account.id,
             poster_storage_path: None,
             title,
         };
 
-        let insert_entity = insert_model.to_entity();
+        let mut insert_entity = insert_model.to_entity();
+        if let Some(status) = &skip_status {
+            insert_entity.status = status.to_string();
+        }
         let recording_id = self.repository.insert(insert_entity).await.map_err(|err| {
             error!(
                 platform = %platform,
@@ -115,13 +172,106 @@ impl RecordingEngineWebhookUseCase {
             );
             err
         })?;
-        info!(%recording_id, "live_start: recording inserted");
+
+        let Some(skip_status) = skip_status else {
+            info!(%recording_id, "live_start: recording inserted");
+            return Ok(recording_id);
+        };
+
+        warn!(
+            %recording_id,
+            live_account_id = %live_account.id,
+            follower_count = followers.len(),
+            status = %skip_status,
+            "live_start: no follower can take this recording; skipping recording"
+        );
+        // The output of a skipped recording is discarded on transmux_finish, so a failed stop
+        // only wastes recorder capacity.
+        if let Err(err) = self
+            .recording_engine_control
+            .stop_recording(&platform.to_string(), &channel)
+            .await
+        {
+            error!(
+                %recording_id,
+                platform = %platform,
+                channel,
+                error = ?err,
+                "live_start: failed to stop recording engine"
+            );
+        }
+
         Ok(recording_id)
     }
 
+    /// Status to record the live as when no follower can take it: no concurrent capacity, or,
+    /// under `stop_recording`, every follower with capacity is over a usage quota.
+    async fn recording_skip_status(
+        &self,
+        followers: &[FollowerRecordingCapacity],
+    ) -> Option<RecordingStatus> {
+        let with_capacity = followers
+            .iter()
+            .filter(|follower| follower.has_capacity())
+            .collect::<Vec<_>>();
+        if with_capacity.is_empty() {
+            return Some(RecordingStatus::SkippedConcurrencyLimit);
+        }
+        if self.quota_enforcement != QuotaEnforcementPolicy::StopRecording {
+            return None;
+        }
+
+        let month_start = usage_month_start(Utc::now());
+        for follower in with_capacity {
+            if !follower.has_quota() {
+                return None;
+            }
+
+            let usage = match self
+                .repository
+                .find_recording_usage(
+                    follower.user_id,
+                    i64::from(follower.retention_days.max(0)),
+                    month_start,
+                )
+                .await
+            {
+                Ok(usage) => usage,
+                Err(err) => {
+                    // Fail open: a usage lookup error should not cost anyone a recording.
+                    error!(
+                        user_id = %follower.user_id,
+                        db_error = ?err,
+                        "live_start: failed to load recording usage"
+                    );
+                    return None;
+                }
+            };
+
+            if !usage.reached_storage_limit(follower.max_storage_bytes)
+                && !usage.reached_recording_hours_limit(follower.max_recording_hours_per_month)
+            {
+                return None;
+            }
+        }
+
+        Some(RecordingStatus::SkippedQuotaExceeded)
+    }
+
     pub async fn handle_transmux_finish(
         &self,
         payload: RecordingEngineTransmuxFinishWebhook,
+    ) -> Result<Uuid> {
+        let event = Self::webhook_event(payload.id, &payload.type_, payload.ts);
+        self.process_once(event, async {
+            self.process_transmux_finish(payload).await.map(Some)
+        })
+        .await
+    }
+
+    async fn process_transmux_finish(
+        &self,
+        payload: RecordingEngineTransmuxFinishWebhook,
     ) -> Result<Uuid> {
         info!(
             payload_id = %payload.id,
@@ -144,24 +294,31 @@ impl RecordingEngineWebhookUseCase {
             Self::container_to_host_path(&storage_path_raw, &self.recording_engine_paths)?;
 
         let recording_id = if let Some(recording) = self
-            .repository
-            .find_recording_by_live_account_and_status(
-                platform_string.clone(),
-                channel.clone(),
-                RecordingStatus::LiveRecording,
-            )
-            .await
-            .map_err(|err| {
-                error!(
-                    platform = %platform_string,
-                    channel,
-                    db_error = ?err,
-                    "transmux_finish: failed to find live recording by status"
-                );
-                err
-            })? {
+            .find_unfinished_recording(&platform_string, &channel)
+            .await?
+        {
             recording.id
         } else {
+            if let Some(skipped) = self
+                .find_skipped_recording(&platform_string, &channel)
+                .await?
+            {
+                Self::discard_skipped_output(skipped.id, &storage_path);
+                return Ok(skipped.id);
+            }
+
+            if let Some(uploaded) = self
+                .find_raw_uploaded_recording(&platform_string, &channel, data.input.as_deref())
+                .await?
+            {
+                warn!(
+                    recording_id = %uploaded.id,
+                    path = %storage_path.display(),
+                    "transmux_finish: raw file was already uploaded after the transmux timeout; ignoring output"
+                );
+                return Ok(uploaded.id);
+            }
+
             let live_account = self
                 .repository
                 .find_live_account_by_platform_and_account_id(
@@ -214,32 +371,33 @@ impl RecordingEngineWebhookUseCase {
             new_id
         };
 
-        let duration_sec = if Self::is_mp4_path(&storage_path) {
-            match Self::read_mp4_duration_seconds(storage_path.clone()).await {
-                Ok(duration) => Some(duration),
-                Err(err) => {
-                    error!(
-                        path = %storage_path.display(),
-                        error = ?err,
-                        "failed to read duration for mp4 output"
-                    );
-                    None
-                }
+        self.finalize_recording(recording_id, &storage_path).await
+    }
+
+    /// Probes duration and quality, uploads a cover, marks the recording `waiting_upload` with
+    /// `storage_path` as its local file and enqueues the remux that precedes the upload.
+    async fn finalize_recording(&self, recording_id: Uuid, storage_path: &Path) -> Result<Uuid> {
+        let media = match media_probe::probe(storage_path).await {
+            Ok(media) => media,
+            Err(err) => {
+                error!(
+                    path = %storage_path.display(),
+                    error = ?err,
+                    "finalize_recording: failed to probe recording file"
+                );
+                MediaInfo::default()
             }
-        } else {
-            warn!(path = %storage_path.display(), "transmux output is not an mp4 file");
-            None
         };
 
         let poster_storage_path = self
-            .generate_and_upload_cover_from_video(recording_id, &storage_path)
+            .generate_and_upload_cover_from_video(recording_id, storage_path)
             .await
             .map_err(|err| {
                 error!(
                     %recording_id,
                     path = %storage_path.display(),
                     error = ?err,
-                    "transmux_finish: failed to generate/upload cover"
+                    "finalize_recording: failed to generate/upload cover"
                 );
                 err
             })?;
@@ -248,10 +406,16 @@ impl RecordingEngineWebhookUseCase {
         let path_str = storage_path.to_string_lossy().into_owned();
         let changeset = RecordingTransmuxUpdateEntity {
             storage_path: Some(path_str.clone()),
-            duration_sec,
+            duration_sec: media.duration_sec,
             status: RecordingStatus::WaitingUpload.to_string(),
             updated_at: Utc::now(),
             poster_storage_path: Some(Some(poster_storage_path)),
+            width: media.width,
+            height: media.height,
+            video_codec: media.video_codec,
+            audio_codec: media.audio_codec,
+            bitrate_bps: media.bitrate_bps,
+            fps: media.fps,
         };
 
         let updated_recording_id = self
@@ -262,30 +426,260 @@ impl RecordingEngineWebhookUseCase {
                 error!(
                     %recording_id,
                     db_error = ?err,
-                    "transmux_finish: failed to update recording status"
+                    "finalize_recording: failed to update recording status"
                 );
                 err
             })?;
 
-        // Enqueue upload job
+        // Remux to faststart mp4 first; the remux job enqueues the upload.
         self.job_repository
-            .enqueue_recording_upload_job(updated_recording_id, path_str)
+            .enqueue_recording_remux_job(updated_recording_id, path_str)
             .await
             .map_err(|err| {
                 error!(
                     %updated_recording_id,
                     job_error = ?err,
-                    "transmux_finish: failed to enqueue upload job"
+                    "finalize_recording: failed to enqueue remux job"
                 );
                 err
             })?;
 
-        info!(%updated_recording_id, "transmux_finish: enqueued upload job and updated recording");
+        info!(%updated_recording_id, "finalize_recording: enqueued remux job and updated recording");
+
+        if let Err(err) = self
+            .repository
+            .clear_engine_errors_for_recording(updated_recording_id)
+            .await
+        {
+            warn!(
+                recording_id = %updated_recording_id,
+                db_error = ?err,
+                "finalize_recording: failed to clear engine error streak"
+            );
+        }
+
+        Ok(updated_recording_id)
+    }
+
+    pub async fn handle_live_end(&self, payload: RecordingEngineLiveEndWebhook) -> Result<Uuid> {
+        let event = Self::webhook_event(payload.id, &payload.type_, payload.ts);
+        self.process_once(event, async {
+            self.process_live_end(payload).await.map(Some)
+        })
+        .await
+    }
+
+    async fn process_live_end(&self, payload: RecordingEngineLiveEndWebhook) -> Result<Uuid> {
+        info!(
+            payload_id = %payload.id,
+            payload = ?payload,
+            "handling live_end webhook"
+        );
+        let data = payload.data;
+        let platform = self.parse_platform(data.platform)?.to_string();
+        let channel = data
+            .channel
+            .ok_or_else(|| anyhow::anyhow!("channel is required"))?;
+
+        let Some(recording) = self.find_unfinished_recording(&platform, &channel).await? else {
+            return self.finished_recording_fallback(&platform, &channel).await;
+        };
+
+        let changeset = RecordingLiveEndUpdateEntity {
+            status: RecordingStatus::LiveEnd.to_string(),
+            ended_at: recording.ended_at.is_none().then_some(payload.ts),
+            duration_sec: None,
+            size_bytes: None,
+            storage_temp_path: None,
+            updated_at: Utc::now(),
+        };
+        self.update_live_end(recording.id, changeset, "live_end")
+            .await
+    }
+
+    pub async fn handle_file_finish(
+        &self,
+        payload: RecordingEngineFileFinishWebhook,
+    ) -> Result<Uuid> {
+        let event = Self::webhook_event(payload.id, &payload.type_, payload.ts);
+        self.process_once(event, async {
+            self.process_file_finish(payload).await.map(Some)
+        })
+        .await
+    }
+
+    async fn process_file_finish(&self, payload: RecordingEngineFileFinishWebhook) -> Result<Uuid> {
+        info!(
+            payload_id = %payload.id,
+            payload = ?payload,
+            "handling video_file_finish webhook"
+        );
+        let data = payload.data;
+        let platform = self.parse_platform(data.platform)?.to_string();
+        let channel = data
+            .channel
+            .ok_or_else(|| anyhow::anyhow!("channel is required"))?;
+        let raw_path = data
+            .path
+            .ok_or_else(|| anyhow::anyhow!("file path is required"))?;
+        let raw_path = Self::container_to_host_path(&raw_path, &self.recording_engine_paths)?;
+
+        let Some(recording) = self.find_unfinished_recording(&platform, &channel).await? else {
+            return self.finished_recording_fallback(&platform, &channel).await;
+        };
+
+        let changeset = RecordingLiveEndUpdateEntity {
+            status: RecordingStatus::LiveEnd.to_string(),
+            ended_at: recording.ended_at.is_none().then_some(payload.ts),
+            duration_sec: data
+                .duration
+                .filter(|duration| duration.is_finite() && *duration >= 0.0)
+                .map(|duration| duration.round().min(f64::from(i32::MAX)) as i32),
+            size_bytes: data.filesize.and_then(|size| i64::try_from(size).ok()),
+            storage_temp_path: Some(raw_path.to_string_lossy().into_owned()),
+            updated_at: Utc::now(),
+        };
+        self.update_live_end(recording.id, changeset, "video_file_finish")
+            .await
+    }
+
+    /// Uploads the raw file of recordings the engine finished but never transmuxed within
+    /// `timeout`; returns how many were handed to the upload queue.
+    pub async fn upload_untransmuxed_recordings(
+        &self,
+        timeout: Duration,
+        limit: i64,
+    ) -> Result<usize> {
+        let finished_before = Utc::now() - timeout;
+        let recordings = self
+            .repository
+            .list_untransmuxed_recordings(finished_before, limit)
+            .await?;
+
+        let mut uploaded = 0;
+        for recording in recordings {
+            let Some(raw_path) = recording.storage_temp_path.as_deref().map(PathBuf::from) else {
+                continue;
+            };
+
+            if !self
+                .repository
+                .claim_untransmuxed_recording(recording.id, finished_before)
+                .await?
+            {
+                continue;
+            }
+
+            if !raw_path.is_file() {
+                error!(
+                    recording_id = %recording.id,
+                    path = %raw_path.display(),
+                    "transmux_fallback: raw file is missing; marking recording failed"
+                );
+                self.reset_untransmuxed_recording(recording.id, RecordingStatus::Failed)
+                    .await;
+                continue;
+            }
+
+            warn!(
+                recording_id = %recording.id,
+                path = %raw_path.display(),
+                "transmux_fallback: no video_transmux_finish within timeout; uploading raw file"
+            );
+            match self.finalize_recording(recording.id, &raw_path).await {
+                Ok(_) => uploaded += 1,
+                Err(err) => {
+                    error!(
+                        recording_id = %recording.id,
+                        error = ?err,
+                        "transmux_fallback: failed to queue raw file; retrying after the next timeout"
+                    );
+                    self.reset_untransmuxed_recording(recording.id, RecordingStatus::LiveEnd)
+                        .await;
+                }
+            }
+        }
+
+        Ok(uploaded)
+    }
+
+    async fn reset_untransmuxed_recording(&self, recording_id: Uuid, status: RecordingStatus) {
+        let changeset = RecordingLiveEndUpdateEntity {
+            status: status.to_string(),
+            ended_at: None,
+            duration_sec: None,
+            size_bytes: None,
+            storage_temp_path: None,
+            updated_at: Utc::now(),
+        };
+        if let Err(err) = self
+            .repository
+            .update_live_end(recording_id, changeset)
+            .await
+        {
+            error!(
+                %recording_id,
+                db_error = ?err,
+                "transmux_fallback: failed to reset recording status"
+            );
+        }
+    }
+
+    async fn update_live_end(
+        &self,
+        recording_id: Uuid,
+        changeset: RecordingLiveEndUpdateEntity,
+        label: &str,
+    ) -> Result<Uuid> {
+        let updated_recording_id = self
+            .repository
+            .update_live_end(recording_id, changeset)
+            .await
+            .map_err(|err| {
+                error!(
+                    %recording_id,
+                    db_error = ?err,
+                    "{label}: failed to mark recording live_end"
+                );
+                err
+            })?;
 
+        info!(%updated_recording_id, "{label}: recording marked live_end");
         Ok(updated_recording_id)
     }
 
+    /// Answer for end-of-live webhooks whose recording has already moved on: the skipped
+    /// recording if the live was never recorded, otherwise not found.
+    async fn finished_recording_fallback(&self, platform: &str, channel: &str) -> Result<Uuid> {
+        if let Some(skipped) = self.find_skipped_recording(platform, channel).await? {
+            return Ok(skipped.id);
+        }
+
+        warn!(
+            platform,
+            channel, "recording not found in live_recording or live_end"
+        );
+        bail!(
+            "Recording not found for platform {} and channel {} in status live_recording or live_end",
+            platform,
+            channel
+        )
+    }
+
     pub async fn handle_uploading_status(
+        &self,
+        payload: RecordingEngineFileFinishWebhook,
+    ) -> Result<Uuid> {
+        let event = Self::webhook_event(payload.id, &payload.type_, payload.ts);
+        self.process_once(event, async {
+            self.process_uploading_status(payload.data.platform, payload.data.channel)
+                .await
+                .map(Some)
+        })
+        .await
+    }
+
+    async fn process_uploading_status(
         &self,
         platform: Option<String>,
         channel: Option<String>,
@@ -343,7 +737,110 @@ impl RecordingEngineWebhookUseCase {
     }
 
     pub async fn handle_error(&self, payload: RecordingEngineErrorWebhook) -> Result<Uuid> {
+        let event = Self::webhook_event(payload.id, &payload.type_, payload.ts);
+        self.process_once(event, self.process_error(payload)).await
+    }
+
+    /// Stores the error against its channel and links it to the channel's open recording.
+    /// Once the channel is persistently failing its live account flips to `error` and an open
+    /// recording with no raw file to salvage is marked `failed`.
+    async fn process_error(&self, payload: RecordingEngineErrorWebhook) -> Result<Option<Uuid>> {
+        Self::log_engine_error(&payload);
         let data = payload.data;
+        let (Some(platform), Some(channel)) = (data.platform, data.channel) else {
+            warn!(
+                payload_id = %payload.id,
+                "error: platform or channel missing; error not stored"
+            );
+            return Ok(None);
+        };
+
+        let live_account_id = self
+            .repository
+            .find_live_account_by_platform_and_account_id(platform.clone(), channel.clone())
+            .await?
+            .map(|live_account| live_account.id);
+        let recording = self.find_unfinished_recording(&platform, &channel).await?;
+
+        let stored = self
+            .repository
+            .record_engine_error(
+                InsertRecordingEngineErrorEntity {
+                    platform,
+                    channel,
+                    live_account_id,
+                    recording_id: recording.as_ref().map(|recording| recording.id),
+                    last_error: data.error.unwrap_or_else(|| "missing error".to_string()),
+                    first_error_at: payload.ts,
+                    last_error_at: payload.ts,
+                },
+                Utc::now() - Duration::seconds(self.error_policy.streak_window_secs),
+            )
+            .await?;
+
+        // The engine is still talking about this channel, so its open recording is not stale.
+        if let Some(recording) = &recording
+            && recording.status == RecordingStatus::LiveRecording.to_string()
+        {
+            self.repository.touch_live_recording(recording.id).await?;
+        }
+
+        if stored.consecutive_errors >= self.error_policy.failing_threshold {
+            self.mark_channel_failing(&stored, recording.as_ref(), payload.ts)
+                .await?;
+        }
+
+        Ok(recording.map(|recording| recording.id))
+    }
+
+    async fn mark_channel_failing(
+        &self,
+        stored: &RecordingEngineErrorEntity,
+        recording: Option<&RecordingEntity>,
+        ts: DateTime<Utc>,
+    ) -> Result<()> {
+        warn!(
+            platform = %stored.platform,
+            channel = %stored.channel,
+            consecutive_errors = stored.consecutive_errors,
+            "error: channel is persistently failing"
+        );
+
+        if let Some(live_account_id) = stored.live_account_id {
+            self.repository
+                .mark_live_account_error(live_account_id)
+                .await?;
+        }
+
+        if let Some(recording) = recording
+            && recording.storage_temp_path.is_none()
+        {
+            let changeset = RecordingLiveEndUpdateEntity {
+                status: RecordingStatus::Failed.to_string(),
+                ended_at: recording.ended_at.is_none().then_some(ts),
+                duration_sec: None,
+                size_bytes: None,
+                storage_temp_path: None,
+                updated_at: Utc::now(),
+            };
+            self.repository
+                .update_live_end(recording.id, changeset)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Channels currently in an error streak, longest streak first.
+    pub async fn list_failing_channels(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<RecordingEngineErrorEntity>> {
+        self.repository.list_failing_channels(limit).await
+    }
+
+    fn log_engine_error(payload: &RecordingEngineErrorWebhook) {
+        let data = &payload.data;
         let platform = data.platform.as_deref().unwrap_or("unknown");
         let channel = data.channel.as_deref().unwrap_or("unknown");
         let error_message = data.error.as_deref().unwrap_or("missing error");
@@ -357,67 +854,283 @@ impl RecordingEngineWebhookUseCase {
             error = %error_message,
             "recording_engine_webhook: error received"
         );
+    }
 
-        Ok(payload.id)
+    fn webhook_event(
+        id: Uuid,
+        type_: &str,
+        ts: DateTime<Utc>,
+    ) -> InsertRecordingEngineWebhookEventEntity {
+        InsertRecordingEngineWebhookEventEntity {
+            id,
+            type_: type_.to_string(),
+            ts,
+        }
     }
 
-    fn parse_platform(&self, platform: Option<String>) -> Result<Platform> {
-        let platform_str = platform.ok_or_else(|| {
-            warn!("webhook: platform is required but missing in payload");
-            anyhow::anyhow!("platform is required")
-        })?;
-        Platform::from_str(&platform_str).map_err(|_| {
-            warn!(
-                platform = %platform_str,
-                "webhook: unsupported platform received"
+    /// Runs `process` once per webhook id. A retried delivery gets the first delivery's
+    /// answer, the recording id or, for webhooks without one, the webhook id itself.
+    async fn process_once(
+        &self,
+        event: InsertRecordingEngineWebhookEventEntity,
+        process: impl Future<Output = Result<Option<Uuid>>>,
+    ) -> Result<Uuid> {
+        let event_id = event.id;
+        let event_type = event.type_.clone();
+        let stale_before = Utc::now() - Duration::minutes(WEBHOOK_CLAIM_TIMEOUT_MINUTES);
+
+        let claim = self
+            .repository
+            .claim_webhook_event(event, stale_before)
+            .await
+            .map_err(|err| {
+                error!(
+                    %event_id,
+                    event_type,
+                    db_error = ?err,
+                    "recording_engine_webhook: failed to claim webhook id"
+                );
+                err
+            })?;
+
+        match claim {
+            WebhookEventClaim::Claimed => {}
+            WebhookEventClaim::Processed { recording_id } => {
+                info!(
+                    %event_id,
+                    event_type,
+                    recording_id = ?recording_id,
+                    "recording_engine_webhook: duplicate webhook; returning original result"
+                );
+                return Ok(recording_id.unwrap_or(event_id));
+            }
+            WebhookEventClaim::InProgress => {
+                warn!(
+                    %event_id,
+                    event_type,
+                    "recording_engine_webhook: duplicate webhook while first delivery is in progress"
+                );
+                bail!("webhook {} is already being processed", event_id);
+            }
+        }
+
+        let recording_id = match process.await {
+            Ok(recording_id) => recording_id,
+            Err(err) => {
+                if let Err(release_err) = self.repository.release_webhook_event(event_id).await {
+                    error!(
+                        %event_id,
+                        event_type,
+                        db_error = ?release_err,
+                        "recording_engine_webhook: failed to release webhook claim"
+                    );
+                }
+                return Err(err);
+            }
+        };
+
+        // The work is done either way; an unrecorded completion only means a later retry,
+        // once the claim goes stale, is processed again.
+        if let Err(err) = self
+            .repository
+            .complete_webhook_event(event_id, recording_id)
+            .await
+        {
+            error!(
+                %event_id,
+                event_type,
+                db_error = ?err,
+                "recording_engine_webhook: failed to record processed webhook"
             );
-            anyhow::anyhow!("Unsupported platform: {}", platform_str)
-        })
+        }
+
+        Ok(recording_id.unwrap_or(event_id))
     }
 
-    fn is_mp4_path(path: &Path) -> bool {
-        path.extension()
-            .and_then(|ext| ext.to_str())
-            .map(|ext| ext.eq_ignore_ascii_case("mp4"))
-            .unwrap_or(false)
+    /// Recording of the live still being recorded or finished but not yet transmuxed.
+    async fn find_unfinished_recording(
+        &self,
+        platform: &str,
+        channel: &str,
+    ) -> Result<Option<RecordingEntity>> {
+        for status in [RecordingStatus::LiveRecording, RecordingStatus::LiveEnd] {
+            let recording = self
+                .repository
+                .find_recording_by_live_account_and_status(
+                    platform.to_string(),
+                    channel.to_string(),
+                    status.clone(),
+                )
+                .await
+                .map_err(|err| {
+                    error!(
+                        platform,
+                        channel,
+                        status = %status,
+                        db_error = ?err,
+                        "webhook: failed to find recording by status"
+                    );
+                    err
+                })?;
+            if recording.is_some() {
+                return Ok(recording);
+            }
+        }
+
+        Ok(None)
     }
 
-    async fn read_mp4_duration_seconds(path: PathBuf) -> Result<i32> {
-        tokio::task::spawn_blocking(move || {
-            let file = File::open(&path)?;
-            let size = file.metadata()?.len();
-            let reader = BufReader::new(file);
-            let mp4 = Mp4Reader::read_header(reader, size)?;
-            let duration = mp4.duration().as_secs_f64().round() as i64;
+    /// Latest recording if its raw file, `input` of a late transmux, was already uploaded by
+    /// the transmux fallback.
+    async fn find_raw_uploaded_recording(
+        &self,
+        platform: &str,
+        channel: &str,
+        input: Option<&str>,
+    ) -> Result<Option<RecordingEntity>> {
+        let Some(input) = input else {
+            return Ok(None);
+        };
+        let Ok(raw_path) = Self::container_to_host_path(input, &self.recording_engine_paths) else {
+            return Ok(None);
+        };
+        let raw_path = raw_path.to_string_lossy();
+
+        let latest = self
+            .repository
+            .find_latest_recording_by_live_account(platform.to_string(), channel.to_string())
+            .await?;
 
-            i32::try_from(duration).context("mp4 duration seconds exceed i32")
+        Ok(latest.filter(|recording| {
+            recording.storage_temp_path.as_deref() == Some(raw_path.as_ref())
+                && recording.storage_path == recording.storage_temp_path
+        }))
+    }
+
+    async fn find_skipped_recording(
+        &self,
+        platform: &str,
+        channel: &str,
+    ) -> Result<Option<RecordingEntity>> {
+        let latest = self
+            .repository
+            .find_latest_recording_by_live_account(platform.to_string(), channel.to_string())
+            .await
+            .map_err(|err| {
+                error!(
+                    platform,
+                    channel,
+                    db_error = ?err,
+                    "webhook: failed to load latest recording"
+                );
+                err
+            })?;
+
+        Ok(latest.filter(|recording| {
+            recording.status == RecordingStatus::SkippedConcurrencyLimit.to_string()
+                || recording.status == RecordingStatus::SkippedQuotaExceeded.to_string()
+        }))
+    }
+
+    fn discard_skipped_output(recording_id: Uuid, storage_path: &Path) {
+        match fs::remove_file(storage_path) {
+            Ok(()) => info!(
+                %recording_id,
+                path = %storage_path.display(),
+                "transmux_finish: discarded output of skipped recording"
+            ),
+            Err(err) => warn!(
+                %recording_id,
+                path = %storage_path.display(),
+                "transmux_finish: failed to remove output of skipped recording: {err:?}"
+            ),
+        }
+    }
+
+    fn parse_platform(&self, platform: Option<String>) -> Result<Platform> {
+        let platform_str = platform.ok_or_else(|| {
+            warn!("webhook: platform is required but missing in payload");
+            anyhow::anyhow!("platform is required")
+        })?;
+        Platform::from_str(&platform_str).map_err(|_| {
+            warn!(
+                platform = %platform_str,
+                "webhook: unsupported platform received"
+            );
+            anyhow::anyhow!("Unsupported platform: {}", platform_str)
         })
-        .await
-        .context("failed to join duration reader task")?
     }
 
-    fn container_to_host_path(container_path: &str, paths: &RecordingEnginePaths) -> Result<PathBuf> {
-        let prefix = Path::new(paths.container_prefix.as_str());
+    /// Maps an engine path onto this host through the longest matching container prefix.
+    /// The result must stay inside that mapping's host root once symlinks are resolved.
+    fn container_to_host_path(
+        container_path: &str,
+        paths: &RecordingEnginePaths,
+    ) -> Result<PathBuf> {
         let container_path = Path::new(container_path);
-        let relative = container_path.strip_prefix(prefix).map_err(|_| {
-            anyhow::anyhow!(
-                "transmux output path is not under configured container prefix {}: {}",
-                paths.container_prefix,
-                container_path.display()
-            )
-        })?;
+        let (mapping, relative) = paths
+            .mappings
+            .iter()
+            .filter_map(|mapping| {
+                container_path
+                    .strip_prefix(&mapping.container_prefix)
+                    .ok()
+                    .map(|relative| (mapping, relative))
+            })
+            .max_by_key(|(mapping, _)| mapping.container_prefix.len())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "recording path is not under configured container prefix {}: {}",
+                    paths
+                        .mappings
+                        .iter()
+                        .map(|mapping| mapping.container_prefix.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    container_path.display()
+                )
+            })?;
 
         if relative
             .components()
             .any(|component| matches!(component, Component::ParentDir))
         {
             bail!(
-                "transmux output path contains invalid traversal segments: {}",
+                "recording path contains invalid traversal segments: {}",
                 container_path.display()
             );
         }
 
-        Ok(Path::new("/rec").join(relative))
+        let host_path = mapping.host_root.join(relative);
+        Self::ensure_inside_host_root(&host_path, &mapping.host_root)?;
+        Ok(host_path)
+    }
+
+    /// Resolves symlinks along the deepest existing part of `host_path`, which may not be
+    /// written yet, and rejects paths that escape `host_root`.
+    fn ensure_inside_host_root(host_path: &Path, host_root: &Path) -> Result<()> {
+        let canonical_root = host_root.canonicalize().with_context(|| {
+            format!(
+                "host recording root {} is not accessible",
+                host_root.display()
+            )
+        })?;
+        let existing = host_path
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .unwrap_or(host_root);
+        let canonical = existing
+            .canonicalize()
+            .with_context(|| format!("failed to resolve {}", existing.display()))?;
+
+        if !canonical.starts_with(&canonical_root) {
+            bail!(
+                "recording path resolves outside host root {}: {}",
+                host_root.display(),
+                host_path.display()
+            );
+        }
+        Ok(())
     }
 
     async fn generate_and_upload_cover_from_video(
@@ -547,46 +1260,779 @@ impl RecordingEngineWebhookUseCase {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::path::PathBuf;
+    use crate::{
+        config::config_model::RecordingPathMapping,
+        usecases::test_support::{self, TempDir},
+    };
+    use domain::{
+        entities::live_accounts::LiveAccountEntity,
+        repositories::{
+            job::MockJobRepository, recording_engine_control::MockRecordingEngineControl,
+            recording_engine_webhook::MockRecordingEngineWebhookRepository,
+            storage::MockCoverStorageClient,
+        },
+        value_objects::{
+            recording_engine_webhook::{
+                EndData, ErrorData, FileFinishData, LiveInfo, StartData, TransmuxFinishData,
+            },
+            recordings::RecordingUsage,
+        },
+    };
 
-    fn test_paths() -> RecordingEnginePaths {
+    /// Maps `/app/rec` onto `host_root`; path mapping requires it to exist.
+    fn test_paths(host_root: &Path) -> RecordingEnginePaths {
         RecordingEnginePaths {
-            container_prefix: "/app/rec".to_string(),
+            mappings: vec![RecordingPathMapping {
+                container_prefix: "/app/rec".to_string(),
+                host_root: host_root.to_path_buf(),
+            }],
+        }
+    }
+
+    fn live_start_payload() -> RecordingEngineLiveStartWebhook {
+        RecordingEngineLiveStartWebhook {
+            id: Uuid::new_v4(),
+            ts: Utc::now(),
+            type_: "live_start".to_string(),
+            data: StartData {
+                platform: Some("tiktok".to_string()),
+                channel: Some("chan".to_string()),
+                url: None,
+                live_info: Some(LiveInfo {
+                    uid: None,
+                    uname: None,
+                    avatar: None,
+                    title: Some("evening stream".to_string()),
+                    cover: None,
+                    categories: None,
+                    status: None,
+                    live_id: None,
+                }),
+            },
+        }
+    }
+
+    fn live_account_repository(
+        followers: Vec<FollowerRecordingCapacity>,
+        expected_status: RecordingStatus,
+    ) -> MockRecordingEngineWebhookRepository {
+        let live_account = live_account();
+        let live_account_id = live_account.id;
+
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        repository
+            .expect_find_live_account_by_platform_and_account_id()
+            .returning(move |_, _| {
+                let live_account = live_account.clone();
+                Box::pin(async move { Ok(Some(live_account)) })
+            });
+        repository
+            .expect_list_follower_recording_capacity()
+            .withf(move |id, free_plan_id| *id == live_account_id && free_plan_id.is_nil())
+            .returning(move |_, _| {
+                let followers = followers.clone();
+                Box::pin(async move { Ok(followers) })
+            });
+        repository
+            .expect_insert()
+            .withf(move |entity| entity.status == expected_status.to_string())
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(Uuid::new_v4()) }));
+        expect_first_delivery(&mut repository);
+        repository
+    }
+
+    fn expect_first_delivery(repository: &mut MockRecordingEngineWebhookRepository) {
+        repository
+            .expect_claim_webhook_event()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(WebhookEventClaim::Claimed) }));
+        repository
+            .expect_complete_webhook_event()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+    }
+
+    /// A repository that has already processed every webhook; any other call fails the test.
+    fn processed_repository(recording_id: Option<Uuid>) -> MockRecordingEngineWebhookRepository {
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        repository
+            .expect_claim_webhook_event()
+            .times(1)
+            .returning(move |_, _| {
+                Box::pin(async move { Ok(WebhookEventClaim::Processed { recording_id }) })
+            });
+        repository
+    }
+
+    fn transmux_finish_payload() -> RecordingEngineTransmuxFinishWebhook {
+        RecordingEngineTransmuxFinishWebhook {
+            id: Uuid::new_v4(),
+            ts: Utc::now(),
+            type_: "video_transmux_finish".to_string(),
+            data: TransmuxFinishData {
+                platform: Some("tiktok".to_string()),
+                channel: Some("chan".to_string()),
+                input: Some("/app/rec/tiktok/chan/video.flv".to_string()),
+                output: Some("/app/rec/tiktok/chan/video.mp4".to_string()),
+            },
         }
     }
 
+    fn file_finish_payload() -> RecordingEngineFileFinishWebhook {
+        RecordingEngineFileFinishWebhook {
+            id: Uuid::new_v4(),
+            ts: Utc::now(),
+            type_: "video_file_finish".to_string(),
+            data: FileFinishData {
+                platform: Some("tiktok".to_string()),
+                channel: Some("chan".to_string()),
+                path: None,
+                filesize: None,
+                duration: None,
+            },
+        }
+    }
+
+    fn error_payload() -> RecordingEngineErrorWebhook {
+        RecordingEngineErrorWebhook {
+            id: Uuid::new_v4(),
+            ts: Utc::now(),
+            type_: "error".to_string(),
+            data: ErrorData {
+                platform: Some("tiktok".to_string()),
+                channel: Some("chan".to_string()),
+                error: Some("connection reset".to_string()),
+            },
+        }
+    }
+
+    fn recording(ended_at: Option<DateTime<Utc>>) -> RecordingEntity {
+        RecordingEntity {
+            ended_at,
+            ..test_support::recording(RecordingStatus::LiveRecording)
+        }
+    }
+
+    fn live_account() -> LiveAccountEntity {
+        LiveAccountEntity {
+            id: Uuid::new_v4(),
+            platform: "tiktok".to_string(),
+            account_id: "chan".to_string(),
+            canonical_url: "https://www.tiktok.com/@chan/live".to_string(),
+            status: "synced".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    /// Repository for an error webhook on a channel with an open `live_recording` recording,
+    /// storing the error as the `consecutive_errors`-th of its streak.
+    fn engine_error_repository(
+        open: RecordingEntity,
+        live_account_id: Uuid,
+        consecutive_errors: i32,
+    ) -> MockRecordingEngineWebhookRepository {
+        let recording_id = open.id;
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        expect_first_delivery(&mut repository);
+        repository
+            .expect_find_live_account_by_platform_and_account_id()
+            .returning(move |_, _| {
+                let mut live_account = live_account();
+                live_account.id = live_account_id;
+                Box::pin(async move { Ok(Some(live_account)) })
+            });
+        repository
+            .expect_find_recording_by_live_account_and_status()
+            .withf(|_, _, status| *status == RecordingStatus::LiveRecording)
+            .returning(move |_, _, _| {
+                let open = open.clone();
+                Box::pin(async move { Ok(Some(open)) })
+            });
+        repository
+            .expect_touch_live_recording()
+            .withf(move |id| *id == recording_id)
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        repository
+            .expect_record_engine_error()
+            .withf(move |error, _| {
+                error.platform == "tiktok"
+                    && error.channel == "chan"
+                    && error.live_account_id == Some(live_account_id)
+                    && error.recording_id == Some(recording_id)
+                    && error.last_error == "connection reset"
+            })
+            .times(1)
+            .returning(move |error, _| {
+                Box::pin(async move {
+                    Ok(RecordingEngineErrorEntity {
+                        platform: error.platform,
+                        channel: error.channel,
+                        live_account_id: error.live_account_id,
+                        recording_id: error.recording_id,
+                        last_error: error.last_error,
+                        error_count: consecutive_errors,
+                        consecutive_errors,
+                        first_error_at: error.first_error_at,
+                        last_error_at: error.last_error_at,
+                        created_at: Utc::now(),
+                        updated_at: Utc::now(),
+                    })
+                })
+            });
+        repository
+    }
+
+    fn follower(
+        max_concurrent_recordings: i32,
+        active_recordings: i64,
+    ) -> FollowerRecordingCapacity {
+        FollowerRecordingCapacity {
+            user_id: Uuid::new_v4(),
+            max_concurrent_recordings,
+            active_recordings,
+            retention_days: 7,
+            max_storage_bytes: None,
+            max_recording_hours_per_month: None,
+        }
+    }
+
+    /// A use case whose host root is the system temp dir; tests that resolve recording files
+    /// pass their own root to `usecase_with_host_root`.
+    fn usecase(
+        repository: MockRecordingEngineWebhookRepository,
+        control: MockRecordingEngineControl,
+        quota_enforcement: QuotaEnforcementPolicy,
+    ) -> RecordingEngineWebhookUseCase {
+        usecase_with_host_root(
+            repository,
+            control,
+            quota_enforcement,
+            &std::env::temp_dir(),
+        )
+    }
+
+    fn usecase_with_host_root(
+        repository: MockRecordingEngineWebhookRepository,
+        control: MockRecordingEngineControl,
+        quota_enforcement: QuotaEnforcementPolicy,
+        host_root: &Path,
+    ) -> RecordingEngineWebhookUseCase {
+        RecordingEngineWebhookUseCase::new(
+            Arc::new(repository),
+            Arc::new(MockJobRepository::new()),
+            Arc::new(MockCoverStorageClient::new()),
+            Arc::new(control),
+            test_paths(host_root),
+            Uuid::nil(),
+            quota_enforcement,
+        )
+        .with_error_policy(RecordingEngineErrorPolicy {
+            failing_threshold: 3,
+            streak_window_secs: 86_400,
+        })
+    }
+
+    #[tokio::test]
+    async fn live_start_records_when_any_follower_has_capacity() {
+        let followers = vec![follower(1, 1), follower(3, 2)];
+        let repository = live_account_repository(followers, RecordingStatus::LiveRecording);
+        let mut control = MockRecordingEngineControl::new();
+        control.expect_stop_recording().never();
+
+        usecase(repository, control, QuotaEnforcementPolicy::Warn)
+            .handle_live_start(live_start_payload())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn live_start_skips_and_stops_engine_when_followers_are_at_limit() {
+        let followers = vec![follower(1, 1)];
+        let repository =
+            live_account_repository(followers, RecordingStatus::SkippedConcurrencyLimit);
+        let mut control = MockRecordingEngineControl::new();
+        control
+            .expect_stop_recording()
+            .withf(|platform, channel| platform == "tiktok" && channel == "chan")
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        usecase(repository, control, QuotaEnforcementPolicy::Warn)
+            .handle_live_start(live_start_payload())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn live_start_skips_accounts_without_active_followers() {
+        let repository =
+            live_account_repository(Vec::new(), RecordingStatus::SkippedConcurrencyLimit);
+        let mut control = MockRecordingEngineControl::new();
+        control
+            .expect_stop_recording()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Err(anyhow::anyhow!("engine unreachable")) }));
+
+        usecase(repository, control, QuotaEnforcementPolicy::Warn)
+            .handle_live_start(live_start_payload())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn live_start_skips_when_followers_with_capacity_are_over_quota() {
+        let mut over_quota = follower(1, 0);
+        over_quota.max_recording_hours_per_month = Some(10);
+        let mut repository =
+            live_account_repository(vec![over_quota], RecordingStatus::SkippedQuotaExceeded);
+        repository
+            .expect_find_recording_usage()
+            .times(1)
+            .returning(|_, _, _| {
+                Box::pin(async {
+                    Ok(RecordingUsage {
+                        storage_bytes: 0,
+                        recorded_seconds: 10 * 3600,
+                    })
+                })
+            });
+        let mut control = MockRecordingEngineControl::new();
+        control
+            .expect_stop_recording()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        usecase(repository, control, QuotaEnforcementPolicy::StopRecording)
+            .handle_live_start(live_start_payload())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn live_start_retry_returns_original_recording_without_inserting() {
+        let recording_id = Uuid::new_v4();
+        let mut control = MockRecordingEngineControl::new();
+        control.expect_stop_recording().never();
+
+        let answered = usecase(
+            processed_repository(Some(recording_id)),
+            control,
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_live_start(live_start_payload())
+        .await
+        .unwrap();
+
+        assert_eq!(answered, recording_id);
+    }
+
+    #[tokio::test]
+    async fn transmux_finish_retry_returns_original_recording_without_enqueuing() {
+        let recording_id = Uuid::new_v4();
+
+        let answered = usecase(
+            processed_repository(Some(recording_id)),
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_transmux_finish(transmux_finish_payload())
+        .await
+        .unwrap();
+
+        assert_eq!(answered, recording_id);
+    }
+
+    #[tokio::test]
+    async fn uploading_status_retry_returns_original_recording() {
+        let recording_id = Uuid::new_v4();
+
+        let answered = usecase(
+            processed_repository(Some(recording_id)),
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_uploading_status(file_finish_payload())
+        .await
+        .unwrap();
+
+        assert_eq!(answered, recording_id);
+    }
+
+    #[tokio::test]
+    async fn error_retry_returns_webhook_id() {
+        let payload = error_payload();
+        let payload_id = payload.id;
+
+        let answered = usecase(
+            processed_repository(None),
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_error(payload)
+        .await
+        .unwrap();
+
+        assert_eq!(answered, payload_id);
+    }
+
+    #[tokio::test]
+    async fn retry_during_first_delivery_is_rejected() {
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        repository
+            .expect_claim_webhook_event()
+            .returning(|_, _| Box::pin(async { Ok(WebhookEventClaim::InProgress) }));
+
+        let err = usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_transmux_finish(transmux_finish_payload())
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("already being processed"));
+    }
+
+    #[tokio::test]
+    async fn failed_processing_releases_claim_for_retry() {
+        let mut payload = live_start_payload();
+        payload.data.platform = Some("myspace".to_string());
+        let payload_id = payload.id;
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        repository
+            .expect_claim_webhook_event()
+            .returning(|_, _| Box::pin(async { Ok(WebhookEventClaim::Claimed) }));
+        repository
+            .expect_release_webhook_event()
+            .withf(move |id| *id == payload_id)
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        repository.expect_complete_webhook_event().never();
+
+        usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_live_start(payload)
+        .await
+        .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn live_end_marks_recording_live_end() {
+        let payload = RecordingEngineLiveEndWebhook {
+            id: Uuid::new_v4(),
+            ts: Utc::now(),
+            type_: "live_end".to_string(),
+            data: EndData {
+                platform: Some("tiktok".to_string()),
+                channel: Some("chan".to_string()),
+                url: None,
+                live_info: None,
+            },
+        };
+        let ended_at = payload.ts;
+        let live = recording(None);
+        let recording_id = live.id;
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        expect_first_delivery(&mut repository);
+        repository
+            .expect_find_recording_by_live_account_and_status()
+            .withf(|_, _, status| *status == RecordingStatus::LiveRecording)
+            .returning(move |_, _, _| {
+                let live = live.clone();
+                Box::pin(async move { Ok(Some(live)) })
+            });
+        repository
+            .expect_update_live_end()
+            .withf(move |id, changeset| {
+                *id == recording_id
+                    && changeset.status == RecordingStatus::LiveEnd.to_string()
+                    && changeset.ended_at == Some(ended_at)
+                    && changeset.storage_temp_path.is_none()
+            })
+            .times(1)
+            .returning(|id, _| Box::pin(async move { Ok(id) }));
+
+        let updated = usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_live_end(payload)
+        .await
+        .unwrap();
+
+        assert_eq!(updated, recording_id);
+    }
+
+    #[tokio::test]
+    async fn file_finish_records_size_duration_and_raw_path() {
+        let host_root = TempDir::new();
+        let raw_path = host_root
+            .path()
+            .join("tiktok/chan/video.flv")
+            .to_string_lossy()
+            .into_owned();
+        let mut payload = file_finish_payload();
+        payload.data.path = Some("/app/rec/tiktok/chan/video.flv".to_string());
+        payload.data.filesize = Some(1_048_576);
+        payload.data.duration = Some(61.6);
+        let ended = recording(Some(Utc::now()));
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        expect_first_delivery(&mut repository);
+        repository
+            .expect_find_recording_by_live_account_and_status()
+            .returning(move |_, _, status| {
+                let recording = (status == RecordingStatus::LiveEnd).then(|| ended.clone());
+                Box::pin(async move { Ok(recording) })
+            });
+        repository
+            .expect_update_live_end()
+            .withf(move |_, changeset| {
+                changeset.status == RecordingStatus::LiveEnd.to_string()
+                    && changeset.ended_at.is_none()
+                    && changeset.size_bytes == Some(1_048_576)
+                    && changeset.duration_sec == Some(62)
+                    && changeset.storage_temp_path.as_deref() == Some(raw_path.as_str())
+            })
+            .times(1)
+            .returning(|id, _| Box::pin(async move { Ok(id) }));
+
+        usecase_with_host_root(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+            host_root.path(),
+        )
+        .handle_file_finish(payload)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn transmux_fallback_fails_recording_whose_raw_file_is_missing() {
+        let mut untransmuxed = recording(Some(Utc::now()));
+        untransmuxed.storage_temp_path = Some("/rec/tiktok/chan/missing.flv".to_string());
+        let recording_id = untransmuxed.id;
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        repository
+            .expect_list_untransmuxed_recordings()
+            .returning(move |_, _| {
+                let untransmuxed = untransmuxed.clone();
+                Box::pin(async move { Ok(vec![untransmuxed]) })
+            });
+        repository
+            .expect_claim_untransmuxed_recording()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(true) }));
+        repository
+            .expect_update_live_end()
+            .withf(move |id, changeset| {
+                *id == recording_id && changeset.status == RecordingStatus::Failed.to_string()
+            })
+            .times(1)
+            .returning(|id, _| Box::pin(async move { Ok(id) }));
+
+        let uploaded = usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .upload_untransmuxed_recordings(Duration::hours(1), 10)
+        .await
+        .unwrap();
+
+        assert_eq!(uploaded, 0);
+    }
+
+    #[tokio::test]
+    async fn transmux_fallback_skips_recording_claimed_elsewhere() {
+        let host_root = TempDir::new();
+        let raw_path = host_root.write("video.flv", b"flv");
+        let mut untransmuxed = recording(Some(Utc::now()));
+        untransmuxed.storage_temp_path = Some(raw_path.to_string_lossy().into_owned());
+        let mut repository = MockRecordingEngineWebhookRepository::new();
+        repository
+            .expect_list_untransmuxed_recordings()
+            .returning(move |_, _| {
+                let untransmuxed = untransmuxed.clone();
+                Box::pin(async move { Ok(vec![untransmuxed]) })
+            });
+        repository
+            .expect_claim_untransmuxed_recording()
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(false) }));
+        repository.expect_update_live_end().never();
+
+        let uploaded = usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .upload_untransmuxed_recordings(Duration::hours(1), 10)
+        .await
+        .unwrap();
+
+        assert_eq!(uploaded, 0);
+    }
+
+    #[tokio::test]
+    async fn error_is_stored_against_channel_and_open_recording() {
+        let open = recording(None);
+        let recording_id = open.id;
+        let mut repository = engine_error_repository(open, Uuid::new_v4(), 2);
+        repository.expect_mark_live_account_error().never();
+        repository.expect_update_live_end().never();
+
+        let linked = usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_error(error_payload())
+        .await
+        .unwrap();
+
+        assert_eq!(linked, recording_id);
+    }
+
+    #[tokio::test]
+    async fn persistent_errors_flip_live_account_and_fail_open_recording() {
+        let open = recording(None);
+        let recording_id = open.id;
+        let live_account_id = Uuid::new_v4();
+        let mut repository = engine_error_repository(open, live_account_id, 3);
+        repository
+            .expect_mark_live_account_error()
+            .withf(move |id| *id == live_account_id)
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        repository
+            .expect_update_live_end()
+            .withf(move |id, changeset| {
+                *id == recording_id
+                    && changeset.status == RecordingStatus::Failed.to_string()
+                    && changeset.ended_at.is_some()
+            })
+            .times(1)
+            .returning(|id, _| Box::pin(async move { Ok(id) }));
+
+        usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_error(error_payload())
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn persistent_errors_keep_recording_with_raw_file() {
+        let mut open = recording(Some(Utc::now()));
+        open.storage_temp_path = Some("/rec/tiktok/chan/video.flv".to_string());
+        let mut repository = engine_error_repository(open, Uuid::new_v4(), 4);
+        repository
+            .expect_mark_live_account_error()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        repository.expect_update_live_end().never();
+
+        usecase(
+            repository,
+            MockRecordingEngineControl::new(),
+            QuotaEnforcementPolicy::Warn,
+        )
+        .handle_error(error_payload())
+        .await
+        .unwrap();
+    }
+
     #[test]
     fn container_to_host_path_maps_under_prefix() {
+        let host_root = TempDir::new();
+
         let mapped = RecordingEngineWebhookUseCase::container_to_host_path(
             "/app/rec/tiktok/chan/2025-12-19/video.mp4",
-            &test_paths(),
+            &test_paths(host_root.path()),
         )
         .expect("expected valid path mapping");
 
         assert_eq!(
             mapped,
-            PathBuf::from("/rec/tiktok/chan/2025-12-19/video.mp4")
+            host_root.path().join("tiktok/chan/2025-12-19/video.mp4")
         );
     }
 
+    #[test]
+    fn container_to_host_path_prefers_longest_prefix() {
+        let host_root = TempDir::new();
+        let engine_root = host_root.path().join("engine");
+        let volume_root = host_root.path().join("volume");
+        std::fs::create_dir_all(&engine_root).unwrap();
+        std::fs::create_dir_all(&volume_root).unwrap();
+        let paths = RecordingEnginePaths {
+            mappings: vec![
+                RecordingPathMapping {
+                    container_prefix: "/app/".to_string(),
+                    host_root: engine_root,
+                },
+                RecordingPathMapping {
+                    container_prefix: "/app/volume/".to_string(),
+                    host_root: volume_root.clone(),
+                },
+            ],
+        };
+
+        let mapped = RecordingEngineWebhookUseCase::container_to_host_path(
+            "/app/volume/tiktok/video.mp4",
+            &paths,
+        )
+        .expect("expected valid path mapping");
+
+        assert_eq!(mapped, volume_root.join("tiktok/video.mp4"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn container_to_host_path_rejects_symlink_escape() {
+        let host_root = TempDir::new();
+        std::os::unix::fs::symlink(std::env::temp_dir(), host_root.path().join("escape")).unwrap();
+
+        let err = RecordingEngineWebhookUseCase::container_to_host_path(
+            "/app/rec/escape/video.mp4",
+            &test_paths(host_root.path()),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("outside host root"));
+    }
+
     #[test]
     fn container_to_host_path_rejects_outside_prefix() {
         let err = RecordingEngineWebhookUseCase::container_to_host_path(
             "/other/rec/tiktok/video.mp4",
-            &test_paths(),
+            &test_paths(&std::env::temp_dir()),
         )
         .unwrap_err();
 
-        assert!(err
-            .to_string()
-            .contains("not under configured container prefix"));
+        assert!(
+            err.to_string()
+                .contains("not under configured container prefix")
+        );
     }
 
     #[test]
     fn container_to_host_path_rejects_traversal() {
         let err = RecordingEngineWebhookUseCase::container_to_host_path(
             "/app/rec/../secrets.mp4",
-            &test_paths(),
+            &test_paths(&std::env::temp_dir()),
         )
         .unwrap_err();
 
@@ -0,0 +1,337 @@
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use crates::domain::{
+    entities::recordings::RecordingRemuxUpdateEntity,
+    repositories::{job::JobRepository, recording_upload::RecordingUploadRepository},
+    value_objects::recording_upload::RecordingRemuxPayload,
+};
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::process::Command;
+use tracing::{error, info, warn};
+
+use crate::media_probe::{self, MediaInfo};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordingRemuxOutcome {
+    /// The file was already a faststart MP4 and went straight to upload.
+    AlreadyFaststart(PathBuf),
+    Remuxed(PathBuf),
+}
+
+pub struct RecordingRemuxUseCase {
+    job_repository: Arc<dyn JobRepository + Send + Sync>,
+    recording_repository: Arc<dyn RecordingUploadRepository + Send + Sync>,
+}
+
+impl RecordingRemuxUseCase {
+    pub fn new(
+        job_repository: Arc<dyn JobRepository + Send + Sync>,
+        recording_repository: Arc<dyn RecordingUploadRepository + Send + Sync>,
+    ) -> Self {
+        Self {
+            job_repository,
+            recording_repository,
+        }
+    }
+
+    /// Turns the recording file into a faststart MP4 with a stream copy, points the recording
+    /// at it once it probes fine, and only then enqueues the upload.
+    pub async fn remux(&self, payload: &RecordingRemuxPayload) -> Result<RecordingRemuxOutcome> {
+        let recording = self
+            .recording_repository
+            .find_recording_by_id(payload.recording_id)
+            .await?
+            .context("recording not found")?;
+
+        // A retry after the swap finds the source gone and the recording on the new MP4.
+        let source = [
+            Some(payload.local_path.as_str()),
+            recording.storage_path.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(PathBuf::from)
+        .find(|path| path.is_file())
+        .with_context(|| format!("recording file not found: {}", payload.local_path))?;
+
+        if media_probe::is_faststart_mp4(&source).await? {
+            self.enqueue_upload(recording.id, &source).await?;
+            return Ok(RecordingRemuxOutcome::AlreadyFaststart(source));
+        }
+
+        let source_media = match media_probe::probe(&source).await {
+            Ok(media) => Some(media),
+            Err(err) => {
+                warn!(
+                    recording_id = %recording.id,
+                    path = %source.display(),
+                    error = ?err,
+                    "recording_remux: failed to probe source; remuxing anyway"
+                );
+                None
+            }
+        };
+
+        let output = source.with_extension("mp4");
+        let partial = source.with_extension("remux.mp4");
+        let media = match remux_to_faststart(&source, &partial).await {
+            Ok(()) => verify_remux_output(&partial, source_media.as_ref()).await,
+            Err(err) => Err(err),
+        };
+        let media = match media {
+            Ok(media) => media,
+            Err(err) => {
+                remove_file_if_exists(&partial).await;
+                return Err(err);
+            }
+        };
+        tokio::fs::rename(&partial, &output)
+            .await
+            .with_context(|| format!("failed to move remuxed file to {}", output.display()))?;
+
+        let size_bytes = tokio::fs::metadata(&output).await?.len();
+        self.recording_repository
+            .update_remuxed_recording(
+                recording.id,
+                RecordingRemuxUpdateEntity {
+                    storage_path: output.to_string_lossy().into_owned(),
+                    size_bytes: i64::try_from(size_bytes).ok(),
+                    duration_sec: media.duration_sec,
+                    width: media.width,
+                    height: media.height,
+                    video_codec: media.video_codec,
+                    audio_codec: media.audio_codec,
+                    bitrate_bps: media.bitrate_bps,
+                    fps: media.fps,
+                    updated_at: Utc::now(),
+                },
+            )
+            .await?;
+        info!(
+            recording_id = %recording.id,
+            source = %source.display(),
+            output = %output.display(),
+            "recording_remux: remuxed recording to faststart mp4"
+        );
+
+        if source != output {
+            remove_file_if_exists(&source).await;
+        }
+        self.enqueue_upload(recording.id, &output).await?;
+        Ok(RecordingRemuxOutcome::Remuxed(output))
+    }
+
+    async fn enqueue_upload(&self, recording_id: uuid::Uuid, path: &Path) -> Result<()> {
+        let job_id = self
+            .job_repository
+            .enqueue_recording_upload_job(recording_id, path.to_string_lossy().into_owned())
+            .await?;
+        info!(
+            %recording_id,
+            %job_id,
+            path = %path.display(),
+            "recording_remux: enqueued upload"
+        );
+        Ok(())
+    }
+}
+
+async fn remux_to_faststart(source: &Path, output: &Path) -> Result<()> {
+    let output_status = Command::new("ffmpeg")
+        .arg("-y")
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(source)
+        .arg("-map")
+        .arg("0:v?")
+        .arg("-map")
+        .arg("0:a?")
+        .arg("-c")
+        .arg("copy")
+        .arg("-movflags")
+        .arg("+faststart")
+        .arg("-f")
+        .arg("mp4")
+        .arg(output)
+        .output()
+        .await
+        .context("failed to run ffmpeg for remux")?;
+
+    if !output_status.status.success() {
+        let stderr = String::from_utf8_lossy(&output_status.stderr);
+        bail!(
+            "ffmpeg remux failed with {}: {}",
+            output_status.status,
+            stderr.trim()
+        );
+    }
+    Ok(())
+}
+
+async fn verify_remux_output(output: &Path, source: Option<&MediaInfo>) -> Result<MediaInfo> {
+    if !media_probe::is_faststart_mp4(output).await? {
+        bail!("remuxed file is not a faststart mp4: {}", output.display());
+    }
+    let media = media_probe::probe(output).await?;
+    check_remuxed_media(source, &media)?;
+    Ok(media)
+}
+
+/// Stream copy must not lose streams or a noticeable part of the recording.
+fn check_remuxed_media(source: Option<&MediaInfo>, output: &MediaInfo) -> Result<()> {
+    if output.video_codec.is_none() && output.audio_codec.is_none() {
+        bail!("remuxed file has no audio or video stream");
+    }
+    let Some(output_duration) = output.duration_sec.filter(|duration| *duration > 0) else {
+        bail!("remuxed file has no duration");
+    };
+
+    let Some(source) = source else {
+        return Ok(());
+    };
+    if source.video_codec.is_some() && output.video_codec.is_none() {
+        bail!("remuxed file lost the video stream");
+    }
+    if source.audio_codec.is_some() && output.audio_codec.is_none() {
+        bail!("remuxed file lost the audio stream");
+    }
+    if let Some(source_duration) = source.duration_sec {
+        let tolerance = (source_duration / 100).max(2);
+        if output_duration + tolerance < source_duration {
+            bail!("remuxed file is {output_duration}s long but the source is {source_duration}s");
+        }
+    }
+    Ok(())
+}
+
+async fn remove_file_if_exists(path: &Path) {
+    match tokio::fs::remove_file(path).await {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::NotFound => {}
+        Err(err) => {
+            error!(
+                path = %path.display(),
+                error = %err,
+                "recording_remux: failed to delete file"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usecases::test_support;
+    use crates::domain::{
+        entities::recordings::RecordingEntity,
+        repositories::{job::MockJobRepository, recording_upload::MockRecordingUploadRepository},
+        value_objects::enums::recording_statuses::RecordingStatus,
+    };
+    use uuid::Uuid;
+
+    fn recording(id: Uuid, storage_path: Option<String>) -> RecordingEntity {
+        RecordingEntity {
+            id,
+            ended_at: Some(Utc::now()),
+            storage_path,
+            ..test_support::recording(RecordingStatus::WaitingUpload)
+        }
+    }
+
+    fn media(duration_sec: i32, audio: bool) -> MediaInfo {
+        MediaInfo {
+            duration_sec: Some(duration_sec),
+            video_codec: Some("h264".to_string()),
+            audio_codec: audio.then(|| "aac".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn usecase(
+        job_repository: MockJobRepository,
+        recording: RecordingEntity,
+    ) -> RecordingRemuxUseCase {
+        let mut recording_repository = MockRecordingUploadRepository::new();
+        recording_repository
+            .expect_find_recording_by_id()
+            .returning(move |_| {
+                let recording = recording.clone();
+                Box::pin(async move { Ok(Some(recording)) })
+            });
+        recording_repository
+            .expect_update_remuxed_recording()
+            .never();
+        RecordingRemuxUseCase::new(Arc::new(job_repository), Arc::new(recording_repository))
+    }
+
+    #[tokio::test]
+    async fn faststart_mp4_goes_straight_to_upload() {
+        let path = std::env::temp_dir().join(format!("{}.mp4", Uuid::new_v4()));
+        let mut bytes = Vec::new();
+        for (box_type, body_size) in [(b"ftyp", 8usize), (b"moov", 16), (b"mdat", 32)] {
+            bytes.extend_from_slice(&u32::try_from(body_size + 8).unwrap().to_be_bytes());
+            bytes.extend_from_slice(box_type);
+            bytes.resize(bytes.len() + body_size, 0);
+        }
+        std::fs::write(&path, bytes).unwrap();
+        let recording_id = Uuid::new_v4();
+        let expected_path = path.to_string_lossy().into_owned();
+        let mut job_repository = MockJobRepository::new();
+        job_repository
+            .expect_enqueue_recording_upload_job()
+            .withf(move |id, local_path| *id == recording_id && *local_path == expected_path)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(Uuid::new_v4()) }));
+        let payload = RecordingRemuxPayload {
+            recording_id,
+            local_path: path.to_string_lossy().into_owned(),
+        };
+
+        let outcome = usecase(job_repository, recording(recording_id, None))
+            .remux(&payload)
+            .await;
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            outcome.unwrap(),
+            RecordingRemuxOutcome::AlreadyFaststart(path)
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_file_is_not_uploaded() {
+        let mut job_repository = MockJobRepository::new();
+        job_repository.expect_enqueue_recording_upload_job().never();
+        let recording_id = Uuid::new_v4();
+        let payload = RecordingRemuxPayload {
+            recording_id,
+            local_path: "/nonexistent/video.flv".to_string(),
+        };
+
+        let err = usecase(
+            job_repository,
+            recording(recording_id, Some("/nonexistent/video.flv".to_string())),
+        )
+        .remux(&payload)
+        .await
+        .unwrap_err();
+
+        assert!(err.to_string().contains("recording file not found"));
+    }
+
+    #[test]
+    fn remuxed_media_must_keep_streams_and_duration() {
+        let source = media(600, true);
+
+        assert!(check_remuxed_media(Some(&source), &media(598, true)).is_ok());
+        assert!(check_remuxed_media(None, &media(10, false)).is_ok());
+        assert!(check_remuxed_media(Some(&source), &media(500, true)).is_err());
+        assert!(check_remuxed_media(Some(&source), &media(600, false)).is_err());
+        assert!(check_remuxed_media(Some(&source), &MediaInfo::default()).is_err());
+    }
+}
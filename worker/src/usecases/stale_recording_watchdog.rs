@@ -0,0 +1,642 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use crates::domain::{
+    entities::recordings::{RecordingEntity, RecordingLiveEndUpdateEntity},
+    repositories::{
+        job::JobRepository, recording_engine_control::RecordingEngineControl,
+        recording_watchdog::RecordingWatchdogRepository,
+    },
+    value_objects::enums::recording_statuses::RecordingStatus,
+};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Clone)]
+pub struct StaleRecordingWatchdogParams {
+    /// `live_recording` rows older than this are closed; `None` leaves them alone.
+    pub live_recording_timeout: Option<Duration>,
+    /// `waiting_upload`/`uploading` rows older than this are re-queued or failed.
+    pub uploading_timeout: Option<Duration>,
+    pub limit: i64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StaleRecordingWatchdogResult {
+    pub closed_live_end: usize,
+    pub requeued: usize,
+    pub failed: usize,
+    pub pending_upload: usize,
+    /// Stale-looking live recordings the engine is still writing.
+    pub still_live: usize,
+}
+
+pub struct StaleRecordingWatchdogUseCase {
+    repository: Arc<dyn RecordingWatchdogRepository + Send + Sync>,
+    job_repository: Arc<dyn JobRepository + Send + Sync>,
+    recording_engine_control: Arc<dyn RecordingEngineControl + Send + Sync>,
+    recording_roots: Vec<PathBuf>,
+}
+
+impl StaleRecordingWatchdogUseCase {
+    pub fn new(
+        repository: Arc<dyn RecordingWatchdogRepository + Send + Sync>,
+        job_repository: Arc<dyn JobRepository + Send + Sync>,
+        recording_engine_control: Arc<dyn RecordingEngineControl + Send + Sync>,
+        recording_roots: Vec<PathBuf>,
+    ) -> Self {
+        Self {
+            repository,
+            job_repository,
+            recording_engine_control,
+            recording_roots,
+        }
+    }
+
+    pub async fn run(
+        &self,
+        params: StaleRecordingWatchdogParams,
+    ) -> Result<StaleRecordingWatchdogResult> {
+        let mut result = StaleRecordingWatchdogResult::default();
+
+        if let Some(timeout) = params.live_recording_timeout {
+            self.close_stale_live_recordings(Utc::now() - timeout, params.limit, &mut result)
+                .await?;
+        }
+
+        if let Some(timeout) = params.uploading_timeout {
+            let stale_before = Utc::now() - timeout;
+            for status in [RecordingStatus::WaitingUpload, RecordingStatus::Uploading] {
+                self.recover_stale_uploads(status, stale_before, params.limit, &mut result)
+                    .await?;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// A live the engine never reported as ended: while the engine is still recording it only
+    /// gets its `updated_at` refreshed, otherwise with a raw file it becomes `live_end` for
+    /// the transmux fallback to upload, and without one there is nothing to keep.
+    async fn close_stale_live_recordings(
+        &self,
+        stale_before: DateTime<Utc>,
+        limit: i64,
+        result: &mut StaleRecordingWatchdogResult,
+    ) -> Result<()> {
+        let recordings = self
+            .repository
+            .list_stale_recordings(RecordingStatus::LiveRecording, stale_before, limit)
+            .await?;
+
+        for recording in recordings {
+            if self.is_still_live(&recording, stale_before).await? {
+                if self
+                    .transition(
+                        &recording,
+                        RecordingStatus::LiveRecording,
+                        stale_before,
+                        RecordingStatus::LiveRecording,
+                    )
+                    .await?
+                {
+                    result.still_live += 1;
+                    info!(
+                        recording_id = %recording.id,
+                        started_at = %recording.started_at,
+                        "stale_recording_watchdog: long live recording is still running"
+                    );
+                }
+                continue;
+            }
+
+            let raw_file = self.local_file(recording.storage_temp_path.as_deref());
+            let status = if raw_file.is_some() {
+                RecordingStatus::LiveEnd
+            } else {
+                RecordingStatus::Failed
+            };
+
+            if !self
+                .transition(
+                    &recording,
+                    RecordingStatus::LiveRecording,
+                    stale_before,
+                    status.clone(),
+                )
+                .await?
+            {
+                continue;
+            }
+
+            if status == RecordingStatus::LiveEnd {
+                result.closed_live_end += 1;
+                warn!(
+                    recording_id = %recording.id,
+                    started_at = %recording.started_at,
+                    "stale_recording_watchdog: closed stuck live recording as live_end"
+                );
+            } else {
+                result.failed += 1;
+                warn!(
+                    recording_id = %recording.id,
+                    started_at = %recording.started_at,
+                    "stale_recording_watchdog: stuck live recording has no local file; marked failed"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Asks the engine first; when it cannot answer, a file written under the channel's
+    /// directory (`<root>/<platform>/<channel>`) since `stale_before` means it is still
+    /// recording.
+    async fn is_still_live(
+        &self,
+        recording: &RecordingEntity,
+        stale_before: DateTime<Utc>,
+    ) -> Result<bool> {
+        let Some(live_account) = self
+            .repository
+            .find_live_account(recording.live_account_id)
+            .await?
+        else {
+            return Ok(false);
+        };
+
+        match self
+            .recording_engine_control
+            .is_recording(&live_account.platform, &live_account.account_id)
+            .await
+        {
+            Ok(Some(recording)) => return Ok(recording),
+            Ok(None) => {}
+            Err(err) => {
+                warn!(
+                    recording_id = %recording.id,
+                    error = ?err,
+                    "stale_recording_watchdog: failed to ask engine for live status; checking files"
+                );
+            }
+        }
+
+        let channel_dir = Path::new(&live_account.platform).join(&live_account.account_id);
+        if !channel_dir
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            return Ok(false);
+        }
+        let dirs: Vec<PathBuf> = self
+            .recording_roots
+            .iter()
+            .map(|root| root.join(&channel_dir))
+            .collect();
+        let since = SystemTime::from(stale_before);
+        tokio::task::spawn_blocking(move || dirs.iter().any(|dir| written_since(dir, since)))
+            .await
+            .context("failed to join channel directory scan")
+    }
+
+    /// An upload with no live job: re-queued while its local file is still there, failed
+    /// otherwise.
+    async fn recover_stale_uploads(
+        &self,
+        status: RecordingStatus,
+        stale_before: DateTime<Utc>,
+        limit: i64,
+        result: &mut StaleRecordingWatchdogResult,
+    ) -> Result<()> {
+        let recordings = self
+            .repository
+            .list_stale_recordings(status.clone(), stale_before, limit)
+            .await?;
+
+        for recording in recordings {
+            if self
+                .job_repository
+                .has_pending_recording_job(recording.id, stale_before)
+                .await?
+            {
+                result.pending_upload += 1;
+                info!(
+                    recording_id = %recording.id,
+                    status = %status,
+                    "stale_recording_watchdog: remux or upload job still pending; leaving recording"
+                );
+                continue;
+            }
+
+            let Some(local_path) = self.local_file(recording.storage_path.as_deref()) else {
+                if self
+                    .transition(
+                        &recording,
+                        status.clone(),
+                        stale_before,
+                        RecordingStatus::Failed,
+                    )
+                    .await?
+                {
+                    result.failed += 1;
+                    warn!(
+                        recording_id = %recording.id,
+                        status = %status,
+                        storage_path = ?recording.storage_path,
+                        "stale_recording_watchdog: stuck upload has no local file; marked failed"
+                    );
+                }
+                continue;
+            };
+
+            if !self
+                .transition(
+                    &recording,
+                    status.clone(),
+                    stale_before,
+                    RecordingStatus::WaitingUpload,
+                )
+                .await?
+            {
+                continue;
+            }
+
+            let local_path = local_path.to_string_lossy().into_owned();
+            match self
+                .job_repository
+                .enqueue_recording_remux_job(recording.id, local_path.clone())
+                .await
+            {
+                Ok(job_id) => {
+                    result.requeued += 1;
+                    warn!(
+                        recording_id = %recording.id,
+                        %job_id,
+                        status = %status,
+                        path = %local_path,
+                        "stale_recording_watchdog: re-enqueued stuck upload"
+                    );
+                }
+                Err(err) => {
+                    error!(
+                        recording_id = %recording.id,
+                        job_error = ?err,
+                        "stale_recording_watchdog: failed to re-enqueue upload; retrying next sweep"
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn transition(
+        &self,
+        recording: &RecordingEntity,
+        from: RecordingStatus,
+        stale_before: DateTime<Utc>,
+        to: RecordingStatus,
+    ) -> Result<bool> {
+        let now = Utc::now();
+        let closes_live = from == RecordingStatus::LiveRecording && to != from;
+        let changeset = RecordingLiveEndUpdateEntity {
+            status: to.to_string(),
+            ended_at: (closes_live && recording.ended_at.is_none()).then_some(now),
+            duration_sec: None,
+            size_bytes: None,
+            storage_temp_path: None,
+            updated_at: now,
+        };
+
+        let updated = self
+            .repository
+            .update_stale_recording(recording.id, from, stale_before, changeset)
+            .await?;
+        if !updated {
+            info!(
+                recording_id = %recording.id,
+                "stale_recording_watchdog: recording changed since it was listed; skipping"
+            );
+        }
+        Ok(updated)
+    }
+
+    /// `path` if it is a file that, with symlinks resolved, lies under a recording root.
+    fn local_file(&self, path: Option<&str>) -> Option<PathBuf> {
+        let path = Path::new(path?);
+        let canonical = path.canonicalize().ok()?;
+        let inside_root = self
+            .recording_roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .any(|root| canonical.starts_with(root));
+
+        (inside_root && canonical.is_file()).then(|| path.to_path_buf())
+    }
+}
+
+/// Whether any file under `dir` was modified at or after `since`; unreadable entries count
+/// as untouched.
+fn written_since(dir: &Path, since: SystemTime) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| match entry.file_type() {
+        Ok(file_type) if file_type.is_dir() => written_since(&entry.path(), since),
+        Ok(file_type) if file_type.is_file() => entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified >= since),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usecases::test_support::{self, TempDir};
+    use crates::domain::{
+        entities::live_accounts::LiveAccountEntity,
+        repositories::{
+            job::MockJobRepository, recording_engine_control::MockRecordingEngineControl,
+            recording_watchdog::MockRecordingWatchdogRepository,
+        },
+    };
+    use uuid::Uuid;
+
+    fn params() -> StaleRecordingWatchdogParams {
+        StaleRecordingWatchdogParams {
+            live_recording_timeout: Some(Duration::hours(24)),
+            uploading_timeout: Some(Duration::hours(6)),
+            limit: 50,
+        }
+    }
+
+    /// A recording untouched for two days, well past every watchdog timeout.
+    fn recording(status: RecordingStatus) -> RecordingEntity {
+        let two_days_ago = Utc::now() - Duration::days(2);
+        RecordingEntity {
+            started_at: two_days_ago,
+            created_at: two_days_ago,
+            updated_at: two_days_ago,
+            ..test_support::recording(status)
+        }
+    }
+
+    fn repository_listing(
+        status: RecordingStatus,
+        stale: RecordingEntity,
+    ) -> MockRecordingWatchdogRepository {
+        let mut repository = MockRecordingWatchdogRepository::new();
+        repository
+            .expect_list_stale_recordings()
+            .returning(move |listed, _, _| {
+                let recordings = if listed == status {
+                    vec![stale.clone()]
+                } else {
+                    Vec::new()
+                };
+                Box::pin(async move { Ok(recordings) })
+            });
+        repository
+            .expect_find_live_account()
+            .returning(|live_account_id| {
+                Box::pin(async move {
+                    Ok(Some(LiveAccountEntity {
+                        id: live_account_id,
+                        platform: "bigo".to_string(),
+                        account_id: "sai239233".to_string(),
+                        canonical_url: "https://www.bigo.tv/sai239233".to_string(),
+                        status: "synced".to_string(),
+                        created_at: Utc::now(),
+                        updated_at: Utc::now(),
+                    }))
+                })
+            });
+        repository
+    }
+
+    /// An engine answering `is_recording` with `recording`, or unable to answer on `None`.
+    fn engine(recording: Option<bool>) -> MockRecordingEngineControl {
+        let mut engine = MockRecordingEngineControl::new();
+        engine
+            .expect_is_recording()
+            .returning(move |_, _| Box::pin(async move { Ok(recording) }));
+        engine
+    }
+
+    fn expect_transition(
+        repository: &mut MockRecordingWatchdogRepository,
+        from: RecordingStatus,
+        to: RecordingStatus,
+    ) {
+        repository
+            .expect_update_stale_recording()
+            .withf(move |_, status, _, changeset| {
+                *status == from && changeset.status == to.to_string()
+            })
+            .times(1)
+            .returning(|_, _, _, _| Box::pin(async { Ok(true) }));
+    }
+
+    fn usecase(
+        repository: MockRecordingWatchdogRepository,
+        job_repository: MockJobRepository,
+        recording_root: &Path,
+    ) -> StaleRecordingWatchdogUseCase {
+        usecase_with_engine(
+            repository,
+            job_repository,
+            engine(Some(false)),
+            recording_root,
+        )
+    }
+
+    fn usecase_with_engine(
+        repository: MockRecordingWatchdogRepository,
+        job_repository: MockJobRepository,
+        engine: MockRecordingEngineControl,
+        recording_root: &Path,
+    ) -> StaleRecordingWatchdogUseCase {
+        StaleRecordingWatchdogUseCase::new(
+            Arc::new(repository),
+            Arc::new(job_repository),
+            Arc::new(engine),
+            vec![recording_root.to_path_buf()],
+        )
+    }
+
+    #[tokio::test]
+    async fn stuck_live_recording_with_raw_file_is_closed_as_live_end() {
+        let root = TempDir::new();
+        let file = root.write("video.mp4", b"mp4");
+        let mut stale = recording(RecordingStatus::LiveRecording);
+        stale.storage_temp_path = Some(file.to_string_lossy().into_owned());
+        let mut repository = repository_listing(RecordingStatus::LiveRecording, stale);
+        expect_transition(
+            &mut repository,
+            RecordingStatus::LiveRecording,
+            RecordingStatus::LiveEnd,
+        );
+
+        let result = usecase(repository, MockJobRepository::new(), root.path())
+            .run(params())
+            .await
+            .unwrap();
+
+        assert_eq!(result.closed_live_end, 1);
+    }
+
+    #[tokio::test]
+    async fn stuck_live_recording_without_file_is_failed() {
+        let root = TempDir::new();
+        let stale = recording(RecordingStatus::LiveRecording);
+        let mut repository = repository_listing(RecordingStatus::LiveRecording, stale);
+        expect_transition(
+            &mut repository,
+            RecordingStatus::LiveRecording,
+            RecordingStatus::Failed,
+        );
+
+        let result = usecase(repository, MockJobRepository::new(), root.path())
+            .run(params())
+            .await
+            .unwrap();
+
+        assert_eq!(result.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn long_live_recording_the_engine_still_records_is_not_closed() {
+        let root = TempDir::new();
+        let stale = recording(RecordingStatus::LiveRecording);
+        let mut repository = repository_listing(RecordingStatus::LiveRecording, stale);
+        expect_transition(
+            &mut repository,
+            RecordingStatus::LiveRecording,
+            RecordingStatus::LiveRecording,
+        );
+
+        let result = usecase_with_engine(
+            repository,
+            MockJobRepository::new(),
+            engine(Some(true)),
+            root.path(),
+        )
+        .run(params())
+        .await
+        .unwrap();
+
+        assert_eq!(result.still_live, 1);
+        assert_eq!(result.failed, 0);
+        assert_eq!(result.closed_live_end, 0);
+    }
+
+    #[tokio::test]
+    async fn long_live_recording_with_a_growing_file_is_not_closed() {
+        let root = TempDir::new();
+        root.write("bigo/sai239233/segment.flv", b"flv");
+        let stale = recording(RecordingStatus::LiveRecording);
+        let mut repository = repository_listing(RecordingStatus::LiveRecording, stale);
+        expect_transition(
+            &mut repository,
+            RecordingStatus::LiveRecording,
+            RecordingStatus::LiveRecording,
+        );
+
+        let result = usecase_with_engine(
+            repository,
+            MockJobRepository::new(),
+            engine(None),
+            root.path(),
+        )
+        .run(params())
+        .await
+        .unwrap();
+
+        assert_eq!(result.still_live, 1);
+        assert_eq!(result.failed, 0);
+    }
+
+    #[tokio::test]
+    async fn stuck_upload_with_local_file_is_requeued() {
+        let root = TempDir::new();
+        let file = root.write("video.mp4", b"mp4");
+        let mut stale = recording(RecordingStatus::Uploading);
+        stale.storage_path = Some(file.to_string_lossy().into_owned());
+        let recording_id = stale.id;
+        let expected_path = stale.storage_path.clone().unwrap();
+        let mut repository = repository_listing(RecordingStatus::Uploading, stale);
+        expect_transition(
+            &mut repository,
+            RecordingStatus::Uploading,
+            RecordingStatus::WaitingUpload,
+        );
+        let mut job_repository = MockJobRepository::new();
+        job_repository
+            .expect_has_pending_recording_job()
+            .returning(|_, _| Box::pin(async { Ok(false) }));
+        job_repository
+            .expect_enqueue_recording_remux_job()
+            .withf(move |id, path| *id == recording_id && *path == expected_path)
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(Uuid::new_v4()) }));
+
+        let result = usecase(repository, job_repository, root.path())
+            .run(params())
+            .await
+            .unwrap();
+
+        assert_eq!(result.requeued, 1);
+    }
+
+    #[tokio::test]
+    async fn stuck_upload_outside_recording_root_is_failed() {
+        let root = TempDir::new();
+        let mut stale = recording(RecordingStatus::WaitingUpload);
+        stale.storage_path = Some(format!("{}/../video.mp4", root.path().display()));
+        let mut repository = repository_listing(RecordingStatus::WaitingUpload, stale);
+        expect_transition(
+            &mut repository,
+            RecordingStatus::WaitingUpload,
+            RecordingStatus::Failed,
+        );
+        let mut job_repository = MockJobRepository::new();
+        job_repository
+            .expect_has_pending_recording_job()
+            .returning(|_, _| Box::pin(async { Ok(false) }));
+        job_repository.expect_enqueue_recording_remux_job().never();
+
+        let result = usecase(repository, job_repository, root.path())
+            .run(params())
+            .await
+            .unwrap();
+
+        assert_eq!(result.failed, 1);
+    }
+
+    #[tokio::test]
+    async fn upload_with_pending_job_is_left_alone() {
+        let root = TempDir::new();
+        let file = root.write("video.mp4", b"mp4");
+        let mut stale = recording(RecordingStatus::Uploading);
+        stale.storage_path = Some(file.to_string_lossy().into_owned());
+        let mut repository = repository_listing(RecordingStatus::Uploading, stale);
+        repository.expect_update_stale_recording().never();
+        let mut job_repository = MockJobRepository::new();
+        job_repository
+            .expect_has_pending_recording_job()
+            .returning(|_, _| Box::pin(async { Ok(true) }));
+        job_repository.expect_enqueue_recording_remux_job().never();
+
+        let result = usecase(repository, job_repository, root.path())
+            .run(params())
+            .await
+            .unwrap();
+
+        assert_eq!(result.pending_upload, 1);
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{Context, Result, bail};
+use crates::domain::{
+    entities::deliveries::InsertDeliveryEntity,
+    repositories::{
+        deliveries::DeliveryRepository, notification_channel::NotificationChannel,
+        user_contacts::UserContactRepository,
+    },
+    value_objects::{
+        enums::delivery_statuses::DeliveryStatus,
+        subscription_notifications::{NotificationRecipient, SubscriptionNotificationPayload},
+    },
+};
+use std::sync::Arc;
+use tracing::{error, info};
+
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionNotificationResult {
+    pub sent: usize,
+    pub already_sent: usize,
+    pub failed: usize,
+}
+
+/// Fans a subscription notification out to every configured channel.
+///
+/// Each channel gets one `deliveries` row per dedupe key, so a retried job only
+/// re-sends through the channels that failed last time.
+pub struct SubscriptionNotificationUseCase {
+    delivery_repository: Arc<dyn DeliveryRepository + Send + Sync>,
+    contact_repository: Arc<dyn UserContactRepository + Send + Sync>,
+    channels: Vec<Arc<dyn NotificationChannel + Send + Sync>>,
+}
+
+impl SubscriptionNotificationUseCase {
+    pub fn new(
+        delivery_repository: Arc<dyn DeliveryRepository + Send + Sync>,
+        contact_repository: Arc<dyn UserContactRepository + Send + Sync>,
+        channels: Vec<Arc<dyn NotificationChannel + Send + Sync>>,
+    ) -> Self {
+        Self {
+            delivery_repository,
+            contact_repository,
+            channels,
+        }
+    }
+
+    pub fn has_channels(&self) -> bool {
+        !self.channels.is_empty()
+    }
+
+    pub async fn deliver(
+        &self,
+        notification: &SubscriptionNotificationPayload,
+    ) -> Result<SubscriptionNotificationResult> {
+        let email = self
+            .contact_repository
+            .find_email(notification.user_id)
+            .await
+            .context("failed to look up notification recipient")?;
+        let recipient = NotificationRecipient {
+            user_id: notification.user_id,
+            email,
+        };
+
+        let mut result = SubscriptionNotificationResult::default();
+        for channel in &self.channels {
+            let via = channel.channel();
+            let delivery = self
+                .delivery_repository
+                .find_or_create(InsertDeliveryEntity {
+                    recording_id: None,
+                    user_id: notification.user_id,
+                    via: via.to_string(),
+                    delivered_at: None,
+                    status: DeliveryStatus::Queued.to_string(),
+                    error: None,
+                    subscription_id: Some(notification.subscription_id),
+                    kind: Some(notification.kind.to_string()),
+                    dedupe_key: Some(notification.dedupe_key.clone()),
+                })
+                .await?;
+
+            if delivery.status == DeliveryStatus::Sent.as_str() {
+                result.already_sent += 1;
+                continue;
+            }
+
+            match channel.send(&recipient, notification).await {
+                Ok(()) => {
+                    self.delivery_repository.mark_sent(delivery.id).await?;
+                    info!(
+                        delivery_id = %delivery.id,
+                        %via,
+                        dedupe_key = %notification.dedupe_key,
+                        "subscription_notification: delivered"
+                    );
+                    result.sent += 1;
+                }
+                Err(err) => {
+                    error!(
+                        delivery_id = %delivery.id,
+                        %via,
+                        dedupe_key = %notification.dedupe_key,
+                        error = %err,
+                        "subscription_notification: delivery failed"
+                    );
+                    self.delivery_repository
+                        .mark_failed(delivery.id, &err.to_string())
+                        .await?;
+                    result.failed += 1;
+                }
+            }
+        }
+
+        if result.failed > 0 {
+            bail!(
+                "{} of {} notification channels failed",
+                result.failed,
+                self.channels.len()
+            );
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crates::domain::{
+        entities::deliveries::DeliveryEntity,
+        repositories::{
+            deliveries::MockDeliveryRepository, notification_channel::MockNotificationChannel,
+            user_contacts::MockUserContactRepository,
+        },
+        value_objects::enums::{
+            delivery_channels::DeliveryChannel, notification_kinds::NotificationKind,
+        },
+    };
+    use uuid::Uuid;
+
+    fn delivery(insert: InsertDeliveryEntity, status: DeliveryStatus) -> DeliveryEntity {
+        DeliveryEntity {
+            id: Uuid::new_v4(),
+            recording_id: insert.recording_id,
+            user_id: insert.user_id,
+            via: insert.via,
+            delivered_at: None,
+            status: status.to_string(),
+            error: None,
+            subscription_id: insert.subscription_id,
+            kind: insert.kind,
+            dedupe_key: insert.dedupe_key,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn skips_channels_already_sent_and_records_new_deliveries() {
+        let notification = SubscriptionNotificationPayload {
+            kind: NotificationKind::SubscriptionExpired,
+            user_id: Uuid::new_v4(),
+            subscription_id: Uuid::new_v4(),
+            plan_id: Uuid::new_v4(),
+            ends_at: Utc::now(),
+            dedupe_key: "subscription_expired:test".to_string(),
+            next_plan_name: None,
+        };
+
+        let mut contact_repository = MockUserContactRepository::new();
+        contact_repository
+            .expect_find_email()
+            .returning(|_| Box::pin(async { Ok(Some("buyer@example.com".to_string())) }));
+
+        let mut delivery_repository = MockDeliveryRepository::new();
+        delivery_repository
+            .expect_find_or_create()
+            .returning(|insert| {
+                let status = if insert.via == DeliveryChannel::Email.as_str() {
+                    DeliveryStatus::Sent
+                } else {
+                    DeliveryStatus::Failed
+                };
+                let delivery = delivery(insert, status);
+                Box::pin(async move { Ok(delivery) })
+            });
+        delivery_repository
+            .expect_mark_sent()
+            .times(1)
+            .returning(|_| Box::pin(async { Ok(()) }));
+        delivery_repository.expect_mark_failed().never();
+
+        let mut email = MockNotificationChannel::new();
+        email.expect_channel().return_const(DeliveryChannel::Email);
+        email.expect_send().never();
+
+        let mut webhook = MockNotificationChannel::new();
+        webhook
+            .expect_channel()
+            .return_const(DeliveryChannel::Webhook);
+        webhook
+            .expect_send()
+            .withf(|recipient, _| recipient.email.as_deref() == Some("buyer@example.com"))
+            .times(1)
+            .returning(|_, _| Box::pin(async { Ok(()) }));
+
+        let usecase = SubscriptionNotificationUseCase::new(
+            Arc::new(delivery_repository),
+            Arc::new(contact_repository),
+            vec![Arc::new(email), Arc::new(webhook)],
+        );
+
+        let result = usecase.deliver(&notification).await.unwrap();
+
+        assert_eq!(result.sent, 1);
+        assert_eq!(result.already_sent, 1);
+        assert_eq!(result.failed, 0);
+    }
+}
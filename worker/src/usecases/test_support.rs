@@ -0,0 +1,69 @@
+//! Fixtures shared by the use case tests.
+
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use crates::domain::{
+    entities::recordings::RecordingEntity,
+    value_objects::enums::recording_statuses::RecordingStatus,
+};
+use uuid::Uuid;
+
+/// A recording in `status` with only the required columns set; tests override the rest with
+/// struct update syntax.
+pub(crate) fn recording(status: RecordingStatus) -> RecordingEntity {
+    let now = Utc::now();
+    RecordingEntity {
+        id: Uuid::new_v4(),
+        live_account_id: Uuid::new_v4(),
+        recording_key: None,
+        title: None,
+        started_at: now,
+        ended_at: None,
+        duration_sec: None,
+        size_bytes: None,
+        storage_path: None,
+        storage_temp_path: None,
+        status: status.to_string(),
+        poster_storage_path: None,
+        created_at: now,
+        updated_at: now,
+        width: None,
+        height: None,
+        video_codec: None,
+        audio_codec: None,
+        bitrate_bps: None,
+        fps: None,
+    }
+}
+
+/// A uniquely named directory under the system temp dir; removed on drop.
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    pub(crate) fn new() -> Self {
+        let root = std::env::temp_dir().join(Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&root).unwrap();
+        Self(root)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Writes `contents` to `relative`, creating parent directories, and returns the full path.
+    pub(crate) fn write(&self, relative: &str, contents: &[u8]) -> PathBuf {
+        let path = self.0.join(relative);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}